@@ -0,0 +1,9 @@
+//! Feeds arbitrary bytes to the NBT parser: it must return Ok or Err, never panic or hang.
+//! Run with `cargo +nightly fuzz run parse_nbt_bytes` from the repository root.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = fastnbt::file_parser::parse_nbt_bytes(data);
+});
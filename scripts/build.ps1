@@ -17,11 +17,11 @@ else {
 switch ($buildMode) {
     "debug" {
         Write-Host "Running cargo build (debug mode)..."
-        cargo build
+        cargo build --features python
     }
     "release" {
         Write-Host "Running cargo build (release mode)..."
-        cargo build --release
+        cargo build --release --features python
     }
     "clean" {
         Write-Host "Cleaning project..."
@@ -40,7 +40,7 @@ if ($LASTEXITCODE -eq 0) {
     Write-Host "Build completed successfully" -ForegroundColor Green
     Write-Host "=========================="
     Write-Host "Build Python package with maturing"
-    maturin develop
+    maturin develop --features python
 } else {
     Write-Host "Build failed" -ForegroundColor Red
 }
@@ -5,29 +5,90 @@
 // - 2023-12-17
 //
 // ## File Version
-// - 1.0.0
+// - 1.0.9
 //
 // ## Changelog
 // - 1.0.0: Initial version
+// - 1.0.1: Python bindings available only with the "python" feature [mrmarkolinus:2026-10-16]
+// - 1.0.2: Coordinates can be printed and compared [mrmarkolinus:2026-10-16]
+// - 1.0.3: Added ResourceLocation [mrmarkolinus:2026-10-16]
+// - 1.0.4: Added BlockSearchReport [mrmarkolinus:2026-10-16]
+// - 1.0.5: Added search_blocks_multi [mrmarkolinus:2026-10-16]
+// - 1.0.6: Added SearchOptions and sort_blocks_by_position, for a deterministic order of the found blocks [mrmarkolinus:2026-10-16]
+// - 1.0.7: Added BLOCK_TAGS, block_tags and the is_ore, is_log, is_leaves and is_fluid classification [mrmarkolinus:2026-10-16]
+// - 1.0.8: Added blocks_in_category [mrmarkolinus:2026-10-16]
+// - 1.0.9: Re-exported items::enchantments [mrmarkolinus:2026-10-16]
 
+use crate::chunk_format;
+use crate::error::RnbtError;
+use crate::McWorldDescriptor;
+
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+use rayon::prelude::*;
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+pub use crate::items::enchantments;
+
+#[cfg(test)]
+mod tests;
+
+/// Namespace of the vanilla identifiers, used when an identifier has no namespace.
+pub const DEFAULT_NAMESPACE: &str = "minecraft";
+
+/// The common vanilla block tags (Java Edition 1.20) with their blocks, the nested tags expanded.
+/// 
+/// https://minecraft.wiki/w/Tag#Block_tags_2
+/// 
+/// Only a subset of the game tags, useful to search the blocks by category: the ores, the logs and the leaves.
+pub const BLOCK_TAGS: &[(&str, &[&str])] = &[
+    ("minecraft:coal_ores", &["minecraft:coal_ore", "minecraft:deepslate_coal_ore"]),
+    ("minecraft:iron_ores", &["minecraft:iron_ore", "minecraft:deepslate_iron_ore"]),
+    ("minecraft:copper_ores", &["minecraft:copper_ore", "minecraft:deepslate_copper_ore"]),
+    ("minecraft:gold_ores", &["minecraft:gold_ore", "minecraft:deepslate_gold_ore", "minecraft:nether_gold_ore"]),
+    ("minecraft:redstone_ores", &["minecraft:redstone_ore", "minecraft:deepslate_redstone_ore"]),
+    ("minecraft:lapis_ores", &["minecraft:lapis_ore", "minecraft:deepslate_lapis_ore"]),
+    ("minecraft:diamond_ores", &["minecraft:diamond_ore", "minecraft:deepslate_diamond_ore"]),
+    ("minecraft:emerald_ores", &["minecraft:emerald_ore", "minecraft:deepslate_emerald_ore"]),
+    ("minecraft:logs", &[
+        "minecraft:oak_log", "minecraft:oak_wood", "minecraft:stripped_oak_log", "minecraft:stripped_oak_wood",
+        "minecraft:spruce_log", "minecraft:spruce_wood", "minecraft:stripped_spruce_log", "minecraft:stripped_spruce_wood",
+        "minecraft:birch_log", "minecraft:birch_wood", "minecraft:stripped_birch_log", "minecraft:stripped_birch_wood",
+        "minecraft:jungle_log", "minecraft:jungle_wood", "minecraft:stripped_jungle_log", "minecraft:stripped_jungle_wood",
+        "minecraft:acacia_log", "minecraft:acacia_wood", "minecraft:stripped_acacia_log", "minecraft:stripped_acacia_wood",
+        "minecraft:dark_oak_log", "minecraft:dark_oak_wood", "minecraft:stripped_dark_oak_log", "minecraft:stripped_dark_oak_wood",
+        "minecraft:mangrove_log", "minecraft:mangrove_wood", "minecraft:stripped_mangrove_log", "minecraft:stripped_mangrove_wood",
+        "minecraft:cherry_log", "minecraft:cherry_wood", "minecraft:stripped_cherry_log", "minecraft:stripped_cherry_wood",
+        "minecraft:crimson_stem", "minecraft:crimson_hyphae", "minecraft:stripped_crimson_stem", "minecraft:stripped_crimson_hyphae",
+        "minecraft:warped_stem", "minecraft:warped_hyphae", "minecraft:stripped_warped_stem", "minecraft:stripped_warped_hyphae",
+    ]),
+    ("minecraft:leaves", &[
+        "minecraft:oak_leaves", "minecraft:spruce_leaves", "minecraft:birch_leaves", "minecraft:jungle_leaves", "minecraft:acacia_leaves",
+        "minecraft:dark_oak_leaves", "minecraft:mangrove_leaves", "minecraft:cherry_leaves", "minecraft:azalea_leaves", "minecraft:flowering_azalea_leaves",
+    ]),
+];
+
+/// The category of `blocks_in_category` with all the ores, see `ores`.
+pub const ORES_CATEGORY: &str = "ores";
+/// The category of `blocks_in_category` with the fluids, see `is_fluid`.
+pub const FLUIDS_CATEGORY: &str = "fluids";
+
+/// The ores without a vanilla `*_ores` tag, counted as ores by `is_ore`.
+const OTHER_ORES: [&str; 2] = ["minecraft:nether_quartz_ore", "minecraft:ancient_debris"];
+/// The fluid blocks: `is_fluid`.
+const FLUIDS: [&str; 3] = ["minecraft:water", "minecraft:lava", "minecraft:bubble_column"];
 
-#[pyclass]
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
 pub struct MinecraftBlock{
-    #[pyo3(get, set)]
     pub name: String,
-    #[pyo3(get, set)]
     pub coord: Coordinates,
-    #[pyo3(get, set)]
     pub chunk: MinecraftChunk,
-    #[pyo3(get, set)]
     pub properties: HashMap<String, String>
 }
 
-#[pymethods]
 impl MinecraftBlock {
-    #[new]
     pub fn new (name: String, coord: Vec<i32>, chunk_coord: Vec<i32>, properties: HashMap<String, String>) -> Self {
         Self {
             name,
@@ -38,22 +99,26 @@ impl MinecraftBlock {
     }
 }
 
+#[cfg(feature = "python")]
+#[pymethods]
+impl MinecraftBlock {
+    #[new]
+    fn py_new (name: String, coord: Vec<i32>, chunk_coord: Vec<i32>, properties: HashMap<String, String>) -> Self {
+        Self::new(name, coord, chunk_coord, properties)
+    }
+}
 
-#[pyclass]
-#[derive(Clone)]
+
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Coordinates
 {
-    #[pyo3(get, set)]
     pub x: i32,
-    #[pyo3(get, set)]
     pub y: i32,
-    #[pyo3(get, set)]
     pub z: i32,
 }
 
-#[pymethods]
 impl Coordinates {
-    #[new]
     pub fn new (coord: Vec<i32>) -> Self {
         Self {
             x : coord[0],
@@ -63,17 +128,23 @@ impl Coordinates {
     }
 }
 
-#[pyclass]
+#[cfg(feature = "python")]
+#[pymethods]
+impl Coordinates {
+    #[new]
+    fn py_new (coord: Vec<i32>) -> Self {
+        Self::new(coord)
+    }
+}
+
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
 #[derive(Clone)]
 pub struct MinecraftChunk
 {
-    #[pyo3(get, set)]
     pub coord: Coordinates,
 }
 
-#[pymethods]
 impl MinecraftChunk {
-    #[new]
     pub fn new (coord: Vec<i32>) -> Self {
         Self {
             coord: Coordinates::new(coord),
@@ -81,6 +152,15 @@ impl MinecraftChunk {
     }
 }
 
+#[cfg(feature = "python")]
+#[pymethods]
+impl MinecraftChunk {
+    #[new]
+    fn py_new (coord: Vec<i32>) -> Self {
+        Self::new(coord)
+    }
+}
+
 
 
 
@@ -88,3 +168,211 @@ pub struct BlockBatch {
     pub blocks: Vec<MinecraftBlock>,
 }
 
+/// Result of a block search that also tells why a requested block has no matches.
+/// 
+/// A block listed in `not_in_palette` appears in no palette of the searched chunks, which usually means a typo 
+/// in its name (or a block from another version or mod); a block in `blocks` with an empty list appears in
+/// some palette but no position in the sections uses it.
+#[derive(Default)]
+pub struct BlockSearchReport {
+    /// The matching blocks for each searched resource location, every valid requested one has an entry.
+    pub blocks: HashMap<String, Vec<MinecraftBlock>>,
+    /// The requested resource locations that never appear in any palette.
+    pub not_in_palette: Vec<String>,
+    /// The requested strings that are not valid resource locations.
+    pub invalid: Vec<String>,
+}
+
+/// Options of `McWorldDescriptor::search_blocks_with_options`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Sorts the blocks of each resource location by (x, y, z), see `sort_blocks_by_position`.
+    /// Off by default: without it the order follows the chunks and the sections, and may change between runs.
+    pub sort_by_position: bool,
+}
+
+/// Sorts the blocks of each resource location by (x, y, z), so that the results of two searches can be compared.
+pub fn sort_blocks_by_position(blocks_found: &mut HashMap<String, Vec<MinecraftBlock>>) {
+    for blocks in blocks_found.values_mut() {
+        blocks.par_sort_unstable_by_key(|block| (block.coord.x, block.coord.y, block.coord.z));
+    }
+}
+
+/// A validated `namespace:path` identifier of a block, item or entity (e.g. `minecraft:diamond_ore`).
+/// 
+/// https://minecraft.wiki/w/Resource_location
+/// 
+/// The namespace may contain `a-z 0-9 _ - .`, the path the same characters plus `/`.
+/// When the namespace is omitted (`diamond_ore` or `:diamond_ore`) it defaults to `minecraft`, as in the game.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ResourceLocation {
+    namespace: String,
+    path: String,
+}
+
+impl ResourceLocation {
+    pub fn new(resource_location: &str) -> Result<Self, RnbtError> {
+        let (namespace, path) = match resource_location.split_once(':') {
+            Some(("", path)) => (DEFAULT_NAMESPACE, path),
+            Some((namespace, path)) => (namespace, path),
+            None => (DEFAULT_NAMESPACE, resource_location),
+        };
+
+        let is_namespace_char = |c: char| matches!(c, 'a'..='z' | '0'..='9' | '_' | '-' | '.');
+        let is_path_char = |c: char| is_namespace_char(c) || c == '/';
+
+        if path.is_empty() || !namespace.chars().all(is_namespace_char) || !path.chars().all(is_path_char) {
+            return Err(RnbtError::InvalidResourceLocation(resource_location.to_string()));
+        }
+
+        Ok(Self { namespace: namespace.to_string(), path: path.to_string() })
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl fmt::Display for ResourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.path)
+    }
+}
+
+impl FromStr for ResourceLocation {
+    type Err = RnbtError;
+
+    fn from_str(resource_location: &str) -> Result<Self, Self::Err> {
+        Self::new(resource_location)
+    }
+}
+
+impl TryFrom<&str> for ResourceLocation {
+    type Error = RnbtError;
+
+    fn try_from(resource_location: &str) -> Result<Self, Self::Error> {
+        Self::new(resource_location)
+    }
+}
+
+impl TryFrom<String> for ResourceLocation {
+    type Error = RnbtError;
+
+    fn try_from(resource_location: String) -> Result<Self, Self::Error> {
+        Self::new(&resource_location)
+    }
+}
+
+impl TryFrom<&String> for ResourceLocation {
+    type Error = RnbtError;
+
+    fn try_from(resource_location: &String) -> Result<Self, Self::Error> {
+        Self::new(resource_location)
+    }
+}
+
+/// Searches the blocks in several loaded worlds at once (in parallel), as `McWorldDescriptor::search_blocks`
+/// does on each of them. Every block found is tagged with the index of its world in `worlds`,
+/// and the blocks of each resource location are ordered by world index.
+/// 
+/// The chunks of a world still in the lazy storage (`LoadMode::Lazy`) are not searched, see `McWorldDescriptor::decode_all`.
+pub fn search_blocks_multi(worlds: &[McWorldDescriptor], block_resource_location: Vec<String>) -> HashMap<String, Vec<(usize, MinecraftBlock)>> {
+    let resource_locations: Vec<String> = block_resource_location.iter()
+        .filter_map(|resource_location| match ResourceLocation::new(resource_location) {
+            Ok(resource_location) => Some(resource_location.to_string()),
+            Err(e) => {
+                log::warn!("search_blocks_multi: {}", e);
+                None
+            }
+        })
+        .collect();
+
+    let worlds_blocks: Vec<HashMap<String, Vec<MinecraftBlock>>> = worlds.par_iter()
+        .map(|world| chunk_format::inspect_chunks(resource_locations.clone(), &world.tag_compounds_list))
+        .collect();
+
+    let mut blocks_found = HashMap::<String, Vec<(usize, MinecraftBlock)>>::new();
+    for (world_index, world_blocks) in worlds_blocks.into_iter().enumerate() {
+        for (resource_location, blocks) in world_blocks {
+            blocks_found.entry(resource_location)
+                .or_default()
+                .extend(blocks.into_iter().map(|block| (world_index, block)));
+        }
+    }
+
+    blocks_found
+}
+
+/// Returns the tags of `BLOCK_TAGS` containing the block, e.g. `minecraft:logs` for `oak_log`.
+/// The namespace may be omitted; an invalid resource location has no tags.
+pub fn block_tags(resource_location: &str) -> Vec<&'static str> {
+    let Ok(resource_location) = ResourceLocation::new(resource_location) else {
+        return Vec::new();
+    };
+    let block_name = resource_location.to_string();
+
+    BLOCK_TAGS.iter()
+        .filter(|(_, blocks)| blocks.contains(&block_name.as_str()))
+        .map(|(tag, _)| *tag)
+        .collect()
+}
+
+/// Returns the blocks of a tag of `BLOCK_TAGS` (the namespace may be omitted), empty for the other tags. 
+/// E.g. `search_blocks(blocks_in_tag("diamond_ores").to_vec())`.
+pub fn blocks_in_tag(tag: &str) -> &'static [&'static str] {
+    let Ok(tag) = ResourceLocation::new(tag) else {
+        return &[];
+    };
+    let tag = tag.to_string();
+
+    BLOCK_TAGS.iter()
+        .find(|(tag_name, _)| *tag_name == tag)
+        .map_or(&[], |(_, blocks)| *blocks)
+}
+
+/// Returns the blocks of a category: `ORES_CATEGORY`, `FLUIDS_CATEGORY` or a tag of `BLOCK_TAGS` (see `blocks_in_tag`).
+/// Empty for an unknown category.
+pub fn blocks_in_category(category: &str) -> Vec<&'static str> {
+    match category {
+        ORES_CATEGORY => ores(),
+        FLUIDS_CATEGORY => FLUIDS.to_vec(),
+        tag => blocks_in_tag(tag).to_vec(),
+    }
+}
+
+/// Returns all the ores: the blocks of the vanilla `*_ores` tags, the nether quartz ore and the ancient debris.
+pub fn ores() -> Vec<&'static str> {
+    BLOCK_TAGS.iter()
+        .filter(|(tag, _)| tag.ends_with("_ores"))
+        .flat_map(|(_, blocks)| blocks.iter().copied())
+        .chain(OTHER_ORES)
+        .collect()
+}
+
+/// Returns true if the block is an ore, see `ores`.
+pub fn is_ore(resource_location: &str) -> bool {
+    is_one_of(resource_location, &ores())
+}
+
+/// Returns true if the block is in the `minecraft:logs` tag (logs, wood, stems and hyphae, stripped or not).
+pub fn is_log(resource_location: &str) -> bool {
+    is_one_of(resource_location, blocks_in_tag("minecraft:logs"))
+}
+
+/// Returns true if the block is in the `minecraft:leaves` tag.
+pub fn is_leaves(resource_location: &str) -> bool {
+    is_one_of(resource_location, blocks_in_tag("minecraft:leaves"))
+}
+
+/// Returns true if the block is a fluid: water, lava or a bubble column. The waterlogged blocks are not fluids.
+pub fn is_fluid(resource_location: &str) -> bool {
+    is_one_of(resource_location, &FLUIDS)
+}
+
+fn is_one_of(resource_location: &str, block_names: &[&str]) -> bool {
+    ResourceLocation::new(resource_location).is_ok_and(|resource_location| block_names.contains(&resource_location.to_string().as_str()))
+}
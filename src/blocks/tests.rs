@@ -0,0 +1,64 @@
+#[cfg(test)]
+
+use super::*;
+
+#[test]
+fn test_resource_location_valid() {
+    let diamond_ore = ResourceLocation::new("minecraft:diamond_ore").unwrap();
+    assert_eq!(diamond_ore.namespace(), "minecraft");
+    assert_eq!(diamond_ore.path(), "diamond_ore");
+    assert_eq!(diamond_ore.to_string(), "minecraft:diamond_ore");
+
+    let custom = ResourceLocation::new("my-mod.v2:blocks/custom_lever").unwrap();
+    assert_eq!(custom.namespace(), "my-mod.v2");
+    assert_eq!(custom.path(), "blocks/custom_lever");
+}
+
+#[test]
+fn test_resource_location_default_namespace() {
+    let expected = ResourceLocation::new("minecraft:stone").unwrap();
+    assert_eq!(ResourceLocation::new("stone").unwrap(), expected);
+    assert_eq!(ResourceLocation::new(":stone").unwrap(), expected);
+    assert_eq!("stone".parse::<ResourceLocation>().unwrap(), expected);
+}
+
+#[test]
+fn test_resource_location_invalid() {
+    for invalid in ["", "minecraft:", "Minecraft:stone", "minecraft:Stone", "minecraft:diamond ore",
+                    "minecraft:stone:1", "mine/craft:stone", "minecraft:stöne"] {
+        assert_eq!(ResourceLocation::new(invalid), Err(RnbtError::InvalidResourceLocation(invalid.to_string())), "{:?}", invalid);
+    }
+}
+
+#[test]
+fn test_block_tags() {
+    assert_eq!(block_tags("minecraft:deepslate_diamond_ore"), vec!["minecraft:diamond_ores"]);
+    assert_eq!(block_tags("stripped_warped_hyphae"), vec!["minecraft:logs"]);
+    assert!(block_tags("minecraft:stone").is_empty());
+    assert!(block_tags("Stone").is_empty());
+
+    assert_eq!(blocks_in_tag("gold_ores"), ["minecraft:gold_ore", "minecraft:deepslate_gold_ore", "minecraft:nether_gold_ore"]);
+    assert!(blocks_in_tag("minecraft:planks").is_empty());
+    // every block of a tag has the tag
+    for (tag, blocks) in BLOCK_TAGS {
+        assert!(blocks.iter().all(|block| block_tags(block).contains(tag)), "{}", tag);
+    }
+}
+
+#[test]
+fn test_block_classification() {
+    assert!(is_ore("minecraft:coal_ore"));
+    assert!(is_ore("ancient_debris"));
+    assert!(!is_ore("minecraft:coal_block"));
+    assert_eq!(ores().len(), 19);
+
+    assert!(is_log("minecraft:cherry_log"));
+    assert!(is_log("minecraft:crimson_stem"));
+    assert!(!is_log("minecraft:oak_planks"));
+    assert!(is_leaves("minecraft:flowering_azalea_leaves"));
+    assert!(!is_leaves("minecraft:oak_log"));
+
+    assert!(is_fluid("water"));
+    assert!(is_fluid("minecraft:bubble_column"));
+    assert!(!is_fluid("minecraft:ice"));
+}
@@ -5,16 +5,63 @@
 // - 2023-12-17
 //
 // ## File Version
-// - 1.0.0
+// - 1.0.18
 //
 // ## Changelog
 // - 1.0.0: Initial version
+// - 1.0.1: Added block-state property filters to the block search [mrmarkolinus:2026-10-16]
+// - 1.0.2: Added chunk_status to read the generation status of a chunk [mrmarkolinus:2026-10-16]
+// - 1.0.3: Chunk coordinates read from the pre-1.18 Level compound or derived from the region position [mrmarkolinus:2026-10-16]
+// - 1.0.4: Added replace_block to swap a block in the section palettes [mrmarkolinus:2026-10-16]
+// - 1.0.5: Added unpack_block_states, the search ignores the padding indices and handles sections without data array [mrmarkolinus:2026-10-16]
+// - 1.0.6: Added pack_block_states [mrmarkolinus:2026-10-16]
+// - 1.0.7: Added light_at to read the block and sky light of a block [mrmarkolinus:2026-10-16]
+// - 1.0.8: Added block entities and entities readers [mrmarkolinus:2026-10-16]
+// - 1.0.9: Added collect_palette_block_names [mrmarkolinus:2026-10-16]
+// - 1.0.10: Added the heightmap decoder and the grayscale terrain map (PNG with the "image" feature) [mrmarkolinus:2026-10-16]
+// - 1.0.11: Added ChunkAccessor, a version-independent view of a chunk [mrmarkolinus:2026-10-16]
+// - 1.0.12: Added sections, Section and BlockState, the decoded sections of a chunk [mrmarkolinus:2026-10-16]
+// - 1.0.13: Added set_block to change a single block of a chunk [mrmarkolinus:2026-10-16]
+// - 1.0.14: Added structures and StructureRef, the generated structures touching a chunk [mrmarkolinus:2026-10-16]
+// - 1.0.15: Added count_blocks_in_box [mrmarkolinus:2026-10-16]
+// - 1.0.16: The functions reading a list of chunks take any AsRef<NbtTagCompound>, e.g. the SourcedCompound of McWorldDescriptor [mrmarkolinus:2026-10-16]
+// - 1.0.17: Added ChunkPosition, the block search and the heightmap place the chunks with it [mrmarkolinus:2026-10-16]
+// - 1.0.18: Added MAX_MAP_PIXELS, heightmap_grayscale returns None for a larger image [mrmarkolinus:2026-10-16]
 
 use crate::nbt_tag;
 use crate::blocks;
 
 use std::collections::{HashMap, HashSet};
 
+#[cfg(test)]
+mod tests;
+
+/// Number of blocks in a section (subchunk) of 16x16x16 blocks.
+pub const SECTION_BLOCKS_NUM: usize = 4096;
+/// First DataVersion (20w17a) where the block state indices are padded to never span two longs.
+pub const BLOCK_STATES_PADDING_DATA_VERSION: i32 = 2529;
+/// First DataVersion (21w43a, 1.18) storing the blocks in the `block_states` compound of the sections.
+pub const BLOCK_STATES_DATA_VERSION: i32 = 2844;
+/// Number of block columns in a chunk (16x16), the entries of a heightmap.
+pub const CHUNK_COLUMNS_NUM: usize = 256;
+/// Size of a heightmap entry, enough for the 384 blocks of the world height since 1.18.
+pub const HEIGHTMAP_ENTRY_SIZE_IN_BIT: u32 = 9;
+/// The tags of the chunk renamed in 1.18, when the `Level` compound was removed: (name since 1.18, name in `Level`).
+pub const LEVEL_TAG_RENAMES: &[(&str, &str)] = &[
+    ("sections", "Sections"),
+    ("block_entities", "TileEntities"),
+    ("block_ticks", "TileTicks"),
+    ("fluid_ticks", "LiquidTicks"),
+    ("structures", "Structures"),
+    ("CarvingMasks", "CarvingMasks"),
+    ("PostProcessing", "PostProcessing"),
+];
+/// The `id` of the empty structure starts, saved in the chunks before 1.18 for every structure type not started there.
+pub const INVALID_STRUCTURE_ID: &str = "INVALID";
+/// The maximum number of pixels of a map of the chunks (`heightmap_grayscale`, `render::topdown_blocks`),
+/// a square of 4096 blocks (8x8 regions): the map covers the bounding box of the chunks, also the areas between them.
+pub const MAX_MAP_PIXELS: usize = 4096 * 4096;
+
 /// Inspects Minecraft chunks and extracts block positions based on resource locations.
 /// 
 /// This function parses NBT (Named Binary Tag) data of Minecraft chunks to identify and return 
@@ -25,8 +72,8 @@ use std::collections::{HashMap, HashSet};
 /// 
 /// * `block_resource_location` - Vec<String>: A vector of strings representing the resource 
 ///   locations of blocks to be inspected.
-/// * `tag_compounds_list` - &Vec<nbt_tag::NbtTagCompound>: A reference to a vector of 
-///   NbtTagCompound, representing the NBT data of chunks.
+/// * `tag_compounds_list` - &[C]: A slice of NbtTagCompound (or of `world::SourcedCompound`),
+///   representing the NBT data of chunks, placed by `ChunkPosition::chunk_coordinates`.
 /// 
 /// # Returns
 /// 
@@ -34,28 +81,54 @@ use std::collections::{HashMap, HashSet};
 /// string, and the value is a vector of Coordinates structs representing the positions of 
 /// the blocks in the Minecraft world.
 /// 
-pub fn inspect_chunks<'a>(block_resource_location: Vec::<String>, tag_compounds_list: &'a Vec<nbt_tag::NbtTagCompound>) -> HashMap::<String, Vec::<blocks::MinecraftBlock>> {
+pub fn inspect_chunks<C: ChunkPosition>(block_resource_location: Vec::<String>, tag_compounds_list: &[C]) -> HashMap::<String, Vec::<blocks::MinecraftBlock>> {
+    inspect_chunks_with_props(to_block_filters(&block_resource_location), tag_compounds_list)
+}
+
+/// Inspects Minecraft chunks and extracts block positions based on resource locations and block-state properties.
+///
+/// Works like `inspect_chunks`, but every searched block can be constrained by a list of block-state
+/// properties (e.g. `("axis", "x")` for a log). Only palette entries whose properties match all the
+/// given constraints are collected. Properties that are not listed are treated as wildcards, so an empty
+/// constraint list matches every state of the block.
+///
+/// # Arguments
+///
+/// * `block_filters` - Vec<(String, Vec<(String, String)>)>: A vector of (resource location, properties) pairs,
+///   where properties is a list of (property name, property value) constraints.
+/// * `tag_compounds_list` - &[C]: A slice of NbtTagCompound (or of `world::SourcedCompound`),
+///   representing the NBT data of chunks, placed by `ChunkPosition::chunk_coordinates`.
+///
+/// # Returns
+///
+/// HashMap<String, Vec<blocks::MinecraftBlock>>: A HashMap where each key is a resource location
+/// string, and the value is a vector of the matching blocks. Filters sharing the same resource
+/// location are collected under the same key.
+pub fn inspect_chunks_with_props<C: ChunkPosition>(block_filters: Vec::<(String, Vec::<(String, String)>)>, tag_compounds_list: &[C]) -> HashMap::<String, Vec::<blocks::MinecraftBlock>> {
     // Refer to https://minecraft.fandom.com/wiki/Chunk_format to see how a block is saved in a chunk
     //sections (TAG List)
     // block_states (TAG Compound)
     // -- palette (TAG List)
     // ---- block (TAG Compound)
     // ------ Name (TAG String)
+    // ------ Properties (TAG Compound)
     let mut blocks_positions_list = HashMap::<String, Vec::<blocks::MinecraftBlock>>::new();
 
-    for tag_compound in tag_compounds_list.iter() {
-        let mut chunk_pos = get_chunk_coordinates(tag_compound);
+    for chunk in tag_compounds_list.iter() {
+        let tag_compound = chunk.as_ref();
+        let mut chunk_pos = chunk.chunk_coordinates();
         
         if let Some(sections_tag) = tag_compound.values.get("sections") {
             if let Some(sections_list) = sections_tag.list_as_ref(){
                 for sections in sections_list.values.iter() {
                     if let Some(block_states_tag) = find_block_states_in_section(sections) {
-                        //TODO: replace unwraps
-                        let subchunk_y_pos = sections.compound_as_ref().unwrap().values.get("Y").unwrap().byte().unwrap().value as i32;
                         // The y position got from get_chunk_coordinates is always -4, since the chunk always starts at -4 * 16 = -64
                         // what we need is the actual subchunk position
+                        let Some(subchunk_y_pos) = get_section_y(sections) else {
+                            continue;
+                        };
                         chunk_pos.y = subchunk_y_pos;
-                        _ = get_absolute_blocks_positions(block_states_tag, &block_resource_location, &chunk_pos, &mut blocks_positions_list);
+                        _ = get_absolute_blocks_positions_with_props(block_states_tag, &block_filters, &chunk_pos, &mut blocks_positions_list);
                     }
                 }
             }
@@ -66,6 +139,11 @@ pub fn inspect_chunks<'a>(block_resource_location: Vec::<String>, tag_compounds_
 
 }
 
+/// Converts a list of resource locations into block filters without property constraints.
+fn to_block_filters(block_resource_location: &[String]) -> Vec::<(String, Vec::<(String, String)>)> {
+    block_resource_location.iter().map(|block_name| (block_name.clone(), Vec::new())).collect()
+}
+
 /// Calculates the absolute positions of blocks within Minecraft chunks.
 ///
 /// Analyzes a block state NBT tag and identifies the absolute positions of specified blocks within a chunk. 
@@ -92,6 +170,28 @@ pub fn get_absolute_blocks_positions<'a>   (block_states_tag: &nbt_tag::NbtTag,
                                             block_resource_location: & 'a Vec::<String>, 
                                             chunk_pos: &blocks::Coordinates, 
                                             blocks_positions_list: & 'a mut HashMap::<String, Vec::<blocks::MinecraftBlock>>) -> bool {
+    get_absolute_blocks_positions_with_props(block_states_tag, &to_block_filters(block_resource_location), chunk_pos, blocks_positions_list)
+}
+
+/// Calculates the absolute positions of blocks within Minecraft chunks, honouring block-state property filters.
+///
+/// Same as `get_absolute_blocks_positions`, but each searched block is given as a (resource location, properties)
+/// pair. A palette entry is collected only if its name matches and all the listed properties have the requested value.
+///
+/// # Arguments
+///
+/// * `block_states_tag` - A reference to the NbtTag, representing the block states in a Minecraft chunk.
+/// * `block_filters` - A slice of (resource location, properties) pairs.
+/// * `chunk_pos` - A reference to the coordinates of the chunk being inspected.
+/// * `blocks_positions_list` - A mutable reference to a HashMap where keys are block names (String) and values are vectors of blocks.
+///
+/// # Returns
+///
+/// Returns `true` if the function successfully finds and processes the block positions, `false` otherwise.
+pub fn get_absolute_blocks_positions_with_props(block_states_tag: &nbt_tag::NbtTag, 
+                                                block_filters: &[(String, Vec::<(String, String)>)], 
+                                                chunk_pos: &blocks::Coordinates, 
+                                                blocks_positions_list: &mut HashMap::<String, Vec::<blocks::MinecraftBlock>>) -> bool {
     /* #10: Find palette TAG list in block states following the format https://minecraft.fandom.com/wiki/Chunk_format
     * block_states (TAG Compound)
     * -- palette (TAG List)
@@ -102,70 +202,62 @@ pub fn get_absolute_blocks_positions<'a>   (block_states_tag: &nbt_tag::NbtTag,
 
     match palette_list_option {
         Some(palette_list) => {
-            let (unique_set_created, searched_blocks_palette_ids) = create_unique_palette_id_set(&palette_list, block_resource_location);
+            let (unique_set_created, searched_blocks_palette_ids) = create_unique_palette_id_set_with_props(palette_list, block_filters);
 
             if unique_set_created {
-                match blocks_data_array_option {
-                    /* #30: if the searched block was found scan the data array associated to the palette.
-                    * A data array is a 64bit unsing integer array with a specific format (see Chunk_format)
-                    * A data array needs to contain all the blocks in the subchunk (section), which is 16x16x16
-                    * The blocks are saved as id referincing the palette and compressed in 64bit unsigned integer
-                    * Example:  palette list {minecraft:bedrock, minecreat:stone, minecraft:dirt}
-                    *           we need 2 bits to represent the 3 possible blocks in the section (0, 1, 2)
-                    *           the chunk file format specifies that min 4 bits must be used, so we get 4 bits.
-                    *           data array dec:     1180177 
-                    *           data array bin:     0000 0000 0001 0010 0000 0010 0001 0001
-                    *           data array palette: bedrock bedrock stone dirt bedrock dirt stone stone
-                    * For more details refer to https://minecraft.fandom.com/wiki/Chunk_format
-                    */
-                    Some(blocks_data_array) => { 
-                        let data_index_bit_size = get_palette_id_size_in_bit(palette_list);
-
-                        let mut subchunk_x_pos = 0;
-                        let mut subchunk_y_pos = 0;
-                        let mut subchunk_z_pos = 0;  
-
-                        for blocks_data in blocks_data_array {
-                            let palette_ids = get_palette_ids_from_data_array_element(blocks_data.clone(), data_index_bit_size);
-                        
-                            /* #40: get the block position in the subchunk 
-                            * block position is a tridimensional coordinate x,y,z. The blocks are stored with YZX order
-                            * X increases each block
-                            * Z increases each 16 blocks
-                            * Y increases each 16x16 = 256 blocks
-                            */                      
-                            for palette_id in palette_ids {
-                                //we are interested only in the searched blocks
-                                for (block_name, block_palette_ids) in searched_blocks_palette_ids.iter() {
-                                    if block_palette_ids.contains(&palette_id) {
-
-                                        if !blocks_positions_list.contains_key(block_name) {
-                                            blocks_positions_list.insert(block_name.clone(), vec![]);
-                                        }
-                                        
-                                        if let Some(current_block_positions_list) = blocks_positions_list.get_mut(block_name) {   
-                                            if let Some(block_tag) = palette_list.values.get(palette_id as usize) {
-                                                let block_properties = get_block_properties(block_tag);
-                                                
-                                                let mc_block = blocks::MinecraftBlock::new(block_name.to_owned(),
-                                                                                                [(chunk_pos.x * 16) + subchunk_x_pos, 
-                                                                                                        ((chunk_pos.y * 16) + subchunk_y_pos), 
-                                                                                                        (chunk_pos.z * 16) + subchunk_z_pos].to_vec(), 
-                                                                                            [chunk_pos.x, chunk_pos.y, chunk_pos.z].to_vec(),
-                                                                                                        block_properties);
-                                            
-                                                current_block_positions_list.push(mc_block);
-                                            }   
-                                        }
-                                    }
-                                }  
-                                advance_block_position(&mut subchunk_x_pos, &mut subchunk_y_pos, &mut subchunk_z_pos);               
+                /* #30: if the searched block was found scan the data array associated to the palette.
+                * A data array is a 64bit unsing integer array with a specific format (see Chunk_format)
+                * A data array needs to contain all the blocks in the subchunk (section), which is 16x16x16
+                * The blocks are saved as id referincing the palette and compressed in 64bit unsigned integer
+                * Example:  palette list {minecraft:bedrock, minecreat:stone, minecraft:dirt}
+                *           we need 2 bits to represent the 3 possible blocks in the section (0, 1, 2)
+                *           the chunk file format specifies that min 4 bits must be used, so we get 4 bits.
+                *           data array dec:     1180177 
+                *           data array bin:     0000 0000 0001 0010 0000 0010 0001 0001
+                *           data array palette: bedrock bedrock stone dirt bedrock dirt stone stone
+                * A section without data array is entirely made of the first block in the palette.
+                * For more details refer to https://minecraft.fandom.com/wiki/Chunk_format
+                */
+                let blocks_data_array = blocks_data_array_option.map(|data| data.as_slice()).unwrap_or(&[]);
+                let palette_ids = unpack_block_states(blocks_data_array, palette_list.values.len(), BLOCK_STATES_DATA_VERSION);
+
+                let mut subchunk_x_pos = 0;
+                let mut subchunk_y_pos = 0;
+                let mut subchunk_z_pos = 0;  
+
+                /* #40: get the block position in the subchunk 
+                * block position is a tridimensional coordinate x,y,z. The blocks are stored with YZX order
+                * X increases each block
+                * Z increases each 16 blocks
+                * Y increases each 16x16 = 256 blocks
+                */                      
+                for palette_id in palette_ids {
+                    let palette_id = palette_id as u32;
+                    //we are interested only in the searched blocks
+                    for (block_name, block_palette_ids) in searched_blocks_palette_ids.iter() {
+                        if block_palette_ids.contains(&palette_id) {
+
+                            if !blocks_positions_list.contains_key(block_name) {
+                                blocks_positions_list.insert(block_name.clone(), vec![]);
+                            }
+                            
+                            if let Some(current_block_positions_list) = blocks_positions_list.get_mut(block_name) {   
+                                if let Some(block_tag) = palette_list.values.get(palette_id as usize) {
+                                    let block_properties = get_block_properties(block_tag);
+                                    
+                                    let mc_block = blocks::MinecraftBlock::new(block_name.to_owned(),
+                                                                                    [(chunk_pos.x * 16) + subchunk_x_pos, 
+                                                                                            ((chunk_pos.y * 16) + subchunk_y_pos), 
+                                                                                            (chunk_pos.z * 16) + subchunk_z_pos].to_vec(), 
+                                                                                [chunk_pos.x, chunk_pos.y, chunk_pos.z].to_vec(),
+                                                                                            block_properties);
+                                
+                                    current_block_positions_list.push(mc_block);
+                                }   
                             }
                         }
-                    },
-                    None => {
-                        //TODO
-                    }
+                    }  
+                    advance_block_position(&mut subchunk_x_pos, &mut subchunk_y_pos, &mut subchunk_z_pos);               
                 }
             }
                         
@@ -235,6 +327,27 @@ pub fn advance_block_position(x_pos: &mut i32, y_pos: &mut i32, z_pos: &mut i32)
 /// Each match is identified by a unique palette ID (index in the palette list), which is added to a HashSet.
 /// This process helps in tracking all variations of a block, which may have different IDs despite having the same name.
 pub fn create_unique_palette_id_set<'a>(palette_list: &nbt_tag::NbtTagList, block_resource_location: & 'a Vec::<String>) -> (bool, HashMap<String, HashSet<u32>>){
+    create_unique_palette_id_set_with_props(palette_list, &to_block_filters(block_resource_location))
+}
+
+/// Creates a unique set of palette IDs for specified blocks, matching only the requested block-state properties.
+///
+/// Same as `create_unique_palette_id_set`, but a palette entry is added to the set only if its name matches the
+/// resource location and its properties match every (name, value) constraint of the filter.
+/// Properties not listed in the filter are treated as wildcards.
+///
+/// # Arguments
+///
+/// * `palette_list` - A reference to the NbtTagList representing the palette list of a Minecraft chunk.
+/// * `block_filters` - A slice of (resource location, properties) pairs.
+///
+/// # Returns
+///
+/// Returns a tuple containing:
+/// * A boolean indicating if at least one unique set was created.
+/// * A HashMap where keys are block names (String) and values are sets of palette IDs (HashSet<u32>).
+///   Filters with the same block name are merged in the same set.
+pub fn create_unique_palette_id_set_with_props(palette_list: &nbt_tag::NbtTagList, block_filters: &[(String, Vec::<(String, String)>)]) -> (bool, HashMap<String, HashSet<u32>>){
     /*Some blocks may have different palette ids with same names (for example a repeater oriented in different ways)*/
     
     /* Init the data structure to contain multiple blocks finding */
@@ -243,26 +356,20 @@ pub fn create_unique_palette_id_set<'a>(palette_list: &nbt_tag::NbtTagList, bloc
     let mut unique_set_created = false;
     
     
-    for block_name in block_resource_location.iter() {
-        let mut palette_current_index = 0;
-        let mut block_unique_set = HashSet::new();
-        for blocks in palette_list.values.iter() {
+    for (block_name, block_properties) in block_filters.iter() {
+        let block_unique_set = searched_blocks_palette_ids.entry(block_name.clone()).or_default();
+        for (palette_current_index, blocks) in palette_list.values.iter().enumerate() {
             /* #20: scan every block in the palette and check if the name is the one we are looking for
             * -- palette (TAG List)
             * ---- block (TAG Compound)
             * ------ Name (TAG String)
+            * ------ Properties (TAG Compound)
             */
-            if find_block_name_in_palette(blocks, block_name) {
-                block_unique_set.insert(palette_current_index);
-                
-                if !unique_set_created {
-                    unique_set_created = true;
-                }
-                
+            if find_block_name_in_palette(blocks, block_name) && find_block_properties_in_palette(blocks, block_properties) {
+                block_unique_set.insert(palette_current_index as u32);
+                unique_set_created = true;
             }
-            palette_current_index += 1;
         }
-        searched_blocks_palette_ids.insert(block_name.clone(), block_unique_set);
     }
 
     (unique_set_created, searched_blocks_palette_ids)
@@ -296,9 +403,9 @@ pub fn get_block_properties(block_tag: &nbt_tag::NbtTag) -> HashMap<String, Stri
     if let Some(block_compound) = block_tag.compound_as_ref() {
         if let Some(block_properties_tag) = block_compound.values.get("Properties") {
             if let Some(block_properties_compound) = block_properties_tag.compound_as_ref() {
-                for (key, value) in block_properties_compound.values.iter() {
-                    if value.ty() == nbt_tag::NbtTagType::String {
-                        block_properties.insert(key.to_string(), value.string().unwrap().value);
+                for (key, value) in block_properties_compound.iter() {
+                    if let Ok(property_value) = value.try_string() {
+                        block_properties.insert(key.to_string(), property_value.value.clone());
                     }
                 }
             }
@@ -323,14 +430,21 @@ pub fn get_block_properties(block_tag: &nbt_tag::NbtTag) -> HashMap<String, Stri
 ///
 /// usize: The size in bits required to represent a palette ID, considering the given palette size.
 pub fn get_palette_id_size_in_bit(palette_list: &nbt_tag::NbtTagList) -> u32 {
+    get_palette_id_size_in_bit_from_len(palette_list.values.len())
+}
+
+/// Calculates the size of palette IDs in bits from the number of entries in the palette.
+///
+/// Same as `get_palette_id_size_in_bit`, for the callers that only know the palette length.
+pub fn get_palette_id_size_in_bit_from_len(palette_len: usize) -> u32 {
     /* the number of palettes in the section determines the number of bits used for the indexes in data
     * the indexes in data are n bits long, where n is the number needed to represent all the palettes (log2(n_palettes))
     * minimum 4 bits
     * example: 4 palettes = 2 bits needed to represent them. 4 used
     * example: 36 palettes = 6 bits needed to represent them. 6 used
         */
-    let num_palette_in_section = palette_list.values.len() as u32;
-    let num_bits = (std::mem::size_of_val(&num_palette_in_section) * 8) as u32;
+    let num_palette_in_section = palette_len.max(1) as u32;
+    let num_bits = u32::BITS;
     
     //fast log2 function. index of the palette start from 0
     let mut data_index_bit_size = num_bits - (num_palette_in_section - 1).leading_zeros();
@@ -344,6 +458,138 @@ pub fn get_palette_id_size_in_bit(palette_list: &nbt_tag::NbtTagList) -> u32 {
 
 }
 
+/// Unpacks the block states data array of a section into the palette index of each of its 4096 blocks.
+///
+/// The indices are in YZX order (X increases each block, Z each 16 blocks, Y each 256 blocks),
+/// each one `get_palette_id_size_in_bit_from_len(palette_len)` bits long.
+/// Since 20w17a (`BLOCK_STATES_PADDING_DATA_VERSION`) an index never spans two longs and the unused high bits
+/// of each long are padding; before, the indices are packed back to back across the longs.
+///
+/// # Arguments
+///
+/// * `long_array` - &[i64]: The `data` long array of the section block states.
+/// * `palette_len` - usize: The number of entries in the section palette.
+/// * `data_version` - i32: The `DataVersion` of the chunk, selecting the packing layout.
+///
+/// # Returns
+///
+/// Vec<u16>: Always 4096 palette indices. A section with a single palette entry (no data array) is all 0,
+/// a too short data array is completed with 0.
+pub fn unpack_block_states(long_array: &[i64], palette_len: usize, data_version: i32) -> Vec<u16> {
+    if palette_len <= 1 {
+        return vec![0; SECTION_BLOCKS_NUM];
+    }
+
+    let index_size_in_bit = get_palette_id_size_in_bit_from_len(palette_len);
+    unpack_long_array(long_array, index_size_in_bit, SECTION_BLOCKS_NUM, data_version >= BLOCK_STATES_PADDING_DATA_VERSION)
+}
+
+/// Unpacks `entries_num` unsigned values of `entry_size_in_bit` bits each from a packed long array,
+/// the layout shared by the block states, the biomes and the heightmaps.
+///
+/// # Arguments
+///
+/// * `long_array` - &[i64]: The packed long array.
+/// * `entry_size_in_bit` - u32: The size of each value, between 1 and 16 bits.
+/// * `entries_num` - usize: The number of values to unpack.
+/// * `padded` - bool: The values never span two longs and the unused high bits of each long are padding
+///   (since 20w17a, see `BLOCK_STATES_PADDING_DATA_VERSION`), otherwise they are packed back to back across the longs.
+///
+/// # Returns
+///
+/// Vec<u16>: Always `entries_num` values, a too short long array is completed with 0.
+pub fn unpack_long_array(long_array: &[i64], entry_size_in_bit: u32, entries_num: usize, padded: bool) -> Vec<u16> {
+    let mut values = Vec::with_capacity(entries_num);
+    let index_size_in_bit = entry_size_in_bit as usize;
+    let bit_mask = (1u64 << index_size_in_bit) - 1;
+
+    if padded {
+        let indexes_in_data_element = 64 / index_size_in_bit;
+        'data: for data_element in long_array {
+            for element_data_index in 0..indexes_in_data_element {
+                if values.len() == entries_num {
+                    break 'data;
+                }
+                let value = (*data_element as u64 >> (element_data_index * index_size_in_bit)) & bit_mask;
+                values.push(value as u16);
+            }
+        }
+    }
+    else {
+        for entry_index in 0..entries_num {
+            let bit_offset = entry_index * index_size_in_bit;
+            let Some(data_element) = long_array.get(bit_offset / 64) else {
+                break;
+            };
+
+            let shift_amount = bit_offset % 64;
+            let mut value = *data_element as u64 >> shift_amount;
+            // the value continues in the low bits of the next long
+            if shift_amount + index_size_in_bit > 64 {
+                if let Some(next_data_element) = long_array.get(bit_offset / 64 + 1) {
+                    value |= (*next_data_element as u64) << (64 - shift_amount);
+                }
+            }
+            values.push((value & bit_mask) as u16);
+        }
+    }
+
+    values.resize(entries_num, 0);
+    values
+}
+
+/// Packs the palette index of each block of a section into the block states data array, the inverse of `unpack_block_states`.
+///
+/// The layout (padded or compact) is selected by `data_version` as in `unpack_block_states`,
+/// so that `pack_block_states(&unpack_block_states(data, len, version), len, version)` gives back `data`
+/// for any valid data array.
+///
+/// # Arguments
+///
+/// * `palette_ids` - &[u16]: The palette indices in YZX order, usually 4096. Indices must be lower than `palette_len`.
+/// * `palette_len` - usize: The number of entries in the section palette.
+/// * `data_version` - i32: The `DataVersion` of the chunk the section is written to.
+///
+/// # Returns
+///
+/// Vec<i64>: The `data` long array. It is empty when the palette has a single entry, since such sections have no data array.
+pub fn pack_block_states(palette_ids: &[u16], palette_len: usize, data_version: i32) -> Vec<i64> {
+    if palette_len <= 1 {
+        return Vec::new();
+    }
+
+    let index_size_in_bit = get_palette_id_size_in_bit_from_len(palette_len) as usize;
+    let bit_mask = (1u64 << index_size_in_bit) - 1;
+
+    if data_version >= BLOCK_STATES_PADDING_DATA_VERSION {
+        let indexes_in_data_element = 64 / index_size_in_bit;
+        palette_ids.chunks(indexes_in_data_element)
+            .map(|element_palette_ids| {
+                element_palette_ids.iter().enumerate()
+                    .fold(0u64, |data_element, (element_data_index, palette_id)| {
+                        data_element | (*palette_id as u64 & bit_mask) << (element_data_index * index_size_in_bit)
+                    }) as i64
+            })
+            .collect()
+    }
+    else {
+        let mut long_array = vec![0u64; (palette_ids.len() * index_size_in_bit).div_ceil(64)];
+        for (block_index, palette_id) in palette_ids.iter().enumerate() {
+            let bit_offset = block_index * index_size_in_bit;
+            let shift_amount = bit_offset % 64;
+            let palette_id = *palette_id as u64 & bit_mask;
+
+            long_array[bit_offset / 64] |= palette_id << shift_amount;
+            // the index continues in the low bits of the next long
+            if shift_amount + index_size_in_bit > 64 {
+                long_array[bit_offset / 64 + 1] |= palette_id >> (64 - shift_amount);
+            }
+        }
+
+        long_array.into_iter().map(|data_element| data_element as i64).collect()
+    }
+}
+
 /// Extracts palette IDs from a data array element in Minecraft chunk data.
 ///
 /// This function decodes and retrieves palette IDs from a given data array element. These IDs 
@@ -390,6 +636,8 @@ pub fn get_palette_ids_from_data_array_element(data_array_element : i64, index_s
 /// This function parses the NBT (Named Binary Tag) data of a Minecraft chunk to extract its 
 /// coordinates. Chunk coordinates are essential for identifying the location of chunks in the 
 /// Minecraft world, especially for tasks like map rendering or data analysis.
+/// The `xPos` and `zPos` tags are the absolute chunk coordinates in the world (not the position in the region file).
+/// Chunks saved before 1.18 store them inside the `Level` compound.
 ///
 /// # Arguments
 ///
@@ -402,22 +650,23 @@ pub fn get_palette_ids_from_data_array_element(data_array_element : i64, index_s
 pub fn get_chunk_coordinates(chunk_compound: &nbt_tag::NbtTagCompound) -> blocks::Coordinates {
 
     let mut result: blocks::Coordinates = blocks::Coordinates::new(vec![0, 0, 0]);
+    let position_compound = get_chunk_position_compound(chunk_compound);
     
-    if let Some(x_coord_tag) = chunk_compound.values.get("xPos") {
+    if let Some(x_coord_tag) = position_compound.values.get("xPos") {
         if let Some(x_coord) = x_coord_tag.int() {
             result.x = x_coord.value;
         }
         
     }
 
-    if let Some(y_coord_tag) = chunk_compound.values.get("yPos") {
+    if let Some(y_coord_tag) = position_compound.values.get("yPos") {
         if let Some(y_coord) = y_coord_tag.int() {
             result.y = y_coord.value;
         }
         
     }
 
-    if let Some(z_coord_tag) = chunk_compound.values.get("zPos") {
+    if let Some(z_coord_tag) = position_compound.values.get("zPos") {
         if let Some(z_coord) = z_coord_tag.int() {
             result.z = z_coord.value;
         }
@@ -428,6 +677,481 @@ pub fn get_chunk_coordinates(chunk_compound: &nbt_tag::NbtTagCompound) -> blocks
 
 }
 
+/// A chunk that may know its position apart from its NBT data, as a `world::SourcedCompound` read from
+/// a region file knows its slot: a chunk may not store `xPos` and `zPos` (e.g. pruned by `WorldLoadOptions::keep_only`).
+pub trait ChunkPosition: AsRef<nbt_tag::NbtTagCompound> {
+    /// Returns the coordinates of the chunk, by default read from its NBT data with `get_chunk_coordinates`.
+    fn chunk_coordinates(&self) -> blocks::Coordinates {
+        get_chunk_coordinates(self.as_ref())
+    }
+}
+
+impl ChunkPosition for nbt_tag::NbtTagCompound {}
+
+/// Determines if a chunk stores its own coordinates (`xPos` and `zPos`), either at the root or in the pre-1.18 `Level` compound.
+pub fn has_chunk_coordinates(chunk_compound: &nbt_tag::NbtTagCompound) -> bool {
+    let position_compound = get_chunk_position_compound(chunk_compound);
+    
+    position_compound.values.get("xPos").and_then(|tag| tag.int()).is_some() 
+        && position_compound.values.get("zPos").and_then(|tag| tag.int()).is_some()
+}
+
+/// Calculates the absolute coordinates of a chunk from the region coordinates and the chunk index in the region file.
+///
+/// https://minecraft.fandom.com/wiki/Region_file_format
+///
+/// A region contains 32x32 chunks, stored in the header in the order (x + z * 32).
+/// The absolute chunk coordinates are the region coordinates * 32 plus the local chunk coordinates.
+///
+/// # Arguments
+///
+/// * `region_coords` - The (x, z) coordinates of the region, as in the region file name `r.X.Z.mca`.
+/// * `chunk_index` - The index of the chunk in the region header (0..1024).
+///
+/// # Returns
+///
+/// blocks::Coordinates: A Coordinates struct representing the absolute x and z coordinates of the chunk (y is 0).
+pub fn get_chunk_coordinates_from_region(region_coords: (i32, i32), chunk_index: usize) -> blocks::Coordinates {
+    let (region_x, region_z) = region_coords;
+    let local_x = (chunk_index % 32) as i32;
+    let local_z = ((chunk_index / 32) % 32) as i32;
+
+    blocks::Coordinates::new(vec![region_x * 32 + local_x, 0, region_z * 32 + local_z])
+}
+
+/// Returns the compound holding the chunk position: the `Level` compound for chunks saved before 1.18, the chunk itself otherwise.
+fn get_chunk_position_compound(chunk_compound: &nbt_tag::NbtTagCompound) -> &nbt_tag::NbtTagCompound {
+    if chunk_compound.values.contains_key("xPos") {
+        return chunk_compound;
+    }
+
+    match chunk_compound.values.get("Level").and_then(|level_tag| level_tag.compound_as_ref()) {
+        Some(level_compound) => level_compound,
+        None => chunk_compound,
+    }
+}
+
+/// Retrieves the vertical position of a section (subchunk) in a Minecraft chunk.
+///
+/// The `Y` tag is the section index: the section covers the blocks from Y * 16 to Y * 16 + 15.
+/// It is stored as a Byte, but some tools save it as an Int, so both are accepted.
+///
+/// # Returns
+///
+/// Returns the section Y index, or `None` if the section has no valid `Y` tag.
+pub fn get_section_y(section_tag: &nbt_tag::NbtTag) -> Option<i32> {
+    let y_tag = section_tag.compound_as_ref()?.values.get("Y")?;
+
+    match y_tag {
+        nbt_tag::NbtTag::Byte(y) => Some(y.value as i32),
+        nbt_tag::NbtTag::Int(y) => Some(y.value),
+        _ => None,
+    }
+}
+
+/// A version-independent view of a chunk: the tags are read where the version of the chunk saved them,
+/// at the root since 1.18 or in the `Level` compound before (with the old names, see `LEVEL_TAG_RENAMES`).
+/// 
+/// The `DataVersion` and the `Level` compound are looked up once, when the accessor is created.
+#[derive(Clone, Copy, Debug)]
+pub struct ChunkAccessor<'a> {
+    chunk_compound: &'a nbt_tag::NbtTagCompound,
+    /// The `Level` compound of the chunks saved before 1.18.
+    level_compound: Option<&'a nbt_tag::NbtTagCompound>,
+    data_version: Option<i32>,
+}
+
+impl<'a> ChunkAccessor<'a> {
+    pub fn new(chunk_compound: &'a nbt_tag::NbtTagCompound) -> Self {
+        ChunkAccessor {
+            chunk_compound,
+            level_compound: chunk_compound.values.get("Level").and_then(|level_tag| level_tag.compound_as_ref()),
+            data_version: get_data_version(chunk_compound),
+        }
+    }
+
+    /// Returns the wrapped chunk.
+    pub fn compound(&self) -> &'a nbt_tag::NbtTagCompound {
+        self.chunk_compound
+    }
+
+    /// Returns the `DataVersion` of the chunk, `None` before 1.9.
+    pub fn data_version(&self) -> Option<i32> {
+        self.data_version
+    }
+
+    /// Returns true if the chunk has the `Level` compound of the chunks saved before 1.18.
+    pub fn has_level(&self) -> bool {
+        self.level_compound.is_some()
+    }
+
+    /// Returns the tag `name` (as named since 1.18) of the chunk, at the root or under its old name in `Level`.
+    pub fn get(&self, name: &str) -> Option<&'a nbt_tag::NbtTag> {
+        self.chunk_compound.values.get(name).or_else(|| {
+            let level_name = LEVEL_TAG_RENAMES.iter()
+                .find(|(new_name, _)| *new_name == name)
+                .map_or(name, |(_, level_name)| *level_name);
+            self.level_compound?.values.get(level_name)
+        })
+    }
+
+    pub fn x_pos(&self) -> Option<i32> {
+        self.get("xPos").and_then(|tag| i32::try_from(tag).ok())
+    }
+
+    /// Returns the index of the lowest section, saved since 1.18.
+    pub fn y_pos(&self) -> Option<i32> {
+        self.get("yPos").and_then(|tag| i32::try_from(tag).ok())
+    }
+
+    pub fn z_pos(&self) -> Option<i32> {
+        self.get("zPos").and_then(|tag| i32::try_from(tag).ok())
+    }
+
+    /// Returns the generation status of the chunk, see `chunk_status`.
+    pub fn status(&self) -> Option<&'a str> {
+        self.get("Status").and_then(|tag| <&str>::try_from(tag).ok())
+    }
+
+    /// Returns the sections (subchunks) list: `sections` since 1.18, `Level/Sections` before.
+    pub fn sections(&self) -> Option<&'a nbt_tag::NbtTagList> {
+        self.get("sections").and_then(|tag| tag.list_as_ref())
+    }
+
+    /// Returns the block entities (chests, furnaces, signs...): `block_entities` since 1.18, `Level/TileEntities` before.
+    pub fn block_entities(&self) -> Vec<&'a nbt_tag::NbtTagCompound> {
+        self.compounds_list("block_entities")
+    }
+
+    /// Returns the entities: `Level/Entities` before 1.17, the `Entities` list at the root of the chunks
+    /// of the `entities` region files since.
+    pub fn entities(&self) -> Vec<&'a nbt_tag::NbtTagCompound> {
+        self.compounds_list("Entities")
+    }
+
+    /// Returns the `Heightmaps` compound, see `get_heightmap`.
+    pub fn heightmaps(&self) -> Option<&'a nbt_tag::NbtTagCompound> {
+        self.get("Heightmaps").and_then(|tag| tag.compound_as_ref())
+    }
+
+    fn compounds_list(&self, name: &str) -> Vec<&'a nbt_tag::NbtTagCompound> {
+        match self.get(name).and_then(|tag| tag.list_as_ref()) {
+            Some(list) => list.iter().filter_map(|tag| tag.compound_as_ref()).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// A block state of a section palette: the block name and its properties (e.g. `facing` = `north`), borrowed from the chunk.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockState<'a> {
+    pub name: &'a str,
+    pub properties: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> BlockState<'a> {
+    /// Reads a palette entry (`Name` and `Properties`), `None` if it has no `Name`.
+    pub fn from_palette_entry(block_tag: &'a nbt_tag::NbtTag) -> Option<Self> {
+        let block_compound = block_tag.compound_as_ref()?;
+        let name = <&str>::try_from(block_compound.values.get("Name")?).ok()?;
+        let properties = match block_compound.values.get("Properties").and_then(|tag| tag.compound_as_ref()) {
+            Some(properties) => properties.iter()
+                .filter_map(|(key, value)| Some((key.as_str(), <&str>::try_from(value).ok()?)))
+                .collect(),
+            None => HashMap::new(),
+        };
+
+        Some(BlockState { name, properties })
+    }
+
+    pub fn property(&self, name: &str) -> Option<&'a str> {
+        self.properties.get(name).copied()
+    }
+}
+
+/// A section (16x16x16 blocks) of a chunk, borrowed from the chunk, see `sections`.
+#[derive(Clone, Copy, Debug)]
+pub struct Section<'a> {
+    section_compound: &'a nbt_tag::NbtTagCompound,
+    y: i32,
+    palette_list: Option<&'a nbt_tag::NbtTagList>,
+    data: Option<&'a [i64]>,
+    data_version: i32,
+}
+
+impl<'a> Section<'a> {
+    /// Reads a section of a chunk saved with `data_version`, `None` if it has no `Y`.
+    /// 
+    /// The blocks are read from `block_states` since 1.18, from the `Palette` and `BlockStates` of the section before (since 1.13).
+    pub fn new(section_tag: &'a nbt_tag::NbtTag, data_version: i32) -> Option<Self> {
+        let section_compound = section_tag.compound_as_ref()?;
+        let y = get_section_y(section_tag)?;
+
+        let (palette_list, data) = match find_block_states_in_section(section_tag) {
+            Some(block_states_tag) => find_palette_in_block_states(block_states_tag),
+            None => (
+                section_compound.values.get("Palette").and_then(|tag| tag.list_as_ref()),
+                section_compound.values.get("BlockStates").and_then(|tag| tag.long_array_as_ref()).map(|data| &data.values),
+            ),
+        };
+
+        Some(Section { section_compound, y, palette_list, data: data.map(|data| data.as_slice()), data_version })
+    }
+
+    /// Returns the section compound.
+    pub fn compound(&self) -> &'a nbt_tag::NbtTagCompound {
+        self.section_compound
+    }
+
+    /// Returns the section index: the section covers the blocks from y * 16 to y * 16 + 15.
+    pub fn y(&self) -> i32 {
+        self.y
+    }
+
+    pub fn palette_len(&self) -> usize {
+        self.palette_list.map_or(0, |palette_list| palette_list.len())
+    }
+
+    /// Returns the block states of the palette, indexed by `block_indices`. 
+    /// An entry without `Name` is kept as an empty name, so that the indices still match.
+    pub fn palette(&self) -> Vec<BlockState<'a>> {
+        match self.palette_list {
+            Some(palette_list) => palette_list.iter().map(|block_tag| BlockState::from_palette_entry(block_tag).unwrap_or_default()).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the palette index of each of the 4096 blocks, in YZX order (see `unpack_block_states`).
+    /// Empty if the section has no palette (e.g. a section with only light data).
+    pub fn block_indices(&self) -> Vec<u16> {
+        if self.palette_list.is_none() {
+            return Vec::new();
+        }
+
+        unpack_block_states(self.data.unwrap_or(&[]), self.palette_len(), self.data_version)
+    }
+}
+
+/// Returns the sections of a chunk (any version since 1.13) in the order they are saved, skipping the ones without `Y`.
+pub fn sections(chunk_compound: &nbt_tag::NbtTagCompound) -> Vec<Section<'_>> {
+    let chunk = ChunkAccessor::new(chunk_compound);
+    let data_version = chunk.data_version().unwrap_or(BLOCK_STATES_DATA_VERSION);
+
+    match chunk.sections() {
+        Some(sections_list) => sections_list.iter().filter_map(|section_tag| Section::new(section_tag, data_version)).collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Counts the blocks of a chunk inside the box between `min` and `max` (world coordinates, both included), by block name.
+/// The corners can be given in any order.
+/// 
+/// Only the sections intersecting the box are decoded, and in a section partially inside the box the blocks
+/// outside are not counted. A chunk without coordinates (`xPos` and `zPos`) counts nothing.
+pub fn count_blocks_in_box(chunk_compound: &nbt_tag::NbtTagCompound, min: &blocks::Coordinates, max: &blocks::Coordinates) -> HashMap<String, u64> {
+    let mut blocks_count = HashMap::new();
+    if !has_chunk_coordinates(chunk_compound) {
+        return blocks_count;
+    }
+
+    // the box range along one axis, relative to a chunk or section starting at `origin`, None if they do not intersect
+    let local_range = |from: i32, to: i32, origin: i32| {
+        let (from, to) = (from.min(to).max(origin), from.max(to).min(origin + 15));
+        (from <= to).then(|| ((from - origin) as usize, (to - origin) as usize))
+    };
+
+    let chunk_coords = get_chunk_coordinates(chunk_compound);
+    let (Some((min_x, max_x)), Some((min_z, max_z))) = (local_range(min.x, max.x, chunk_coords.x * 16), local_range(min.z, max.z, chunk_coords.z * 16)) else {
+        return blocks_count;
+    };
+
+    for section in sections(chunk_compound) {
+        let Some((min_y, max_y)) = local_range(min.y, max.y, section.y() * 16) else {
+            continue;
+        };
+        let palette = section.palette();
+        let palette_ids = section.block_indices();
+        if palette_ids.is_empty() {
+            continue;
+        }
+
+        let mut palette_count = vec![0u64; palette.len()];
+        for y in min_y..=max_y {
+            for z in min_z..=max_z {
+                for x in min_x..=max_x {
+                    if let Some(count) = palette_count.get_mut(palette_ids[y * 256 + z * 16 + x] as usize) {
+                        *count += 1;
+                    }
+                }
+            }
+        }
+
+        for (block_state, count) in palette.iter().zip(palette_count).filter(|(block_state, count)| *count > 0 && !block_state.name.is_empty()) {
+            *blocks_count.entry(block_state.name.to_string()).or_default() += count;
+        }
+    }
+
+    blocks_count
+}
+
+/// Returns the block entities (chests, furnaces, signs...) of a chunk: `block_entities` since 1.18, `Level/TileEntities` before.
+pub fn get_block_entities(chunk_compound: &nbt_tag::NbtTagCompound) -> Vec<&nbt_tag::NbtTagCompound> {
+    ChunkAccessor::new(chunk_compound).block_entities()
+}
+
+/// Returns the entities of a chunk. 
+/// 
+/// Since 1.17 the entities are saved in the region files of the `entities` folder, with an `Entities` list at the root,
+/// before they are in `Level/Entities` of the chunk.
+pub fn get_entities(chunk_compound: &nbt_tag::NbtTagCompound) -> Vec<&nbt_tag::NbtTagCompound> {
+    ChunkAccessor::new(chunk_compound).entities()
+}
+
+/// Returns the world coordinates of a block entity, from its `x`, `y` and `z` tags.
+pub fn get_block_entity_coordinates(block_entity: &nbt_tag::NbtTagCompound) -> Option<blocks::Coordinates> {
+    let coordinate = |name: &str| block_entity.values.get(name).and_then(|tag| i32::try_from(tag).ok());
+    Some(blocks::Coordinates::new(vec![coordinate("x")?, coordinate("y")?, coordinate("z")?]))
+}
+
+/// Returns the coordinates of the block an entity is in, from its `Pos` list of doubles.
+pub fn get_entity_coordinates(entity: &nbt_tag::NbtTagCompound) -> Option<blocks::Coordinates> {
+    let position = entity.values.get("Pos")?.list_as_ref()?;
+    let coordinates: Vec<i32> = position.values.iter()
+        .map(|tag| f64::try_from(tag).ok().map(|value| value.floor() as i32))
+        .collect::<Option<Vec<i32>>>()?;
+
+    if coordinates.len() != 3 {
+        return None;
+    }
+    Some(blocks::Coordinates::new(coordinates))
+}
+
+/// A generated structure (village, fortress, stronghold...) touching a chunk, see `structures`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructureRef {
+    /// The structure id, e.g. `minecraft:village_plains` (`Village` in the old versions).
+    pub id: String,
+    /// The (x, z) coordinates of the chunk holding the start of the structure.
+    pub start_chunk: (i32, i32),
+    /// The bounding box of the structure in blocks: min x, min y, min z, max x, max y, max z (inclusive).
+    /// Saved only in the start chunk, `None` for the structures referenced from the other chunks.
+    pub bounding_box: Option<[i32; 6]>,
+}
+
+/// Returns the structures touching a chunk, read from `structures` since 1.18, `Level/Structures` before:
+/// the structures starting in the chunk (`starts`, with their bounding box) and the ones started in another chunk
+/// and reaching this one (`References`). Sorted by id and start chunk.
+/// 
+/// The bounding box of a start is its `BB`, or the union of the `BB` of its pieces (`Children`) if missing.
+pub fn structures(chunk_compound: &nbt_tag::NbtTagCompound) -> Vec<StructureRef> {
+    let chunk = ChunkAccessor::new(chunk_compound);
+    let Some(structures_compound) = chunk.get("structures").and_then(|tag| tag.compound_as_ref()) else {
+        return Vec::new();
+    };
+    let chunk_position = chunk.x_pos().zip(chunk.z_pos());
+    let child_compound = |name: &str| structures_compound.values.get(name).and_then(|tag| tag.compound_as_ref());
+
+    let mut structure_refs = Vec::new();
+    if let Some(starts_compound) = child_compound("starts").or_else(|| child_compound("Starts")) {
+        for (key, start_tag) in starts_compound.iter() {
+            let Some(start_compound) = start_tag.compound_as_ref() else {
+                continue;
+            };
+            let id = start_compound.values.get("id").and_then(|tag| String::try_from(tag).ok()).unwrap_or_else(|| key.clone());
+            if id == INVALID_STRUCTURE_ID {
+                continue;
+            }
+            let start_int = |name: &str| start_compound.values.get(name).and_then(|tag| i32::try_from(tag).ok());
+            let Some(start_chunk) = start_int("ChunkX").zip(start_int("ChunkZ")).or(chunk_position) else {
+                continue;
+            };
+
+            structure_refs.push(StructureRef { id, start_chunk, bounding_box: structure_start_bounding_box(start_compound) });
+        }
+    }
+
+    if let Some(references_compound) = child_compound("References") {
+        for (id, references_tag) in references_compound.iter() {
+            let Ok(references) = references_tag.try_long_array() else {
+                continue;
+            };
+            for packed_position in references.values.iter() {
+                // x in the low 32 bits, z in the high 32 bits
+                let start_chunk = (*packed_position as i32, (*packed_position >> 32) as i32);
+                let already_started = structure_refs.iter().any(|structure_ref| structure_ref.id == *id && structure_ref.start_chunk == start_chunk);
+                if !already_started {
+                    structure_refs.push(StructureRef { id: id.clone(), start_chunk, bounding_box: None });
+                }
+            }
+        }
+    }
+
+    structure_refs.sort_by(|a, b| (&a.id, a.start_chunk).cmp(&(&b.id, b.start_chunk)));
+    structure_refs
+}
+
+/// Returns the `BB` of a structure start, or the union of the `BB` of its `Children`.
+fn structure_start_bounding_box(start_compound: &nbt_tag::NbtTagCompound) -> Option<[i32; 6]> {
+    let bounding_box = |compound: &nbt_tag::NbtTagCompound| -> Option<[i32; 6]> {
+        let values = &compound.values.get("BB")?.try_int_array().ok()?.values;
+        values.as_slice().try_into().ok()
+    };
+    if let Some(start_bounding_box) = bounding_box(start_compound) {
+        return Some(start_bounding_box);
+    }
+
+    let children_list = start_compound.values.get("Children")?.list_as_ref()?;
+    children_list.iter()
+        .filter_map(|child_tag| child_tag.compound_as_ref().and_then(bounding_box))
+        .reduce(|union, child| [
+            union[0].min(child[0]), union[1].min(child[1]), union[2].min(child[2]),
+            union[3].max(child[3]), union[4].max(child[4]), union[5].max(child[5]),
+        ])
+}
+
+/// Returns the sections list of a chunk: `sections` since 1.18, `Level/Sections` before.
+pub fn get_sections_list(chunk_compound: &nbt_tag::NbtTagCompound) -> Option<&nbt_tag::NbtTagList> {
+    ChunkAccessor::new(chunk_compound).sections()
+}
+
+/// Returns the section of the chunk covering the blocks at height y.
+pub fn find_section_at_height(chunk_compound: &nbt_tag::NbtTagCompound, y: i32) -> Option<&nbt_tag::NbtTag> {
+    let section_y = y.div_euclid(16);
+    get_sections_list(chunk_compound)?.values.iter()
+        .find(|section| get_section_y(section) == Some(section_y))
+}
+
+/// Retrieves the block light and the sky light levels (0-15) of a block in a chunk.
+///
+/// The sections store the light as `BlockLight` and `SkyLight` arrays of 2048 bytes, 
+/// one nibble per block in YZX order, the low nibble being the even block.
+///
+/// # Arguments
+///
+/// * `chunk_compound` - &nbt_tag::NbtTagCompound: The NBT data of the chunk.
+/// * `x`, `y`, `z` - i32: The world coordinates of the block. Only x and z modulo 16 are used,
+///   so coordinates local to the chunk are accepted as well.
+///
+/// # Returns
+///
+/// Returns the (block light, sky light) levels, or `None` if the section at height y does not exist or has no light array.
+/// If only one of the arrays is present (e.g. the sky light in the Nether), the other level is 0.
+pub fn light_at(chunk_compound: &nbt_tag::NbtTagCompound, x: i32, y: i32, z: i32) -> Option<(u8, u8)> {
+    let section_compound = find_section_at_height(chunk_compound, y)?.compound_as_ref()?;
+
+    let block_index = (y.rem_euclid(16) * 256 + z.rem_euclid(16) * 16 + x.rem_euclid(16)) as usize;
+    let light_level = |light_name: &str| -> Option<u8> {
+        let light_array = &section_compound.values.get(light_name)?.try_byte_array().ok()?.values;
+        let light_byte = *light_array.get(block_index / 2)? as u8;
+        Some((light_byte >> ((block_index % 2) * 4)) & 0x0F)
+    };
+
+    match (light_level("BlockLight"), light_level("SkyLight")) {
+        (None, None) => None,
+        (block_light, sky_light) => Some((block_light.unwrap_or(0), sky_light.unwrap_or(0))),
+    }
+}
+
 /// Finds and returns the block states in a given section of a Minecraft chunk.
 ///
 /// Examines a provided NBT tag to locate the "block_states" compound, which represents the state of each block in a Minecraft chunk section.
@@ -553,3 +1277,395 @@ pub fn find_block_name_in_palette(blocks_tag: &nbt_tag::NbtTag, block_resouce_lo
 
     block_name_found
 }
+
+/// Determines if a block tag in a Minecraft palette has all the requested block-state properties.
+///
+/// Compares the `Properties` compound of a palette entry with a list of (name, value) constraints.
+/// Properties that are not listed are treated as wildcards, so an empty list always matches.
+///
+/// # Arguments
+///
+/// * `blocks_tag` - A reference to the NbtTag, representing a block in the Minecraft palette.
+/// * `block_properties` - A slice of (property name, property value) pairs that must all match.
+///
+/// # Returns
+///
+/// Returns `true` if every requested property is present with the requested value, `false` otherwise.
+pub fn find_block_properties_in_palette(blocks_tag: &nbt_tag::NbtTag, block_properties: &[(String, String)]) -> bool {
+    
+    if block_properties.is_empty() {
+        return true;
+    }
+
+    let palette_properties = get_block_properties(blocks_tag);
+
+    block_properties.iter().all(|(key, value)| palette_properties.get(key) == Some(value))
+}
+
+/// Retrieves the generation status of a chunk from its NBT tag compound.
+///
+/// Chunks are saved while they are still being generated (proto-chunks), the `Status` tag tells how far
+/// the generation went, e.g. `minecraft:full` for a fully generated chunk or `minecraft:empty` for a chunk
+/// that was only reserved. Chunks saved before 1.18 store the status in `Level/Status` and without namespace (`full`).
+///
+/// # Arguments
+///
+/// * `chunk_compound` - A reference to the NbtTagCompound representing the NBT data of a single Minecraft chunk.
+///
+/// # Returns
+///
+/// Returns the status string as stored in the chunk, or `None` if the chunk has no status tag.
+pub fn chunk_status(chunk_compound: &nbt_tag::NbtTagCompound) -> Option<String> {
+    ChunkAccessor::new(chunk_compound).status().map(str::to_string)
+}
+
+/// Determines if a chunk is fully generated.
+///
+/// A chunk is fully generated if its status is `minecraft:full` (or `full` for chunks saved before 1.18).
+/// Compounds without a status tag (e.g. chunks older than 1.13 or generic NBT files) are considered fully generated,
+/// since there is no information telling otherwise.
+///
+/// # Arguments
+///
+/// * `chunk_compound` - A reference to the NbtTagCompound representing the NBT data of a single Minecraft chunk.
+///
+/// # Returns
+///
+/// Returns `true` if the chunk is fully generated or has no status, `false` for proto-chunks.
+pub fn is_chunk_fully_generated(chunk_compound: &nbt_tag::NbtTagCompound) -> bool {
+    match chunk_status(chunk_compound) {
+        Some(status) => status == "minecraft:full" || status == "full",
+        None => true,
+    }
+}
+
+/// Collects the names of the block-state properties used by a block in the given chunks.
+///
+/// There is no block registry in the library, so the properties supported by a block are learnt from the
+/// palettes where the block appears. 
+///
+/// # Arguments
+///
+/// * `block_resource_location` - The resource location of the block, e.g. `minecraft:oak_log`.
+/// * `tag_compounds_list` - A slice of NbtTagCompound, representing the NBT data of chunks.
+///
+/// # Returns
+///
+/// Returns `Some` set of property names if the block appears in at least one palette, `None` if it was never found.
+pub fn collect_block_property_names<C: AsRef<nbt_tag::NbtTagCompound>>(block_resource_location: &str, tag_compounds_list: &[C]) -> Option<HashSet<String>> {
+    let mut property_names: Option<HashSet<String>> = None;
+
+    for tag_compound in tag_compounds_list.iter().map(AsRef::as_ref) {
+        let Some(sections_list) = tag_compound.values.get("sections").and_then(|tag| tag.list_as_ref()) else {
+            continue;
+        };
+
+        for section in sections_list.values.iter() {
+            let Some(block_states_tag) = find_block_states_in_section(section) else {
+                continue;
+            };
+            let (Some(palette_list), _) = find_palette_in_block_states(block_states_tag) else {
+                continue;
+            };
+
+            for block_tag in palette_list.values.iter() {
+                if find_block_name_in_palette(block_tag, block_resource_location) {
+                    property_names.get_or_insert_with(HashSet::new).extend(get_block_properties(block_tag).into_keys());
+                }
+            }
+        }
+    }
+
+    property_names
+}
+
+/// Collects the names of all the blocks appearing in the section palettes of the given chunks.
+///
+/// A block in a palette is not necessarily placed in the section (palettes may keep unused entries), 
+/// but a block missing from every palette is surely absent from the chunks.
+///
+/// # Arguments
+///
+/// * `tag_compounds_list` - A slice of NbtTagCompound, representing the NBT data of chunks.
+///
+/// # Returns
+///
+/// Returns the set of the block resource locations found in the palettes.
+pub fn collect_palette_block_names<C: AsRef<nbt_tag::NbtTagCompound>>(tag_compounds_list: &[C]) -> HashSet<String> {
+    let mut block_names = HashSet::new();
+
+    for tag_compound in tag_compounds_list.iter().map(AsRef::as_ref) {
+        let Some(sections_list) = tag_compound.values.get("sections").and_then(|tag| tag.list_as_ref()) else {
+            continue;
+        };
+
+        for section in sections_list.values.iter() {
+            let Some(block_states_tag) = find_block_states_in_section(section) else {
+                continue;
+            };
+            let (Some(palette_list), _) = find_palette_in_block_states(block_states_tag) else {
+                continue;
+            };
+
+            for block_tag in palette_list.values.iter() {
+                if let Some(block_name) = block_tag.compound_as_ref().and_then(|block| block.values.get("Name")).and_then(|name| name.string()) {
+                    block_names.insert(block_name.value);
+                }
+            }
+        }
+    }
+
+    block_names
+}
+
+/// Replaces every occurrence of a block with another block in a chunk.
+///
+/// The blocks are not stored one by one, but as indexes in the palette of each section, so replacing a block
+/// only requires renaming the matching palette entries: the data array is left untouched.
+/// The block-state properties are preserved, except the ones not supported by the target block.
+///
+/// # Arguments
+///
+/// * `chunk_compound` - A mutable reference to the NbtTagCompound of the chunk to edit.
+/// * `from` - The resource location of the block to replace.
+/// * `to` - The resource location of the new block.
+/// * `target_property_names` - The properties supported by the target block (see `collect_block_property_names`).
+///   If `None` the properties are kept as they are, since there is no way to know which ones the target supports.
+///
+/// # Returns
+///
+/// Returns the number of blocks replaced in the chunk.
+///
+/// # Details
+///
+/// After the replacement the palette may contain the same block state twice (if the target block was already
+/// in the section): this is still a valid palette, each entry is referenced by its own index.
+pub fn replace_block(chunk_compound: &mut nbt_tag::NbtTagCompound, from: &str, to: &str, target_property_names: Option<&HashSet<String>>) -> usize {
+    let mut replaced_blocks = 0;
+
+    let Some(sections_list) = chunk_compound.values.get_mut("sections").and_then(|tag| tag.list_as_mut()) else {
+        return 0;
+    };
+
+    for section in sections_list.values.iter_mut() {
+        let Some(block_states_compound) = section.compound_as_mut()
+                                                .and_then(|section_compound| section_compound.values.get_mut("block_states"))
+                                                .and_then(|block_states_tag| block_states_tag.compound_as_mut()) else {
+            continue;
+        };
+
+        /* #10: rename the palette entries of the replaced block, keeping the supported properties */
+        let mut replaced_palette_ids = HashSet::<u32>::new();
+        let mut palette_len = 0;
+
+        if let Some(palette_list) = block_states_compound.values.get_mut("palette").and_then(|tag| tag.list_as_mut()) {
+            palette_len = palette_list.values.len();
+
+            for (palette_id, block_tag) in palette_list.values.iter_mut().enumerate() {
+                if !find_block_name_in_palette(block_tag, from) {
+                    continue;
+                }
+
+                if let Some(block_compound) = block_tag.compound_as_mut() {
+                    block_compound.values.insert("Name".to_string(), 
+                                                 nbt_tag::NbtTag::String(nbt_tag::NbtTagString::new("Name".to_string(), to.to_string())));
+                    
+                    if let Some(property_names) = target_property_names {
+                        if let Some(properties_compound) = block_compound.values.get_mut("Properties").and_then(|tag| tag.compound_as_mut()) {
+                            properties_compound.values.retain(|key, _| property_names.contains(key));
+                        }
+                        if block_compound.values.get("Properties").and_then(|tag| tag.compound_as_ref()).is_some_and(|properties| properties.values.is_empty()) {
+                            block_compound.values.remove("Properties");
+                        }
+                    }
+                    
+                    replaced_palette_ids.insert(palette_id as u32);
+                }
+            }
+        }
+
+        if replaced_palette_ids.is_empty() {
+            continue;
+        }
+
+        /* #20: count the replaced blocks. A section with a single block in the palette has no data array: 
+        * the whole section (16x16x16) is made of that block
+        */
+        let blocks_data_array = block_states_compound.values.get("data")
+                                    .and_then(|tag| tag.long_array_as_ref())
+                                    .map(|data| data.values.as_slice())
+                                    .unwrap_or(&[]);
+        replaced_blocks += unpack_block_states(blocks_data_array, palette_len, BLOCK_STATES_DATA_VERSION).iter()
+                                .filter(|palette_id| replaced_palette_ids.contains(&(**palette_id as u32)))
+                                .count();
+    }
+
+    replaced_blocks
+}
+
+/// Sets the block at the given coordinates of a chunk (1.18 and later, with `block_states` in the sections)
+/// to the default state of `block_resource_location`.
+///
+/// Only x and z modulo 16 are used, so coordinates local to the chunk are accepted as well.
+/// The block is added to the section palette if needed; the entries no longer used are left in the palette,
+/// which is still valid.
+///
+/// # Returns
+///
+/// Returns false if the chunk has no section at height y (the section is not created).
+pub fn set_block(chunk_compound: &mut nbt_tag::NbtTagCompound, x: i32, y: i32, z: i32, block_resource_location: &str) -> bool {
+    let data_version = get_data_version(chunk_compound).unwrap_or(BLOCK_STATES_DATA_VERSION);
+    let section_y = y.div_euclid(16);
+
+    let Some(block_states_compound) = chunk_compound.values.get_mut("sections")
+                                        .and_then(|tag| tag.list_as_mut())
+                                        .and_then(|sections_list| sections_list.values.iter_mut().find(|section| get_section_y(section) == Some(section_y)))
+                                        .and_then(|section| section.compound_as_mut())
+                                        .and_then(|section_compound| section_compound.values.get_mut("block_states"))
+                                        .and_then(|block_states_tag| block_states_tag.compound_as_mut()) else {
+        return false;
+    };
+
+    /* #10: find the block in the palette, without properties, or append it */
+    let Some(palette_list) = block_states_compound.values.get_mut("palette").and_then(|tag| tag.list_as_mut()) else {
+        return false;
+    };
+    let old_palette_len = palette_list.values.len();
+    let palette_id = match palette_list.values.iter().position(|block_tag| {
+        find_block_name_in_palette(block_tag, block_resource_location)
+            && block_tag.compound_as_ref().is_some_and(|block_compound| !block_compound.values.contains_key("Properties"))
+    }) {
+        Some(palette_id) => palette_id,
+        None => {
+            let mut block_compound = nbt_tag::NbtTagCompound::new("");
+            block_compound.values.insert("Name".to_string(), 
+                                         nbt_tag::NbtTag::String(nbt_tag::NbtTagString::new("Name".to_string(), block_resource_location.to_string())));
+            palette_list.values.push(nbt_tag::NbtTag::Compound(block_compound));
+            palette_list.values.len() - 1
+        },
+    };
+    let palette_len = palette_list.values.len();
+
+    /* #20: change the index of the block, the data array grows with the palette */
+    let blocks_data_array = block_states_compound.values.get("data")
+                                .and_then(|tag| tag.long_array_as_ref())
+                                .map(|data| data.values.as_slice())
+                                .unwrap_or(&[]);
+    let mut palette_ids = unpack_block_states(blocks_data_array, old_palette_len, data_version);
+    let block_index = (y.rem_euclid(16) * 256 + z.rem_euclid(16) * 16 + x.rem_euclid(16)) as usize;
+    palette_ids[block_index] = palette_id as u16;
+
+    let data = pack_block_states(&palette_ids, palette_len, data_version);
+    if data.is_empty() {
+        block_states_compound.values.remove("data");
+    }
+    else {
+        block_states_compound.values.insert("data".to_string(), nbt_tag::NbtTag::LongArray(nbt_tag::NbtTagLongArray::new("data".to_string(), data)));
+    }
+
+    true
+}
+
+/// Returns the `DataVersion` of a chunk, saved since 1.9 (15w32a).
+pub fn get_data_version(chunk_compound: &nbt_tag::NbtTagCompound) -> Option<i32> {
+    chunk_compound.values.get("DataVersion").and_then(|tag| i32::try_from(tag).ok())
+}
+
+/// Decodes a packed heightmap long array into the height of each of the 256 columns of a chunk.
+///
+/// https://minecraft.wiki/w/Chunk_format#Heightmaps
+///
+/// Each height is `HEIGHTMAP_ENTRY_SIZE_IN_BIT` bits long, in ZX order (X increases each column, Z each 16 columns).
+/// The heights are counted from the bottom of the world (y = -64 since 1.18), and are the Y of the highest
+/// matching block + 1, so 0 means no block in the column.
+/// The padding layout follows the block states, selected by `data_version`.
+pub fn decode_heightmap(long_array: &[i64], data_version: i32) -> Vec<u16> {
+    unpack_long_array(long_array, HEIGHTMAP_ENTRY_SIZE_IN_BIT, CHUNK_COLUMNS_NUM, data_version >= BLOCK_STATES_PADDING_DATA_VERSION)
+}
+
+/// Returns the decoded heightmap `map_name` (e.g. `WORLD_SURFACE`, `OCEAN_FLOOR`, `MOTION_BLOCKING`) of a chunk,
+/// read from `Heightmaps` (or `Level/Heightmaps` for the chunks saved before 1.18). See `decode_heightmap`.
+///
+/// Returns `None` if the chunk has no such heightmap (e.g. a chunk not fully generated).
+pub fn get_heightmap(chunk_compound: &nbt_tag::NbtTagCompound, map_name: &str) -> Option<Vec<u16>> {
+    let chunk = ChunkAccessor::new(chunk_compound);
+    let long_array = Vec::<i64>::try_from(chunk.heightmaps()?.values.get(map_name)?).ok()?;
+    let data_version = chunk.data_version().unwrap_or(BLOCK_STATES_DATA_VERSION);
+
+    Some(decode_heightmap(&long_array, data_version))
+}
+
+/// A grayscale top-down map of the chunks, one pixel per block column, see `heightmap_grayscale`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HeightmapGrayscale {
+    /// The world X coordinate of the leftmost column of pixels.
+    pub min_block_x: i32,
+    /// The world Z coordinate of the top row of pixels.
+    pub min_block_z: i32,
+    pub width: u32,
+    pub height: u32,
+    /// The pixels row by row (Z), left to right (X).
+    pub pixels: Vec<u8>,
+}
+
+/// Renders the heightmap `map_name` of the given chunks into a grayscale terrain map.
+///
+/// The chunks are placed by their world coordinates, on an image covering all of them. The heights are scaled
+/// between the lowest (black) and the highest (white) column found; the areas not covered by a chunk with the heightmap are black.
+///
+/// Returns `None` if no chunk has the heightmap, or if the image would have more than `MAX_MAP_PIXELS` pixels
+/// (the chunks are far apart): render the chunks of a smaller area.
+pub fn heightmap_grayscale<C: ChunkPosition>(tag_compounds_list: &[C], map_name: &str) -> Option<HeightmapGrayscale> {
+    let heightmaps: Vec<(blocks::Coordinates, Vec<u16>)> = tag_compounds_list.iter()
+        .filter_map(|chunk| Some((chunk.chunk_coordinates(), get_heightmap(chunk.as_ref(), map_name)?)))
+        .collect();
+
+    let min_chunk_x = heightmaps.iter().map(|(chunk_pos, _)| chunk_pos.x).min()?;
+    let max_chunk_x = heightmaps.iter().map(|(chunk_pos, _)| chunk_pos.x).max()?;
+    let min_chunk_z = heightmaps.iter().map(|(chunk_pos, _)| chunk_pos.z).min()?;
+    let max_chunk_z = heightmaps.iter().map(|(chunk_pos, _)| chunk_pos.z).max()?;
+    let min_height = heightmaps.iter().flat_map(|(_, heights)| heights.iter()).min().copied()?;
+    let max_height = heightmaps.iter().flat_map(|(_, heights)| heights.iter()).max().copied()?;
+
+    let (width, height) = map_size((min_chunk_x, min_chunk_z), (max_chunk_x, max_chunk_z))?;
+    let mut pixels = vec![0u8; width * height];
+
+    let height_range = (max_height - min_height).max(1) as u32;
+    for (chunk_pos, heights) in heightmaps.iter() {
+        let origin_x = ((chunk_pos.x - min_chunk_x) * 16) as usize;
+        let origin_z = ((chunk_pos.z - min_chunk_z) * 16) as usize;
+
+        for (column_index, column_height) in heights.iter().enumerate() {
+            let pixel_index = (origin_z + column_index / 16) * width + origin_x + column_index % 16;
+            pixels[pixel_index] = ((*column_height - min_height) as u32 * 255 / height_range) as u8;
+        }
+    }
+
+    Some(HeightmapGrayscale {
+        min_block_x: min_chunk_x * 16,
+        min_block_z: min_chunk_z * 16,
+        width: width as u32,
+        height: height as u32,
+        pixels,
+    })
+}
+
+/// Returns the (width, height) in blocks of a map covering the chunks from `min_chunk` to `max_chunk` (x, z),
+/// None if it has more than `MAX_MAP_PIXELS` pixels.
+pub(crate) fn map_size(min_chunk: (i32, i32), max_chunk: (i32, i32)) -> Option<(usize, usize)> {
+    let width = (max_chunk.0 as i64 - min_chunk.0 as i64 + 1) * 16;
+    let height = (max_chunk.1 as i64 - min_chunk.1 as i64 + 1) * 16;
+    if width * height > MAX_MAP_PIXELS as i64 {
+        log::warn!("The map of the chunks from {:?} to {:?} is {}x{} blocks, more than MAX_MAP_PIXELS", min_chunk, max_chunk, width, height);
+        return None;
+    }
+
+    Some((width as usize, height as usize))
+}
+
+/// Same as `heightmap_grayscale`, as an `image::GrayImage` ready to be saved (e.g. as PNG). Requires the "image" feature.
+#[cfg(feature = "image")]
+pub fn heightmap_image<C: ChunkPosition>(tag_compounds_list: &[C], map_name: &str) -> Option<image::GrayImage> {
+    let grayscale = heightmap_grayscale(tag_compounds_list, map_name)?;
+    image::GrayImage::from_raw(grayscale.width, grayscale.height, grayscale.pixels)
+}
@@ -0,0 +1,500 @@
+#[cfg(test)]
+
+use super::*;
+
+/// Packs the indices in the padded (20w17a+) or compact (before) layout, one bit at a time.
+fn pack_reference(palette_ids: &[u16], index_size_in_bit: usize, padded: bool) -> Vec<i64> {
+    let indexes_in_data_element = 64 / index_size_in_bit;
+    let mut long_array = Vec::new();
+
+    for (block_index, palette_id) in palette_ids.iter().enumerate() {
+        let bit_offset = if padded {
+            (block_index / indexes_in_data_element) * 64 + (block_index % indexes_in_data_element) * index_size_in_bit
+        } else {
+            block_index * index_size_in_bit
+        };
+
+        for bit in 0..index_size_in_bit {
+            let long_index = (bit_offset + bit) / 64;
+            if long_array.len() <= long_index {
+                long_array.resize(long_index + 1, 0u64);
+            }
+            long_array[long_index] |= ((*palette_id as u64 >> bit) & 1) << ((bit_offset + bit) % 64);
+        }
+    }
+
+    long_array.into_iter().map(|data_element| data_element as i64).collect()
+}
+
+fn sample_palette_ids(palette_len: usize) -> Vec<u16> {
+    (0..SECTION_BLOCKS_NUM).map(|block_index| ((block_index * 7 + block_index / 13) % palette_len) as u16).collect()
+}
+
+#[test]
+fn test_unpack_block_states_4_bits() {
+    // 4 bits divide 64, both layouts are the same
+    let palette_ids = sample_palette_ids(16);
+    let long_array = pack_reference(&palette_ids, 4, true);
+    assert_eq!(long_array.len(), 256);
+
+    assert_eq!(unpack_block_states(&long_array, 16, BLOCK_STATES_DATA_VERSION), palette_ids);
+    assert_eq!(unpack_block_states(&long_array, 16, 1976), palette_ids);
+}
+
+#[test]
+fn test_unpack_block_states_9_bits_padded() {
+    // 7 indices per long, the highest bit of each long is padding: ceil(4096 / 7) = 586 longs
+    let palette_ids = sample_palette_ids(300);
+    let long_array = pack_reference(&palette_ids, 9, true);
+    assert_eq!(long_array.len(), 586);
+
+    let unpacked = unpack_block_states(&long_array, 300, BLOCK_STATES_PADDING_DATA_VERSION);
+    assert_eq!(unpacked.len(), SECTION_BLOCKS_NUM);
+    assert_eq!(unpacked, palette_ids);
+}
+
+#[test]
+fn test_unpack_block_states_9_bits_compact() {
+    // before 20w17a the indices span across longs: 4096 * 9 / 64 = 576 longs
+    let palette_ids = sample_palette_ids(300);
+    let long_array = pack_reference(&palette_ids, 9, false);
+    assert_eq!(long_array.len(), 576);
+
+    assert_eq!(unpack_block_states(&long_array, 300, BLOCK_STATES_PADDING_DATA_VERSION - 1), palette_ids);
+}
+
+#[test]
+fn test_unpack_block_states_single_palette() {
+    // a section with a single block has no data array
+    assert_eq!(unpack_block_states(&[], 1, BLOCK_STATES_DATA_VERSION), vec![0; SECTION_BLOCKS_NUM]);
+    // a short data array is completed with the first palette entry
+    let unpacked = unpack_block_states(&[0x21], 2, BLOCK_STATES_DATA_VERSION);
+    assert_eq!(unpacked.len(), SECTION_BLOCKS_NUM);
+    assert_eq!(unpacked[..3], [1, 2, 0]);
+}
+
+#[test]
+fn test_pack_block_states_layouts() {
+    for (palette_len, index_size_in_bit) in [(2, 4), (16, 4), (17, 5), (300, 9), (4096, 12)] {
+        let palette_ids = sample_palette_ids(palette_len);
+        assert_eq!(pack_block_states(&palette_ids, palette_len, BLOCK_STATES_DATA_VERSION), pack_reference(&palette_ids, index_size_in_bit, true));
+        assert_eq!(pack_block_states(&palette_ids, palette_len, 1976), pack_reference(&palette_ids, index_size_in_bit, false));
+    }
+}
+
+#[test]
+fn test_pack_unpack_roundtrip() {
+    for data_version in [1976, BLOCK_STATES_PADDING_DATA_VERSION, BLOCK_STATES_DATA_VERSION] {
+        for palette_len in [2, 5, 33, 300, 4096] {
+            let palette_ids = sample_palette_ids(palette_len);
+            let long_array = pack_block_states(&palette_ids, palette_len, data_version);
+            assert_eq!(unpack_block_states(&long_array, palette_len, data_version), palette_ids);
+            assert_eq!(pack_block_states(&unpack_block_states(&long_array, palette_len, data_version), palette_len, data_version), long_array);
+        }
+    }
+}
+
+#[test]
+fn test_pack_block_states_single_palette() {
+    assert!(pack_block_states(&[0; SECTION_BLOCKS_NUM], 1, BLOCK_STATES_DATA_VERSION).is_empty());
+}
+
+fn light_test_chunk() -> nbt_tag::NbtTagCompound {
+    // section Y = -1 with both light arrays, section Y = 0 with only the block light
+    let mut block_light = vec![0i8; 2048];
+    block_light[0] = 0x2F;                                  // block (0, 0, 0): 15, block (1, 0, 0): 2
+    block_light[(15 * 256 + 15 * 16 + 15) / 2] = 0x70u8 as i8; // block (15, 15, 15): 7
+    let sky_light = vec![0x5Au8 as i8; 2048];
+
+    let mut lit_section = nbt_tag::NbtTagCompound::new("");
+    lit_section.values.insert("Y".to_string(), nbt_tag::NbtTag::Byte(nbt_tag::NbtTagByte::new("Y".to_string(), -1)));
+    lit_section.values.insert("BlockLight".to_string(), nbt_tag::NbtTag::ByteArray(nbt_tag::NbtTagByteArray::new("BlockLight".to_string(), block_light.clone())));
+    lit_section.values.insert("SkyLight".to_string(), nbt_tag::NbtTag::ByteArray(nbt_tag::NbtTagByteArray::new("SkyLight".to_string(), sky_light)));
+
+    let mut block_lit_section = nbt_tag::NbtTagCompound::new("");
+    block_lit_section.values.insert("Y".to_string(), nbt_tag::NbtTag::Byte(nbt_tag::NbtTagByte::new("Y".to_string(), 0)));
+    block_lit_section.values.insert("BlockLight".to_string(), nbt_tag::NbtTag::ByteArray(nbt_tag::NbtTagByteArray::new("BlockLight".to_string(), block_light)));
+
+    let mut dark_section = nbt_tag::NbtTagCompound::new("");
+    dark_section.values.insert("Y".to_string(), nbt_tag::NbtTag::Byte(nbt_tag::NbtTagByte::new("Y".to_string(), 1)));
+
+    let sections = vec![nbt_tag::NbtTag::Compound(lit_section), nbt_tag::NbtTag::Compound(block_lit_section), nbt_tag::NbtTag::Compound(dark_section)];
+    let mut chunk = nbt_tag::NbtTagCompound::new("");
+    chunk.values.insert("sections".to_string(), nbt_tag::NbtTag::List(nbt_tag::NbtTagList::new("sections".to_string(), nbt_tag::NbtTagType::Compound, sections)));
+    chunk
+}
+
+#[test]
+fn test_light_at() {
+    let chunk = light_test_chunk();
+
+    // section Y = -1 covers the heights -16..-1, sky light is 0xA for even blocks and 0x5 for odd ones
+    assert_eq!(light_at(&chunk, 0, -16, 0), Some((15, 0xA)));
+    assert_eq!(light_at(&chunk, 1, -16, 0), Some((2, 0x5)));
+    assert_eq!(light_at(&chunk, 15, -1, 15), Some((7, 0x5)));
+    // world coordinates are reduced to the chunk
+    assert_eq!(light_at(&chunk, -31, -16, 32), Some((2, 0x5)));
+}
+
+#[test]
+fn test_light_at_missing_light() {
+    let chunk = light_test_chunk();
+
+    // only the block light, the sky light is 0
+    assert_eq!(light_at(&chunk, 0, 0, 0), Some((15, 0)));
+    // section without light arrays
+    assert_eq!(light_at(&chunk, 0, 16, 0), None);
+    // section not present
+    assert_eq!(light_at(&chunk, 0, 100, 0), None);
+}
+
+/// A chunk at (chunk_x, chunk_z) whose WORLD_SURFACE height is `base + x`, saved with the 1.18 layout.
+fn heightmap_chunk(chunk_x: i32, chunk_z: i32, base: u16) -> nbt_tag::NbtTagCompound {
+    let heights: Vec<u16> = (0..CHUNK_COLUMNS_NUM).map(|column_index| base + (column_index % 16) as u16).collect();
+    let mut heightmaps = nbt_tag::NbtTagCompound::new("Heightmaps");
+    heightmaps.values.insert("WORLD_SURFACE".to_string(), 
+        nbt_tag::NbtTag::LongArray(nbt_tag::NbtTagLongArray::new("WORLD_SURFACE".to_string(), pack_reference(&heights, 9, true))));
+
+    let mut chunk = nbt_tag::NbtTagCompound::new("");
+    for (name, value) in [("xPos", chunk_x), ("zPos", chunk_z), ("DataVersion", 3465)] {
+        chunk.values.insert(name.to_string(), nbt_tag::NbtTag::Int(nbt_tag::NbtTagInt::new(name.to_string(), value)));
+    }
+    chunk.values.insert("Heightmaps".to_string(), nbt_tag::NbtTag::Compound(heightmaps));
+    chunk
+}
+
+#[test]
+fn test_decode_heightmap_layouts() {
+    let heights: Vec<u16> = (0..CHUNK_COLUMNS_NUM).map(|column_index| (column_index * 37 % 385) as u16).collect();
+
+    // 7 heights per long with padding (37 longs), back to back before 20w17a (36 longs)
+    let padded = pack_reference(&heights, 9, true);
+    let compact = pack_reference(&heights, 9, false);
+    assert_eq!(padded.len(), 37);
+    assert_eq!(compact.len(), 36);
+
+    assert_eq!(decode_heightmap(&padded, BLOCK_STATES_DATA_VERSION), heights);
+    assert_eq!(decode_heightmap(&compact, BLOCK_STATES_PADDING_DATA_VERSION - 1), heights);
+}
+
+#[test]
+fn test_get_heightmap() {
+    let chunk = heightmap_chunk(0, 0, 70);
+    let heights = get_heightmap(&chunk, "WORLD_SURFACE").unwrap();
+    assert_eq!(heights[0], 70);
+    assert_eq!(heights[16 + 5], 75);
+    assert!(get_heightmap(&chunk, "OCEAN_FLOOR").is_none());
+}
+
+#[test]
+fn test_heightmap_grayscale_stitching() {
+    // two chunks side by side on Z, with a gap of one chunk on X
+    let chunks = vec![heightmap_chunk(-1, 2, 100), heightmap_chunk(1, 3, 115)];
+    let grayscale = heightmap_grayscale(&chunks, "WORLD_SURFACE").unwrap();
+
+    assert_eq!((grayscale.min_block_x, grayscale.min_block_z), (-16, 32));
+    assert_eq!((grayscale.width, grayscale.height), (48, 32));
+    assert_eq!(grayscale.pixels.len(), 48 * 32);
+
+    let pixel = |x: usize, z: usize| grayscale.pixels[z * 48 + x];
+    // heights from 100 to 130, the lowest is black and the highest white
+    assert_eq!(pixel(0, 0), 0);
+    assert_eq!(pixel(47, 16), 255);
+    assert_eq!(pixel(32, 16), (15 * 255 / 30) as u8);
+    // the chunks not loaded are black
+    assert_eq!(pixel(20, 5), 0);
+    assert_eq!(pixel(40, 5), 0);
+
+    assert!(heightmap_grayscale(&chunks, "OCEAN_FLOOR").is_none());
+}
+
+#[test]
+fn test_heightmap_grayscale_too_large() {
+    // the map would cover the whole world between the two chunks
+    let chunks = vec![heightmap_chunk(-1_875_000, 0, 100), heightmap_chunk(1_875_000, 0, 100)];
+    assert!(heightmap_grayscale(&chunks, "WORLD_SURFACE").is_none());
+
+    assert_eq!(map_size((0, 0), (255, 255)), Some((4096, 4096)));
+    assert_eq!(map_size((0, 0), (256, 255)), None);
+    assert_eq!(map_size((i32::MIN, 0), (i32::MAX, 0)), None);
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn test_heightmap_image() {
+    let chunks = vec![heightmap_chunk(0, 0, 64)];
+    let image = heightmap_image(&chunks, "WORLD_SURFACE").unwrap();
+
+    assert_eq!(image.dimensions(), (16, 16));
+    assert_eq!(image.get_pixel(15, 0).0, [255]);
+}
+
+/// The same chunk content saved with the 1.18 layout (at the root) or the pre-1.18 one (in `Level`, old names).
+fn versioned_chunk(with_level: bool) -> nbt_tag::NbtTagCompound {
+    let int = |name: &str, value: i32| nbt_tag::NbtTag::Int(nbt_tag::NbtTagInt::new(name.to_string(), value));
+    let compounds_list = |name: &str, len: usize| nbt_tag::NbtTag::List(nbt_tag::NbtTagList::new(name.to_string(), nbt_tag::NbtTagType::Compound, 
+        vec![nbt_tag::NbtTag::Compound(nbt_tag::NbtTagCompound::new("")); len]));
+    let (sections_name, block_entities_name) = if with_level { ("Sections", "TileEntities") } else { ("sections", "block_entities") };
+
+    let mut data = nbt_tag::NbtTagCompound::new(if with_level { "Level" } else { "" });
+    data.values.insert("xPos".to_string(), int("xPos", -3));
+    data.values.insert("zPos".to_string(), int("zPos", 7));
+    data.values.insert("Status".to_string(), nbt_tag::NbtTag::String(nbt_tag::NbtTagString::new("Status".to_string(), "full".to_string())));
+    data.values.insert(sections_name.to_string(), compounds_list(sections_name, 3));
+    data.values.insert(block_entities_name.to_string(), compounds_list(block_entities_name, 2));
+    data.values.insert("Entities".to_string(), compounds_list("Entities", 1));
+
+    if !with_level {
+        data.values.insert("DataVersion".to_string(), int("DataVersion", 3465));
+        return data;
+    }
+    let mut chunk = nbt_tag::NbtTagCompound::new("");
+    chunk.values.insert("DataVersion".to_string(), int("DataVersion", 2586));
+    chunk.values.insert("Level".to_string(), nbt_tag::NbtTag::Compound(data));
+    chunk
+}
+
+#[test]
+fn test_chunk_accessor_versions() {
+    for with_level in [false, true] {
+        let chunk_compound = versioned_chunk(with_level);
+        let chunk = ChunkAccessor::new(&chunk_compound);
+
+        assert_eq!(chunk.has_level(), with_level);
+        assert_eq!(chunk.data_version(), Some(if with_level { 2586 } else { 3465 }));
+        assert_eq!((chunk.x_pos(), chunk.z_pos()), (Some(-3), Some(7)));
+        assert_eq!(chunk.y_pos(), None);
+        assert_eq!(chunk.status(), Some("full"));
+        assert_eq!(chunk.sections().map(|sections| sections.len()), Some(3));
+        assert_eq!(chunk.block_entities().len(), 2);
+        assert_eq!(chunk.entities().len(), 1);
+        assert!(chunk.heightmaps().is_none());
+        assert!(std::ptr::eq(chunk.compound(), &chunk_compound));
+
+        // the helpers read the chunk through the accessor
+        assert_eq!(get_sections_list(&chunk_compound).map(|sections| sections.len()), Some(3));
+        assert_eq!(get_block_entities(&chunk_compound).len(), 2);
+        assert_eq!(chunk_status(&chunk_compound).as_deref(), Some("full"));
+    }
+
+    let empty = nbt_tag::NbtTagCompound::new("");
+    let chunk = ChunkAccessor::new(&empty);
+    assert!(chunk.sections().is_none());
+    assert!(chunk.block_entities().is_empty());
+    assert_eq!(chunk.data_version(), None);
+}
+
+fn palette_entry(name: &str, properties: &[(&str, &str)]) -> nbt_tag::NbtTag {
+    let mut block_compound = nbt_tag::NbtTagCompound::new("");
+    block_compound.values.insert("Name".to_string(), nbt_tag::NbtTag::String(nbt_tag::NbtTagString::new("Name".to_string(), name.to_string())));
+    if !properties.is_empty() {
+        let mut properties_compound = nbt_tag::NbtTagCompound::new("Properties");
+        for (key, value) in properties {
+            properties_compound.values.insert(key.to_string(), nbt_tag::NbtTag::String(nbt_tag::NbtTagString::new(key.to_string(), value.to_string())));
+        }
+        block_compound.values.insert("Properties".to_string(), nbt_tag::NbtTag::Compound(properties_compound));
+    }
+    nbt_tag::NbtTag::Compound(block_compound)
+}
+
+/// A chunk with a section of blocks (Y = 2), a section with only light (Y = 3) and a section without Y,
+/// saved with the 1.18 layout (`block_states`) or the 1.16 one (`Palette` and `BlockStates` in `Level`).
+fn blocks_chunk(palette_ids: &[u16], with_level: bool) -> nbt_tag::NbtTagCompound {
+    let palette = vec![palette_entry("minecraft:air", &[]), palette_entry("minecraft:stone", &[]), palette_entry("minecraft:oak_stairs", &[("facing", "north"), ("half", "top")])];
+    let palette_list = |name: &str| nbt_tag::NbtTag::List(nbt_tag::NbtTagList::new(name.to_string(), nbt_tag::NbtTagType::Compound, palette.clone()));
+    let data_version = if with_level { 2586 } else { BLOCK_STATES_DATA_VERSION };
+    let data = pack_block_states(palette_ids, palette.len(), data_version);
+
+    let mut blocks_section = nbt_tag::NbtTagCompound::new("");
+    blocks_section.values.insert("Y".to_string(), nbt_tag::NbtTag::Byte(nbt_tag::NbtTagByte::new("Y".to_string(), 2)));
+    if with_level {
+        blocks_section.values.insert("Palette".to_string(), palette_list("Palette"));
+        blocks_section.values.insert("BlockStates".to_string(), nbt_tag::NbtTag::LongArray(nbt_tag::NbtTagLongArray::new("BlockStates".to_string(), data)));
+    } else {
+        let mut block_states = nbt_tag::NbtTagCompound::new("block_states");
+        block_states.values.insert("palette".to_string(), palette_list("palette"));
+        block_states.values.insert("data".to_string(), nbt_tag::NbtTag::LongArray(nbt_tag::NbtTagLongArray::new("data".to_string(), data)));
+        blocks_section.values.insert("block_states".to_string(), nbt_tag::NbtTag::Compound(block_states));
+    }
+
+    let mut light_section = nbt_tag::NbtTagCompound::new("");
+    light_section.values.insert("Y".to_string(), nbt_tag::NbtTag::Byte(nbt_tag::NbtTagByte::new("Y".to_string(), 3)));
+
+    let sections = vec![nbt_tag::NbtTag::Compound(blocks_section), nbt_tag::NbtTag::Compound(light_section), nbt_tag::NbtTag::Compound(nbt_tag::NbtTagCompound::new(""))];
+    let sections_name = if with_level { "Sections" } else { "sections" };
+    let mut data_compound = nbt_tag::NbtTagCompound::new(if with_level { "Level" } else { "" });
+    data_compound.values.insert(sections_name.to_string(), nbt_tag::NbtTag::List(nbt_tag::NbtTagList::new(sections_name.to_string(), nbt_tag::NbtTagType::Compound, sections)));
+
+    let mut chunk = nbt_tag::NbtTagCompound::new("");
+    chunk.values.insert("DataVersion".to_string(), nbt_tag::NbtTag::Int(nbt_tag::NbtTagInt::new("DataVersion".to_string(), data_version)));
+    if with_level {
+        chunk.values.insert("Level".to_string(), nbt_tag::NbtTag::Compound(data_compound));
+    } else {
+        chunk.values.extend(data_compound.values);
+    }
+    chunk
+}
+
+#[test]
+fn test_sections_versions() {
+    let palette_ids = sample_palette_ids(3);
+
+    for with_level in [false, true] {
+        let chunk = blocks_chunk(&palette_ids, with_level);
+        let sections = sections(&chunk);
+
+        assert_eq!(sections.iter().map(|section| section.y()).collect::<Vec<_>>(), vec![2, 3]);
+
+        let blocks_section = &sections[0];
+        assert_eq!(blocks_section.palette_len(), 3);
+        assert_eq!(blocks_section.block_indices(), palette_ids);
+
+        let palette = blocks_section.palette();
+        assert_eq!(palette.iter().map(|block_state| block_state.name).collect::<Vec<_>>(), vec!["minecraft:air", "minecraft:stone", "minecraft:oak_stairs"]);
+        assert!(palette[1].properties.is_empty());
+        assert_eq!(palette[2].property("facing"), Some("north"));
+        assert_eq!(palette[2].property("half"), Some("top"));
+        assert_eq!(palette[2].property("waterlogged"), None);
+
+        let light_section = &sections[1];
+        assert!(light_section.palette().is_empty());
+        assert!(light_section.block_indices().is_empty());
+        assert!(light_section.compound().values.contains_key("Y"));
+    }
+
+    assert!(sections(&nbt_tag::NbtTagCompound::new("")).is_empty());
+}
+
+#[test]
+fn test_block_state_from_palette_entry() {
+    assert_eq!(BlockState::from_palette_entry(&palette_entry("minecraft:stone", &[])).map(|block_state| block_state.name), Some("minecraft:stone"));
+    assert!(BlockState::from_palette_entry(&nbt_tag::NbtTag::Compound(nbt_tag::NbtTagCompound::new(""))).is_none());
+    assert!(BlockState::from_palette_entry(&nbt_tag::NbtTag::Int(nbt_tag::NbtTagInt::new("Name".to_string(), 1))).is_none());
+}
+
+#[test]
+fn test_set_block() {
+    let palette_ids = sample_palette_ids(3);
+    let mut chunk = blocks_chunk(&palette_ids, false);
+
+    // local coordinates of the section Y = 2, the palette gets a new entry
+    assert!(set_block(&mut chunk, 17, 37, -3, "minecraft:diamond_block"));
+    // an existing entry without properties is reused
+    assert!(set_block(&mut chunk, 0, 32, 0, "minecraft:stone"));
+    // no section at this height
+    assert!(!set_block(&mut chunk, 0, 100, 0, "minecraft:stone"));
+
+    let sections = sections(&chunk);
+    let blocks_section = &sections[0];
+    assert_eq!(blocks_section.palette_len(), 4);
+    assert_eq!(blocks_section.palette()[3].name, "minecraft:diamond_block");
+
+    let mut expected = palette_ids.clone();
+    expected[5 * 256 + 13 * 16 + 1] = 3;
+    expected[0] = 1;
+    assert_eq!(blocks_section.block_indices(), expected);
+}
+
+#[test]
+fn test_count_blocks_in_box() {
+    let palette_ids = sample_palette_ids(3);
+    let mut chunk = blocks_chunk(&palette_ids, false);
+    assert!(count_blocks_in_box(&chunk, &blocks::Coordinates::new(vec![-100, -100, -100]), &blocks::Coordinates::new(vec![100, 100, 100])).is_empty());
+
+    // the chunk (-1, 2) covers x -16..=-1 and z 32..=47, its section Y = 2 covers y 32..=47
+    chunk.values.insert("xPos".to_string(), nbt_tag::NbtTag::Int(nbt_tag::NbtTagInt::new("xPos".to_string(), -1)));
+    chunk.values.insert("zPos".to_string(), nbt_tag::NbtTag::Int(nbt_tag::NbtTagInt::new("zPos".to_string(), 2)));
+    let count_in = |min: [i32; 3], max: [i32; 3]| count_blocks_in_box(&chunk, &blocks::Coordinates::new(min.to_vec()), &blocks::Coordinates::new(max.to_vec()));
+
+    let whole_section = count_in([-16, 32, 32], [-1, 47, 47]);
+    assert_eq!(whole_section.values().sum::<u64>(), SECTION_BLOCKS_NUM as u64);
+    assert_eq!(count_in([-100, -100, -100], [100, 100, 100]), whole_section);
+
+    // a box across the chunk border and the section bottom, with the corners swapped
+    let partial = count_in([-10, 40, 50], [5, 20, 30]);
+    let mut expected = HashMap::<String, u64>::new();
+    for y in 0..=8 {
+        for z in 0..16 {
+            for x in 6..=15 {
+                let name = ["minecraft:air", "minecraft:stone", "minecraft:oak_stairs"][palette_ids[y * 256 + z * 16 + x] as usize];
+                *expected.entry(name.to_string()).or_default() += 1;
+            }
+        }
+    }
+    assert_eq!(partial.values().sum::<u64>(), 9 * 16 * 10);
+    assert_eq!(partial, expected);
+
+    // outside the chunk, or only in the section without blocks
+    assert!(count_in([0, 32, 32], [15, 47, 47]).is_empty());
+    assert!(count_in([-16, 48, 32], [-1, 63, 47]).is_empty());
+}
+
+/// A chunk at (2, -3) where a village starts (with the 1.18 layout) or a fortress starts (with the `Level` layout,
+/// the bounding box only in the pieces), referencing a mineshaft started in the chunk (1, -3).
+fn structures_chunk(with_level: bool) -> nbt_tag::NbtTagCompound {
+    let int = |name: &str, value: i32| nbt_tag::NbtTag::Int(nbt_tag::NbtTagInt::new(name.to_string(), value));
+    let bounding_box = |values: Vec<i32>| nbt_tag::NbtTag::IntArray(nbt_tag::NbtTagIntArray::new("BB".to_string(), values));
+    let string = |name: &str, value: &str| nbt_tag::NbtTag::String(nbt_tag::NbtTagString::new(name.to_string(), value.to_string()));
+
+    let mut start = nbt_tag::NbtTagCompound::new("");
+    if with_level {
+        start.set_name("Fortress");
+        start.values.insert("id".to_string(), string("id", "Fortress"));
+        let pieces = [vec![30, 40, -50, 40, 50, -40], vec![20, 45, -45, 35, 60, -35]].into_iter()
+            .map(|values| {
+                let mut piece = nbt_tag::NbtTagCompound::new("");
+                piece.values.insert("BB".to_string(), bounding_box(values));
+                nbt_tag::NbtTag::Compound(piece)
+            })
+            .collect();
+        start.values.insert("Children".to_string(), nbt_tag::NbtTag::List(nbt_tag::NbtTagList::new("Children".to_string(), nbt_tag::NbtTagType::Compound, pieces)));
+    } else {
+        start.set_name("minecraft:village_plains");
+        start.values.insert("id".to_string(), string("id", "minecraft:village_plains"));
+        start.values.insert("ChunkX".to_string(), int("ChunkX", 2));
+        start.values.insert("ChunkZ".to_string(), int("ChunkZ", -3));
+        start.values.insert("BB".to_string(), bounding_box(vec![20, 60, -60, 70, 80, -30]));
+    }
+    let start_id = start.name().to_string();
+    let mut invalid_start = nbt_tag::NbtTagCompound::new("Monument");
+    invalid_start.values.insert("id".to_string(), string("id", INVALID_STRUCTURE_ID));
+
+    let mut starts = nbt_tag::NbtTagCompound::new(if with_level { "Starts" } else { "starts" });
+    starts.values.insert(start_id.clone(), nbt_tag::NbtTag::Compound(start));
+    starts.values.insert("Monument".to_string(), nbt_tag::NbtTag::Compound(invalid_start));
+
+    // the start chunk references its own structure too
+    let pack = |chunk_x: i32, chunk_z: i32| ((chunk_z as i64) << 32) | (chunk_x as u32 as i64);
+    let mut references = nbt_tag::NbtTagCompound::new("References");
+    references.values.insert(start_id.clone(), nbt_tag::NbtTag::LongArray(nbt_tag::NbtTagLongArray::new(start_id, vec![pack(2, -3)])));
+    references.values.insert("minecraft:mineshaft".to_string(), nbt_tag::NbtTag::LongArray(nbt_tag::NbtTagLongArray::new("minecraft:mineshaft".to_string(), vec![pack(1, -3)])));
+
+    let mut structures = nbt_tag::NbtTagCompound::new(if with_level { "Structures" } else { "structures" });
+    structures.values.insert(starts.name().to_string(), nbt_tag::NbtTag::Compound(starts));
+    structures.values.insert("References".to_string(), nbt_tag::NbtTag::Compound(references));
+
+    let mut data_compound = nbt_tag::NbtTagCompound::new(if with_level { "Level" } else { "" });
+    data_compound.values.insert("xPos".to_string(), int("xPos", 2));
+    data_compound.values.insert("zPos".to_string(), int("zPos", -3));
+    data_compound.values.insert(structures.name().to_string(), nbt_tag::NbtTag::Compound(structures));
+    if !with_level {
+        return data_compound;
+    }
+    let mut chunk = nbt_tag::NbtTagCompound::new("");
+    chunk.values.insert("Level".to_string(), nbt_tag::NbtTag::Compound(data_compound));
+    chunk
+}
+
+#[test]
+fn test_structures() {
+    let mineshaft = StructureRef { id: "minecraft:mineshaft".to_string(), start_chunk: (1, -3), bounding_box: None };
+
+    let village = StructureRef { id: "minecraft:village_plains".to_string(), start_chunk: (2, -3), bounding_box: Some([20, 60, -60, 70, 80, -30]) };
+    assert_eq!(structures(&structures_chunk(false)), vec![mineshaft.clone(), village]);
+
+    // the start chunk is the chunk itself, the bounding box is the union of the pieces
+    let fortress = StructureRef { id: "Fortress".to_string(), start_chunk: (2, -3), bounding_box: Some([20, 40, -50, 40, 60, -35]) };
+    assert_eq!(structures(&structures_chunk(true)), vec![fortress, mineshaft]);
+
+    assert!(structures(&nbt_tag::NbtTagCompound::new("")).is_empty());
+}
@@ -0,0 +1,144 @@
+// ## Author
+// - mrmarkolinus
+//
+// ## Date
+// - 2026-10-16
+//
+// ## File Version
+// - 1.0.9
+//
+// ## Changelog
+// - 1.0.0: Initial version
+// - 1.0.1: Added TruncatedData and InvalidTagType for malformed files [mrmarkolinus:2026-10-16]
+// - 1.0.2: Added MaxDepthExceeded [mrmarkolinus:2026-10-16]
+// - 1.0.3: Added InvalidString [mrmarkolinus:2026-10-16]
+// - 1.0.4: Added InvalidResourceLocation [mrmarkolinus:2026-10-16]
+// - 1.0.5: Added InvalidPath [mrmarkolinus:2026-10-16]
+// - 1.0.6: Added WorldLocked [mrmarkolinus:2026-10-16]
+// - 1.0.7: Added DuplicateKey [mrmarkolinus:2026-10-16]
+// - 1.0.8: Added CorruptNbt, the parse errors carry the offset of the failing byte [mrmarkolinus:2026-10-16]
+// - 1.0.9: Added InvalidCache [mrmarkolinus:2026-10-16]
+
+use crate::nbt_tag::NbtTagType;
+
+use std::fmt;
+use std::io;
+#[cfg(feature = "python")]
+use pyo3::PyErr;
+
+/// Errors raised by the library when the content of a tag or a file is not the expected one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RnbtError {
+    /// The tag has a different type than the one requested.
+    TypeMismatch {
+        expected: NbtTagType,
+        found: NbtTagType,
+    },
+    /// The data ends before the declared content, or a declared length is negative.
+    TruncatedData {
+        needed: i64,
+        remaining: usize,
+    },
+    /// The tag type id is not a valid NBT tag type.
+    InvalidTagType(u8),
+    /// Lists and compounds are nested deeper than the supported limit.
+    MaxDepthExceeded(usize),
+    /// A string is not valid Modified UTF-8.
+    InvalidString(String),
+    /// A block or entity identifier is not a valid `namespace:path` resource location.
+    InvalidResourceLocation(String),
+    /// A tag path does not follow the path grammar (see `nbt_path`).
+    InvalidPath(String),
+    /// The world is open in a game or a server (its `session.lock` is locked), the files may change while read.
+    WorldLocked(String),
+    /// A compound has the same key twice (see `file_parser::DuplicateKeys`).
+    DuplicateKey(String),
+    /// The NBT data is malformed at the byte `offset` of the uncompressed buffer, `error` tells how.
+    /// Every error of the parsers in `file_parser` is wrapped in this variant.
+    CorruptNbt {
+        offset: u64,
+        error: Box<RnbtError>,
+    },
+    /// The data is not a cache written by `NbtTagCompound::to_cache`, or by a version with another cache format.
+    InvalidCache(String),
+}
+
+impl RnbtError {
+    /// Returns the error without the location added by `CorruptNbt`.
+    pub fn cause(&self) -> &RnbtError {
+        match self {
+            RnbtError::CorruptNbt { error, .. } => error.cause(),
+            _ => self,
+        }
+    }
+
+    /// Returns the byte offset of a `CorruptNbt` error.
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            RnbtError::CorruptNbt { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RnbtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RnbtError::TypeMismatch { expected, found } => {
+                write!(f, "Tag type mismatch: expected {:?}, found {:?}", expected, found)
+            }
+            RnbtError::TruncatedData { needed, remaining } => {
+                write!(f, "Truncated data: {} bytes needed, {} remaining", needed, remaining)
+            }
+            RnbtError::InvalidTagType(id) => {
+                write!(f, "Invalid tag type id {}", id)
+            }
+            RnbtError::MaxDepthExceeded(max_depth) => {
+                write!(f, "Nesting deeper than {} levels", max_depth)
+            }
+            RnbtError::InvalidString(value) => {
+                write!(f, "Invalid Modified UTF-8 string: {}", value)
+            }
+            RnbtError::InvalidResourceLocation(value) => {
+                write!(f, "Invalid resource location: {:?}", value)
+            }
+            RnbtError::InvalidPath(path) => {
+                write!(f, "Invalid tag path: {:?}", path)
+            }
+            RnbtError::WorldLocked(world_path) => {
+                write!(f, "World {} is in use, its session.lock is locked", world_path)
+            }
+            RnbtError::DuplicateKey(key) => {
+                write!(f, "Duplicate key {:?} in a compound", key)
+            }
+            RnbtError::CorruptNbt { offset, error } => {
+                write!(f, "Corrupt NBT at byte {:#X}: {}", offset, error)
+            }
+            RnbtError::InvalidCache(reason) => {
+                write!(f, "Invalid NBT cache: {}", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RnbtError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RnbtError::CorruptNbt { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<RnbtError> for io::Error {
+    fn from(err: RnbtError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+#[cfg(feature = "python")]
+impl From<RnbtError> for PyErr {
+    fn from(err: RnbtError) -> Self {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(err.to_string())
+    }
+}
@@ -5,21 +5,37 @@
 // - 2023-12-17
 //
 // ## File Version
-// - 1.0.1
+// - 1.0.12
 //
 // ## Changelog
 // - 1.0.0: Initial version [caelunshun:2019-07-09]
 // - 1.0.1: Splitted the file_parser logic from the nbt_tag logic [mrmarkolinus:2023-12-17]
+// - 1.0.2: Length prefixes are validated against the remaining buffer, parse errors are reported as RnbtError [mrmarkolinus:2026-10-16]
+// - 1.0.3: Added parse_nbt_bytes, a no-panic entry point for untrusted data, and a nesting depth limit [mrmarkolinus:2026-10-16]
+// - 1.0.4: Strings are decoded as Modified UTF-8 [mrmarkolinus:2026-10-16]
+// - 1.0.5: Added ParseOptions and parse_nbt_bytes_with_options, the duplicate keys of a compound are detected [mrmarkolinus:2026-10-16]
+// - 1.0.6: Added scan_nbt_bytes, a streaming parser emitting events instead of building the tree [mrmarkolinus:2026-10-16]
+// - 1.0.7: Added Endianness to ParseOptions, to read little-endian files [mrmarkolinus:2026-10-16]
+// - 1.0.8: The parse errors are RnbtError::CorruptNbt, with the offset of the failing byte [mrmarkolinus:2026-10-16]
+// - 1.0.9: Added ParseOptions::keep_only, pruning the tags not requested while parsing [mrmarkolinus:2026-10-16]
+// - 1.0.10: Added ParseOptions::unnamed_root, for the NBT without root name [mrmarkolinus:2026-10-16]
+// - 1.0.11: Added parse_network_nbt_bytes, for the NBT of the network protocol [mrmarkolinus:2026-10-16]
+// - 1.0.12: scan_nbt_bytes reports no End after the handler stopped on the last element of a list [mrmarkolinus:2026-10-16]
 
 use crate::nbt_tag::*;
 use crate::generic_bin;
+use crate::error::RnbtError;
+use crate::mutf8;
 
-use byteorder::{BigEndian, ReadBytesExt};
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
 use std::io::Cursor;
 use std::path::PathBuf;
 use std::fs;
 use std::io::BufReader;
 use std::io::Read;
+use std::marker::PhantomData;
 
 #[cfg(test)]
 mod tests;
@@ -52,10 +68,8 @@ impl FileParser {
         };
 
         // Handle the result from parse_bytes
-        match parse_bytes(&buf) {
-            Ok(nbt_tag) => Ok(nbt_tag),  // On success, return the NbtTag
-            Err(_) => Err(std::io::Error::new(std::io::ErrorKind::Other, "Parse error")),  // On error, return an std::io::Error
-        }
+        let nbt_tag = parse_bytes(&buf)?;
+        Ok(nbt_tag)
     }
 
     pub fn read (&self) -> std::io::Result<Vec<u8>> {
@@ -94,82 +108,347 @@ impl FileParser {
 }
 
 
-//TODO: put these guys in FileParser, workaround for region file
-pub fn parse_bytes(bytes: &[u8]) -> Result<NbtTag, ()> {
-    let mut cursor = Cursor::new(bytes);
+/// Maximum nesting depth of lists and compounds, same limit used by Minecraft.
+/// Deeper files are rejected instead of overflowing the stack.
+pub const MAX_NESTING_DEPTH: usize = 512;
+
+/// How the parser handles a key found twice in the same compound, which only happens in corrupt files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateKeys {
+    /// The last value replaces the previous ones, as in Minecraft, with a warning in the log.
+    #[default]
+    Overwrite,
+    /// The parsing fails with `RnbtError::DuplicateKey`.
+    Error,
+    /// All the values are kept: the second one is stored at the key `<key>#2`, the third one at `<key>#3` and so on.
+    /// The tags keep their name, so the file is written back with the duplicates.
+    Collect,
+}
+
+/// Byte order of the numbers and length prefixes of an NBT file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Endianness {
+    /// The byte order of Java Edition, used by every file the game writes.
+    #[default]
+    Big,
+    /// Used by some third-party tools (and by Bedrock Edition).
+    Little,
+}
+
+impl Endianness {
+    fn read_u16(self, cursor: &mut Cursor<&[u8]>) -> Result<u16, RnbtError> {
+        read_checked(cursor, 2, |cursor| match self {
+            Endianness::Big => cursor.read_u16::<BigEndian>(),
+            Endianness::Little => cursor.read_u16::<LittleEndian>(),
+        })
+    }
+
+    fn read_i16(self, cursor: &mut Cursor<&[u8]>) -> Result<i16, RnbtError> {
+        read_checked(cursor, 2, |cursor| match self {
+            Endianness::Big => cursor.read_i16::<BigEndian>(),
+            Endianness::Little => cursor.read_i16::<LittleEndian>(),
+        })
+    }
+
+    fn read_i32(self, cursor: &mut Cursor<&[u8]>) -> Result<i32, RnbtError> {
+        read_checked(cursor, 4, |cursor| match self {
+            Endianness::Big => cursor.read_i32::<BigEndian>(),
+            Endianness::Little => cursor.read_i32::<LittleEndian>(),
+        })
+    }
+
+    fn read_i64(self, cursor: &mut Cursor<&[u8]>) -> Result<i64, RnbtError> {
+        read_checked(cursor, 8, |cursor| match self {
+            Endianness::Big => cursor.read_i64::<BigEndian>(),
+            Endianness::Little => cursor.read_i64::<LittleEndian>(),
+        })
+    }
+
+    fn read_f32(self, cursor: &mut Cursor<&[u8]>) -> Result<f32, RnbtError> {
+        read_checked(cursor, 4, |cursor| match self {
+            Endianness::Big => cursor.read_f32::<BigEndian>(),
+            Endianness::Little => cursor.read_f32::<LittleEndian>(),
+        })
+    }
+
+    fn read_f64(self, cursor: &mut Cursor<&[u8]>) -> Result<f64, RnbtError> {
+        read_checked(cursor, 8, |cursor| match self {
+            Endianness::Big => cursor.read_f64::<BigEndian>(),
+            Endianness::Little => cursor.read_f64::<LittleEndian>(),
+        })
+    }
+}
+
+/// Options of the NBT parser, see `parse_nbt_bytes_with_options`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub duplicate_keys: DuplicateKeys,
+    /// Big-endian by default, as written by Java Edition.
+    pub endianness: Endianness,
+    /// If not empty, only the tags with these names (at any depth) are kept, with their whole content, and the
+    /// compounds and lists leading to them. The other tags are dropped as soon as they are parsed.
+    /// 
+    /// A list leading to a kept tag keeps all its elements, so that the indices do not change: the elements without
+    /// kept tags are empty compounds. The pruned data cannot be written back, the rest of the tree is lost.
+    pub keep_only: Vec<String>,
+    /// The root compound is not followed by its name, as in the network protocol since 1.20.2
+    /// (see `NbtTagCompound::to_binary_unnamed`). The parsed root has an empty name.
+    pub unnamed_root: bool,
+}
+
+impl ParseOptions {
+    /// Returns the options parsing the content of a kept tag: the same options without `keep_only`.
+    fn keeping_all(&self) -> ParseOptions {
+        ParseOptions { duplicate_keys: self.duplicate_keys, endianness: self.endianness, keep_only: Vec::new(), unnamed_root: self.unnamed_root }
+    }
+}
+
+/// Parses an uncompressed NBT buffer whose root is a compound.
+/// 
+/// This is the entry point to use for untrusted data: it never panics and never hangs, 
+/// whatever the content of `data`. Every malformed input is reported as an `RnbtError::CorruptNbt`
+/// with the offset of the failing byte, wrapping:
+/// - `TruncatedData` if the buffer ends early or a length prefix is negative or larger than the buffer
+/// - `InvalidTagType` if a tag type id is unknown (or TAG_End is used as a value)
+/// - `MaxDepthExceeded` if lists and compounds are nested deeper than `MAX_NESTING_DEPTH`
+/// - `TypeMismatch` if the root tag is not a compound
+/// 
+/// Trailing bytes after the root compound are ignored.
+/// The function is fuzzed with the target in `fuzz/fuzz_targets/parse_nbt_bytes.rs`.
+pub fn parse_nbt_bytes(data: &[u8]) -> Result<NbtTagCompound, RnbtError> {
+    parse_nbt_bytes_with_options(data, &ParseOptions::default())
+}
+
+/// Same as `parse_nbt_bytes`, with the given options. Also returns `DuplicateKey` with `DuplicateKeys::Error`.
+pub fn parse_nbt_bytes_with_options(data: &[u8], options: &ParseOptions) -> Result<NbtTagCompound, RnbtError> {
+    let mut cursor = Cursor::new(data);
     
     // Read root compound - read type first
-    let ty = {
-        let id = cursor.read_u8().map_err(|_| ())?;
-        NbtTagType::from_id(id).ok_or_else(|| ())?
-    };
-    if ty != NbtTagType::Compound {
-        return Err(());
+    parse_root_type(&mut cursor)?;
+
+    let name = if options.unnamed_root { String::new() } else { parse_name(&mut cursor, options.endianness)? };
+    parse_compound(&mut cursor, name, 0, options)
+}
+
+/// Parses the NBT sent by the network protocol since 1.20.2 (e.g. from packet captures): the root tag has its type
+/// but no name, and it may be of any type (a string for a text component). A TAG_End root, sent when there is no
+/// data, is returned as `NbtTag::End`. The root tag has an empty name.
+/// 
+/// Malformed data is reported as by `parse_nbt_bytes`.
+pub fn parse_network_nbt_bytes(data: &[u8]) -> Result<NbtTag, RnbtError> {
+    parse_network_nbt_bytes_with_options(data, &ParseOptions::default())
+}
+
+/// Same as `parse_network_nbt_bytes`, with the given options. `ParseOptions::unnamed_root` is implied.
+pub fn parse_network_nbt_bytes_with_options(data: &[u8], options: &ParseOptions) -> Result<NbtTag, RnbtError> {
+    let mut cursor = Cursor::new(data);
+
+    Ok(match parse_tag_type(&mut cursor)? {
+        NbtTagType::End => NbtTag::End,
+        NbtTagType::Compound => NbtTag::Compound(parse_compound(&mut cursor, String::new(), 0, options)?),
+        NbtTagType::List => NbtTag::List(parse_list(&mut cursor, String::new(), 0, options)?),
+        ty => parse_flat_value(&mut cursor, ty, String::new(), options.endianness)?,
+    })
+}
+
+//TODO: put these guys in FileParser, workaround for region file
+pub fn parse_bytes(bytes: &[u8]) -> Result<NbtTag, RnbtError> {
+    let root = parse_nbt_bytes(bytes)?;
+    Ok(NbtTag::Compound(root))
+}
+
+/// Returns the number of bytes left to read in the buffer.
+fn remaining_bytes(cursor: &Cursor<&[u8]>) -> usize {
+    let len = cursor.get_ref().len();
+    len.saturating_sub(cursor.position() as usize)
+}
+
+/// Wraps a parse error with the offset of the byte where the data is malformed.
+fn corrupt(offset: u64, error: RnbtError) -> RnbtError {
+    RnbtError::CorruptNbt { offset, error: Box::new(error) }
+}
+
+/// Returns a `TruncatedData` error at the current position.
+fn truncated(cursor: &Cursor<&[u8]>, needed: i64) -> RnbtError {
+    corrupt(cursor.position(), RnbtError::TruncatedData { needed, remaining: remaining_bytes(cursor) })
+}
+
+/// Reads a value of `size` bytes, the cursor is left at the start of the value if the buffer is too short
+/// so that the error points at it.
+fn read_checked<T, R>(cursor: &mut Cursor<&[u8]>, size: usize, read: R) -> Result<T, RnbtError>
+where
+    R: FnOnce(&mut Cursor<&[u8]>) -> std::io::Result<T>,
+{
+    if remaining_bytes(cursor) < size {
+        return Err(truncated(cursor, size as i64));
     }
 
-    let name_len = cursor.read_i16::<BigEndian>().map_err(|_| ())?;
-    let mut name = String::with_capacity(name_len as usize);
-    for _ in 0..name_len {
-        let ch = cursor.read_u8().map_err(|_| ())?;
-        name.push(ch as char);
+    read(cursor).map_err(|_| truncated(cursor, size as i64))
+}
+
+/// Validates a length prefix read from the file. 
+/// 
+/// The length must be non-negative and the declared elements (each at least `element_size` bytes long)
+/// must fit in the remaining buffer, otherwise a corrupt file could make us allocate gigabytes
+/// or panic on the `as usize` cast.
+fn checked_length(cursor: &Cursor<&[u8]>, declared_len: i64, element_size: usize) -> Result<usize, RnbtError> {
+    let needed = declared_len.saturating_mul(element_size as i64);
+    if declared_len < 0 || needed > remaining_bytes(cursor) as i64 {
+        return Err(truncated(cursor, needed));
     }
 
-    let root = parse_compound(&mut cursor, name)?;
+    Ok(declared_len as usize)
+}
 
-    Ok(NbtTag::Compound(root))
+/// Minimum number of bytes a value of the given type takes in the payload of a list.
+fn min_payload_size(ty: NbtTagType) -> usize {
+    match ty {
+        NbtTagType::End => 0,
+        NbtTagType::Byte => 1,
+        NbtTagType::Short => 2,
+        NbtTagType::Int => 4,
+        NbtTagType::Long => 8,
+        NbtTagType::Float => 4,
+        NbtTagType::Double => 8,
+        NbtTagType::ByteArray => 4,
+        NbtTagType::String => 2,
+        NbtTagType::List => 5,
+        NbtTagType::Compound => 1,
+        NbtTagType::IntArray => 4,
+        NbtTagType::LongArray => 4,
+    }
+}
+
+fn parse_tag_type(cursor: &mut Cursor<&[u8]>) -> Result<NbtTagType, RnbtError> {
+    let offset = cursor.position();
+    let id = read_checked(cursor, 1, |cursor| cursor.read_u8())?;
+    NbtTagType::from_id(id).ok_or_else(|| corrupt(offset, RnbtError::InvalidTagType(id)))
+}
+
+/// Reads the type of the root tag, which must be a compound.
+fn parse_root_type(cursor: &mut Cursor<&[u8]>) -> Result<NbtTagType, RnbtError> {
+    let ty = parse_tag_type(cursor)?;
+    if ty != NbtTagType::Compound {
+        return Err(corrupt(0, RnbtError::TypeMismatch { expected: NbtTagType::Compound, found: ty }));
+    }
+
+    Ok(ty)
+}
+
+fn parse_name(cursor: &mut Cursor<&[u8]>, endianness: Endianness) -> Result<String, RnbtError> {
+    parse_string(cursor, endianness)
 }
 
-fn parse_compound(cursor: &mut Cursor<&[u8]>, name: String) -> Result<NbtTagCompound, ()> {
+/// Reads a length-prefixed Modified UTF-8 string.
+fn parse_string(cursor: &mut Cursor<&[u8]>, endianness: Endianness) -> Result<String, RnbtError> {
+    let len = endianness.read_u16(cursor)?;
+    let len = checked_length(cursor, len as i64, 1)?;
+
+    let start = cursor.position() as usize;
+    let value = mutf8::decode(&cursor.get_ref()[start..start + len]).map_err(|error| corrupt(start as u64, error))?;
+    cursor.set_position((start + len) as u64);
+
+    Ok(value)
+}
+
+/// Checks the depth of the list or compound whose content starts at the current position.
+fn check_depth(cursor: &Cursor<&[u8]>, depth: usize) -> Result<(), RnbtError> {
+    if depth >= MAX_NESTING_DEPTH {
+        return Err(corrupt(cursor.position(), RnbtError::MaxDepthExceeded(MAX_NESTING_DEPTH)));
+    }
+
+    Ok(())
+}
+
+fn parse_compound(cursor: &mut Cursor<&[u8]>, name: String, depth: usize, options: &ParseOptions) -> Result<NbtTagCompound, RnbtError> {
+    check_depth(cursor, depth)?;
     let mut compound = NbtTagCompound::new(name.as_str());
 
     // Read values until NBT_End is reached
     loop {
-        let type_id = cursor.read_u8().map_err(|_| ())?;
-
-        let ty = NbtTagType::from_id(type_id).ok_or_else(|| ())?;
+        let tag_offset = cursor.position();
+        let ty = parse_tag_type(cursor)?;
         if ty == NbtTagType::End {
             // Finish early - nothing more to read
             break;
         }
 
         // Read name
-        let name = {
-            let len = cursor.read_i16::<BigEndian>().map_err(|_| ())?;
-            let mut name = String::with_capacity(len as usize);
-            for _ in 0..len {
-                let ch = cursor.read_u8().map_err(|_| ())?;
-                name.push(ch as char);
+        let name = parse_name(cursor, options.endianness)?;
+
+        // Read value, with keep_only the tags not leading to a kept tag are dropped
+        let value = if options.keep_only.contains(&name) {
+            parse_value(cursor, ty, name.clone(), depth, &options.keeping_all())?
+        } else {
+            let value = parse_value(cursor, ty, name.clone(), depth, options)?;
+            if !options.keep_only.is_empty() && !has_kept_tags(&value) {
+                continue;
             }
-
-            name
+            value
         };
 
-        // Read value
-        let value = parse_value(cursor, ty, name.clone())?;
-
-        compound.values.insert(name, value);
+        match compound.values.entry(name) {
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+            },
+            Entry::Occupied(entry) => {
+                let name = entry.key().clone();
+                insert_duplicate(&mut compound, name, value, options.duplicate_keys)
+                    .map_err(|error| corrupt(tag_offset, error))?;
+            },
+        }
     }
 
     Ok(compound)
 }
 
-fn parse_list(cursor: &mut Cursor<&[u8]>, name: String) -> Result<NbtTagList, ()> {
+/// Returns true if a tag parsed with `ParseOptions::keep_only` holds kept tags: the pruned compounds are empty.
+fn has_kept_tags(tag: &NbtTag) -> bool {
+    match tag {
+        NbtTag::Compound(compound) => !compound.values.is_empty(),
+        NbtTag::List(list) => list.values.iter().any(has_kept_tags),
+        _ => false,
+    }
+}
+
+fn insert_duplicate(compound: &mut NbtTagCompound, name: String, value: NbtTag, duplicate_keys: DuplicateKeys) -> Result<(), RnbtError> {
+    match duplicate_keys {
+        DuplicateKeys::Overwrite => {
+            log::warn!("Duplicate key {:?} in the compound {:?}, the last value is kept", name, compound.name);
+            compound.values.insert(name, value);
+        },
+        DuplicateKeys::Error => return Err(RnbtError::DuplicateKey(name)),
+        DuplicateKeys::Collect => {
+            let collected_key = (2..).map(|occurrence| format!("{}#{}", name, occurrence))
+                .find(|collected_key| !compound.values.contains_key(collected_key))
+                .expect("a free key");
+            compound.values.insert(collected_key, value);
+        },
+    }
+
+    Ok(())
+}
+
+fn parse_list(cursor: &mut Cursor<&[u8]>, name: String, depth: usize, options: &ParseOptions) -> Result<NbtTagList, RnbtError> {
+    check_depth(cursor, depth)?;
+    
     // Type of values contained in the list
-    let ty = {
-        let id = cursor.read_u8().map_err(|_| ())?;
-        NbtTagType::from_id(id).ok_or_else(|| ())?
-    };
+    let type_offset = cursor.position();
+    let ty = parse_tag_type(cursor)?;
 
     // Length of list, in number of values (not bytes)
-    let len = cursor.read_i32::<BigEndian>().map_err(|_| ())?;
-    if len > 65536 {
-        return Err(());
+    let len = options.endianness.read_i32(cursor)?;
+    let len = checked_length(cursor, len as i64, min_payload_size(ty))?;
+    
+    // empty lists are saved with type End, any other End list is corrupt (and would be read forever)
+    if ty == NbtTagType::End && len > 0 {
+        return Err(corrupt(type_offset, RnbtError::InvalidTagType(0)));
     }
 
-    let mut values = Vec::with_capacity(len as usize);
+    let mut values = Vec::with_capacity(len);
 
     for _ in 0..len {
-        let val = parse_value(cursor, ty, "".to_string())?;
+        let val = parse_value(cursor, ty, "".to_string(), depth, options)?;
         // expose to python
         //let py_val = PyNbtTag::new(&val);
         values.push(val);
@@ -179,94 +458,363 @@ fn parse_list(cursor: &mut Cursor<&[u8]>, name: String) -> Result<NbtTagList, ()
     Ok(NbtTagList::new(name, ty, values))
 }
 
-fn parse_value(cursor: &mut Cursor<&[u8]>, ty: NbtTagType, name: String) -> Result<NbtTag, ()> {
+fn parse_value(cursor: &mut Cursor<&[u8]>, ty: NbtTagType, name: String, depth: usize, options: &ParseOptions) -> Result<NbtTag, RnbtError> {
+    // only the nested types recurse, the others are parsed in a separate function 
+    // to keep the stack frame of the recursion small
+    Ok(match ty {
+        NbtTagType::List => {
+            let list = parse_list(cursor, name, depth + 1, options)?;
+            NbtTag::List(list)
+        }
+        NbtTagType::Compound => {
+            let compound = parse_compound(cursor, name, depth + 1, options)?;
+            NbtTag::Compound(compound)
+        }
+        _ => parse_flat_value(cursor, ty, name, options.endianness)?,
+    })
+}
+
+#[inline(never)]
+fn parse_flat_value(cursor: &mut Cursor<&[u8]>, ty: NbtTagType, name: String, endianness: Endianness) -> Result<NbtTag, RnbtError> {
     Ok(match ty {
-        NbtTagType::End => unreachable!(), // Should already be covered
+        NbtTagType::End => return Err(corrupt(cursor.position(), RnbtError::InvalidTagType(0))), // Should already be covered
         NbtTagType::Byte => {
-            let x = cursor.read_i8().map_err(|_| ())?;
-            NbtTag::Byte(NbtTagByte::new(name.clone(), x))
+            let x = read_checked(cursor, 1, |cursor| cursor.read_i8())?;
+            NbtTag::Byte(NbtTagByte::new(name, x))
         }
         NbtTagType::Short => {
-            let x = cursor.read_i16::<BigEndian>().map_err(|_| ())?;
-            NbtTag::Short(NbtTagShort::new(name.clone(), x))
+            let x = endianness.read_i16(cursor)?;
+            NbtTag::Short(NbtTagShort::new(name, x))
         }
         NbtTagType::Int => {
-            let x = cursor.read_i32::<BigEndian>().map_err(|_| ())?;
-            NbtTag::Int(NbtTagInt::new(name.clone(), x))
+            let x = endianness.read_i32(cursor)?;
+            NbtTag::Int(NbtTagInt::new(name, x))
         }
         NbtTagType::Long => {
-            let x = cursor.read_i64::<BigEndian>().map_err(|_| ())?;
-            NbtTag::Long(NbtTagLong::new(name.clone(), x))
+            let x = endianness.read_i64(cursor)?;
+            NbtTag::Long(NbtTagLong::new(name, x))
         }
         NbtTagType::Float => {
-            let x = cursor.read_f32::<BigEndian>().map_err(|_| ())?;
-            NbtTag::Float(NbtTagFloat::new(name.clone(), x))
+            let x = endianness.read_f32(cursor)?;
+            NbtTag::Float(NbtTagFloat::new(name, x))
         }
         NbtTagType::Double => {
-            let x = cursor.read_f64::<BigEndian>().map_err(|_| ())?;
-            NbtTag::Double(NbtTagDouble::new(name.clone(), x))
+            let x = endianness.read_f64(cursor)?;
+            NbtTag::Double(NbtTagDouble::new(name, x))
         }
         NbtTagType::ByteArray => {
-            let len = cursor.read_i32::<BigEndian>().map_err(|_| ())?;
-            if len > 65536 {
-                // Yeah... no.
-                return Err(());
-            }
+            let len = endianness.read_i32(cursor)?;
+            let len = checked_length(cursor, len as i64, 1)?;
 
-            let mut buf = Vec::with_capacity(len as usize);
+            let mut buf = Vec::with_capacity(len);
             for _ in 0..len {
-                let x = cursor.read_i8().map_err(|_| ())?;
+                let x = read_checked(cursor, 1, |cursor| cursor.read_i8())?;
                 buf.push(x);
             }
 
-            NbtTag::ByteArray(NbtTagByteArray::new(name.clone(), buf))
+            NbtTag::ByteArray(NbtTagByteArray::new(name, buf))
         }
         NbtTagType::String => {
-            let len = cursor.read_u16::<BigEndian>().map_err(|_| ())?;
-            let mut buf = String::with_capacity(len as usize);
-
-            for _ in 0..len {
-                let ch = cursor.read_u8().map_err(|_| ())?;
-                buf.push(ch as char);
-            }
-
-            NbtTag::String(NbtTagString::new(name.clone(), buf))
-        }
-        NbtTagType::List => {
-            let list = parse_list(cursor, name)?;
-            NbtTag::List(list)
-        }
-        NbtTagType::Compound => {
-            let compound = parse_compound(cursor, name)?;
-            NbtTag::Compound(compound)
+            let buf = parse_string(cursor, endianness)?;
+            NbtTag::String(NbtTagString::new(name, buf))
         }
+        NbtTagType::List | NbtTagType::Compound => unreachable!(), // Handled by parse_value
         NbtTagType::IntArray => {
-            let len = cursor.read_i32::<BigEndian>().map_err(|_| ())?;
-            if len > 65536 {
-                return Err(());
-            }
+            let len = endianness.read_i32(cursor)?;
+            let len = checked_length(cursor, len as i64, 4)?;
 
-            let mut buf = Vec::with_capacity(len as usize);
+            let mut buf = Vec::with_capacity(len);
             for _ in 0..len {
-                let x = cursor.read_i32::<BigEndian>().map_err(|_| ())?;
+                let x = endianness.read_i32(cursor)?;
                 buf.push(x);
             }
 
-            NbtTag::IntArray(NbtTagIntArray::new(name.clone(), buf))
+            NbtTag::IntArray(NbtTagIntArray::new(name, buf))
         }
         NbtTagType::LongArray => {
-            let len = cursor.read_i32::<BigEndian>().map_err(|_| ())?;
-            if len > 65536 {
-                return Err(());
-            }
+            let len = endianness.read_i32(cursor)?;
+            let len = checked_length(cursor, len as i64, 8)?;
 
-            let mut buf = Vec::with_capacity(len as usize);
+            let mut buf = Vec::with_capacity(len);
             for _ in 0..len {
-                let x = cursor.read_i64::<BigEndian>().map_err(|_| ())?;
+                let x = endianness.read_i64(cursor)?;
                 buf.push(x);
             }
 
-            NbtTag::LongArray(NbtTagLongArray::new(name.clone(), buf))
+            NbtTag::LongArray(NbtTagLongArray::new(name, buf))
         }
     })
 }
+
+/// A value of a primitive type of an NBT tree, whose data is borrowed from the scanned buffer
+/// or from a temporary string when the text had to be decoded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NbtScalar<'a> {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(Cow<'a, str>),
+    ByteArray(NbtArray<'a, i8>),
+    IntArray(NbtArray<'a, i32>),
+    LongArray(NbtArray<'a, i64>),
+}
+
+impl<'a> NbtScalar<'a> {
+    /// Copies the value into an owned `NbtTag` with the given name.
+    pub fn to_tag(&self, name: &str) -> NbtTag {
+        let name = name.to_string();
+        match self {
+            NbtScalar::Byte(value) => NbtTag::Byte(NbtTagByte::new(name, *value)),
+            NbtScalar::Short(value) => NbtTag::Short(NbtTagShort::new(name, *value)),
+            NbtScalar::Int(value) => NbtTag::Int(NbtTagInt::new(name, *value)),
+            NbtScalar::Long(value) => NbtTag::Long(NbtTagLong::new(name, *value)),
+            NbtScalar::Float(value) => NbtTag::Float(NbtTagFloat::new(name, *value)),
+            NbtScalar::Double(value) => NbtTag::Double(NbtTagDouble::new(name, *value)),
+            NbtScalar::String(value) => NbtTag::String(NbtTagString::new(name, value.to_string())),
+            NbtScalar::ByteArray(values) => NbtTag::ByteArray(NbtTagByteArray::new(name, values.to_vec())),
+            NbtScalar::IntArray(values) => NbtTag::IntArray(NbtTagIntArray::new(name, values.to_vec())),
+            NbtScalar::LongArray(values) => NbtTag::LongArray(NbtTagLongArray::new(name, values.to_vec())),
+        }
+    }
+}
+
+/// Element of a numeric NBT array, stored big-endian in the file.
+pub trait NbtArrayElement: Copy + 'static {
+    const SIZE: usize;
+
+    fn from_be_slice(bytes: &[u8]) -> Self;
+}
+
+impl NbtArrayElement for i8 {
+    const SIZE: usize = 1;
+
+    fn from_be_slice(bytes: &[u8]) -> Self {
+        bytes[0] as i8
+    }
+}
+
+impl NbtArrayElement for i32 {
+    const SIZE: usize = 4;
+
+    fn from_be_slice(bytes: &[u8]) -> Self {
+        i32::from_be_bytes(bytes.try_into().expect("4 bytes"))
+    }
+}
+
+impl NbtArrayElement for i64 {
+    const SIZE: usize = 8;
+
+    fn from_be_slice(bytes: &[u8]) -> Self {
+        i64::from_be_bytes(bytes.try_into().expect("8 bytes"))
+    }
+}
+
+/// A byte, int or long array read in place: the elements are decoded on access, nothing is copied.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NbtArray<'a, T> {
+    bytes: &'a [u8],
+    element: PhantomData<T>,
+}
+
+impl<'a, T: NbtArrayElement> NbtArray<'a, T> {
+    fn new(bytes: &'a [u8]) -> Self {
+        NbtArray { bytes, element: PhantomData }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len() / T::SIZE
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.bytes.chunks_exact(T::SIZE).nth(index).map(T::from_be_slice)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> + 'a {
+        self.bytes.chunks_exact(T::SIZE).map(T::from_be_slice)
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+}
+
+/// An event emitted by `scan_nbt_bytes` while it reads the file, like a SAX XML parser.
+/// 
+/// Elements of a list have an empty name.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NbtEvent<'a> {
+    StartCompound(&'a str),
+    StartList { name: &'a str, element_type: NbtTagType, len: usize },
+    Scalar(&'a str, NbtScalar<'a>),
+    /// Closes the last compound or list started.
+    End,
+}
+
+/// What `scan_nbt_bytes` does after an event, as returned by the handler.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScanControl {
+    Continue,
+    /// After a `StartCompound` or `StartList`, the content of the tag is skipped: no event is emitted until
+    /// the next tag, not even its `End`. Same as `Continue` after the other events.
+    Skip,
+    /// The scan ends after this event, without reading the rest of the buffer.
+    Stop,
+}
+
+/// Scans an uncompressed NBT buffer whose root is a compound, without building the tree.
+/// 
+/// Every tag is reported to `handler` as soon as it is read: this takes no memory beside the buffer,
+/// which is useful to count or find a few tags in large files. Arrays are not copied, and names and strings
+/// are borrowed from the buffer unless they need decoding.
+/// The checks are the same as `parse_nbt_bytes`, with the same errors, but the events before an error have
+/// already been emitted. Duplicate keys are reported as they are found.
+pub fn scan_nbt_bytes<F>(data: &[u8], mut handler: F) -> Result<(), RnbtError>
+where
+    F: FnMut(NbtEvent<'_>) -> ScanControl,
+{
+    let mut scanner = Scanner { cursor: Cursor::new(data), handler: &mut handler, stopped: false };
+
+    let ty = parse_root_type(&mut scanner.cursor)?;
+
+    let name = scan_string(&mut scanner.cursor)?;
+    scanner.scan_value(ty, &name, 0, true)
+}
+
+/// Reads a length-prefixed Modified UTF-8 string, borrowed from the buffer when it is plain ASCII.
+fn scan_string<'a>(cursor: &mut Cursor<&'a [u8]>) -> Result<Cow<'a, str>, RnbtError> {
+    let len = read_checked(cursor, 2, |cursor| cursor.read_u16::<BigEndian>())?;
+    let len = checked_length(cursor, len as i64, 1)?;
+
+    let start = cursor.position();
+    let bytes = scan_bytes(cursor, len);
+    if bytes.is_ascii() {
+        return Ok(Cow::Borrowed(std::str::from_utf8(bytes).expect("ASCII is valid UTF-8")));
+    }
+
+    Ok(Cow::Owned(mutf8::decode(bytes).map_err(|error| corrupt(start, error))?))
+}
+
+/// Reads an array of `T`, borrowed from the buffer.
+fn scan_array<'a, T: NbtArrayElement>(cursor: &mut Cursor<&'a [u8]>) -> Result<NbtArray<'a, T>, RnbtError> {
+    let len = read_checked(cursor, 4, |cursor| cursor.read_i32::<BigEndian>())?;
+    let len = checked_length(cursor, len as i64, T::SIZE)?;
+
+    Ok(NbtArray::new(scan_bytes(cursor, len * T::SIZE)))
+}
+
+/// Returns the next `len` bytes of the buffer, which must have been checked with `checked_length`.
+fn scan_bytes<'a>(cursor: &mut Cursor<&'a [u8]>, len: usize) -> &'a [u8] {
+    let data: &'a [u8] = cursor.get_ref();
+    let start = cursor.position() as usize;
+    cursor.set_position((start + len) as u64);
+
+    &data[start..start + len]
+}
+
+struct Scanner<'a, 'h, F> {
+    cursor: Cursor<&'a [u8]>,
+    handler: &'h mut F,
+    stopped: bool,
+}
+
+impl<'a, 'h, F> Scanner<'a, 'h, F>
+where
+    F: FnMut(NbtEvent<'_>) -> ScanControl,
+{
+    /// Emits the event if `emit` is set, returns whether the content of the tag must be emitted too.
+    fn emit(&mut self, emit: bool, event: NbtEvent<'_>) -> bool {
+        if !emit {
+            return false;
+        }
+
+        match (self.handler)(event) {
+            ScanControl::Continue => true,
+            ScanControl::Skip => false,
+            ScanControl::Stop => {
+                self.stopped = true;
+                false
+            },
+        }
+    }
+
+    fn scan_value(&mut self, ty: NbtTagType, name: &str, depth: usize, emit: bool) -> Result<(), RnbtError> {
+        match ty {
+            NbtTagType::Compound => {
+                check_depth(&self.cursor, depth)?;
+                let emit_content = self.emit(emit, NbtEvent::StartCompound(name));
+
+                loop {
+                    if self.stopped {
+                        return Ok(());
+                    }
+
+                    let ty = parse_tag_type(&mut self.cursor)?;
+                    if ty == NbtTagType::End {
+                        break;
+                    }
+
+                    let name = scan_string(&mut self.cursor)?;
+                    self.scan_value(ty, &name, depth + 1, emit_content)?;
+                }
+
+                self.emit(emit_content, NbtEvent::End);
+            },
+            NbtTagType::List => {
+                check_depth(&self.cursor, depth)?;
+
+                let type_offset = self.cursor.position();
+                let element_type = parse_tag_type(&mut self.cursor)?;
+                let len = read_checked(&mut self.cursor, 4, |cursor| cursor.read_i32::<BigEndian>())?;
+                let len = checked_length(&self.cursor, len as i64, min_payload_size(element_type))?;
+                if element_type == NbtTagType::End && len > 0 {
+                    return Err(corrupt(type_offset, RnbtError::InvalidTagType(0)));
+                }
+
+                let emit_content = self.emit(emit, NbtEvent::StartList { name, element_type, len });
+                for _ in 0..len {
+                    if self.stopped {
+                        return Ok(());
+                    }
+
+                    self.scan_value(element_type, "", depth + 1, emit_content)?;
+                }
+                if self.stopped {
+                    return Ok(());
+                }
+
+                self.emit(emit_content, NbtEvent::End);
+            },
+            _ => {
+                let value = self.scan_scalar(ty)?;
+                self.emit(emit, NbtEvent::Scalar(name, value));
+            },
+        }
+
+        Ok(())
+    }
+
+    fn scan_scalar(&mut self, ty: NbtTagType) -> Result<NbtScalar<'a>, RnbtError> {
+        let cursor = &mut self.cursor;
+        Ok(match ty {
+            NbtTagType::Byte => NbtScalar::Byte(read_checked(cursor, 1, |cursor| cursor.read_i8())?),
+            NbtTagType::Short => NbtScalar::Short(read_checked(cursor, 2, |cursor| cursor.read_i16::<BigEndian>())?),
+            NbtTagType::Int => NbtScalar::Int(read_checked(cursor, 4, |cursor| cursor.read_i32::<BigEndian>())?),
+            NbtTagType::Long => NbtScalar::Long(read_checked(cursor, 8, |cursor| cursor.read_i64::<BigEndian>())?),
+            NbtTagType::Float => NbtScalar::Float(read_checked(cursor, 4, |cursor| cursor.read_f32::<BigEndian>())?),
+            NbtTagType::Double => NbtScalar::Double(read_checked(cursor, 8, |cursor| cursor.read_f64::<BigEndian>())?),
+            NbtTagType::String => NbtScalar::String(scan_string(cursor)?),
+            NbtTagType::ByteArray => NbtScalar::ByteArray(scan_array(cursor)?),
+            NbtTagType::IntArray => NbtScalar::IntArray(scan_array(cursor)?),
+            NbtTagType::LongArray => NbtScalar::LongArray(scan_array(cursor)?),
+            NbtTagType::End => return Err(corrupt(cursor.position(), RnbtError::InvalidTagType(0))),
+            NbtTagType::List | NbtTagType::Compound => unreachable!(), // Handled by scan_value
+        })
+    }
+}
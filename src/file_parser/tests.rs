@@ -42,3 +42,426 @@ fn test_parse_bytes() {
     // assert!(result.is_ok());
     // assert_eq!(result.unwrap(), /* expected NbtTag value */);
 }
+
+/// Builds a minimal valid file: root compound "" with a byte array "a" of 3 elements and a string "s".
+fn sample_nbt() -> Vec<u8> {
+    let mut bytes = vec![10, 0, 0];
+    bytes.extend_from_slice(&[7, 0, 1, b'a', 0, 0, 0, 3, 1, 2, 3]);
+    bytes.extend_from_slice(&[8, 0, 1, b's', 0, 2, b'h', b'i']);
+    bytes.push(0);
+    bytes
+}
+
+#[test]
+fn test_parse_bytes_valid() {
+    let root = parse_bytes(&sample_nbt()).unwrap().compound().unwrap();
+    assert_eq!(root.values.get("a").unwrap().byte_array().unwrap().values, vec![1, 2, 3]);
+    assert_eq!(root.values.get("s").unwrap().string().unwrap().value, "hi");
+}
+
+#[test]
+fn test_parse_bytes_truncated() {
+    // every prefix of a valid file must be rejected without panicking
+    let bytes = sample_nbt();
+    for len in 0..bytes.len() {
+        let result = parse_bytes(&bytes[..len]);
+        assert!(matches!(result.unwrap_err().cause(), RnbtError::TruncatedData { .. }), "prefix of {} bytes", len);
+    }
+}
+
+#[test]
+fn test_parse_bytes_negative_length() {
+    // byte array with length -1
+    let bytes = [10, 0, 0, 7, 0, 1, b'a', 0xFF, 0xFF, 0xFF, 0xFF, 0];
+    assert_eq!(parse_bytes(&bytes).unwrap_err(), corrupt(11, RnbtError::TruncatedData { needed: -1, remaining: 1 }));
+
+    // list of ints with length i32::MIN
+    let bytes = [10, 0, 0, 9, 0, 1, b'l', 3, 0x80, 0, 0, 0, 0];
+    assert!(matches!(parse_bytes(&bytes).unwrap_err().cause(), RnbtError::TruncatedData { .. }));
+}
+
+#[test]
+fn test_parse_bytes_oversized_length() {
+    // long array declaring i32::MAX elements in a 1 byte buffer
+    let bytes = [10, 0, 0, 12, 0, 1, b'a', 0x7F, 0xFF, 0xFF, 0xFF, 0];
+    assert_eq!(parse_bytes(&bytes).unwrap_err(), corrupt(11, RnbtError::TruncatedData { needed: i32::MAX as i64 * 8, remaining: 1 }));
+
+    // string declaring more bytes than available
+    let bytes = [10, 0, 0, 8, 0, 1, b's', 0xFF, 0xFF, b'x', 0];
+    assert!(matches!(parse_bytes(&bytes).unwrap_err().cause(), RnbtError::TruncatedData { .. }));
+}
+
+#[test]
+fn test_parse_bytes_invalid_root() {
+    assert_eq!(parse_bytes(&[13]).unwrap_err(), corrupt(0, RnbtError::InvalidTagType(13)));
+    assert_eq!(parse_bytes(&[1, 0, 0, 5]).unwrap_err(), corrupt(0, RnbtError::TypeMismatch { expected: NbtTagType::Compound, found: NbtTagType::Byte }));
+}
+
+#[test]
+fn test_parse_nbt_bytes_error_offset() {
+    // the second tag of the nested compound "c" has the type 19
+    let bytes = [10, 0, 0,
+        10, 0, 1, b'c',
+        1, 0, 1, b'b', 1,
+        19, 0, 1, b'x',
+        0, 0];
+
+    let error = parse_nbt_bytes(&bytes).unwrap_err();
+    assert_eq!(error, corrupt(12, RnbtError::InvalidTagType(19)));
+    assert_eq!(error.offset(), Some(12));
+    assert_eq!(error.cause(), &RnbtError::InvalidTagType(19));
+    assert_eq!(error.to_string(), "Corrupt NBT at byte 0xC: Invalid tag type id 19");
+
+    // invalid Modified UTF-8 is reported at the first byte of the string
+    let bytes = [10, 0, 0, 8, 0, 1, b's', 0, 1, 0xFF, 0];
+    assert_eq!(parse_nbt_bytes(&bytes).unwrap_err().offset(), Some(9));
+}
+
+#[test]
+fn test_parse_nbt_bytes_max_depth() {
+    // compounds nested one level less than the limit are accepted
+    let nested = |levels: usize| {
+        let mut bytes = vec![10, 0, 0];
+        for _ in 0..levels {
+            bytes.extend_from_slice(&[10, 0, 1, b'c']);
+        }
+        bytes.extend(std::iter::repeat_n(0, levels + 1));
+        bytes
+    };
+
+    assert!(parse_nbt_bytes(&nested(MAX_NESTING_DEPTH - 1)).is_ok());
+    // the content of the innermost compound starts after the root and the nested headers
+    let offset = 3 + MAX_NESTING_DEPTH as u64 * 4;
+    assert_eq!(parse_nbt_bytes(&nested(MAX_NESTING_DEPTH)).unwrap_err(), corrupt(offset, RnbtError::MaxDepthExceeded(MAX_NESTING_DEPTH)));
+}
+
+#[test]
+fn test_parse_nbt_bytes_end_list() {
+    // an empty list of End is valid, a non-empty one is corrupt
+    let bytes = [10, 0, 0, 9, 0, 1, b'l', 0, 0, 0, 0, 0, 0];
+    assert_eq!(parse_nbt_bytes(&bytes).unwrap().values.get("l").unwrap().list().unwrap().values.len(), 0);
+
+    let bytes = [10, 0, 0, 9, 0, 1, b'l', 0, 0x7F, 0xFF, 0xFF, 0xFF, 0];
+    assert_eq!(parse_nbt_bytes(&bytes).unwrap_err(), corrupt(7, RnbtError::InvalidTagType(0)));
+}
+
+#[test]
+fn test_parse_nbt_bytes_end_is_structural() {
+    // the End closing a compound is never a value, even for an empty compound
+    let bytes = [10, 0, 0, 10, 0, 1, b'c', 0, 0];
+    let root = parse_nbt_bytes(&bytes).unwrap();
+    assert_eq!(root.values.len(), 1);
+    assert!(root.values.get("c").unwrap().compound().unwrap().values.is_empty());
+
+    // the empty list of End is written back as is
+    let bytes = [10, 0, 0, 9, 0, 1, b'l', 0, 0, 0, 0, 0, 0];
+    let root = parse_nbt_bytes(&bytes).unwrap();
+    assert_eq!(root.values.get("l").unwrap().list().unwrap().ty, NbtTagType::End);
+    let mut written = Vec::new();
+    crate::nbt_tag::write(&mut written, &root);
+    assert_eq!(written, bytes);
+}
+
+#[test]
+fn test_parse_nbt_bytes_random_mutations() {
+    // poor man's fuzzing: flip random bytes of a valid file, the parser must never panic
+    let original = sample_nbt();
+    let mut seed: u64 = 0x2545F4914F6CDD1D;
+    let mut next = || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    };
+
+    for _ in 0..10000 {
+        let mut bytes = original.clone();
+        for _ in 0..(next() % 4 + 1) {
+            let index = (next() as usize) % bytes.len();
+            bytes[index] = next() as u8;
+        }
+        let _ = parse_nbt_bytes(&bytes);
+    }
+}
+
+#[test]
+fn test_parse_bytes_arrays_big_endian() {
+    // int array "i" [1, 256] and long array "l" [1, 256, -2], as written by Java (big-endian)
+    let mut bytes = vec![10, 0, 0];
+    bytes.extend_from_slice(&[11, 0, 1, b'i', 0, 0, 0, 2]);
+    bytes.extend_from_slice(&[0, 0, 0, 1, 0, 0, 1, 0]);
+    bytes.extend_from_slice(&[12, 0, 1, b'l', 0, 0, 0, 3]);
+    bytes.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE]);
+    // list "s" of shorts [1, 256]
+    bytes.extend_from_slice(&[9, 0, 1, b's', 2, 0, 0, 0, 2, 0, 1, 1, 0]);
+    bytes.push(0);
+
+    let root = parse_nbt_bytes(&bytes).unwrap();
+    assert_eq!(root.values.get("i").unwrap().int_array().unwrap().values, vec![1, 256]);
+    assert_eq!(root.values.get("l").unwrap().long_array().unwrap().values, vec![1, 256, -2]);
+    let shorts: Vec<i16> = root.values.get("s").unwrap().list().unwrap().values.iter().map(|tag| i16::try_from(tag).unwrap()).collect();
+    assert_eq!(shorts, vec![1, 256]);
+
+    // the writer produces the same bytes (a single entry, so that the order of the compound does not matter)
+    for (name, tag) in root.iter() {
+        let mut single = NbtTagCompound::new("");
+        single.values.insert(name.clone(), tag.clone());
+        let mut written = Vec::new();
+        write(&mut written, &single);
+        assert!(bytes.windows(written.len() - 4).any(|window| window == &written[3..written.len() - 1]), "{}", name);
+    }
+}
+
+#[test]
+fn test_parse_nbt_bytes_duplicate_keys() {
+    // the root compound has the key "a" three times: Int 1, Int 2, Byte 3, and the key "a#2": Int 4
+    let bytes = [10, 0, 0,
+        3, 0, 1, b'a', 0, 0, 0, 1,
+        3, 0, 1, b'a', 0, 0, 0, 2,
+        3, 0, 3, b'a', b'#', b'2', 0, 0, 0, 4,
+        1, 0, 1, b'a', 3,
+        0];
+
+    let overwritten = parse_nbt_bytes(&bytes).unwrap();
+    assert_eq!(overwritten.values.len(), 2);
+    assert_eq!(overwritten.values["a"], NbtTag::Byte(NbtTagByte::new("a".to_string(), 3)));
+
+    let options = ParseOptions { duplicate_keys: DuplicateKeys::Error, ..ParseOptions::default() };
+    assert_eq!(parse_nbt_bytes_with_options(&bytes, &options).unwrap_err(), corrupt(11, RnbtError::DuplicateKey("a".to_string())));
+
+    let options = ParseOptions { duplicate_keys: DuplicateKeys::Collect, ..ParseOptions::default() };
+    let collected = parse_nbt_bytes_with_options(&bytes, &options).unwrap();
+    assert_eq!(collected.values.len(), 4);
+    assert_eq!(collected.values["a"], NbtTag::Int(NbtTagInt::new("a".to_string(), 1)));
+    assert_eq!(collected.values["a#2"], NbtTag::Int(NbtTagInt::new("a".to_string(), 2)));
+    assert_eq!(collected.values["a#3"], NbtTag::Byte(NbtTagByte::new("a".to_string(), 3)));
+
+    // a valid file is the same whatever the option
+    let valid = sample_nbt();
+    assert_eq!(parse_nbt_bytes_with_options(&valid, &options).unwrap(), parse_nbt_bytes(&valid).unwrap());
+}
+
+#[test]
+fn test_parse_nbt_bytes_little_endian() {
+    // root compound "r" with Short s = 1, Int i = 2, List l of 2 Int and String t = "x", all little-endian
+    let bytes = [10, 1, 0, b'r',
+        2, 1, 0, b's', 1, 0,
+        3, 1, 0, b'i', 2, 0, 0, 0,
+        9, 1, 0, b'l', 3, 2, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0,
+        8, 1, 0, b't', 1, 0, b'x',
+        0];
+
+    let options = ParseOptions { endianness: Endianness::Little, ..ParseOptions::default() };
+    let root = parse_nbt_bytes_with_options(&bytes, &options).unwrap();
+    assert_eq!(root.name, "r");
+    assert_eq!(root.values["s"], NbtTag::Short(NbtTagShort::new("s".to_string(), 1)));
+    assert_eq!(root.values["i"], NbtTag::Int(NbtTagInt::new("i".to_string(), 2)));
+    assert_eq!(root.values["l"].list().unwrap().values.len(), 2);
+    assert_eq!(root.values["t"], NbtTag::String(NbtTagString::new("t".to_string(), "x".to_string())));
+
+    // read as big-endian the name is 256 bytes long
+    assert!(matches!(parse_nbt_bytes(&bytes).unwrap_err().cause(), RnbtError::TruncatedData { .. }));
+}
+
+#[test]
+fn test_scan_nbt_bytes_events() {
+    let mut events = Vec::new();
+    scan_nbt_bytes(&sample_nbt(), |event| {
+        events.push(format!("{:?}", event));
+        ScanControl::Continue
+    }).unwrap();
+
+    assert_eq!(events, vec![
+        "StartCompound(\"\")".to_string(),
+        format!("{:?}", NbtEvent::Scalar("a", NbtScalar::ByteArray(NbtArray::new(&[1, 2, 3])))),
+        format!("{:?}", NbtEvent::Scalar("s", NbtScalar::String("hi".into()))),
+        "End".to_string(),
+    ]);
+}
+
+#[test]
+fn test_scan_nbt_bytes_skip_and_stop() {
+    // root { c: { x: 1b }, l: [1s, 2s], y: 2b }
+    let bytes = [10, 0, 0,
+        10, 0, 1, b'c', 1, 0, 1, b'x', 1, 0,
+        9, 0, 1, b'l', 2, 0, 0, 0, 2, 0, 1, 0, 2,
+        1, 0, 1, b'y', 2,
+        0];
+
+    // the content of the skipped compound is not reported, the list is
+    let mut names = Vec::new();
+    scan_nbt_bytes(&bytes, |event| match event {
+        NbtEvent::StartCompound("c") => ScanControl::Skip,
+        NbtEvent::StartList { name, element_type, len } => {
+            assert_eq!((element_type, len), (NbtTagType::Short, 2));
+            names.push(name.to_string());
+            ScanControl::Continue
+        },
+        NbtEvent::Scalar(name, value) => {
+            names.push(format!("{}={:?}", name, value));
+            ScanControl::Continue
+        },
+        _ => ScanControl::Continue,
+    }).unwrap();
+    assert_eq!(names, vec!["l", "=Short(1)", "=Short(2)", "y=Byte(2)"]);
+
+    // finding a tag stops the scan, the corrupt end of the buffer is never read
+    let mut found = None;
+    scan_nbt_bytes(&bytes[..bytes.len() - 6], |event| match event {
+        NbtEvent::Scalar("x", value) => {
+            found = Some(value.to_tag("x"));
+            ScanControl::Stop
+        },
+        _ => ScanControl::Continue,
+    }).unwrap();
+    assert_eq!(found, Some(NbtTag::Byte(NbtTagByte::new("x".to_string(), 1))));
+
+    // stopping on the last element of a list, the End of the list is not reported
+    let mut events = Vec::new();
+    scan_nbt_bytes(&bytes, |event| {
+        events.push(format!("{:?}", event));
+        match event {
+            NbtEvent::Scalar("", NbtScalar::Short(2)) => ScanControl::Stop,
+            _ => ScanControl::Continue,
+        }
+    }).unwrap();
+    assert_eq!(events.last(), Some(&format!("{:?}", NbtEvent::Scalar("", NbtScalar::Short(2)))));
+}
+
+#[test]
+fn test_scan_nbt_bytes_same_tree_and_errors() {
+    // the tree built from the events is the one of parse_nbt_bytes
+    let build = |bytes: &[u8]| -> Result<NbtTagCompound, RnbtError> {
+        let mut stack: Vec<NbtTag> = Vec::new();
+        let mut root = None;
+        scan_nbt_bytes(bytes, |event| {
+            let finished = match event {
+                NbtEvent::StartCompound(name) => {
+                    stack.push(NbtTag::Compound(NbtTagCompound::new(name)));
+                    None
+                },
+                NbtEvent::StartList { name, element_type, .. } => {
+                    stack.push(NbtTag::List(NbtTagList::new(name.to_string(), element_type, Vec::new())));
+                    None
+                },
+                NbtEvent::Scalar(name, value) => Some(value.to_tag(name)),
+                NbtEvent::End => stack.pop(),
+            };
+            if let Some(tag) = finished {
+                match stack.last_mut() {
+                    Some(NbtTag::Compound(parent)) => { parent.values.insert(tag.name().to_string(), tag); },
+                    Some(NbtTag::List(parent)) => parent.values.push(tag),
+                    _ => root = tag.compound(),
+                }
+            }
+            ScanControl::Continue
+        })?;
+        Ok(root.expect("the root compound"))
+    };
+
+    let bytes = sample_nbt();
+    assert_eq!(build(&bytes).unwrap(), parse_nbt_bytes(&bytes).unwrap());
+
+    // every error is the same as the tree parser
+    for len in 0..bytes.len() {
+        assert_eq!(scan_nbt_bytes(&bytes[..len], |_| ScanControl::Continue).unwrap_err(), parse_nbt_bytes(&bytes[..len]).unwrap_err());
+    }
+    let corrupt: [&[u8]; 4] = [
+        &[13],
+        &[1, 0, 0, 5],
+        &[10, 0, 0, 12, 0, 1, b'a', 0x7F, 0xFF, 0xFF, 0xFF, 0],
+        &[10, 0, 0, 9, 0, 1, b'l', 0, 0x7F, 0xFF, 0xFF, 0xFF, 0],
+    ];
+    for bytes in corrupt {
+        assert_eq!(scan_nbt_bytes(bytes, |_| ScanControl::Continue).unwrap_err(), parse_nbt_bytes(bytes).unwrap_err());
+    }
+
+    let mut nested = vec![10, 0, 0];
+    for _ in 0..MAX_NESTING_DEPTH {
+        nested.extend_from_slice(&[10, 0, 1, b'c']);
+    }
+    nested.extend(std::iter::repeat_n(0, MAX_NESTING_DEPTH + 1));
+    let error = scan_nbt_bytes(&nested, |_| ScanControl::Continue).unwrap_err();
+    assert_eq!(error.cause(), &RnbtError::MaxDepthExceeded(MAX_NESTING_DEPTH));
+    assert_eq!(error, parse_nbt_bytes(&nested).unwrap_err());
+}
+
+#[test]
+fn test_parse_nbt_bytes_keep_only() {
+    let compound = |name: &str, tags: Vec<NbtTag>| {
+        let mut compound = NbtTagCompound::new(name);
+        for tag in tags {
+            compound.values.insert(tag.name().to_string(), tag);
+        }
+        compound
+    };
+    let byte = |name: &str, value: i8| NbtTag::Byte(NbtTagByte::new(name.to_string(), value));
+    let light = |name: &str| NbtTag::ByteArray(NbtTagByteArray::new(name.to_string(), vec![15; 2048]));
+
+    let block_states = NbtTag::Compound(compound("block_states", vec![NbtTag::LongArray(NbtTagLongArray::new("data".to_string(), vec![1, 2]))]));
+    let sections = vec![
+        NbtTag::Compound(compound("", vec![byte("Y", 0), block_states.clone(), light("BlockLight")])),
+        NbtTag::Compound(compound("", vec![light("SkyLight")])),
+    ];
+    let chunk = compound("", vec![
+        NbtTag::Int(NbtTagInt::new("DataVersion".to_string(), 3465)),
+        NbtTag::Compound(compound("Heightmaps", vec![NbtTag::LongArray(NbtTagLongArray::new("WORLD_SURFACE".to_string(), vec![0; 37]))])),
+        NbtTag::List(NbtTagList::new("sections".to_string(), NbtTagType::Compound, sections)),
+        NbtTag::List(NbtTagList::new("Entities".to_string(), NbtTagType::End, Vec::new())),
+    ]);
+    let mut bytes = Vec::new();
+    write(&mut bytes, &chunk);
+
+    let options = ParseOptions { keep_only: vec!["block_states".to_string(), "Y".to_string()], ..ParseOptions::default() };
+    let pruned = parse_nbt_bytes_with_options(&bytes, &options).unwrap();
+
+    // the list keeps its indices, the section without kept tags is empty
+    let expected_sections = vec![NbtTag::Compound(compound("", vec![byte("Y", 0), block_states])), NbtTag::Compound(NbtTagCompound::new(""))];
+    let expected = compound("", vec![NbtTag::List(NbtTagList::new("sections".to_string(), NbtTagType::Compound, expected_sections))]);
+    assert_eq!(pruned, expected);
+
+    // a kept tag keeps its whole content
+    let options = ParseOptions { keep_only: vec!["Heightmaps".to_string()], ..ParseOptions::default() };
+    assert_eq!(parse_nbt_bytes_with_options(&bytes, &options).unwrap().values["Heightmaps"], chunk.values["Heightmaps"]);
+
+    let options = ParseOptions { keep_only: vec!["Missing".to_string()], ..ParseOptions::default() };
+    assert!(parse_nbt_bytes_with_options(&bytes, &options).unwrap().values.is_empty());
+}
+
+#[test]
+fn test_parse_nbt_bytes_unnamed_root() {
+    let mut root = NbtTagCompound::new("root");
+    root.values.insert("a".to_string(), NbtTag::Int(NbtTagInt::new("a".to_string(), 7)));
+
+    let named = root.to_binary_named();
+    let unnamed = root.to_binary_unnamed();
+    assert_eq!(unnamed, [&[10u8][..], &[3, 0, 1, b'a', 0, 0, 0, 7, 0]].concat());
+    assert_eq!(named.len(), unnamed.len() + 2 + "root".len());
+    assert_eq!(parse_nbt_bytes(&named).unwrap(), root);
+
+    let options = ParseOptions { unnamed_root: true, ..ParseOptions::default() };
+    let parsed = parse_nbt_bytes_with_options(&unnamed, &options).unwrap();
+    assert_eq!(parsed.name, "");
+    assert_eq!(parsed.values, root.values);
+
+    // the first content byte would be read as the length of the root name
+    assert!(parse_nbt_bytes(&unnamed).is_err());
+}
+
+#[test]
+fn test_parse_network_nbt_bytes() {
+    // a compound root without name: {a: 7}
+    let compound = [10, 3, 0, 1, b'a', 0, 0, 0, 7, 0];
+    let NbtTag::Compound(root) = parse_network_nbt_bytes(&compound).unwrap() else { panic!("the root is a compound") };
+    assert_eq!(root.name, "");
+    assert_eq!(root.values["a"], NbtTag::Int(NbtTagInt::new("a".to_string(), 7)));
+    assert_eq!(root.to_binary_unnamed(), compound);
+
+    // a text component sent as a string root, and the empty root
+    let text = [8, 0, 2, b'h', b'i'];
+    assert_eq!(parse_network_nbt_bytes(&text).unwrap(), NbtTag::String(NbtTagString::new(String::new(), "hi".to_string())));
+    assert_eq!(parse_network_nbt_bytes(&[0]).unwrap(), NbtTag::End);
+
+    assert!(matches!(parse_network_nbt_bytes(&compound[..5]), Err(RnbtError::CorruptNbt { .. })));
+    assert!(parse_network_nbt_bytes(&[]).is_err());
+}
@@ -5,12 +5,17 @@
 // - 2023-12-17
 //
 // ## File Version
-// - 1.0.0
+// - 1.0.5
 //
 // ## Changelog
 // - 1.0.0: Initial version
+// - 1.0.1: Added from_bytes to build a GenericBinFile from data already in memory [mrmarkolinus:2026-10-16]
+// - 1.0.2: Parse errors are propagated with their cause [mrmarkolinus:2026-10-16]
+// - 1.0.3: Added from_reader [mrmarkolinus:2026-10-16]
+// - 1.0.4: Added decompress, decode_binary_data without a file [mrmarkolinus:2026-10-16]
+// - 1.0.5: Added new_with_endianness, to force the byte order of the file [mrmarkolinus:2026-10-16]
 
-use crate::file_parser;
+use crate::file_parser::{self, Endianness, ParseOptions};
 use crate::nbt_tag::{NbtTag, NbtTagCompound};
 use std::io;
 use std::path::PathBuf;
@@ -18,6 +23,9 @@ use flate2::read::ZlibDecoder;
 use flate2::read::GzDecoder;
 use std::io::Read;
 
+#[cfg(test)]
+mod tests;
+
 pub enum FileType {
     Nbt,
     Region,
@@ -49,27 +57,48 @@ impl CompressionType {
 }
 
 pub struct GenericBinFile {
-    raw_data: Vec<u8>
+    raw_data: Vec<u8>,
+    endianness: Endianness,
 }
 
 impl GenericBinFile {
     pub fn new(file_path: PathBuf, file_type: FileType) -> io::Result<Self> {
+        Self::new_with_endianness(file_path, file_type, Endianness::default())
+    }
+
+    /// Same as `new`, parsing the file with the given byte order instead of the big-endian of Java Edition.
+    /// Meant for the files saved by tools that do not follow the format.
+    pub fn new_with_endianness(file_path: PathBuf, file_type: FileType, endianness: Endianness) -> io::Result<Self> {
         let bin_file = file_parser::FileParser::new(file_path, file_parser::ReadMode::EntireFile, file_type).read()?;
-        Ok(GenericBinFile { raw_data: bin_file})
+        Ok(GenericBinFile { raw_data: bin_file, endianness })
+    }
+
+    /// Creates a GenericBinFile from data already loaded in memory (e.g. read asynchronously or received from network).
+    pub fn from_bytes(raw_data: Vec<u8>) -> Self {
+        GenericBinFile { raw_data, endianness: Endianness::default() }
+    }
+
+    /// Creates a GenericBinFile from everything left to read in `reader` (e.g. a `Cursor<Vec<u8>>` in the tests).
+    pub fn from_reader<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut raw_data = Vec::new();
+        reader.read_to_end(&mut raw_data)?;
+        Ok(GenericBinFile { raw_data, endianness: Endianness::default() })
     }
 
     pub fn get_raw_data(&self) -> &Vec<u8> {
         &self.raw_data
     }
 
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
     pub fn to_tag(&self) -> std::io::Result<NbtTag> {
         let uncompressed_data = self.try_decode_data()?;
-        let root = match file_parser::parse_bytes(&uncompressed_data) {
-            Ok(nbt_tag) => nbt_tag,  // On success, return the NbtTag
-            Err(_) => return Err(std::io::Error::new(std::io::ErrorKind::Other, "Invalid NBT file")),
-        };
+        let options = ParseOptions { endianness: self.endianness, ..ParseOptions::default() };
+        let root = file_parser::parse_nbt_bytes_with_options(&uncompressed_data, &options)?;
 
-        Ok(root)
+        Ok(NbtTag::Compound(root))
     }
 
     pub fn to_tag_compound(&self) -> std::io::Result<NbtTagCompound> {
@@ -105,30 +134,35 @@ impl GenericBinFile {
     }
 
     pub fn decode_binary_data(&self, chunk_payload: &[u8], chunk_compression_method: &[u8]) -> io::Result<Vec<u8>> {
-        // Decompress chunk data
-        // acoording to minecraft wiki case Gzip and not compressed are not used in practice
-        // but they are officially supported
-        match CompressionType::from_u8(chunk_compression_method[0]) {
-            Some(CompressionType::Gzip) => {
-                // Gzip compression
-                let mut decoder = GzDecoder::new(chunk_payload);
-                let mut chunk_decompressed_payload = Vec::new();
-                decoder.read_to_end(&mut chunk_decompressed_payload)?;
-                Ok(chunk_decompressed_payload)
-            },
-            Some(CompressionType::Zlib) => { 
-                // Zlib compression
-                let mut decoder = ZlibDecoder::new(chunk_payload);
-                let mut chunk_decompressed_payload = Vec::new();
-                decoder.read_to_end(&mut chunk_decompressed_payload)?;
-                Ok(chunk_decompressed_payload)
-            },
-            Some(CompressionType::Uncompressed) => {
-                // Data is uncompressed
-                let chunk_decompressed_payload = chunk_payload.to_vec();
-                Ok(chunk_decompressed_payload)
-            },
-            _ => Err(io::Error::new(io::ErrorKind::InvalidInput, "Unknown compression format"))
-        }
+        decompress(chunk_payload, chunk_compression_method[0])
+    }
+}
+
+/// Decompresses data with the given compression method (the byte stored before each chunk in the region files).
+pub fn decompress(chunk_payload: &[u8], chunk_compression_method: u8) -> io::Result<Vec<u8>> {
+    // Decompress chunk data
+    // acoording to minecraft wiki case Gzip and not compressed are not used in practice
+    // but they are officially supported
+    match CompressionType::from_u8(chunk_compression_method) {
+        Some(CompressionType::Gzip) => {
+            // Gzip compression
+            let mut decoder = GzDecoder::new(chunk_payload);
+            let mut chunk_decompressed_payload = Vec::new();
+            decoder.read_to_end(&mut chunk_decompressed_payload)?;
+            Ok(chunk_decompressed_payload)
+        },
+        Some(CompressionType::Zlib) => { 
+            // Zlib compression
+            let mut decoder = ZlibDecoder::new(chunk_payload);
+            let mut chunk_decompressed_payload = Vec::new();
+            decoder.read_to_end(&mut chunk_decompressed_payload)?;
+            Ok(chunk_decompressed_payload)
+        },
+        Some(CompressionType::Uncompressed) => {
+            // Data is uncompressed
+            let chunk_decompressed_payload = chunk_payload.to_vec();
+            Ok(chunk_decompressed_payload)
+        },
+        _ => Err(io::Error::new(io::ErrorKind::InvalidInput, "Unknown compression format"))
     }
 }
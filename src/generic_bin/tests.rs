@@ -0,0 +1,41 @@
+#[cfg(test)]
+
+use super::*;
+use crate::nbt_tag::{write, NbtTagInt};
+use std::io::{Cursor, Write};
+
+#[test]
+fn test_generic_bin_from_reader() {
+    let mut compound = NbtTagCompound::new("");
+    compound.values.insert("DataVersion".to_string(), NbtTag::Int(NbtTagInt::new("DataVersion".to_string(), 3465)));
+    let mut nbt = Vec::new();
+    write(&mut nbt, &compound);
+
+    // uncompressed and gzip, as level.dat
+    let bin_file = GenericBinFile::from_reader(Cursor::new(nbt.clone())).unwrap();
+    assert_eq!(bin_file.to_tag_compound().unwrap(), compound);
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(&nbt).unwrap();
+    let bin_file = GenericBinFile::from_reader(Cursor::new(encoder.finish().unwrap())).unwrap();
+    assert_eq!(bin_file.to_tag_compound().unwrap(), compound);
+}
+
+#[test]
+fn test_generic_bin_new_with_endianness() {
+    // root compound with Int DataVersion = 3465, little-endian
+    let mut nbt = vec![10, 0, 0, 3, 11, 0];
+    nbt.extend_from_slice(b"DataVersion");
+    nbt.extend_from_slice(&3465i32.to_le_bytes());
+    nbt.push(0);
+    let file_path = std::env::temp_dir().join(format!("rnbt_little_endian_{}.nbt", std::process::id()));
+    std::fs::write(&file_path, &nbt).unwrap();
+
+    let bin_file = GenericBinFile::new_with_endianness(file_path.clone(), FileType::Nbt, Endianness::Little).unwrap();
+    assert_eq!(bin_file.endianness(), Endianness::Little);
+    let compound = bin_file.to_tag_compound().unwrap();
+    assert_eq!(compound.values["DataVersion"], NbtTag::Int(NbtTagInt::new("DataVersion".to_string(), 3465)));
+
+    assert!(GenericBinFile::new(file_path.clone(), FileType::Nbt).unwrap().to_tag_compound().is_err());
+    std::fs::remove_file(&file_path).unwrap();
+}
@@ -0,0 +1,269 @@
+// ## Author
+// - mrmarkolinus
+//
+// ## Date
+// - 2026-10-16
+//
+// ## File Version
+// - 1.0.4
+//
+// ## Changelog
+// - 1.0.0: Initial version
+// - 1.0.1: Items nested in bundles and 1.20.5+ containers, bounded nesting depth [mrmarkolinus:2026-10-16]
+// - 1.0.2: Any item whose tag holds an Items list is treated as a container [mrmarkolinus:2026-10-16]
+// - 1.0.3: Added the villager trades [mrmarkolinus:2026-10-16]
+// - 1.0.4: Added enchantments [mrmarkolinus:2026-10-16]
+
+use crate::blocks::Coordinates;
+use crate::chunk_format;
+use crate::nbt_tag::{NbtTag, NbtTagCompound};
+
+#[cfg(test)]
+mod tests;
+
+/// Maximum depth of items nested in other items (e.g. a bundle in a shulker box in a chest is at depth 2).
+/// Vanilla shulker boxes cannot contain shulker boxes, but bundles can contain bundles.
+pub const MAX_ITEM_NESTING_DEPTH: usize = 8;
+
+/// Entity ids of the villagers: namespaced since 1.11, `Villager` before.
+pub const VILLAGER_IDS: [&str; 2] = ["minecraft:villager", "Villager"];
+
+/// The enchantments by their numeric id, used before 1.13 (`tag/ench`).
+/// 
+/// https://minecraft.wiki/w/Java_Edition_data_values/Pre-flattening#Enchantment_IDs
+pub const LEGACY_ENCHANTMENT_IDS: &[(i16, &str)] = &[
+    (0, "minecraft:protection"), (1, "minecraft:fire_protection"), (2, "minecraft:feather_falling"),
+    (3, "minecraft:blast_protection"), (4, "minecraft:projectile_protection"), (5, "minecraft:respiration"),
+    (6, "minecraft:aqua_affinity"), (7, "minecraft:thorns"), (8, "minecraft:depth_strider"), (9, "minecraft:frost_walker"),
+    (10, "minecraft:binding_curse"), (16, "minecraft:sharpness"), (17, "minecraft:smite"), (18, "minecraft:bane_of_arthropods"),
+    (19, "minecraft:knockback"), (20, "minecraft:fire_aspect"), (21, "minecraft:looting"), (22, "minecraft:sweeping"),
+    (32, "minecraft:efficiency"), (33, "minecraft:silk_touch"), (34, "minecraft:unbreaking"), (35, "minecraft:fortune"),
+    (48, "minecraft:power"), (49, "minecraft:punch"), (50, "minecraft:flame"), (51, "minecraft:infinity"),
+    (61, "minecraft:luck_of_the_sea"), (62, "minecraft:lure"), (70, "minecraft:mending"), (71, "minecraft:vanishing_curse"),
+];
+
+/// An item stack of a trade.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ItemStack {
+    pub id: String,
+    pub count: u8,
+}
+
+/// A trade offered by a villager: `buy` (and `buy_b` if any) for `sell`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trade {
+    pub buy: ItemStack,
+    pub buy_b: Option<ItemStack>,
+    pub sell: ItemStack,
+    /// Number of times the trade was used since the last restock.
+    pub uses: i32,
+    pub max_uses: i32,
+}
+
+/// Returns the id and the count of an item stack compound.
+/// 
+/// The count is the `Count` byte, or the `count` int since 1.20.5 (capped to 255). 
+/// A missing count means a single item.
+pub fn get_item_id_and_count(item: &NbtTagCompound) -> Option<(String, u8)> {
+    let id = String::try_from(item.values.get("id")?).ok()?;
+
+    let count = match (item.values.get("Count"), item.values.get("count")) {
+        (Some(NbtTag::Byte(count)), _) => count.value as u8,
+        (_, Some(NbtTag::Int(count))) => count.value.clamp(0, u8::MAX as i32) as u8,
+        _ => 1,
+    };
+
+    Some((id, count))
+}
+
+/// Returns all the item stacks stored in a chunk, with the coordinates of their container.
+/// 
+/// The items are read from:
+/// - the `Items` of the block entities (chests, barrels, hoppers, furnaces, shulker boxes...)
+/// - the `Items` and `Inventory` of the entities (chest minecarts, donkeys, villagers...) and the single `Item`
+///   of dropped items and item frames
+/// 
+/// The content of the items holding other items (shulker boxes, bundles) is included, at the coordinates of the container,
+/// up to `MAX_ITEM_NESTING_DEPTH` levels.
+pub fn chunk_items(chunk_compound: &NbtTagCompound) -> Vec<(Coordinates, String, u8)> {
+    let mut items = Vec::new();
+
+    for block_entity in chunk_format::get_block_entities(chunk_compound) {
+        if let Some(coordinates) = chunk_format::get_block_entity_coordinates(block_entity) {
+            collect_items_list(block_entity, "Items", &coordinates, 0, &mut items);
+        }
+    }
+
+    for entity in chunk_format::get_entities(chunk_compound) {
+        if let Some(coordinates) = chunk_format::get_entity_coordinates(entity) {
+            collect_items_list(entity, "Items", &coordinates, 0, &mut items);
+            collect_items_list(entity, "Inventory", &coordinates, 0, &mut items);
+            if let Some(item) = entity.values.get("Item").and_then(|tag| tag.compound_as_ref()) {
+                collect_item(item, &coordinates, 0, &mut items);
+            }
+        }
+    }
+
+    items
+}
+
+/// Returns the trades of a villager entity, read from its `Offers/Recipes` list.
+/// 
+/// Returns `None` if the entity is not a villager (see `VILLAGER_IDS`), and an empty list for the villagers
+/// without trades yet (no profession or never seen by a player). The recipes without `buy` or `sell` item are skipped.
+pub fn villager_trades(entity: &NbtTagCompound) -> Option<Vec<Trade>> {
+    let id = String::try_from(entity.values.get("id")?).ok()?;
+    if !VILLAGER_IDS.contains(&id.as_str()) {
+        return None;
+    }
+
+    let recipes = get_compounds_list(get_compound(entity, "Offers"), "Recipes");
+    Some(recipes.into_iter().filter_map(get_trade).collect())
+}
+
+/// Returns the villagers of a chunk (or of an `entities` region file since 1.17) with their coordinates and their trades.
+pub fn chunk_villager_trades(chunk_compound: &NbtTagCompound) -> Vec<(Coordinates, Vec<Trade>)> {
+    chunk_format::get_entities(chunk_compound).into_iter()
+        .filter_map(|entity| Some((chunk_format::get_entity_coordinates(entity)?, villager_trades(entity)?)))
+        .collect()
+}
+
+fn get_trade(recipe: &NbtTagCompound) -> Option<Trade> {
+    let int = |name: &str| recipe.values.get(name).and_then(|tag| i32::try_from(tag).ok()).unwrap_or(0);
+
+    Some(Trade {
+        buy: get_item_stack(get_compound(recipe, "buy")?)?,
+        buy_b: get_compound(recipe, "buyB").and_then(get_item_stack),
+        sell: get_item_stack(get_compound(recipe, "sell")?)?,
+        uses: int("uses"),
+        max_uses: int("maxUses"),
+    })
+}
+
+/// Returns the item stack, `None` for the empty stacks (air or no items) used for the missing second price.
+fn get_item_stack(item: &NbtTagCompound) -> Option<ItemStack> {
+    let (id, count) = get_item_id_and_count(item)?;
+    if id == "minecraft:air" || count == 0 {
+        return None;
+    }
+
+    Some(ItemStack { id, count })
+}
+
+/// Returns the enchantments of an item stack as (id, level) pairs, empty for the items without enchantments.
+/// 
+/// The enchantments are read from `tag/Enchantments` (string ids, since 1.13) or `tag/ench` (numeric ids, before 1.13,
+/// named after `LEGACY_ENCHANTMENT_IDS`, the unknown ones are kept as the number), and from the `levels`
+/// of `components/minecraft:enchantments` since 1.20.5. The enchantments stored in the enchanted books
+/// (`StoredEnchantments`, `minecraft:stored_enchantments`) are included, so a Mending book returns `minecraft:mending`.
+pub fn enchantments(item: &NbtTagCompound) -> Vec<(String, i32)> {
+    let mut enchantments = Vec::new();
+
+    let tag = get_compound(item, "tag");
+    for list_name in ["Enchantments", "StoredEnchantments", "ench"] {
+        for enchantment in get_compounds_list(tag, list_name) {
+            let id = match enchantment.values.get("id") {
+                Some(NbtTag::String(id)) => id.value.clone(),
+                Some(id) => match get_integer(id) {
+                    Some(numeric_id) => legacy_enchantment_name(numeric_id),
+                    None => continue,
+                },
+                None => continue,
+            };
+            let level = enchantment.values.get("lvl").and_then(get_integer).unwrap_or(1);
+            enchantments.push((id, level));
+        }
+    }
+
+    if let Some(components) = get_compound(item, "components") {
+        for component_name in ["minecraft:enchantments", "minecraft:stored_enchantments"] {
+            let Some(levels) = get_compound(components, component_name).and_then(|component| get_compound(component, "levels")) else {
+                continue;
+            };
+            for (id, level) in levels.iter() {
+                if let Some(level) = get_integer(level) {
+                    enchantments.push((id.clone(), level));
+                }
+            }
+        }
+    }
+
+    enchantments
+}
+
+fn legacy_enchantment_name(numeric_id: i32) -> String {
+    LEGACY_ENCHANTMENT_IDS.iter()
+        .find(|(id, _)| *id as i32 == numeric_id)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| numeric_id.to_string())
+}
+
+/// Reads a Byte, Short or Int tag: the enchantment ids and levels are Shorts in the files, but Ints when written by commands.
+fn get_integer(tag: &NbtTag) -> Option<i32> {
+    match tag {
+        NbtTag::Byte(value) => Some(value.value as i32),
+        NbtTag::Short(value) => Some(value.value as i32),
+        NbtTag::Int(value) => Some(value.value),
+        _ => None,
+    }
+}
+
+/// Returns the items nested in an item stack.
+/// 
+/// Before 1.20.5 any item holding an `Items` list in its `tag`, directly or in one of its compounds, is a container:
+/// bundles use `tag/Items`, shulker boxes (and any block item that keeps its content) `tag/BlockEntityTag/Items`.
+/// Since 1.20.5 the content is in `components/minecraft:container` (each entry wraps the stack in `item`)
+/// and `components/minecraft:bundle_contents`.
+pub fn get_nested_items(item: &NbtTagCompound) -> Vec<&NbtTagCompound> {
+    let tag = get_compound(item, "tag");
+    let components = get_compound(item, "components");
+
+    let mut nested_items = get_compounds_list(tag, "Items");
+    if let Some(tag) = tag {
+        for (_, value) in tag.iter() {
+            nested_items.extend(get_compounds_list(value.compound_as_ref(), "Items"));
+        }
+    }
+    nested_items.extend(get_compounds_list(components, "minecraft:container").into_iter()
+                            .filter_map(|slot| get_compound(slot, "item")));
+    nested_items.extend(get_compounds_list(components, "minecraft:bundle_contents"));
+
+    nested_items
+}
+
+fn get_compound<'a>(compound: &'a NbtTagCompound, name: &str) -> Option<&'a NbtTagCompound> {
+    compound.values.get(name)?.compound_as_ref()
+}
+
+fn get_compounds_list<'a>(compound: Option<&'a NbtTagCompound>, name: &str) -> Vec<&'a NbtTagCompound> {
+    compound.and_then(|compound| compound.values.get(name))
+        .and_then(|tag| tag.list_as_ref())
+        .map(|list| list.values.iter().filter_map(|tag| tag.compound_as_ref()).collect())
+        .unwrap_or_default()
+}
+
+/// Collects the items of the list `list_name` of the compound.
+fn collect_items_list(compound: &NbtTagCompound, list_name: &str, coordinates: &Coordinates, depth: usize, items: &mut Vec<(Coordinates, String, u8)>) {
+    let Some(items_list) = compound.values.get(list_name).and_then(|tag| tag.list_as_ref()) else {
+        return;
+    };
+
+    for item in items_list.values.iter().filter_map(|tag| tag.compound_as_ref()) {
+        collect_item(item, coordinates, depth, items);
+    }
+}
+
+/// Collects an item stack and the items nested inside it.
+fn collect_item(item: &NbtTagCompound, coordinates: &Coordinates, depth: usize, items: &mut Vec<(Coordinates, String, u8)>) {
+    let Some((id, count)) = get_item_id_and_count(item) else {
+        return;
+    };
+    items.push((coordinates.clone(), id, count));
+
+    if depth >= MAX_ITEM_NESTING_DEPTH {
+        return;
+    }
+    for nested_item in get_nested_items(item) {
+        collect_item(nested_item, coordinates, depth + 1, items);
+    }
+}
@@ -0,0 +1,265 @@
+#[cfg(test)]
+
+use super::*;
+use crate::nbt_tag::*;
+
+fn item(id: &str, count: i8) -> NbtTag {
+    let mut item = NbtTagCompound::new("");
+    item.values.insert("id".to_string(), NbtTag::String(NbtTagString::new("id".to_string(), id.to_string())));
+    item.values.insert("Count".to_string(), NbtTag::Byte(NbtTagByte::new("Count".to_string(), count)));
+    NbtTag::Compound(item)
+}
+
+fn list(name: &str, values: Vec<NbtTag>) -> NbtTag {
+    NbtTag::List(NbtTagList::new(name.to_string(), NbtTagType::Compound, values))
+}
+
+fn shulker_box(items: Vec<NbtTag>) -> NbtTag {
+    let mut block_entity_tag = NbtTagCompound::new("BlockEntityTag");
+    block_entity_tag.values.insert("Items".to_string(), list("Items", items));
+    let mut tag = NbtTagCompound::new("tag");
+    tag.values.insert("BlockEntityTag".to_string(), NbtTag::Compound(block_entity_tag));
+
+    let NbtTag::Compound(mut shulker_box) = item("minecraft:shulker_box", 1) else { unreachable!() };
+    shulker_box.values.insert("tag".to_string(), NbtTag::Compound(tag));
+    NbtTag::Compound(shulker_box)
+}
+
+fn chest(x: i32, y: i32, z: i32, items: Vec<NbtTag>) -> NbtTag {
+    let mut chest = NbtTagCompound::new("");
+    chest.values.insert("id".to_string(), NbtTag::String(NbtTagString::new("id".to_string(), "minecraft:chest".to_string())));
+    for (name, value) in [("x", x), ("y", y), ("z", z)] {
+        chest.values.insert(name.to_string(), NbtTag::Int(NbtTagInt::new(name.to_string(), value)));
+    }
+    chest.values.insert("Items".to_string(), list("Items", items));
+    NbtTag::Compound(chest)
+}
+
+fn villager(pos: [f64; 3], inventory: Vec<NbtTag>) -> NbtTag {
+    let mut villager = NbtTagCompound::new("");
+    let pos = pos.iter().map(|value| NbtTag::Double(NbtTagDouble::new("".to_string(), *value))).collect();
+    villager.values.insert("Pos".to_string(), NbtTag::List(NbtTagList::new("Pos".to_string(), NbtTagType::Double, pos)));
+    villager.values.insert("Inventory".to_string(), list("Inventory", inventory));
+    NbtTag::Compound(villager)
+}
+
+#[test]
+fn test_get_item_id_and_count() {
+    let NbtTag::Compound(mut diamond) = item("minecraft:diamond", 64) else { unreachable!() };
+    assert_eq!(get_item_id_and_count(&diamond), Some(("minecraft:diamond".to_string(), 64)));
+
+    // 1.20.5+ count
+    diamond.values.remove("Count");
+    diamond.values.insert("count".to_string(), NbtTag::Int(NbtTagInt::new("count".to_string(), 12)));
+    assert_eq!(get_item_id_and_count(&diamond), Some(("minecraft:diamond".to_string(), 12)));
+
+    diamond.values.remove("count");
+    assert_eq!(get_item_id_and_count(&diamond), Some(("minecraft:diamond".to_string(), 1)));
+    assert_eq!(get_item_id_and_count(&NbtTagCompound::new("")), None);
+}
+
+#[test]
+fn test_chunk_items() {
+    let mut chunk = NbtTagCompound::new("");
+    chunk.values.insert("block_entities".to_string(), list("block_entities", vec![
+        chest(10, 64, -3, vec![item("minecraft:diamond", 5), shulker_box(vec![item("minecraft:diamond", 3)])]),
+    ]));
+    chunk.values.insert("Entities".to_string(), list("Entities", vec![
+        villager([1.5, -2.5, 3.0], vec![item("minecraft:bread", 4)]),
+    ]));
+
+    let chest_pos = Coordinates::new(vec![10, 64, -3]);
+    let villager_pos = Coordinates::new(vec![1, -3, 3]);
+    assert_eq!(chunk_items(&chunk), vec![
+        (chest_pos.clone(), "minecraft:diamond".to_string(), 5),
+        (chest_pos.clone(), "minecraft:shulker_box".to_string(), 1),
+        (chest_pos, "minecraft:diamond".to_string(), 3),
+        (villager_pos, "minecraft:bread".to_string(), 4),
+    ]);
+}
+
+#[test]
+fn test_chunk_items_pre_1_18() {
+    let mut level = NbtTagCompound::new("Level");
+    level.values.insert("TileEntities".to_string(), list("TileEntities", vec![chest(0, 5, 0, vec![item("minecraft:coal", 1)])]));
+    let mut chunk = NbtTagCompound::new("");
+    chunk.values.insert("Level".to_string(), NbtTag::Compound(level));
+
+    assert_eq!(chunk_items(&chunk), vec![(Coordinates::new(vec![0, 5, 0]), "minecraft:coal".to_string(), 1)]);
+}
+
+fn bundle(items: Vec<NbtTag>) -> NbtTag {
+    let mut tag = NbtTagCompound::new("tag");
+    tag.values.insert("Items".to_string(), list("Items", items));
+    let NbtTag::Compound(mut bundle) = item("minecraft:bundle", 1) else { unreachable!() };
+    bundle.values.insert("tag".to_string(), NbtTag::Compound(tag));
+    NbtTag::Compound(bundle)
+}
+
+#[test]
+fn test_nested_items_bundle_in_shulker() {
+    let mut chunk = NbtTagCompound::new("");
+    chunk.values.insert("block_entities".to_string(), list("block_entities", vec![
+        chest(0, 0, 0, vec![shulker_box(vec![bundle(vec![item("minecraft:diamond", 2)])])]),
+    ]));
+
+    let ids: Vec<String> = chunk_items(&chunk).into_iter().map(|(_, id, _)| id).collect();
+    assert_eq!(ids, vec!["minecraft:shulker_box", "minecraft:bundle", "minecraft:diamond"]);
+}
+
+#[test]
+fn test_nested_items_components() {
+    // 1.20.5+ shulker box: components/minecraft:container is a list of {slot, item}
+    let mut slot = NbtTagCompound::new("");
+    slot.values.insert("slot".to_string(), NbtTag::Int(NbtTagInt::new("slot".to_string(), 0)));
+    slot.values.insert("item".to_string(), item("minecraft:emerald", 9));
+    let mut components = NbtTagCompound::new("components");
+    components.values.insert("minecraft:container".to_string(), list("minecraft:container", vec![NbtTag::Compound(slot)]));
+    components.values.insert("minecraft:bundle_contents".to_string(), list("minecraft:bundle_contents", vec![item("minecraft:stick", 1)]));
+    let NbtTag::Compound(mut container) = item("minecraft:shulker_box", 1) else { unreachable!() };
+    container.values.insert("components".to_string(), NbtTag::Compound(components));
+
+    let nested_ids: Vec<String> = get_nested_items(&container).into_iter().filter_map(get_item_id_and_count).map(|(id, _)| id).collect();
+    assert_eq!(nested_ids, vec!["minecraft:emerald", "minecraft:stick"]);
+}
+
+#[test]
+fn test_nested_items_depth_bound() {
+    // bundles nested deeper than the limit are not explored
+    let mut nested = item("minecraft:diamond", 1);
+    for _ in 0..MAX_ITEM_NESTING_DEPTH + 2 {
+        nested = bundle(vec![nested]);
+    }
+    let mut chunk = NbtTagCompound::new("");
+    chunk.values.insert("block_entities".to_string(), list("block_entities", vec![chest(0, 0, 0, vec![nested])]));
+
+    let items = chunk_items(&chunk);
+    assert_eq!(items.len(), MAX_ITEM_NESTING_DEPTH + 1);
+    assert!(items.iter().all(|(_, id, _)| id == "minecraft:bundle"));
+}
+
+#[test]
+fn test_nested_items_bundle_in_chest() {
+    let mut chunk = NbtTagCompound::new("");
+    chunk.values.insert("block_entities".to_string(), list("block_entities", vec![
+        chest(3, 64, -7, vec![bundle(vec![item("minecraft:arrow", 16), item("minecraft:string", 3)])]),
+    ]));
+
+    let coordinates = Coordinates::new(vec![3, 64, -7]);
+    assert_eq!(chunk_items(&chunk), vec![
+        (coordinates.clone(), "minecraft:bundle".to_string(), 1),
+        (coordinates.clone(), "minecraft:arrow".to_string(), 16),
+        (coordinates, "minecraft:string".to_string(), 3),
+    ]);
+}
+
+#[test]
+fn test_nested_items_any_items_list() {
+    // items keeping an Items list in any compound of their tag are containers, not only shulker boxes
+    let mut contents = NbtTagCompound::new("Contents");
+    contents.values.insert("Items".to_string(), list("Items", vec![item("minecraft:gold_ingot", 5)]));
+    let mut tag = NbtTagCompound::new("tag");
+    tag.values.insert("Contents".to_string(), NbtTag::Compound(contents));
+    let NbtTag::Compound(mut backpack) = item("mod:backpack", 1) else { unreachable!() };
+    backpack.values.insert("tag".to_string(), NbtTag::Compound(tag));
+
+    let nested_ids: Vec<String> = get_nested_items(&backpack).into_iter().filter_map(get_item_id_and_count).map(|(id, _)| id).collect();
+    assert_eq!(nested_ids, vec!["minecraft:gold_ingot"]);
+}
+
+fn recipe(buy: NbtTag, buy_b: NbtTag, sell: NbtTag, uses: i32) -> NbtTag {
+    let mut recipe = NbtTagCompound::new("");
+    for (name, mut stack) in [("buy", buy), ("buyB", buy_b), ("sell", sell)] {
+        if let NbtTag::Compound(stack) = &mut stack {
+            stack.name = name.to_string();
+        }
+        recipe.values.insert(name.to_string(), stack);
+    }
+    recipe.values.insert("uses".to_string(), NbtTag::Int(NbtTagInt::new("uses".to_string(), uses)));
+    recipe.values.insert("maxUses".to_string(), NbtTag::Int(NbtTagInt::new("maxUses".to_string(), 12)));
+    NbtTag::Compound(recipe)
+}
+
+fn with_id(entity: NbtTag, id: &str) -> NbtTag {
+    let NbtTag::Compound(mut entity) = entity else { unreachable!() };
+    entity.values.insert("id".to_string(), NbtTag::String(NbtTagString::new("id".to_string(), id.to_string())));
+    NbtTag::Compound(entity)
+}
+
+#[test]
+fn test_chunk_villager_trades() {
+    let NbtTag::Compound(mut librarian) = with_id(villager([0.5, 64.0, 0.5], vec![]), "minecraft:villager") else { unreachable!() };
+    let mut offers = NbtTagCompound::new("Offers");
+    offers.values.insert("Recipes".to_string(), list("Recipes", vec![
+        // the empty second price is saved as air
+        recipe(item("minecraft:paper", 24), item("minecraft:air", 0), item("minecraft:emerald", 1), 3),
+        recipe(item("minecraft:emerald", 9), item("minecraft:book", 1), item("minecraft:enchanted_book", 1), 0),
+    ]));
+    librarian.values.insert("Offers".to_string(), NbtTag::Compound(offers));
+
+    let mut chunk = NbtTagCompound::new("");
+    chunk.values.insert("Entities".to_string(), list("Entities", vec![
+        NbtTag::Compound(librarian),
+        // unemployed villager without Offers, and a chest minecart
+        with_id(villager([-1.5, 70.0, 2.0], vec![]), "minecraft:villager"),
+        with_id(villager([3.0, 64.0, 3.0], vec![item("minecraft:coal", 1)]), "minecraft:chest_minecart"),
+    ]));
+
+    let stack = |id: &str, count: u8| ItemStack { id: id.to_string(), count };
+    assert_eq!(chunk_villager_trades(&chunk), vec![
+        (Coordinates::new(vec![0, 64, 0]), vec![
+            Trade { buy: stack("minecraft:paper", 24), buy_b: None, sell: stack("minecraft:emerald", 1), uses: 3, max_uses: 12 },
+            Trade { buy: stack("minecraft:emerald", 9), buy_b: Some(stack("minecraft:book", 1)), sell: stack("minecraft:enchanted_book", 1), uses: 0, max_uses: 12 },
+        ]),
+        (Coordinates::new(vec![-2, 70, 2]), vec![]),
+    ]);
+}
+
+fn enchantment(id: NbtTag, level: i16) -> NbtTag {
+    let mut enchantment = NbtTagCompound::new("");
+    enchantment.values.insert("id".to_string(), id);
+    enchantment.values.insert("lvl".to_string(), NbtTag::Short(NbtTagShort::new("lvl".to_string(), level)));
+    NbtTag::Compound(enchantment)
+}
+
+fn with_tag(item_tag: NbtTag, list_name: &str, enchantments: Vec<NbtTag>) -> NbtTagCompound {
+    let NbtTag::Compound(mut item) = item_tag else { unreachable!() };
+    let mut tag = NbtTagCompound::new("tag");
+    tag.values.insert(list_name.to_string(), list(list_name, enchantments));
+    item.values.insert("tag".to_string(), NbtTag::Compound(tag));
+    item
+}
+
+#[test]
+fn test_enchantments() {
+    let string_id = |id: &str| NbtTag::String(NbtTagString::new("id".to_string(), id.to_string()));
+    let numeric_id = |id: i16| NbtTag::Short(NbtTagShort::new("id".to_string(), id));
+
+    let pickaxe = with_tag(item("minecraft:diamond_pickaxe", 1), "Enchantments", 
+                           vec![enchantment(string_id("minecraft:efficiency"), 5), enchantment(string_id("minecraft:unbreaking"), 3)]);
+    assert_eq!(enchantments(&pickaxe), vec![("minecraft:efficiency".to_string(), 5), ("minecraft:unbreaking".to_string(), 3)]);
+
+    let book = with_tag(item("minecraft:enchanted_book", 1), "StoredEnchantments", vec![enchantment(string_id("minecraft:mending"), 1)]);
+    assert_eq!(enchantments(&book), vec![("minecraft:mending".to_string(), 1)]);
+
+    // before 1.13, unknown numeric ids are kept as the number
+    let sword = with_tag(item("minecraft:diamond_sword", 1), "ench", vec![enchantment(numeric_id(16), 4), enchantment(numeric_id(99), 1)]);
+    assert_eq!(enchantments(&sword), vec![("minecraft:sharpness".to_string(), 4), ("99".to_string(), 1)]);
+
+    let NbtTag::Compound(diamond) = item("minecraft:diamond", 1) else { unreachable!() };
+    assert!(enchantments(&diamond).is_empty());
+}
+
+#[test]
+fn test_enchantments_components() {
+    let mut levels = NbtTagCompound::new("levels");
+    levels.values.insert("minecraft:mending".to_string(), NbtTag::Int(NbtTagInt::new("minecraft:mending".to_string(), 1)));
+    let mut stored_enchantments = NbtTagCompound::new("minecraft:stored_enchantments");
+    stored_enchantments.values.insert("levels".to_string(), NbtTag::Compound(levels));
+    let mut components = NbtTagCompound::new("components");
+    components.values.insert("minecraft:stored_enchantments".to_string(), NbtTag::Compound(stored_enchantments));
+
+    let NbtTag::Compound(mut book) = item("minecraft:enchanted_book", 1) else { unreachable!() };
+    book.values.insert("components".to_string(), NbtTag::Compound(components));
+    assert_eq!(enchantments(&book), vec![("minecraft:mending".to_string(), 1)]);
+}
@@ -0,0 +1,188 @@
+// ## Author
+// - mrmarkolinus
+//
+// ## Date
+// - 2026-10-16
+//
+// ## File Version
+// - 1.0.2
+//
+// ## Changelog
+// - 1.0.0: Initial version
+// - 1.0.1: Added WorldSummary, the world details shown by launchers [mrmarkolinus:2026-10-16]
+// - 1.0.2: WorldMetadata::read also reads the little-endian level.dat of Bedrock Edition [mrmarkolinus:2026-10-16]
+
+//! The world metadata stored in `level.dat`.
+//!
+//! https://minecraft.wiki/w/Java_Edition_level_format#level.dat_format
+//!
+//! `level.dat` is a gzip compressed NBT file, whose root compound holds a single `Data` compound with
+//! the world name, seed, game mode, version and so on.
+//!
+//! Bedrock Edition writes `level.dat` uncompressed and little-endian, after an 8 bytes header (the storage version
+//! and the length of the NBT data), and its root compound holds the fields directly.
+
+use crate::file_parser::{self, Endianness, ParseOptions};
+use crate::generic_bin::GenericBinFile;
+use crate::nbt_tag::{NbtTag, NbtTagCompound, NbtTagType};
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+pub const LEVEL_DAT_FILE_NAME: &str = "level.dat";
+pub const SESSION_LOCK_FILE_NAME: &str = "session.lock";
+pub const ICON_FILE_NAME: &str = "icon.png";
+/// Length of the header before the NBT data in the `level.dat` of Bedrock Edition.
+pub const BEDROCK_HEADER_LENGTH: usize = 8;
+
+/// The content of `level.dat`, with the presence of the other world files useful to list the worlds.
+#[derive(Clone, Debug, Default)]
+pub struct WorldMetadata {
+    /// The `Data` compound of `level.dat`.
+    pub data: NbtTagCompound,
+    /// The world folder has a `session.lock`, the world may be open in a game or a server.
+    pub has_session_lock: bool,
+    /// The world folder has an `icon.png`.
+    pub has_icon: bool,
+}
+
+/// The details of a world shown in a world list, see `WorldMetadata::summary`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorldSummary {
+    /// The `LevelName` of the world, or the name of its folder when missing.
+    pub name: String,
+    pub seed: Option<i64>,
+    /// The default game mode: 0 survival, 1 creative, 2 adventure, 3 spectator.
+    pub game_type: Option<i32>,
+    pub hardcore: bool,
+    /// The Minecraft version that last saved the world (e.g. `1.20.1`), since 1.9.
+    pub version_name: Option<String>,
+    /// The Unix time in milliseconds when the world was last played.
+    pub last_played: Option<i64>,
+    /// The world folder has an `icon.png`.
+    pub has_icon: bool,
+}
+
+impl WorldMetadata {
+    /// Reads the metadata of a world, given either the world folder or the `level.dat` file.
+    /// Only `level.dat` is parsed, the region files are not touched.
+    /// 
+    /// The `level.dat` of Bedrock Edition is recognized by its header, see `is_bedrock_level_dat`.
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let level_dat_path = level_dat_path(path);
+        let data = fs::read(&level_dat_path)?;
+
+        let mut metadata = if is_bedrock_level_dat(&data) {
+            Self::from_bedrock_level_dat(&data)?
+        }
+        else {
+            Self::from_level_dat(GenericBinFile::from_bytes(data).to_tag_compound()?)?
+        };
+        if let Some(world_path) = level_dat_path.parent() {
+            metadata.has_session_lock = world_path.join(SESSION_LOCK_FILE_NAME).is_file();
+            metadata.has_icon = world_path.join(ICON_FILE_NAME).is_file();
+        }
+
+        Ok(metadata)
+    }
+
+    /// Builds the metadata from the root compound of `level.dat`.
+    pub fn from_level_dat(mut root: NbtTagCompound) -> io::Result<Self> {
+        match root.values.remove("Data") {
+            Some(NbtTag::Compound(data)) => Ok(Self { data, ..Default::default() }),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, "level.dat has no Data compound")),
+        }
+    }
+
+    /// Builds the metadata from the content of a Bedrock Edition `level.dat`, header included.
+    /// The fields shared with Java Edition (`LevelName`, `RandomSeed`, `GameType`, spawn) have the same accessors.
+    pub fn from_bedrock_level_dat(data: &[u8]) -> io::Result<Self> {
+        if !is_bedrock_level_dat(data) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a Bedrock Edition level.dat"));
+        }
+
+        let options = ParseOptions { endianness: Endianness::Little, ..ParseOptions::default() };
+        let data = file_parser::parse_nbt_bytes_with_options(&data[BEDROCK_HEADER_LENGTH..], &options)?;
+        Ok(Self { data, ..Default::default() })
+    }
+
+    /// Returns the name of the world shown in the world list.
+    pub fn level_name(&self) -> Option<&str> {
+        self.data.get_path("LevelName").and_then(|tag| <&str>::try_from(tag).ok())
+    }
+
+    /// Returns the world seed, stored in `WorldGenSettings` since 1.16 and in `RandomSeed` before.
+    pub fn seed(&self) -> Option<i64> {
+        self.data.get_path("WorldGenSettings/seed")
+            .or_else(|| self.data.get_path("RandomSeed"))
+            .and_then(|tag| i64::try_from(tag).ok())
+    }
+
+    /// Returns the default game mode: 0 survival, 1 creative, 2 adventure, 3 spectator.
+    pub fn game_type(&self) -> Option<i32> {
+        self.data.get_path("GameType").and_then(|tag| i32::try_from(tag).ok())
+    }
+
+    pub fn hardcore(&self) -> bool {
+        self.data.get_path("hardcore").and_then(|tag| i8::try_from(tag).ok()).is_some_and(|hardcore| hardcore != 0)
+    }
+
+    /// Returns the name of the Minecraft version that last saved the world (e.g. `1.20.1`), since 1.9.
+    pub fn version_name(&self) -> Option<&str> {
+        self.data.get_path("Version/Name").and_then(|tag| <&str>::try_from(tag).ok())
+    }
+
+    /// Returns the data version of the world, since 1.9.
+    pub fn data_version(&self) -> Option<i32> {
+        self.data.get_path("DataVersion").and_then(|tag| i32::try_from(tag).ok())
+    }
+
+    /// Returns the Unix time in milliseconds when the world was last played.
+    pub fn last_played(&self) -> Option<i64> {
+        self.data.get_path("LastPlayed").and_then(|tag| i64::try_from(tag).ok())
+    }
+
+    /// Returns the details shown in a world list. `folder_name` is the name used when `level.dat` has no `LevelName`.
+    pub fn summary(&self, folder_name: &str) -> WorldSummary {
+        WorldSummary {
+            name: self.level_name().unwrap_or(folder_name).to_string(),
+            seed: self.seed(),
+            game_type: self.game_type(),
+            hardcore: self.hardcore(),
+            version_name: self.version_name().map(str::to_string),
+            last_played: self.last_played(),
+            has_icon: self.has_icon,
+        }
+    }
+
+    /// Returns the world spawn point (x, y, z).
+    pub fn spawn(&self) -> Option<(i32, i32, i32)> {
+        let coordinate = |name: &str| self.data.get_path(name).and_then(|tag| i32::try_from(tag).ok());
+        Some((coordinate("SpawnX")?, coordinate("SpawnY")?, coordinate("SpawnZ")?))
+    }
+}
+
+/// Returns the path of `level.dat`, given either the world folder or the file itself.
+pub fn level_dat_path(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        path.join(LEVEL_DAT_FILE_NAME)
+    }
+    else {
+        path.to_path_buf()
+    }
+}
+
+/// Returns true if `data` is a Bedrock Edition `level.dat`: a header whose second little-endian int is the length
+/// of the rest of the file, followed by an uncompressed compound.
+pub fn is_bedrock_level_dat(data: &[u8]) -> bool {
+    if data.len() <= BEDROCK_HEADER_LENGTH {
+        return false;
+    }
+
+    let length = u32::from_le_bytes([data[4], data[5], data[6], data[7]]) as usize;
+    length == data.len() - BEDROCK_HEADER_LENGTH && NbtTagType::from_id(data[BEDROCK_HEADER_LENGTH]) == Some(NbtTagType::Compound)
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+
+use super::*;
+use crate::nbt_tag::*;
+
+use std::fs;
+
+fn sample_level_dat() -> NbtTagCompound {
+    let mut version = NbtTagCompound::new("Version");
+    version.values.insert("Name".to_string(), NbtTag::String(NbtTagString::new("Name".to_string(), "1.20.1".to_string())));
+    let mut world_gen_settings = NbtTagCompound::new("WorldGenSettings");
+    world_gen_settings.values.insert("seed".to_string(), NbtTag::Long(NbtTagLong::new("seed".to_string(), -4172144997902289642)));
+
+    let mut data = NbtTagCompound::new("Data");
+    data.values.insert("LevelName".to_string(), NbtTag::String(NbtTagString::new("LevelName".to_string(), "My World".to_string())));
+    data.values.insert("GameType".to_string(), NbtTag::Int(NbtTagInt::new("GameType".to_string(), 1)));
+    data.values.insert("hardcore".to_string(), NbtTag::Byte(NbtTagByte::new("hardcore".to_string(), 0)));
+    data.values.insert("DataVersion".to_string(), NbtTag::Int(NbtTagInt::new("DataVersion".to_string(), 3465)));
+    data.values.insert("LastPlayed".to_string(), NbtTag::Long(NbtTagLong::new("LastPlayed".to_string(), 1700000000000)));
+    for (name, value) in [("SpawnX", 16), ("SpawnY", 70), ("SpawnZ", -32)] {
+        data.values.insert(name.to_string(), NbtTag::Int(NbtTagInt::new(name.to_string(), value)));
+    }
+    data.values.insert("Version".to_string(), NbtTag::Compound(version));
+    data.values.insert("WorldGenSettings".to_string(), NbtTag::Compound(world_gen_settings));
+
+    let mut root = NbtTagCompound::new("");
+    root.values.insert("Data".to_string(), NbtTag::Compound(data));
+    root
+}
+
+#[test]
+fn test_metadata_accessors() {
+    let metadata = WorldMetadata::from_level_dat(sample_level_dat()).unwrap();
+
+    assert_eq!(metadata.level_name(), Some("My World"));
+    assert_eq!(metadata.seed(), Some(-4172144997902289642));
+    assert_eq!(metadata.game_type(), Some(1));
+    assert!(!metadata.hardcore());
+    assert_eq!(metadata.version_name(), Some("1.20.1"));
+    assert_eq!(metadata.data_version(), Some(3465));
+    assert_eq!(metadata.last_played(), Some(1700000000000));
+    assert_eq!(metadata.spawn(), Some((16, 70, -32)));
+}
+
+#[test]
+fn test_metadata_summary() {
+    let mut metadata = WorldMetadata::from_level_dat(sample_level_dat()).unwrap();
+    metadata.has_icon = true;
+
+    assert_eq!(metadata.summary("world"), WorldSummary {
+        name: "My World".to_string(),
+        seed: Some(-4172144997902289642),
+        game_type: Some(1),
+        hardcore: false,
+        version_name: Some("1.20.1".to_string()),
+        last_played: Some(1700000000000),
+        has_icon: true,
+    });
+
+    metadata.data.values.remove("LevelName");
+    assert_eq!(metadata.summary("world").name, "world");
+}
+
+#[test]
+fn test_metadata_pre_1_16_seed() {
+    let mut root = sample_level_dat();
+    let Some(NbtTag::Compound(data)) = root.values.get_mut("Data") else { unreachable!() };
+    data.values.remove("WorldGenSettings");
+    data.values.insert("RandomSeed".to_string(), NbtTag::Long(NbtTagLong::new("RandomSeed".to_string(), 42)));
+
+    assert_eq!(WorldMetadata::from_level_dat(root).unwrap().seed(), Some(42));
+}
+
+#[test]
+fn test_metadata_without_data() {
+    assert!(WorldMetadata::from_level_dat(NbtTagCompound::new("")).is_err());
+}
+
+#[test]
+fn test_metadata_read_world_folder() {
+    let world_path = std::env::temp_dir().join(format!("fastnbt_level_dat_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&world_path);
+    fs::create_dir_all(&world_path).unwrap();
+
+    let mut level_dat = Vec::new();
+    write(&mut level_dat, &sample_level_dat());
+    fs::write(world_path.join(LEVEL_DAT_FILE_NAME), level_dat).unwrap();
+    fs::write(world_path.join(SESSION_LOCK_FILE_NAME), [0xE2, 0x98, 0x83]).unwrap();
+
+    let metadata = WorldMetadata::read(&world_path).unwrap();
+    assert_eq!(metadata.level_name(), Some("My World"));
+    assert!(metadata.has_session_lock);
+    assert!(!metadata.has_icon);
+
+    // the level.dat file can be given directly too
+    let metadata = WorldMetadata::read(&world_path.join(LEVEL_DAT_FILE_NAME)).unwrap();
+    assert_eq!(metadata.seed(), Some(-4172144997902289642));
+
+    fs::remove_dir_all(&world_path).unwrap();
+}
+
+/// Builds a Bedrock Edition level.dat: the header, then a little-endian compound with the name and seed of the world.
+fn sample_bedrock_level_dat() -> Vec<u8> {
+    let mut nbt = vec![10, 0, 0];
+    nbt.extend([8, 9, 0]);
+    nbt.extend(b"LevelName");
+    nbt.extend(8u16.to_le_bytes());
+    nbt.extend(b"My World");
+    nbt.extend([4, 10, 0]);
+    nbt.extend(b"RandomSeed");
+    nbt.extend(42i64.to_le_bytes());
+    nbt.push(0);
+
+    let mut level_dat = 10i32.to_le_bytes().to_vec();
+    level_dat.extend((nbt.len() as u32).to_le_bytes());
+    level_dat.extend(nbt);
+    level_dat
+}
+
+#[test]
+fn test_metadata_bedrock_level_dat() {
+    let level_dat = sample_bedrock_level_dat();
+    assert!(is_bedrock_level_dat(&level_dat));
+    assert!(!is_bedrock_level_dat(&level_dat[..level_dat.len() - 1]));
+
+    let metadata = WorldMetadata::from_bedrock_level_dat(&level_dat).unwrap();
+    assert_eq!(metadata.level_name(), Some("My World"));
+    assert_eq!(metadata.seed(), Some(42));
+
+    let mut java_level_dat = Vec::new();
+    write(&mut java_level_dat, &sample_level_dat());
+    assert!(!is_bedrock_level_dat(&java_level_dat));
+    assert!(WorldMetadata::from_bedrock_level_dat(&java_level_dat).is_err());
+}
@@ -5,10 +5,67 @@
 // - 2023-12-17
 //
 // ## File Version
-// - 1.0.0
+// - 1.0.57
 //
 // ## Changelog
 // - 1.0.0: Initial version
+// - 1.0.1: Added search_blocks_with_props to filter blocks by block-state properties [mrmarkolinus:2026-10-16]
+// - 1.0.2: PyNbtTag conversion returns python exceptions instead of panicking [mrmarkolinus:2026-10-16]
+// - 1.0.3: Added WorldLoadOptions to skip chunks that are not fully generated [mrmarkolinus:2026-10-16]
+// - 1.0.4: Moved the python bindings to the python module, built only with the "python" feature [mrmarkolinus:2026-10-16]
+// - 1.0.5: Added new_async to load worlds with tokio (feature "async") [mrmarkolinus:2026-10-16]
+// - 1.0.6: Added replace_block to swap a block with another in all the loaded chunks [mrmarkolinus:2026-10-16]
+// - 1.0.7: Added the mutf8 module for NBT strings [mrmarkolinus:2026-10-16]
+// - 1.0.8: Added loading of the Nether and End dimensions and McWorldDescriptor::dimension [mrmarkolinus:2026-10-16]
+// - 1.0.9: Added the source (dimension and region) of each loaded compound and load_sourced_compounds [mrmarkolinus:2026-10-16]
+// - 1.0.10: Added all_items to list the item stacks of containers and entities [mrmarkolinus:2026-10-16]
+// - 1.0.11: Added search_items [mrmarkolinus:2026-10-16]
+// - 1.0.12: search_blocks validates the resource locations [mrmarkolinus:2026-10-16]
+// - 1.0.13: Added search_blocks_report [mrmarkolinus:2026-10-16]
+// - 1.0.14: Added export_each_to_json [mrmarkolinus:2026-10-16]
+// - 1.0.15: Added the nbt_path module [mrmarkolinus:2026-10-16]
+// - 1.0.16: World folders load level.dat, added open_metadata_only [mrmarkolinus:2026-10-16]
+// - 1.0.17: Added render_topdown (feature "image") [mrmarkolinus:2026-10-16]
+// - 1.0.18: Added render_topdown_with_palette [mrmarkolinus:2026-10-16]
+// - 1.0.19: Added the session.lock check to WorldLoadOptions [mrmarkolinus:2026-10-16]
+// - 1.0.20: Added LoadMode::Lazy, chunk_at and decode_all [mrmarkolinus:2026-10-16]
+// - 1.0.21: Added world_summary [mrmarkolinus:2026-10-16]
+// - 1.0.22: Added the saved_data module, scoreboard, maps and last_map_id [mrmarkolinus:2026-10-16]
+// - 1.0.23: Added region_files, listing the region files without reading them [mrmarkolinus:2026-10-16]
+// - 1.0.24: Added the JSON export with options (longs as strings) [mrmarkolinus:2026-10-16]
+// - 1.0.25: Added load_into, appending another world or file to the descriptor [mrmarkolinus:2026-10-16]
+// - 1.0.26: Added villager_trades [mrmarkolinus:2026-10-16]
+// - 1.0.27: Added search_blocks_with_options [mrmarkolinus:2026-10-16]
+// - 1.0.28: Added the structure module, search_blocks also searches the loaded structure files [mrmarkolinus:2026-10-16]
+// - 1.0.29: Added diff_world, comparing the chunks of two worlds [mrmarkolinus:2026-10-16]
+// - 1.0.30: Added from_compounds, a descriptor built in memory [mrmarkolinus:2026-10-16]
+// - 1.0.31: Added WorldLoadOptions::skip_corrupt and skipped_files, the unreadable files can be skipped [mrmarkolinus:2026-10-16]
+// - 1.0.32: The source of each compound holds its file path and chunk coordinates, added source_files [mrmarkolinus:2026-10-16]
+// - 1.0.33: Added save, writing the chunks marked as modified back to their region files [mrmarkolinus:2026-10-16]
+// - 1.0.34: Added chunk_handle and chunk_handle_at to edit a chunk tracking the changes [mrmarkolinus:2026-10-16]
+// - 1.0.35: Added snapshot and restore to undo the edits [mrmarkolinus:2026-10-16]
+// - 1.0.36: Added chunk_count and dimension_chunk_count [mrmarkolinus:2026-10-16]
+// - 1.0.37: Added nearest_structure, locating a structure as /locate [mrmarkolinus:2026-10-16]
+// - 1.0.38: Added save_with_compression [mrmarkolinus:2026-10-16]
+// - 1.0.39: Added find_entities_where, filtering the entities with a predicate [mrmarkolinus:2026-10-16]
+// - 1.0.40: Added as_tag, the loaded compounds under a single root compound [mrmarkolinus:2026-10-16]
+// - 1.0.41: Added WorldLoadOptions::keep_only, pruning the chunks while parsing [mrmarkolinus:2026-10-16]
+// - 1.0.42: Added edition, Bedrock Edition worlds load only their level.dat [mrmarkolinus:2026-10-16]
+// - 1.0.43: Added count_blocks_in_box [mrmarkolinus:2026-10-16]
+// - 1.0.44: World folders load the command storage, added command_storage [mrmarkolinus:2026-10-16]
+// - 1.0.45: Added WorldLoadOptions::max_compounds, loading only the first compounds as a preview [mrmarkolinus:2026-10-16]
+// - 1.0.46: Added search_by_tag, searching the blocks of a tag or category [mrmarkolinus:2026-10-16]
+// - 1.0.47: Added next_map_id [mrmarkolinus:2026-10-16]
+// - 1.0.48: tag_compounds_list holds SourcedCompound, the sources are no longer kept in a separate list [mrmarkolinus:2026-10-16]
+// - 1.0.49: The modified chunks are flagged per compound, a chunk loaded twice is saved only if its edited copy is [mrmarkolinus:2026-10-16]
+// - 1.0.50: snapshot shares the compounds instead of copying them, replace_block only touches the chunks with the block [mrmarkolinus:2026-10-16]
+// - 1.0.51: dimension and loaded_dimensions find the compounds by their source, also after the list is edited [mrmarkolinus:2026-10-16]
+// - 1.0.52: The chunks read from region files are sourced with the coordinates of their slot [mrmarkolinus:2026-10-16]
+// - 1.0.53: to_json and to_json_with_options return a NotFound error when no compound is loaded [mrmarkolinus:2026-10-16]
+// - 1.0.54: nearest_structure searches the Overworld, added nearest_structure_in for the other dimensions [mrmarkolinus:2026-10-16]
+// - 1.0.55: render_topdown documents the map size limit [mrmarkolinus:2026-10-16]
+// - 1.0.56: max_compounds counts only the chunks kept by skip_non_full_chunks [mrmarkolinus:2026-10-16]
+// - 1.0.57: next_map_id returns None when the last map id is i32::MAX [mrmarkolinus:2026-10-16]
 
 pub mod nbt_tag;
 pub mod file_parser;
@@ -16,118 +73,161 @@ pub mod region;
 pub mod generic_bin;
 pub mod blocks;
 pub mod chunk_format;
+pub mod error;
+pub mod mutf8;
+pub mod world;
+pub mod items;
+pub mod nbt_path;
+pub mod level_dat;
+pub mod render;
+pub mod saved_data;
+pub mod structure;
+
+#[cfg(feature = "python")]
+pub mod python;
+
+#[cfg(feature = "python")]
+pub use python::{PyMcWorldDescriptor, PyNbtTag};
 
 use std::collections::HashMap;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use rayon::prelude::*;
+
+pub use world::{CompoundSource, Dimension, Edition, SourcedCompound};
+
+/// The version of a descriptor whose Minecraft version is unknown (no `level.dat`), see `get_mc_version`.
+pub const UNKNOWN_VERSION: &str = "0.0.0";
+
+/// The compounds read from a file, each with the (x, z) coordinates of its slot if read from a region file.
+type SlotCompounds = Vec<(Option<(i32, i32)>, nbt_tag::NbtTagCompound)>;
+
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
-use pyo3::wrap_pyfunction;
-use pyo3::types::{PyDict, PyList};
-use log::info;
-use pyo3_log;
-
-#[pymodule]
-fn fastnbt(py: Python, m: &PyModule) -> PyResult<()> {
-    pyo3_log::init();
-    m.add_class::<PyMcWorldDescriptor>()?;
-    m.add_class::<PyNbtTag>()?;
-    m.add_class::<blocks::MinecraftBlock>()?;
-    m.add_class::<blocks::Coordinates>()?;
-    
-    m.add_function(wrap_pyfunction!(load_binary, m)?)?;
-    m.add_function(wrap_pyfunction!(py_log, m)?)?;
 
-    Ok(())
+/// Options to tune how a world or a file is loaded by `McWorldDescriptor`.
+/// 
+/// The default options load everything, as `McWorldDescriptor::new` does.
+#[derive(Clone, Debug, Default)]
+pub struct WorldLoadOptions {
+    /// Drops the chunks whose generation status is not full (proto-chunks), see `chunk_format::chunk_status`.
+    pub skip_non_full_chunks: bool,
+    /// Loads also the Nether (`DIM-1/region`) and the End (`DIM1/region`) of a world folder, when present.
+    /// By default only the Overworld (`region`) is loaded.
+    pub load_all_dimensions: bool,
+    /// What to do when the world is open in a game or a server (see `world::is_session_locked`). Not checked by default.
+    pub session_lock_check: SessionLockCheck,
+    /// Decodes all the chunks upfront (default) or on first access.
+    pub load_mode: LoadMode,
+    /// Skips the files of a world folder that cannot be read, instead of failing the whole load (default).
+    /// Each skipped file is logged as a warning and listed by `McWorldDescriptor::skipped_files`.
+    pub skip_corrupt: bool,
+    /// If not empty, the chunks of the region files keep only the tags with these names and the compounds and lists
+    /// leading to them, the rest is dropped while parsing (see `file_parser::ParseOptions::keep_only`). 
+    /// E.g. `block_states` and `Y` for the blocks since 1.18, `Palette`, `BlockStates` and `Y` before.
+    /// 
+    /// The tags read by the other options must be kept too (`Status` for `skip_non_full_chunks`).
+    /// The pruned chunks cannot be written back: `McWorldDescriptor::save` fails.
+    pub keep_only: Vec<String>,
+    /// If set, stops loading once this number of compounds is reached: the next files are not read, and only the 
+    /// first chunks of a region file are decoded. Meant for previews (e.g. a world browser showing a few chunks):
+    /// the world is not completely loaded, which chunks are loaded depends on the order of the files in the folder.
+    /// The chunks skipped by `skip_non_full_chunks` do not count.
+    /// 
+    /// In `LoadMode::Lazy` the undecoded chunks of the region files read count as well, the region files are not cut.
+    pub max_compounds: Option<usize>,
 }
-#[pyfunction]
-fn py_log(message: String)  {
-    info!("{}", message);
+
+/// When `McWorldDescriptor` decodes the chunks of the region files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LoadMode {
+    /// All the chunks are decoded while loading, into `tag_compounds_list`.
+    #[default]
+    Eager,
+    /// The region files are only read in memory, each chunk is decoded on its first access with `chunk_at`.
+    /// Opening a world is much faster (a region of 992 chunks in about 2 ms instead of 70 ms, in release mode),
+    /// and targeted queries pay only for the chunks they read (about 50 µs each).
+    /// `tag_compounds_list` holds only the other files, until `decode_all` is called.
+    Lazy,
 }
 
-#[pyfunction]
-fn load_binary(input_path: String) -> PyResult<PyMcWorldDescriptor> {   
-    let path_buf = PathBuf::from(input_path);
-    let mc_world = McWorldDescriptor::new(path_buf)?; 
-    PyMcWorldDescriptor::new(mc_world).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+/// How `McWorldDescriptor` reacts to a world in use, whose region files may change while they are read.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SessionLockCheck {
+    /// The `session.lock` is not checked.
+    #[default]
+    Ignore,
+    /// A warning is logged and the world is loaded anyway.
+    Warn,
+    /// The loading fails with `error::RnbtError::WorldLocked`.
+    Fail,
 }
 
-#[pyclass]
+/// The loaded compounds of a `McWorldDescriptor` at a point in time, see `McWorldDescriptor::snapshot`.
 #[derive(Clone, Debug)]
-pub struct PyMcWorldDescriptor {
-    mc_world_descriptor: McWorldDescriptor,
-    //TEST
-    #[pyo3(get, set)]
-    pub tag_compounds_list: Vec::<Py<PyDict>>
+pub struct Snapshot {
+    tag_compounds_list: Vec<SourcedCompound>,
+    lazy_chunks: world::LazyChunks,
 }
 
-#[pymethods]
-impl PyMcWorldDescriptor {
-    #[new]
-    pub fn new(rust_mc_world_descriptor: McWorldDescriptor) -> std::io::Result<Self> {
-
-        let mut py_tag_list = Vec::<Py<PyDict>>::new();
-        
-        rust_mc_world_descriptor.tag_compounds_list.iter().for_each(|item| {
-            let tag_root = nbt_tag::NbtTag::Compound(item.clone());
-            py_tag_list.push(PyNbtTag::new(&tag_root).python_dict)
-        });
-
-        Ok(PyMcWorldDescriptor{ 
-            mc_world_descriptor: rust_mc_world_descriptor, 
-            tag_compounds_list: py_tag_list 
-        })
+impl Snapshot {
+    /// Returns the number of compounds captured in the snapshot.
+    pub fn len(&self) -> usize {
+        self.tag_compounds_list.len()
     }
 
-    pub fn to_json(&self, path: String) -> PyResult<()> {
-        self.mc_world_descriptor.to_json(path).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+    pub fn is_empty(&self) -> bool {
+        self.tag_compounds_list.is_empty()
     }
-
-    pub fn get_mc_version(&self) -> String {
-        self.mc_world_descriptor.get_mc_version()
-    }
-
-    pub fn search_compound(&self, key: &str) -> (bool, Vec::<Py<PyDict>>) {
-        
-        let mut py_tag_list = Vec::<Py<PyDict>>::new();
-
-        let (compound_found, compound_tag_list) = self.mc_world_descriptor.search_compound(key, false);
-        
-        if compound_found {
-            for item in compound_tag_list {
-                let tag_root = nbt_tag::NbtTag::Compound(item.clone());
-                py_tag_list.push(PyNbtTag::new(&tag_root).python_dict);
-            }
-            (true, py_tag_list)
-        } else {
-            (false, py_tag_list)
-        }
-
-    }
-
-    pub fn search_blocks(&self, block_resource_location: Vec::<String>) -> HashMap::<String, Vec::<blocks::MinecraftBlock>> {
-        self.mc_world_descriptor.search_blocks(block_resource_location)
-    }
-
 }
 
-#[pyclass]
+#[cfg_attr(feature = "python", pyclass)]
 #[derive(Clone, Debug, Default)]
 pub struct McWorldDescriptor {
     pub input_path: PathBuf,
     pub version: String,
-    pub tag_compounds_list: Vec<nbt_tag::NbtTagCompound>,
+    /// The loaded compounds, each with the file and the dimension it was read from (it dereferences to the compound).
+    /// Editing the list keeps the sources with their compounds: `save` writes each chunk to its own slot.
+    pub tag_compounds_list: Vec<SourcedCompound>,
+    /// The content of `level.dat`, when the input is a world folder.
+    metadata: Option<level_dat::WorldMetadata>,
+    /// The chunks not decoded yet, in `LoadMode::Lazy`.
+    lazy_chunks: world::LazyChunks,
+    /// The files not loaded with `WorldLoadOptions::skip_corrupt`.
+    skipped_files: Vec<world::SkippedFile>,
+    /// The chunks were loaded with `WorldLoadOptions::keep_only` and cannot be saved.
+    pruned_chunks: bool,
+    /// The edition of Minecraft that saved the input, see `Edition::detect`.
+    edition: Edition,
+    /// The storages of the `/data` command, when the input is a world folder.
+    command_storage: Option<nbt_tag::NbtTagCompound>,
 }
 
 impl McWorldDescriptor {
     pub fn new(input_path: PathBuf) -> std::io::Result<Self> {
+        Self::new_with_options(input_path, WorldLoadOptions::default())
+    }
+
+    pub fn new_with_options(input_path: PathBuf, load_options: WorldLoadOptions) -> std::io::Result<Self> {
         let cloned_input_path = input_path.clone();
+        Self::check_session_lock(&input_path, load_options.session_lock_check)?;
+
+        let edition = Edition::detect(&input_path);
+        if edition == Edition::Bedrock {
+            return Self::open_bedrock(input_path);
+        }
+
+        let loaded_compounds = match load_options.load_mode {
+            LoadMode::Eager => Self::read_input_path(input_path, &load_options)
+                .map(|(list, skipped_files)| (Self::filter_sourced_compounds(list, &load_options), world::LazyChunks::default(), skipped_files)),
+            LoadMode::Lazy => Self::load_lazy_chunks(input_path, &load_options),
+        };
         
-        if let Ok(nbt_tag_compounds_list) = Self::read_input_path(input_path) {
-            Ok(McWorldDescriptor {
-                input_path: cloned_input_path,
-                version: "0.0.0".to_string(),
-                tag_compounds_list: nbt_tag_compounds_list,
-            })
+        if let Ok((sourced_compounds_list, lazy_chunks, skipped_files)) = loaded_compounds {
+            let metadata = Self::read_world_metadata(&cloned_input_path);
+            let command_storage = Self::read_command_storage(&cloned_input_path);
+            let mc_world = Self::from_sourced_compounds(cloned_input_path, sourced_compounds_list).with_metadata(metadata);
+            Ok(McWorldDescriptor { lazy_chunks, skipped_files, pruned_chunks: !load_options.keep_only.is_empty(), edition, command_storage, ..mc_world })
         }
         else{
             //TODO: read a file not only based on the extension, but checking the internal format
@@ -137,14 +237,477 @@ impl McWorldDescriptor {
         
     }
 
-    fn read_input_path(input_path: PathBuf) -> std::io::Result<Vec<nbt_tag::NbtTagCompound>> {
+    /// Asynchronous version of `new`, see `new_async_with_options`.
+    #[cfg(feature = "async")]
+    pub async fn new_async(input_path: PathBuf) -> std::io::Result<Self> {
+        Self::new_async_with_options(input_path, WorldLoadOptions::default()).await
+    }
+
+    /// Asynchronous version of `new_with_options`, to load worlds from an async runtime without blocking the executor.
+    /// 
+    /// The files are read with `tokio::fs`, while the CPU-bound work (decompression and NBT parsing) 
+    /// is spawned on the tokio blocking thread pool. Requires the "async" feature.
+    #[cfg(feature = "async")]
+    pub async fn new_async_with_options(input_path: PathBuf, load_options: WorldLoadOptions) -> std::io::Result<Self> {
+        if load_options.load_mode == LoadMode::Lazy {
+            // the region files are only read, there is no decoding to keep out of the executor
+            return tokio::task::spawn_blocking(move || Self::new_with_options(input_path, load_options))
+                .await
+                .map_err(io::Error::other)?;
+        }
+
+        let cloned_input_path = input_path.clone();
+        Self::check_session_lock(&input_path, load_options.session_lock_check)?;
+
+        let edition_path = input_path.clone();
+        let edition = tokio::task::spawn_blocking(move || Edition::detect(&edition_path))
+            .await
+            .map_err(io::Error::other)?;
+        if edition == Edition::Bedrock {
+            return tokio::task::spawn_blocking(move || Self::open_bedrock(input_path))
+                .await
+                .map_err(io::Error::other)?;
+        }
+        
+        if let Ok((sourced_compounds_list, skipped_files)) = Self::read_input_path_async(input_path, &load_options).await {
+            let sourced_compounds_list = Self::filter_sourced_compounds(sourced_compounds_list, &load_options);
+            let metadata_path = cloned_input_path.clone();
+            let (metadata, command_storage) = tokio::task::spawn_blocking(move || (Self::read_world_metadata(&metadata_path), Self::read_command_storage(&metadata_path)))
+                .await
+                .map_err(io::Error::other)?;
+            let mc_world = Self::from_sourced_compounds(cloned_input_path, sourced_compounds_list).with_metadata(metadata);
+            Ok(McWorldDescriptor { skipped_files, pruned_chunks: !load_options.keep_only.is_empty(), edition, command_storage, ..mc_world })
+        }
+        else{
+            Err(std::io::Error::other("McWorldDescriptor not created because of input file error"))
+        } 
+    }
+
+    /// Reads only the metadata of a world (`level.dat`, and the presence of `session.lock` and `icon.png`), 
+    /// given the world folder or the `level.dat` file. The region files are not read, `tag_compounds_list` is empty.
+    /// 
+    /// This is much faster than `new` and enough to list the worlds by name and seed, as launchers do.
+    pub fn open_metadata_only(input_path: PathBuf) -> std::io::Result<Self> {
+        let metadata = level_dat::WorldMetadata::read(&input_path)?;
+        let edition = Edition::detect(&input_path);
+        Ok(McWorldDescriptor { input_path, version: UNKNOWN_VERSION.to_string(), edition, ..Default::default() }.with_metadata(Some(metadata)))
+    }
+
+    /// Opens a Bedrock Edition world: its chunks are stored in a LevelDB database (`db/`), which is not supported,
+    /// so only `level.dat` is read and `tag_compounds_list` is empty.
+    fn open_bedrock(input_path: PathBuf) -> std::io::Result<Self> {
+        log::warn!("{}: Bedrock Edition world, the chunks in ./{} are not read", input_path.display(), world::BEDROCK_DB_FOLDER_NAME);
+        Self::open_metadata_only(input_path)
+    }
+
+    /// Creates a descriptor from compounds built in memory, without reading any file (e.g. to test the searches
+    /// on generated chunks). The compounds are in the Overworld and the input path is empty.
+    pub fn from_compounds(compounds: Vec<nbt_tag::NbtTagCompound>, version: String) -> Self {
+        let sourced_compounds_list = compounds.into_iter()
+            .map(|compound| {
+                let source = CompoundSource { chunk_coords: Self::chunk_coords(&compound), ..CompoundSource::default() };
+                SourcedCompound::new(source, compound)
+            })
+            .collect();
+
+        McWorldDescriptor { version, ..Self::from_sourced_compounds(PathBuf::new(), sourced_compounds_list) }
+    }
+
+    /// Returns the edition of Minecraft that saved the world, detected from the input when opened.
+    /// Descriptors built in memory (`from_compounds`) are `Edition::Unknown`.
+    pub fn edition(&self) -> Edition {
+        self.edition
+    }
+
+    /// Returns the content of `level.dat`, available when a world folder (or `level.dat` itself) was opened.
+    pub fn metadata(&self) -> Option<&level_dat::WorldMetadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Returns the files that could not be read and were skipped, with `WorldLoadOptions::skip_corrupt`.
+    pub fn skipped_files(&self) -> &[world::SkippedFile] {
+        &self.skipped_files
+    }
+
+    /// Returns the name, seed, game mode, version, last played time and icon presence of the world, as listed by launchers.
+    /// 
+    /// Returns a `NotFound` error when no `level.dat` was read (the input is not a world folder, or `level.dat` is missing or unreadable).
+    pub fn world_summary(&self) -> std::io::Result<level_dat::WorldSummary> {
+        let metadata = self.metadata.as_ref().ok_or_else(|| std::io::Error::new(
+            std::io::ErrorKind::NotFound, format!("{}: no {} read", self.input_path.display(), level_dat::LEVEL_DAT_FILE_NAME)))?;
+
+        let world_path = if self.input_path.is_dir() { self.input_path.as_path() } else { self.input_path.parent().unwrap_or(Path::new("")) };
+        let folder_name = world_path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+        Ok(metadata.summary(&folder_name))
+    }
+
+    /// Lists the region files with their (x, z) region coordinates, without reading them, e.g. to draw the outline 
+    /// of a world or to choose the regions to load. `path` is a world folder (its Overworld regions) or a region folder.
+    /// 
+    /// The files are sorted by z, then x. See `world::RegionFileSet::region_files` for the other dimensions.
+    pub fn region_files(path: &Path) -> std::io::Result<Vec<(i32, i32, PathBuf)>> {
+        let region_path = Dimension::Overworld.region_path(path);
+        if region_path.is_dir() {
+            world::list_region_files(&region_path)
+        } else {
+            world::list_region_files(path)
+        }
+    }
+
+    /// Reads the scoreboard of the world (`data/scoreboard.dat`). The input must be a world folder.
+    pub fn scoreboard(&self) -> std::io::Result<saved_data::Scoreboard> {
+        saved_data::Scoreboard::read(self.world_folder()?)
+    }
+
+    /// Reads the maps of the world (`data/map_<id>.dat`), sorted by id. The input must be a world folder.
+    pub fn maps(&self) -> std::io::Result<Vec<saved_data::MapItem>> {
+        saved_data::read_maps(self.world_folder()?)
+    }
+
+    /// Returns the id of the last map created in the world (`data/idcounts.dat`). The input must be a world folder.
+    pub fn last_map_id(&self) -> std::io::Result<Option<i32>> {
+        saved_data::read_last_map_id(self.world_folder()?)
+    }
+
+    /// Returns the id the game gives to the next map created in the world (`data/idcounts.dat`), 
+    /// for the tools adding maps to a world. `None` if the file is absent (or unreadable, or its last map id is `i32::MAX`, with a warning): 
+    /// the game then starts from 0, but only once it has written the file.
+    pub fn next_map_id(&self) -> Option<i32> {
+        let world_path = self.world_folder().ok()?;
+        saved_data::read_next_map_id(world_path).unwrap_or_else(|e| {
+            log::warn!("{}: {}", world_path.join(saved_data::DATA_FOLDER_NAME).join(saved_data::IDCOUNTS_FILE_NAME).display(), e);
+            None
+        })
+    }
+
+    /// Returns the storages of the `/data` command, read from `data/command_storage_<namespace>.dat` when the world folder was loaded, 
+    /// keyed by their id (e.g. `mymap:state`). `None` if the world has no command storage or the input is not a world folder.
+    pub fn command_storage(&self) -> Option<&nbt_tag::NbtTagCompound> {
+        self.command_storage.as_ref()
+    }
+
+    fn world_folder(&self) -> std::io::Result<&Path> {
+        if self.input_path.is_dir() {
+            Ok(&self.input_path)
+        } else {
+            Err(std::io::Error::new(std::io::ErrorKind::NotFound, format!("{} is not a world folder", self.input_path.display())))
+        }
+    }
+
+    /// Checks if the world folder is in use, as requested by `session_lock_check`. Files are never locked.
+    fn check_session_lock(input_path: &Path, session_lock_check: SessionLockCheck) -> std::io::Result<()> {
+        if session_lock_check == SessionLockCheck::Ignore || !input_path.is_dir() || !world::is_session_locked(input_path)? {
+            return Ok(());
+        }
+
+        let locked_error = error::RnbtError::WorldLocked(input_path.display().to_string());
+        match session_lock_check {
+            SessionLockCheck::Fail => Err(locked_error.into()),
+            _ => {
+                log::warn!("{}, the data read may be inconsistent", locked_error);
+                Ok(())
+            }
+        }
+    }
+
+    /// Reads `level.dat` of a world folder, if present. An unreadable `level.dat` does not prevent loading the chunks.
+    fn read_world_metadata(input_path: &Path) -> Option<level_dat::WorldMetadata> {
+        let level_dat_path = input_path.join(level_dat::LEVEL_DAT_FILE_NAME);
+        if !input_path.is_dir() || !level_dat_path.is_file() {
+            return None;
+        }
+
+        match level_dat::WorldMetadata::read(input_path) {
+            Ok(metadata) => Some(metadata),
+            Err(e) => {
+                log::warn!("{}: {}", level_dat_path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Reads the command storage of a world folder, if present. An unreadable `data` folder does not prevent loading the chunks.
+    fn read_command_storage(input_path: &Path) -> Option<nbt_tag::NbtTagCompound> {
+        if !input_path.is_dir() {
+            return None;
+        }
+
+        saved_data::read_command_storage(input_path).unwrap_or_else(|e| {
+            log::warn!("{}: {}", input_path.join(saved_data::DATA_FOLDER_NAME).display(), e);
+            None
+        })
+    }
+
+    /// Sets the metadata, and the Minecraft version from it when known.
+    fn with_metadata(mut self, metadata: Option<level_dat::WorldMetadata>) -> Self {
+        if let Some(version_name) = metadata.as_ref().and_then(|metadata| metadata.version_name()) {
+            self.version = version_name.to_string();
+        }
+        self.metadata = metadata;
+        self
+    }
+
+    /// Loads the compounds of a world folder or a file like `new_with_options`, keeping the source of each compound.
+    /// The files skipped with `WorldLoadOptions::skip_corrupt` are only logged.
+    pub fn load_sourced_compounds(input_path: PathBuf, load_options: &WorldLoadOptions) -> std::io::Result<Vec<SourcedCompound>> {
+        let (sourced_compounds_list, _) = Self::read_input_path(input_path, load_options)?;
+        Ok(Self::filter_sourced_compounds(sourced_compounds_list, load_options))
+    }
+
+    /// Asynchronous version of `load_sourced_compounds`. Requires the "async" feature.
+    #[cfg(feature = "async")]
+    pub async fn load_sourced_compounds_async(input_path: PathBuf, load_options: &WorldLoadOptions) -> std::io::Result<Vec<SourcedCompound>> {
+        let (sourced_compounds_list, _) = Self::read_input_path_async(input_path, load_options).await?;
+        Ok(Self::filter_sourced_compounds(sourced_compounds_list, load_options))
+    }
+
+    fn filter_sourced_compounds(mut sourced_compounds_list: Vec<SourcedCompound>, load_options: &WorldLoadOptions) -> Vec<SourcedCompound> {
+        if load_options.skip_non_full_chunks {
+            sourced_compounds_list.retain(|sourced_compound| chunk_format::is_chunk_fully_generated(sourced_compound));
+        }
+        if let Some(max_compounds) = load_options.max_compounds {
+            sourced_compounds_list.truncate(max_compounds);
+        }
+
+        sourced_compounds_list
+    }
+
+    /// Returns true if `loaded_num` compounds reach `WorldLoadOptions::max_compounds`, the next files are not read.
+    fn reached_max_compounds(loaded_num: usize, load_options: &WorldLoadOptions) -> bool {
+        load_options.max_compounds.is_some_and(|max_compounds| loaded_num >= max_compounds)
+    }
+
+    fn from_sourced_compounds(input_path: PathBuf, sourced_compounds_list: Vec<SourcedCompound>) -> Self {
+        McWorldDescriptor {
+            input_path,
+            version: UNKNOWN_VERSION.to_string(),
+            tag_compounds_list: sourced_compounds_list,
+            metadata: None,
+            lazy_chunks: world::LazyChunks::default(),
+            skipped_files: Vec::new(),
+            pruned_chunks: false,
+            edition: Edition::Unknown,
+            command_storage: None,
+        }
+    }
+
+    /// Wraps the compounds read from a file with their source.
+    /// Attaches the source to the compounds read from `file_path`, each with the coordinates of its slot
+    /// if read from a region file (see `read_file_format`). The slot is preferred to the position stored in the chunk.
+    fn to_sourced_compounds(dimension: Dimension, file_path: &Path, nbt_tag_compounds_list: SlotCompounds) -> Vec<SourcedCompound> {
+        let region_coords = region::parse_region_coords(file_path);
+        nbt_tag_compounds_list.into_iter()
+            .map(|(slot_coords, compound)| {
+                let chunk_coords = slot_coords.or_else(|| Self::chunk_coords(&compound));
+                let source = CompoundSource { dimension, region_coords, file_path: Some(file_path.to_path_buf()), chunk_coords };
+                SourcedCompound::new(source, compound)
+            })
+            .collect()
+    }
+
+    /// Returns the (x, z) coordinates of a chunk, None if the compound is not a chunk.
+    fn chunk_coords(compound: &nbt_tag::NbtTagCompound) -> Option<(i32, i32)> {
+        chunk_format::has_chunk_coordinates(compound).then(|| {
+            let coordinates = chunk_format::get_chunk_coordinates(compound);
+            (coordinates.x, coordinates.z)
+        })
+    }
+
+    /// Returns the source of the compound at `index` in `tag_compounds_list`.
+    pub fn compound_source(&self, index: usize) -> Option<CompoundSource> {
+        self.tag_compounds_list.get(index).map(SourcedCompound::source)
+    }
+
+    /// Returns the paths of the files the compounds were loaded from, once each in loading order.
+    pub fn source_files(&self) -> Vec<&Path> {
+        let mut source_files: Vec<&Path> = Vec::new();
+        for file_path in self.tag_compounds_list.iter().filter_map(|sourced_compound| sourced_compound.file_path.as_deref()) {
+            if !source_files.contains(&file_path) {
+                source_files.push(file_path);
+            }
+        }
+
+        source_files
+    }
+
+    /// Returns the dimension the compound at `index` in `tag_compounds_list` was loaded from.
+    pub fn compound_dimension(&self, index: usize) -> Option<Dimension> {
+        self.tag_compounds_list.get(index).map(|sourced_compound| sourced_compound.dimension)
+    }
+
+    /// Returns the compounds loaded from the given dimension, empty if the dimension was not loaded.
+    /// 
+    /// A single file is assigned to the dimension of the folder it is in (see `Dimension::from_region_file_path`),
+    /// the Nether and the End of a world folder are loaded only with `WorldLoadOptions::load_all_dimensions`.
+    /// The compounds are found by their source, wherever they are in `tag_compounds_list` after editing the list.
+    pub fn dimension(&self, dim: Dimension) -> Vec<&SourcedCompound> {
+        self.tag_compounds_list.iter()
+            .filter(|sourced_compound| sourced_compound.dimension == dim)
+            .collect()
+    }
+
+    /// Returns the dimensions loaded, in the order they first appear in `tag_compounds_list`.
+    pub fn loaded_dimensions(&self) -> Vec<Dimension> {
+        let mut dimensions = Vec::new();
+        for sourced_compound in self.tag_compounds_list.iter() {
+            if !dimensions.contains(&sourced_compound.dimension) {
+                dimensions.push(sourced_compound.dimension);
+            }
+        }
+        dimensions
+    }
+
+    /// Returns the number of chunks loaded, in all the dimensions: the compounds read from the chunk slots of region files
+    /// or built from chunks, plus the chunks still in the lazy storage (`LoadMode::Lazy`).
+    /// 
+    /// The other compounds of `tag_compounds_list` (level.dat, a single .nbt file...) are not counted.
+    pub fn chunk_count(&self) -> usize {
+        self.tag_compounds_list.iter().filter(|sourced_compound| sourced_compound.chunk_coords.is_some()).count() + self.lazy_chunks.len()
+    }
+
+    /// Returns the number of chunks loaded from the given dimension, see `chunk_count`.
+    pub fn dimension_chunk_count(&self, dim: Dimension) -> usize {
+        let decoded_chunks_num = self.tag_compounds_list.iter()
+            .filter(|sourced_compound| sourced_compound.dimension == dim && sourced_compound.chunk_coords.is_some())
+            .count();
+
+        decoded_chunks_num + self.lazy_chunks.chunk_coords(dim).len()
+    }
+
+    /// Returns a copy of the loaded compounds under a single root compound, to traverse, query or export the whole world
+    /// with the `NbtTag` API (e.g. `query("minecraft:overworld/*/DataVersion")` or `NbtTagCompound::to_json`).
+    /// 
+    /// The root holds a compound per dimension, named by `Dimension::id` (`minecraft:overworld`...), holding the compounds
+    /// loaded from it: the chunks named by their chunk coordinates (`-32,5`), the other compounds by their file name 
+    /// (`level.dat`) or their index in `tag_compounds_list`. A name already taken is followed by the index, e.g. `-32,5 (120)`.
+    /// 
+    /// The chunks still in the lazy storage are not included, see `decode_all`.
+    pub fn as_tag(&self) -> nbt_tag::NbtTag {
+        let mut root = nbt_tag::NbtTagCompound::new("");
+
+        for (index, source) in self.tag_compounds_list.iter().enumerate() {
+            let dimension_tag = root.values.entry(source.dimension.id().to_string())
+                .or_insert_with(|| nbt_tag::NbtTag::Compound(nbt_tag::NbtTagCompound::new(source.dimension.id())));
+            let Some(dimension_compound) = dimension_tag.compound_as_mut() else {
+                continue;
+            };
+
+            let mut key = match (source.chunk_coords, source.file_path.as_deref().and_then(Path::file_name)) {
+                (Some((chunk_x, chunk_z)), _) => format!("{},{}", chunk_x, chunk_z),
+                (None, Some(file_name)) => file_name.to_string_lossy().into_owned(),
+                (None, None) => index.to_string(),
+            };
+            if dimension_compound.values.contains_key(&key) {
+                key = format!("{} ({})", key, index);
+            }
+
+            let mut compound = nbt_tag::NbtTagCompound::clone(source);
+            compound.set_name(&key);
+            dimension_compound.values.insert(key, nbt_tag::NbtTag::Compound(compound));
+        }
+
+        nbt_tag::NbtTag::Compound(root)
+    }
+
+    /// Returns true if some chunks are still in the lazy storage (`LoadMode::Lazy`), not in `tag_compounds_list`.
+    pub fn is_lazy(&self) -> bool {
+        !self.lazy_chunks.is_empty()
+    }
+
+    /// Returns the chunks not moved to `tag_compounds_list` yet, in `LoadMode::Lazy`.
+    pub fn lazy_chunks(&self) -> &world::LazyChunks {
+        &self.lazy_chunks
+    }
+
+    /// Returns the loaded chunk at the given chunk coordinates of a dimension, `Ok(None)` if it is not loaded.
+    /// 
+    /// In `LoadMode::Lazy` only this chunk is decoded (on the first access), then `tag_compounds_list` is searched.
+    pub fn chunk_at(&self, dimension: Dimension, chunk_x: i32, chunk_z: i32) -> io::Result<Option<&nbt_tag::NbtTagCompound>> {
+        if let Some(chunk_compound) = self.lazy_chunks.chunk_at(dimension, chunk_x, chunk_z)? {
+            return Ok(Some(chunk_compound));
+        }
+
+        let chunk_compound = self.tag_compounds_list.iter()
+            .find(|sourced_compound| sourced_compound.dimension == dimension && sourced_compound.chunk_coords == Some((chunk_x, chunk_z)))
+            .map(AsRef::as_ref);
+
+        Ok(chunk_compound)
+    }
+
+    /// Decodes all the lazy chunks (in parallel) into `tag_compounds_list`, as if the world was loaded
+    /// in `LoadMode::Eager`: the whole-world queries (`search_blocks`, `to_json`...) only see `tag_compounds_list`.
+    /// 
+    /// On error nothing is moved, the chunks decoded so far stay in the lazy storage.
+    pub fn decode_all(&mut self) -> io::Result<()> {
+        if self.lazy_chunks.is_empty() {
+            return Ok(());
+        }
+        let decoded_compounds = self.lazy_chunks.to_sourced_compounds()?;
+        self.lazy_chunks = world::LazyChunks::default();
+
+        self.append_sourced_compounds(decoded_compounds);
+        Ok(())
+    }
+
+    /// Loads another world folder or file and appends its compounds to `tag_compounds_list`, e.g. to add 
+    /// region files one at a time. Returns the number of compounds appended.
+    /// 
+    /// `input_path` stays the first path loaded. The metadata and the version are kept, or taken from the new
+    /// input if unknown so far; a different version is logged as a warning, since the chunk formats may differ.
+    pub fn load_into(&mut self, input_path: PathBuf) -> io::Result<usize> {
+        self.load_into_with_options(input_path, WorldLoadOptions::default())
+    }
+
+    /// Same as `load_into`, with the given options. The appended compounds are always decoded (`LoadMode::Eager`).
+    pub fn load_into_with_options(&mut self, input_path: PathBuf, load_options: WorldLoadOptions) -> io::Result<usize> {
+        let load_options = WorldLoadOptions { load_mode: LoadMode::Eager, ..load_options };
+        let mut loaded_world = Self::new_with_options(input_path, load_options)?;
+
+        if self.metadata.is_none() && loaded_world.metadata.is_some() {
+            self.metadata = loaded_world.metadata.take();
+        }
+        if self.version.is_empty() || self.version == UNKNOWN_VERSION {
+            self.version = std::mem::take(&mut loaded_world.version);
+        }
+        else if loaded_world.version != UNKNOWN_VERSION && loaded_world.version != self.version {
+            log::warn!("{}: version {} differs from the version {} already loaded", loaded_world.input_path.display(), loaded_world.version, self.version);
+        }
+
+        self.skipped_files.append(&mut loaded_world.skipped_files);
+        self.pruned_chunks |= loaded_world.pruned_chunks;
+
+        let appended_num = loaded_world.tag_compounds_list.len();
+        self.append_sourced_compounds(loaded_world.tag_compounds_list);
+        Ok(appended_num)
+    }
+
+    /// Appends compounds to `tag_compounds_list`, keeping the compounds of each dimension together.
+    fn append_sourced_compounds(&mut self, appended_compounds: Vec<SourcedCompound>) {
+        self.tag_compounds_list.extend(appended_compounds);
+        // keep the compounds of each dimension together, as when loaded
+        self.tag_compounds_list.sort_by_key(|sourced_compound| Dimension::ALL.iter().position(|dim| *dim == sourced_compound.dimension));
+    }
+
+    /// Returns the dimensions to read from a world folder.
+    fn dimensions_to_load(load_options: &WorldLoadOptions) -> Vec<Dimension> {
+        if load_options.load_all_dimensions {
+            Dimension::ALL.to_vec()
+        }
+        else {
+            vec![Dimension::Overworld]
+        }
+    }
+
+    /// Lists the files to read, with their dimension: the region files of the dimensions to load of a world folder,
+    /// or the input file itself.
+    fn input_files(input_path: &Path, load_options: &WorldLoadOptions) -> std::io::Result<Vec<(Dimension, PathBuf)>> {
         
         /* #10: The use can give in input either a folder path to Minecraft world or directly a file path.
         *  Here the path is checked, if a folder is found, the subfolder "region" is searched.
         *  If "region" is found, this is likely to be a valid Minecraft world, then the region files are read.
         */
 
-        let mut nbt_tag_compounds_list = Vec::<nbt_tag::NbtTagCompound>::new();
+        let mut input_files = Vec::<(Dimension, PathBuf)>::new();
 
         if input_path.is_dir()
         {
@@ -153,54 +716,130 @@ impl McWorldDescriptor {
                 return Err(std::io::Error::new(std::io::ErrorKind::Other, "World Directory does not exist"));
             }
     
-            let region_path = input_path.join("region");
+            let region_path = Dimension::Overworld.region_path(input_path);
             if !region_path.exists() || !region_path.is_dir() {
                 return Err(std::io::Error::new(std::io::ErrorKind::Other, "SubDir './region' does not exist"));
             }
             
-            /* #30: Input path is a valid folder and contains a subfolder "region"*/
-            match std::fs::read_dir(region_path) {
-                Ok(entries) => {
-                    for entry in entries {
-                        if let Ok(entry) = entry {
-                            let file_path = PathBuf::from(entry.path().to_string_lossy().into_owned());
-                            nbt_tag_compounds_list.append(&mut Self::read_file_format(file_path)?);
+            /* #30: Input path is a valid folder and contains a subfolder "region". 
+            *  The other dimensions are optional, a world may not have generated the Nether or the End yet
+            */
+            for dimension in Self::dimensions_to_load(load_options) {
+                let region_path = dimension.region_path(input_path);
+                if !region_path.is_dir() {
+                    continue;
+                }
+
+                match std::fs::read_dir(region_path) {
+                    Ok(entries) => {
+                        for entry in entries.flatten() {
+                            input_files.push((dimension, PathBuf::from(entry.path().to_string_lossy().into_owned())));
                         }
-                    }
-                },
-                Err(_) => return Err(std::io::Error::new(std::io::ErrorKind::Other, "Error in reading the region files")),
+                    },
+                    Err(_) => return Err(std::io::Error::other("Error in reading the region files")),
+                }
             }
         }
         else {
-            nbt_tag_compounds_list.append(&mut Self::read_file_format(input_path)?);
+            input_files.push((Dimension::from_region_file_path(input_path), input_path.to_path_buf()));
         }
-        
 
-        Ok(nbt_tag_compounds_list)
+        Ok(input_files)
+    }
+
+    fn read_input_path(input_path: PathBuf, load_options: &WorldLoadOptions) -> std::io::Result<(Vec<SourcedCompound>, Vec<world::SkippedFile>)> {
+        let mut sourced_compounds_list = Vec::<SourcedCompound>::new();
+        let mut skipped_files = Vec::<world::SkippedFile>::new();
+
+        for (dimension, file_path) in Self::input_files(&input_path, load_options)? {
+            if Self::reached_max_compounds(sourced_compounds_list.len(), load_options) {
+                break;
+            }
+
+            let read_result = Self::read_file_format(file_path.clone(), load_options);
+            if let Some(nbt_tag_compounds_list) = Self::skip_if_corrupt(read_result, &file_path, load_options, &mut skipped_files)? {
+                let sourced_compounds = Self::to_sourced_compounds(dimension, &file_path, nbt_tag_compounds_list);
+                sourced_compounds_list.append(&mut Self::filter_sourced_compounds(sourced_compounds, load_options));
+            }
+        }
+
+        Ok((sourced_compounds_list, skipped_files))
+    }
+
+    /// Returns the result of reading a file, or None if it failed and `skip_corrupt` is set: 
+    /// the file is then added to `skipped_files`.
+    fn skip_if_corrupt<T>(read_result: io::Result<T>, file_path: &Path, load_options: &WorldLoadOptions, skipped_files: &mut Vec<world::SkippedFile>) -> io::Result<Option<T>> {
+        match read_result {
+            Ok(value) => Ok(Some(value)),
+            Err(err) if load_options.skip_corrupt => {
+                log::warn!("{}: skipped, {}", file_path.display(), err);
+                skipped_files.push(world::SkippedFile { path: file_path.to_path_buf(), error: err.to_string() });
+                Ok(None)
+            },
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Same as `load_sourced_compounds`, but the chunks of the region files are kept undecoded in a `LazyChunks`.
+    /// The other files (and the region files not named `r.X.Z.mca`, whose chunks cannot be keyed) are decoded as usual.
+    fn load_lazy_chunks(input_path: PathBuf, load_options: &WorldLoadOptions) -> std::io::Result<(Vec<SourcedCompound>, world::LazyChunks, Vec<world::SkippedFile>)> {
+        let mut sourced_compounds_list = Vec::<SourcedCompound>::new();
+        let mut lazy_chunks = world::LazyChunks::new(load_options.skip_non_full_chunks);
+        let mut skipped_files = Vec::<world::SkippedFile>::new();
+
+        for (dimension, file_path) in Self::input_files(&input_path, load_options)? {
+            if Self::reached_max_compounds(sourced_compounds_list.len() + lazy_chunks.len(), load_options) {
+                break;
+            }
+
+            if region::parse_region_coords(&file_path).is_some() {
+                let read_result = Self::open_region_file(file_path.clone(), load_options);
+                if let Some(region_file) = Self::skip_if_corrupt(read_result, &file_path, load_options, &mut skipped_files)? {
+                    lazy_chunks.add_region(dimension, region_file);
+                }
+            }
+            else {
+                let read_result = Self::read_file_format(file_path.clone(), load_options);
+                if let Some(nbt_tag_compounds_list) = Self::skip_if_corrupt(read_result, &file_path, load_options, &mut skipped_files)? {
+                    sourced_compounds_list.append(&mut Self::to_sourced_compounds(dimension, &file_path, nbt_tag_compounds_list));
+                }
+            }
+        }
+
+        Ok((Self::filter_sourced_compounds(sourced_compounds_list, load_options), lazy_chunks, skipped_files))
+    }
+
+    /// Reads a region file, parsing its chunks with the `keep_only` of the options.
+    fn open_region_file(file_path: PathBuf, load_options: &WorldLoadOptions) -> std::io::Result<region::RegionFile> {
+        let mut region_file = region::RegionFile::new(file_path)?;
+        region_file.set_parse_options(Self::parse_options(load_options));
+        Ok(region_file)
+    }
 
+    fn parse_options(load_options: &WorldLoadOptions) -> file_parser::ParseOptions {
+        file_parser::ParseOptions { keep_only: load_options.keep_only.clone(), ..file_parser::ParseOptions::default() }
     }
 
-    fn read_file_format(input_path: PathBuf) -> std::io::Result<Vec<nbt_tag::NbtTagCompound>> {
+    /// Reads the compounds of a file, each with the (x, z) coordinates of its slot if the file is a region file
+    /// named `r.X.Z.mca`, None otherwise.
+    fn read_file_format(input_path: PathBuf, load_options: &WorldLoadOptions) -> std::io::Result<SlotCompounds> {
         
         if let Some(ext) = input_path.extension().and_then(|e| e.to_str()) {
 
             //let mut nbt_tag_compounds_list = Vec::<nbt_tag::NbtTagCompound>::new();
 
             if ext == "mcr" || ext == "mca" {
-                let region_file = region::RegionFile::new(input_path)?;
-                let nbt_tag_compounds_list = region_file.to_compounds_list()?;
-                Ok(nbt_tag_compounds_list)
+                let region_file = Self::open_region_file(input_path, load_options)?;
+                Self::read_region_slots(&region_file, load_options.max_compounds, load_options.skip_non_full_chunks)
             }
             else if ext == "nbt" || ext == "litematic" {
                 let bin_content = generic_bin::GenericBinFile::new(input_path, generic_bin::FileType::Nbt)?;
                 let nbt_tag_compounds_list = bin_content.to_compounds_list()?;
-                Ok(nbt_tag_compounds_list)   
+                Ok(nbt_tag_compounds_list.into_iter().map(|compound| (None, compound)).collect())   
             }
             else if ext == "json" {
                 let json_content = nbt_tag::NbtTagCompound::from_json(input_path)?;//Self::from_json(input_path)?;
-                let mut nbt_tag_compounds_list = Vec::<nbt_tag::NbtTagCompound>::new();
-                nbt_tag_compounds_list.push(json_content);
-                Ok(nbt_tag_compounds_list)
+                Ok(vec![(None, json_content)])
             }
             else {
                 Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid file extension"))
@@ -212,18 +851,532 @@ impl McWorldDescriptor {
 
     }
 
+    /// Decodes the chunks of a region file with the coordinates of their slots. With `skip_non_full_chunks` the chunks
+    /// not fully generated are skipped, and with `max_compounds` the decoding stops once that number of chunks is kept.
+    fn read_region_slots(region_file: &region::RegionFile, max_compounds: Option<usize>, skip_non_full_chunks: bool) -> std::io::Result<SlotCompounds> {
+        let mut slot_compounds = SlotCompounds::new();
+
+        for index in region_file.present_chunks() {
+            if max_compounds.is_some_and(|max_compounds| slot_compounds.len() >= max_compounds) {
+                break;
+            }
+            if let Some(chunk_compound) = region_file.read_chunk(index)? {
+                if !skip_non_full_chunks || chunk_format::is_chunk_fully_generated(&chunk_compound) {
+                    slot_compounds.push((region_file.chunk_coords(index), chunk_compound));
+                }
+            }
+        }
+
+        Ok(slot_compounds)
+    }
+
+    #[cfg(feature = "async")]
+    async fn read_input_path_async(input_path: PathBuf, load_options: &WorldLoadOptions) -> std::io::Result<(Vec<SourcedCompound>, Vec<world::SkippedFile>)> {
+        
+        /* Same as read_input_path: a folder must contain the subfolder "region", otherwise the path is read as a single file */
+        let mut sourced_compounds_list = Vec::<SourcedCompound>::new();
+        let mut skipped_files = Vec::<world::SkippedFile>::new();
+
+        if tokio::fs::metadata(&input_path).await?.is_dir() {
+            let region_path = Dimension::Overworld.region_path(&input_path);
+            if !tokio::fs::metadata(&region_path).await.map(|m| m.is_dir()).unwrap_or(false) {
+                return Err(std::io::Error::other("SubDir './region' does not exist"));
+            }
+
+            'dimensions: for dimension in Self::dimensions_to_load(load_options) {
+                let region_path = dimension.region_path(&input_path);
+                if !tokio::fs::metadata(&region_path).await.map(|m| m.is_dir()).unwrap_or(false) {
+                    continue;
+                }
+
+                let mut entries = tokio::fs::read_dir(region_path).await?;
+                while let Some(entry) = entries.next_entry().await? {
+                    if Self::reached_max_compounds(sourced_compounds_list.len(), load_options) {
+                        break 'dimensions;
+                    }
+
+                    let read_result = Self::read_file_format_async(entry.path(), load_options).await;
+                    if let Some(nbt_tag_compounds_list) = Self::skip_if_corrupt(read_result, &entry.path(), load_options, &mut skipped_files)? {
+                        let sourced_compounds = Self::to_sourced_compounds(dimension, &entry.path(), nbt_tag_compounds_list);
+                        sourced_compounds_list.append(&mut Self::filter_sourced_compounds(sourced_compounds, load_options));
+                    }
+                }
+            }
+        }
+        else {
+            let dimension = Dimension::from_region_file_path(&input_path);
+            let read_result = Self::read_file_format_async(input_path.clone(), load_options).await;
+            if let Some(nbt_tag_compounds_list) = Self::skip_if_corrupt(read_result, &input_path, load_options, &mut skipped_files)? {
+                sourced_compounds_list.append(&mut Self::to_sourced_compounds(dimension, &input_path, nbt_tag_compounds_list));
+            }
+        }
+
+        Ok((sourced_compounds_list, skipped_files))
+    }
+
+    #[cfg(feature = "async")]
+    async fn read_file_format_async(input_path: PathBuf, load_options: &WorldLoadOptions) -> std::io::Result<SlotCompounds> {
+        
+        let ext = match input_path.extension().and_then(|e| e.to_str()) {
+            Some(ext) => ext.to_string(),
+            None => return Err(io::Error::new(io::ErrorKind::InvalidInput, "File without extension")),
+        };
+
+        if !["mcr", "mca", "nbt", "litematic", "json"].contains(&ext.as_str()) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid file extension"));
+        }
+
+        let region_coords = region::parse_region_coords(&input_path);
+        let raw_data = tokio::fs::read(&input_path).await?;
+        let parse_options = Self::parse_options(load_options);
+        let max_compounds = load_options.max_compounds;
+        let skip_non_full_chunks = load_options.skip_non_full_chunks;
+
+        // decompression and parsing are CPU-bound, keep them out of the async executor
+        tokio::task::spawn_blocking(move || {
+            if ext == "mcr" || ext == "mca" {
+                let mut region_file = region::RegionFile::from_bytes(raw_data, region_coords)?;
+                region_file.set_parse_options(parse_options);
+                Self::read_region_slots(&region_file, max_compounds, skip_non_full_chunks)
+            }
+            else if ext == "json" {
+                let json_content: nbt_tag::NbtTagCompound = serde_json::from_slice(&raw_data)?;
+                Ok(vec![(None, json_content)])
+            }
+            else {
+                let nbt_tag_compounds_list = generic_bin::GenericBinFile::from_bytes(raw_data).to_compounds_list()?;
+                Ok(nbt_tag_compounds_list.into_iter().map(|compound| (None, compound)).collect())
+            }
+        })
+        .await
+        .map_err(io::Error::other)?
+    }
+
     pub fn get_mc_version(&self) -> String {
         self.version.clone()
     }
 
+    /// Writes the first loaded compound to a JSON file, see `export_each_to_json` to write them all.
+    /// 
+    /// Returns a `NotFound` error if no compound is loaded.
     pub fn to_json<P: AsRef<std::path::Path>>(&self, path: P) -> io::Result<()> {
-        Ok(self.tag_compounds_list.get(0).unwrap().to_json(path)?)
+        self.to_json_with_options(path, &nbt_tag::JsonOptions::default())
+    }
+
+    /// Same as `to_json`, with the given options (e.g. `longs_as_strings` for the JavaScript tools).
+    pub fn to_json_with_options<P: AsRef<std::path::Path>>(&self, path: P, options: &nbt_tag::JsonOptions) -> io::Result<()> {
+        let Some(first_compound) = self.tag_compounds_list.first() else {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "No compound loaded to write to JSON"));
+        };
+
+        first_compound.to_json_with_options(path, options)
+    }
+
+    /// Writes each loaded compound to its own JSON file in `dir`, in parallel.
+    /// 
+    /// The chunks are named after their region and chunk coordinates, `r.<region x>.<region z>_c.<chunk x>.<chunk z>.json`,
+    /// and the Nether and End chunks are written in the `DIM-1` and `DIM1` subfolders, as in the world folder.
+    /// The compounds without chunk coordinates (e.g. a single nbt file) are named `compound.<index>.json`.
+    /// The folders are created if missing, the first write error is returned.
+    pub fn export_each_to_json(&self, dir: PathBuf) -> io::Result<()> {
+        self.export_each_to_json_with_options(dir, &nbt_tag::JsonOptions::default())
     }
 
-    pub fn search_blocks<'a>(&self, block_resource_location: Vec::<String>) -> HashMap::<String, Vec::<blocks::MinecraftBlock>> {
-        chunk_format::inspect_chunks(block_resource_location, &self.tag_compounds_list)
-    } 
+    /// Same as `export_each_to_json`, with the given options.
+    pub fn export_each_to_json_with_options(&self, dir: PathBuf, options: &nbt_tag::JsonOptions) -> io::Result<()> {
+        std::fs::create_dir_all(&dir)?;
+        for dimension in self.loaded_dimensions() {
+            std::fs::create_dir_all(dir.join(dimension.folder()))?;
+        }
+
+        self.tag_compounds_list.par_iter()
+            .enumerate()
+            .try_for_each(|(index, sourced_compound)| sourced_compound.to_json_with_options(dir.join(self.export_file_name(index, sourced_compound)), options))
+    }
 
+    /// Returns the path, relative to the export folder, of the JSON file of the compound at `index`.
+    fn export_file_name(&self, index: usize, compound: &nbt_tag::NbtTagCompound) -> PathBuf {
+        let dimension = self.compound_dimension(index).unwrap_or_default();
+
+        let file_name = if chunk_format::has_chunk_coordinates(compound) {
+            let chunk_pos = chunk_format::get_chunk_coordinates(compound);
+            let (region_x, region_z) = world::chunk_to_region_coords(chunk_pos.x, chunk_pos.z);
+            format!("r.{}.{}_c.{}.{}.json", region_x, region_z, chunk_pos.x, chunk_pos.z)
+        }
+        else {
+            format!("compound.{}.json", index)
+        };
+
+        Path::new(dimension.folder()).join(file_name)
+    }
+
+    /// Searches the blocks by resource location, given as `blocks::ResourceLocation` or as strings.
+    /// 
+    /// The strings are validated through `ResourceLocation`: a missing namespace defaults to `minecraft`
+    /// (the result is keyed by `minecraft:stone` when searching `stone`), malformed ones are logged and skipped.
+    /// 
+    /// The loaded structure files are searched too, with coordinates relative to their origin (see `structure::search_blocks`).
+    pub fn search_blocks<T>(&self, block_resource_location: Vec::<T>) -> HashMap::<String, Vec::<blocks::MinecraftBlock>> 
+    where
+        T: TryInto<blocks::ResourceLocation>,
+        T::Error: std::fmt::Display,
+    {
+        self.search_blocks_with_options(block_resource_location, &blocks::SearchOptions::default())
+    }
+
+    /// Same as `search_blocks`, with the given options (e.g. `sort_by_position` for a reproducible order).
+    pub fn search_blocks_with_options<T>(&self, block_resource_location: Vec::<T>, options: &blocks::SearchOptions) -> HashMap::<String, Vec::<blocks::MinecraftBlock>> 
+    where
+        T: TryInto<blocks::ResourceLocation>,
+        T::Error: std::fmt::Display,
+    {
+        let resource_locations: Vec<String> = block_resource_location.into_iter()
+            .filter_map(|resource_location| match resource_location.try_into() {
+                Ok(resource_location) => Some(resource_location.to_string()),
+                Err(e) => {
+                    log::warn!("search_blocks: {}", e);
+                    None
+                }
+            })
+            .collect();
+
+        let mut blocks_found = chunk_format::inspect_chunks(resource_locations.clone(), &self.tag_compounds_list);
+        for structure_root in self.tag_compounds_list.iter().filter(|compound| structure::is_structure(compound)) {
+            for (resource_location, blocks) in structure::search_blocks(structure_root, &resource_locations) {
+                blocks_found.entry(resource_location).or_default().extend(blocks);
+            }
+        }
+        if options.sort_by_position {
+            blocks::sort_blocks_by_position(&mut blocks_found);
+        }
+
+        blocks_found
+    }
+
+    /// Searches the blocks of a tag (e.g. `minecraft:logs`) or a category (`ores`, `fluids`), see `blocks::blocks_in_category`.
+    /// The blocks found are grouped by their resource location, e.g. `minecraft:deepslate_iron_ore`.
+    /// 
+    /// An unknown tag is logged and finds nothing: only the tags of `blocks::BLOCK_TAGS` are known.
+    pub fn search_by_tag(&self, tag: &str) -> HashMap::<String, Vec::<blocks::MinecraftBlock>> {
+        let block_names = blocks::blocks_in_category(tag);
+        if block_names.is_empty() {
+            log::warn!("search_by_tag: unknown block tag {}", tag);
+            return HashMap::new();
+        }
+
+        self.search_blocks(block_names)
+    }
+
+    /// Same as `search_blocks`, but also reports the requested blocks that can never match:
+    /// the ones missing from every palette (most likely typos) and the malformed ones.
+    /// 
+    /// Example: searching `minecraft:daimond_ore` returns it in `not_in_palette` instead of an empty result
+    /// indistinguishable from "no diamond ore in the world".
+    pub fn search_blocks_report<T>(&self, block_resource_location: Vec::<T>) -> blocks::BlockSearchReport 
+    where
+        T: TryInto<blocks::ResourceLocation> + ToString,
+    {
+        let mut report = blocks::BlockSearchReport::default();
+        let mut resource_locations = Vec::new();
+
+        for resource_location in block_resource_location {
+            let requested = resource_location.to_string();
+            match resource_location.try_into() {
+                Ok(resource_location) => resource_locations.push(resource_location.to_string()),
+                Err(_) => report.invalid.push(requested),
+            }
+        }
+
+        let palette_block_names = chunk_format::collect_palette_block_names(&self.tag_compounds_list);
+        report.not_in_palette = resource_locations.iter()
+            .filter(|resource_location| !palette_block_names.contains(*resource_location))
+            .cloned()
+            .collect();
+
+        report.blocks = chunk_format::inspect_chunks(resource_locations.clone(), &self.tag_compounds_list);
+        for resource_location in resource_locations {
+            report.blocks.entry(resource_location).or_default();
+        }
+
+        report
+    }
+
+    /// Searches blocks by resource location, keeping only the palette entries whose block-state
+    /// properties match the given (name, value) pairs. Unspecified properties act as wildcards.
+    ///
+    /// Example: `search_blocks_with_props(vec![("minecraft:oak_log".to_string(), vec![("axis".to_string(), "x".to_string())])])`
+    pub fn search_blocks_with_props(&self, block_filters: Vec::<(String, Vec::<(String, String)>)>) -> HashMap::<String, Vec::<blocks::MinecraftBlock>> {
+        chunk_format::inspect_chunks_with_props(block_filters, &self.tag_compounds_list)
+    }
+
+
+    /// Replaces every occurrence of the block `from` with the block `to` in all the loaded chunks.
+    /// 
+    /// The section palettes are updated in place, the block-state properties are preserved except
+    /// the ones the target block does not support. The supported properties are learnt from the palettes 
+    /// where the target block already appears; if it appears nowhere, all the properties are kept.
+    /// 
+    /// Returns the number of blocks replaced.
+    /// The chunks with replaced blocks are marked as modified, see `save`.
+    pub fn replace_block(&mut self, from: &str, to: &str) -> usize {
+        let target_property_names = chunk_format::collect_block_property_names(to, &self.tag_compounds_list);
+
+        let replaced_blocks: Vec<usize> = self.tag_compounds_list.iter_mut()
+            .map(|chunk_compound| {
+                // the chunks without the block are left as they are, not copied if shared with a snapshot
+                if !chunk_format::collect_palette_block_names(std::slice::from_ref(&*chunk_compound)).contains(from) {
+                    return 0;
+                }
+                chunk_format::replace_block(chunk_compound, from, to, target_property_names.as_ref())
+            })
+            .collect();
+        for (index, _) in replaced_blocks.iter().enumerate().filter(|(_, replaced)| **replaced > 0) {
+            self.mark_dirty(index);
+        }
+
+        replaced_blocks.iter().sum()
+    }
+
+    /// Returns the compound at `index` in `tag_compounds_list` to edit it, marking it as modified (see `mark_dirty`).
+    pub fn compound_mut(&mut self, index: usize) -> Option<&mut nbt_tag::NbtTagCompound> {
+        self.mark_dirty(index);
+        self.tag_compounds_list.get_mut(index).map(|sourced_compound| &mut **sourced_compound)
+    }
+
+    /// Captures the loaded compounds and their modified flags, to roll back the later edits with `restore`.
+    /// 
+    /// The compounds are shared between the descriptor and the snapshot, not copied: taking a snapshot costs
+    /// one reference per compound. A shared compound is copied when first edited afterwards (through `compound_mut`,
+    /// a `ChunkHandle` or `tag_compounds_list`), so the memory cost is the size of the chunks edited.
+    /// The chunks still in the lazy storage (`LoadMode::Lazy`) are shared as well.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            tag_compounds_list: self.tag_compounds_list.clone(),
+            lazy_chunks: self.lazy_chunks.clone(),
+        }
+    }
+
+    /// Rolls back the loaded compounds to `snapshot`, including the chunks marked as modified.
+    /// 
+    /// The files are not touched: restoring after `save` marks the chunks as they were in the snapshot,
+    /// call `mark_dirty` to write the restored chunks back.
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        self.tag_compounds_list = snapshot.tag_compounds_list;
+        self.lazy_chunks = snapshot.lazy_chunks;
+    }
+
+    /// Returns the compound at `index` in `tag_compounds_list` wrapped in a `ChunkHandle`:
+    /// its edits mark the chunk as modified, to be written by `save`.
+    pub fn chunk_handle(&mut self, index: usize) -> Option<world::ChunkHandle<'_>> {
+        let position = self.saved_chunk_position(index);
+        Some(world::ChunkHandle::new(self.tag_compounds_list.get_mut(index)?, position))
+    }
+
+    /// Returns the loaded chunk at the chunk coordinates (chunk_x, chunk_z) of `dimension`, see `chunk_handle`.
+    pub fn chunk_handle_at(&mut self, dimension: Dimension, chunk_x: i32, chunk_z: i32) -> Option<world::ChunkHandle<'_>> {
+        let index = self.tag_compounds_list.iter()
+            .position(|sourced_compound| sourced_compound.dimension == dimension && sourced_compound.chunk_coords == Some((chunk_x, chunk_z)))?;
+        self.chunk_handle(index)
+    }
+
+    /// Marks the compound at `index` in `tag_compounds_list` as modified, to be written by `save`.
+    /// Call it after editing a chunk directly in `tag_compounds_list`.
+    /// 
+    /// Returns false if the compound cannot be saved: it is not a chunk read from a region file.
+    pub fn mark_dirty(&mut self, index: usize) -> bool {
+        if self.saved_chunk_position(index).is_none() {
+            return false;
+        }
+
+        self.tag_compounds_list[index].dirty = true;
+        true
+    }
+
+    /// Returns whether the compound at `index` in `tag_compounds_list` was modified since loaded or saved.
+    pub fn is_dirty(&self, index: usize) -> bool {
+        self.saved_chunk_position(index).is_some() && self.tag_compounds_list[index].dirty
+    }
+
+    /// Writes the modified chunks back to the region files they were read from, each one in its slot.
+    /// The unchanged chunks are not encoded again, the regions without modified chunks are not written.
+    pub fn save(&mut self) -> io::Result<()> {
+        self.save_with_compression(region::Compression::default())
+    }
+
+    /// Same as `save`, compressing the modified chunks with the `compression` level.
+    pub fn save_with_compression(&mut self, compression: region::Compression) -> io::Result<()> {
+        if self.pruned_chunks && (0..self.tag_compounds_list.len()).any(|index| self.is_dirty(index)) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "The chunks loaded with WorldLoadOptions::keep_only cannot be saved"));
+        }
+
+        let mut region_chunks: HashMap<&Path, Vec<(usize, &nbt_tag::NbtTagCompound)>> = HashMap::new();
+        for (index, sourced_compound) in self.tag_compounds_list.iter().enumerate() {
+            if !self.is_dirty(index) {
+                continue;
+            }
+
+            if let (Some(file_path), Some((chunk_x, chunk_z))) = (sourced_compound.file_path.as_deref(), sourced_compound.chunk_coords) {
+                region_chunks.entry(file_path).or_default().push((world::chunk_index_in_region(chunk_x, chunk_z), sourced_compound.as_ref()));
+            }
+        }
+
+        for (file_path, chunks) in region_chunks {
+            region::RegionFile::new(file_path.to_path_buf())?.write_with_compression(file_path.to_path_buf(), &chunks, compression)?;
+        }
+
+        for sourced_compound in self.tag_compounds_list.iter_mut() {
+            sourced_compound.dirty = false;
+        }
+        Ok(())
+    }
+
+    /// Returns the dimension and the chunk coordinates of the compound at `index`, if it is a chunk
+    /// of the region file it was read from.
+    fn saved_chunk_position(&self, index: usize) -> Option<(Dimension, i32, i32)> {
+        let source = self.tag_compounds_list.get(index)?;
+        let (chunk_x, chunk_z) = source.chunk_coords?;
+        source.file_path.as_ref()?;
+        if source.region_coords? != world::chunk_to_region_coords(chunk_x, chunk_z) {
+            return None;
+        }
+
+        Some((source.dimension, chunk_x, chunk_z))
+    }
+
+    /// Returns every item stack (id, count) stored in the loaded chunks, with the coordinates of its container.
+    /// 
+    /// The containers are the block entities (chests, barrels, hoppers, furnaces...) and the entities
+    /// with an inventory, see `items::chunk_items`. Since 1.17 the entities are saved in the `entities` folder
+    /// of the world, load those region files to include them.
+    pub fn all_items(&self) -> Vec<(blocks::Coordinates, String, u8)> {
+        self.tag_compounds_list.iter().map(AsRef::as_ref)
+            .flat_map(items::chunk_items)
+            .collect()
+    }
+
+    /// Renders a top-down map of the loaded chunks, one pixel per block column colored by its topmost non-air block
+    /// with the built-in colors (`render::BLOCK_COLORS`). Requires the "image" feature.
+    /// 
+    /// Returns `None` if no chunk is loaded, or if the chunks span more than `chunk_format::MAX_MAP_PIXELS` columns
+    /// (e.g. the Overworld and the Nether loaded together). See `render_topdown_with` to use other colors.
+    #[cfg(feature = "image")]
+    pub fn render_topdown(&self) -> Option<image::RgbImage> {
+        self.render_topdown_with(render::default_block_color)
+    }
+
+    /// Same as `render_topdown`, with the color of each block given by `block_color` (e.g. to highlight the ores).
+    #[cfg(feature = "image")]
+    pub fn render_topdown_with<F: Fn(&str) -> [u8; 3]>(&self, block_color: F) -> Option<image::RgbImage> {
+        render::render_topdown(&self.tag_compounds_list, block_color)
+    }
+
+    /// Same as `render_topdown`, with the colors of a palette mapping the block resource locations to RGB.
+    /// The blocks missing from the palette are magenta (`render::UNKNOWN_BLOCK_COLOR`).
+    /// 
+    /// Example, highlight the diamond ore on the built-in colors:
+    /// `let mut palette = render::default_block_palette(); palette.insert("minecraft:diamond_ore".to_string(), [255, 0, 0]);`
+    #[cfg(feature = "image")]
+    pub fn render_topdown_with_palette(&self, palette: &HashMap<String, [u8; 3]>) -> Option<image::RgbImage> {
+        render::render_topdown_with_palette(&self.tag_compounds_list, palette)
+    }
+
+    /// Returns the coordinates of the containers holding the item `item_id` and the stack counts,
+    /// including the items nested in shulker boxes and bundles. See `all_items`.
+    pub fn search_items(&self, item_id: &str) -> Vec<(blocks::Coordinates, u8)> {
+        self.all_items().into_iter()
+            .filter(|(_, id, _)| id == item_id)
+            .map(|(coordinates, _, count)| (coordinates, count))
+            .collect()
+    }
+
+    /// Returns the entities of the loaded chunks matching `predicate`, e.g. the entities with a `CustomName` 
+    /// or the ones with `Health` 20 (see `NbtTag::numeric_eq`). See `chunk_format::get_entities`.
+    /// 
+    /// Since 1.17 the entities are saved in the `entities` folder of the world, load those region files to include them.
+    pub fn find_entities_where<F: Fn(&nbt_tag::NbtTagCompound) -> bool>(&self, predicate: F) -> Vec<&nbt_tag::NbtTagCompound> {
+        self.tag_compounds_list.iter().map(AsRef::as_ref)
+            .flat_map(chunk_format::get_entities)
+            .filter(|entity| predicate(entity))
+            .collect()
+    }
+
+    /// Returns the villagers of the loaded chunks with the coordinates of the block they are in and their trades,
+    /// empty for the villagers without trades yet. See `items::villager_trades`.
+    /// 
+    /// Since 1.17 the entities are saved in the `entities` folder of the world, load those region files to include them.
+    pub fn villager_trades(&self) -> Vec<(blocks::Coordinates, Vec<items::Trade>)> {
+        self.tag_compounds_list.iter().map(AsRef::as_ref)
+            .flat_map(items::chunk_villager_trades)
+            .collect()
+    }
+
+    /// Counts the blocks of the Overworld inside the box between `min` and `max` (world coordinates, both included),
+    /// by block name, e.g. to list the materials of a build. 
+    /// 
+    /// Only the loaded chunks and, in them, the sections intersecting the box are decoded (see `chunk_format::count_blocks_in_box`).
+    /// Use `chunk_format::count_blocks_in_box` on `dimension` to count in the other dimensions.
+    pub fn count_blocks_in_box(&self, min: blocks::Coordinates, max: blocks::Coordinates) -> HashMap<String, u64> {
+        self.dimension(Dimension::Overworld).par_iter()
+            .map(|chunk_compound| chunk_format::count_blocks_in_box(chunk_compound, &min, &max))
+            .reduce(HashMap::new, |mut blocks_count, chunk_blocks_count| {
+                for (block_name, count) in chunk_blocks_count {
+                    *blocks_count.entry(block_name).or_default() += count;
+                }
+                blocks_count
+            })
+    }
+
+    /// Returns the structure of type `id` (e.g. `minecraft:village_plains`) starting closest to `origin`, 
+    /// as the `/locate` command, with its id and the position of its start.
+    /// 
+    /// The structures are read from the loaded chunks (see `chunk_format::structures`): a structure is found if its 
+    /// start chunk or a chunk it reaches is loaded. The distance is horizontal, to the center of the start chunk. 
+    /// The returned position is the center of the start chunk, at the bottom of the structure if its start chunk 
+    /// is loaded (the y of `origin` otherwise).
+    /// 
+    /// Only the Overworld is searched, see `nearest_structure_in` for the other dimensions.
+    pub fn nearest_structure(&self, id: &str, origin: blocks::Coordinates) -> Option<(String, blocks::Coordinates)> {
+        self.nearest_structure_in(Dimension::Overworld, id, origin)
+    }
+
+    /// Same as `nearest_structure`, searching the chunks of `dimension` (e.g. a fortress in the Nether).
+    pub fn nearest_structure_in(&self, dimension: Dimension, id: &str, origin: blocks::Coordinates) -> Option<(String, blocks::Coordinates)> {
+        let mut structure_starts: HashMap<(i32, i32), Option<i32>> = HashMap::new();
+        for structure_ref in self.dimension(dimension).into_iter().flat_map(|chunk| chunk_format::structures(chunk)).filter(|structure_ref| structure_ref.id == id) {
+            let bottom_y = structure_starts.entry(structure_ref.start_chunk).or_default();
+            if let Some(bounding_box) = structure_ref.bounding_box {
+                *bottom_y = Some(bounding_box[1]);
+            }
+        }
+
+        structure_starts.into_iter()
+            .map(|((chunk_x, chunk_z), bottom_y)| blocks::Coordinates::new(vec![chunk_x * 16 + 8, bottom_y.unwrap_or(origin.y), chunk_z * 16 + 8]))
+            .min_by_key(|start| {
+                let (dx, dz) = ((start.x - origin.x) as i64, (start.z - origin.z) as i64);
+                (dx * dx + dz * dz, start.x, start.z)
+            })
+            .map(|start| (id.to_string(), start))
+    }
+
+    /// Compares the loaded chunks with the ones of `other`, as the newer version of the world, see `world::diff_chunks`.
+    /// Only the coordinates of the added, removed and changed chunks are reported.
+    pub fn diff_world(&self, other: &McWorldDescriptor) -> Vec<world::ChunkChange> {
+        self.diff_world_with_options(other, &world::DiffOptions::default())
+    }
+
+    pub fn diff_world_with_options(&self, other: &McWorldDescriptor, options: &world::DiffOptions) -> Vec<world::ChunkChange> {
+        world::diff_chunks(&self.chunks_by_position(), &other.chunks_by_position(), options)
+    }
+
+    /// Returns the loaded chunks by dimension and chunk coordinates, leaving out the compounds that are not chunks.
+    fn chunks_by_position(&self) -> HashMap<(Dimension, i32, i32), &nbt_tag::NbtTagCompound> {
+        self.tag_compounds_list.iter()
+            .filter_map(|sourced_compound| {
+                let (chunk_x, chunk_z) = sourced_compound.chunk_coords?;
+                Some(((sourced_compound.dimension, chunk_x, chunk_z), sourced_compound.as_ref()))
+            })
+            .collect()
+    }
 
     pub fn search_compound(&self, key: &str, stop_at_first: bool) ->  (bool, Vec::<&nbt_tag::NbtTagCompound>) {
         
@@ -252,13 +1405,13 @@ impl McWorldDescriptor {
                                             -> bool {
             
         //End condition: a compound matches the key
-        if tag_compound.name == key {
+        if tag_compound.name() == key {
             result_list.push(tag_compound);
             return true;
         }
         
         //Recursion
-        for (_, v) in tag_compound.values.iter() {
+        for (_, v) in tag_compound.iter() {
             if v.ty() == nbt_tag::NbtTagType::Compound {
                 let compound_option = v.compound_as_ref();
                 
@@ -322,243 +1475,3 @@ impl McWorldDescriptor {
     }*/
 
 }
-
-
-/* #[derive(Clone, Debug)]
-pub struct SerializablePyDict(Py<PyDict>);
-
-impl SerializablePyDict {
-    pub fn get_py_dict(&self) -> &Py<PyDict> {
-        &self.0
-    }
-}
-
-impl IntoPy<Py<PyAny>> for SerializablePyDict {
-    fn into_py(self, py: Python) -> Py<PyAny> {
-        self.0.into_py(py)
-    }
-}
-
-impl ToPyObject for SerializablePyDict {
-    fn to_object(&self, py: Python) -> PyObject {
-        self.0.to_object(py) // Delegate to Py<PyDict>'s implementation
-    }
-}
-
-impl FromPyObject<'_> for SerializablePyDict {
-    fn extract(ob: &'_ PyAny) -> PyResult<Self> {
-        let py_dict: Py<PyDict> = ob.extract()?; // Extract as Py<PyDict> using PyDict's FromPyObject
-        Ok(SerializablePyDict(py_dict)) // Wrap in SerializablePyDict
-    }
-}
-
-impl Serialize for SerializablePyDict {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        Python::with_gil(|py| {
-            let dict: &PyDict = self.0.as_ref(py);
-            let mut rust_dict = HashMap::new();
-
-            for (key, value) in dict.into_iter() {
-                let key_str = key.extract::<String>().map_err(serde::ser::Error::custom)?;
-                let value_str = value.extract::<PyNbtTag>().map_err(serde::ser::Error::custom)?;
-                rust_dict.insert(key_str, value_str);
-            }
-
-            let mut map = serializer.serialize_map(Some(rust_dict.len()))?;
-            for (k, v) in rust_dict {
-                map.serialize_entry(&k, &v.ser_python_dict)?;
-            }
-            map.end()
-        })
-    }
-} */
-
-#[pyclass(get_all)]
-#[derive(Clone, Debug)]
-pub struct PyNbtTag {
-    //pub nbt_tag: &'a NbtTag,
-    pub python_dict: Py<PyDict>,
-    //pub ser_python_dict: SerializablePyDict
-}
-
-//https://github.com/PyO3/pyo3/pull/3582 
-impl PyNbtTag {
-
-    pub fn new(nbt_tag: &nbt_tag::NbtTag) -> Self {
-        let python_dict = Self::to_python_dictionary(&nbt_tag);
-        //let ser_py_dict = Self::to_ser_python_dictionary(python_dict);
-        Self {
-            //python_dict,
-            python_dict
-        }
-    }
-
-    /* fn to_ser_python_dictionary(py_dict: Py<PyDict>) -> SerializablePyDict {
-        SerializablePyDict(py_dict)
-    } */
-
-    fn to_python_dictionary(nbt_tag: & nbt_tag::NbtTag) -> Py<PyDict> {
-        
-        Python::with_gil(|py| {
-            let dict: Py<PyDict> = PyDict::new(py).into();
-            // TODO: Get rid of all these unwraps
-
-            match nbt_tag.ty() {
-                nbt_tag::NbtTagType::End => {
-
-                    //let log_msg = format!("tag_end: Name: {}, Value: {}", "[END]", "[END]");
-                    //crate::py_log(log_msg);
-
-                    dict.as_ref(py).set_item("END_TAG", 0).unwrap();
-                    dict
-                },
-                nbt_tag::NbtTagType::Byte => {
-                    let tag_byte = nbt_tag.byte().unwrap();
-
-                    //let log_msg = format!("tag_byte: Name: {}, Value: {}", tag_byte.name, tag_byte.value);
-                    //crate::py_log(log_msg);
-
-                    dict.as_ref(py).set_item(tag_byte.name, tag_byte.value).unwrap();
-                    dict
-
-                },
-                nbt_tag::NbtTagType::Short => {
-                    let tag_short = nbt_tag.short().unwrap();
-
-                    //let log_msg = format!("tag_short: Name: {}, Value: {}", tag_short.name, tag_short.value);
-                    //crate::py_log(log_msg);
-
-
-                    dict.as_ref(py).set_item(tag_short.name, tag_short.value).unwrap();
-                    dict
-
-                },
-                nbt_tag::NbtTagType::Int => {
-                    let tag_int = nbt_tag.int().unwrap_or_default(); //error without default.
-
-                    //let log_msg = format!("tag_int: Name: {}, Value: {}", tag_int.name, tag_int.value);
-                    //crate::py_log(log_msg);
-
-                    dict.as_ref(py).set_item(tag_int.name, tag_int.value).unwrap();
-                    dict
-
-                },
-                nbt_tag::NbtTagType::Long => {
-                    let tag_long = nbt_tag.long().unwrap();
-
-                    //let log_msg = format!("tag_long: Name: {}, Value: {}", tag_long.name, tag_long.value);
-                    //crate::py_log(log_msg);
-
-                    dict.as_ref(py).set_item(tag_long.name, tag_long.value).unwrap();
-                    dict
-
-                },
-                nbt_tag::NbtTagType::Float => {
-                    let tag_float = nbt_tag.float().unwrap();
-
-                    //let log_msg = format!("tag_float: Name: {}, Value: {}", tag_float.name, tag_float.value);
-                    //crate::py_log(log_msg);
-
-
-                    dict.as_ref(py).set_item(tag_float.name, tag_float.value).unwrap();
-                    dict
-
-                },
-                nbt_tag::NbtTagType::Double => {
-                    let tag_double = nbt_tag.double().unwrap();
-
-                    //let log_msg = format!("tag_double: Name: {}, Value: {}", tag_double.name, tag_double.value);
-                    //crate::py_log(log_msg);
-
-                    dict.as_ref(py).set_item(tag_double.name, tag_double.value).unwrap();
-                    dict
-
-                },
-                nbt_tag::NbtTagType::ByteArray => {
-                    let tag_byte_array = nbt_tag.byte_array().unwrap();
-
-                    //let log_msg = format!("tag_byte_array: Name: {}, Value: {}", tag_byte_array.name, "[Values]");
-                    //crate::py_log(log_msg);
-
-                    dict.as_ref(py).set_item(tag_byte_array.name, tag_byte_array.values).unwrap();
-                    dict
-
-                },
-                nbt_tag::NbtTagType::String => {
-                    let tag_string = nbt_tag.string().unwrap();
-
-                    //let log_msg = format!("tag_string: Name: {}, Value: {}", tag_string.name, tag_string.value);
-                    //crate::py_log(log_msg);
-
-                    dict.as_ref(py).set_item(tag_string.name, tag_string.value).unwrap();
-                    dict
-
-                },
-                nbt_tag::NbtTagType::List => {
-                    let tag_list = nbt_tag.list().unwrap();
-                    let empty_object_array: &[PyObject] = &[];
-                    let py_list: &PyList = PyList::new(py, empty_object_array);
-
-                    //let log_msg = format!("tag_list: Name: {}, Value: {}", tag_list.name, "[NbtTagList]");
-                    //crate::py_log(log_msg);
-
-                    //not efficient, i am processind the data two times, but for now make it work
-                    for list_element in &tag_list.values {
-                        let py_list_element = PyNbtTag::new(list_element);
-                        let _ = py_list.append(py_list_element.python_dict);
-
-                        //let log_msg = format!("tag_list: parsed");
-                        //crate::py_log(log_msg);
-                    }
-
-                    dict.as_ref(py).set_item(tag_list.name, py_list).unwrap();
-                    dict
-
-                },
-                nbt_tag::NbtTagType::Compound => {
-                    let tag_compound = nbt_tag.compound().unwrap();
-                    //let empty_object_array: &[PyObject] = &[];
-                    let py_dict: &PyDict = PyDict::new(py);
-
-                    //let log_msg = format!("tag_compound: Name: {}, Value: {}", tag_compound.name, "[HashMap]");
-                    //crate::py_log(log_msg);
-
-                    for (key, value) in tag_compound.values.iter() {
-                        let py_tag = PyNbtTag::new(value);
-                        let _ = py_dict.set_item(key, py_tag.python_dict);
-
-                        //let log_msg = format!("tag_compound_hashmap: Name: {}, Value: {}", key, "[NbtTag]");
-                        //crate::py_log(log_msg);
-                    }
-
-                    dict.as_ref(py).set_item(tag_compound.name, py_dict).unwrap();
-                    dict
-
-                },
-                nbt_tag::NbtTagType::IntArray => {
-                    let tag_int_array = nbt_tag.int_array().unwrap();
-
-                    //let log_msg = format!("tag_int_array: Name: {}, Value: {}", tag_int_array.name, "[Values]");
-                    //crate::py_log(log_msg);
-
-                    dict.as_ref(py).set_item(tag_int_array.name, tag_int_array.values).unwrap();
-                    dict
-
-                },
-                nbt_tag::NbtTagType::LongArray => {
-                    let tag_long_array = nbt_tag.long_array().unwrap();
-
-                    //let log_msg = format!("tag_long_array: Name: {}, Value: {}", tag_long_array.name, "[Values]");
-                    //crate::py_log(log_msg);
-
-                    dict.as_ref(py).set_item(tag_long_array.name, tag_long_array.values).unwrap();
-                    dict
-
-                }
-            }
-        })
-    }
-}
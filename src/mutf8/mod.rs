@@ -0,0 +1,93 @@
+// ## Author
+// - mrmarkolinus
+//
+// ## Date
+// - 2026-10-16
+//
+// ## File Version
+// - 1.0.0
+//
+// ## Changelog
+// - 1.0.0: Initial version
+
+//! Java Modified UTF-8, the string encoding used by NBT.
+//! 
+//! It differs from standard UTF-8 in two points:
+//! - the null character is encoded with two bytes (0xC0 0x80)
+//! - supplementary characters (e.g. emoji) are encoded as a UTF-16 surrogate pair, three bytes each surrogate
+
+use crate::error::RnbtError;
+
+#[cfg(test)]
+mod tests;
+
+/// Decodes a Modified UTF-8 byte sequence.
+/// 
+/// Standard 4-byte UTF-8 sequences, written by some non-Java tools, are accepted too.
+/// Unpaired surrogates (valid in Java strings) are replaced with U+FFFD.
+pub fn decode(bytes: &[u8]) -> Result<String, RnbtError> {
+    // fast path, most of the names and values are plain ASCII
+    if bytes.is_ascii() {
+        return Ok(String::from_utf8_lossy(bytes).into_owned());
+    }
+
+    let invalid = || RnbtError::InvalidString(String::from_utf8_lossy(bytes).into_owned());
+    let continuation = |index: usize| -> Result<u16, RnbtError> {
+        match bytes.get(index) {
+            Some(byte) if byte & 0xC0 == 0x80 => Ok((byte & 0x3F) as u16),
+            _ => Err(invalid()),
+        }
+    };
+
+    let mut utf16_units = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        if byte < 0x80 {
+            utf16_units.push(byte as u16);
+            index += 1;
+        } else if byte & 0xE0 == 0xC0 {
+            utf16_units.push(((byte & 0x1F) as u16) << 6 | continuation(index + 1)?);
+            index += 2;
+        } else if byte & 0xF0 == 0xE0 {
+            utf16_units.push(((byte & 0x0F) as u16) << 12 | continuation(index + 1)? << 6 | continuation(index + 2)?);
+            index += 3;
+        } else if byte & 0xF8 == 0xF0 {
+            let code_point = ((byte & 0x07) as u32) << 18 
+                | (continuation(index + 1)? as u32) << 12 
+                | (continuation(index + 2)? as u32) << 6 
+                | continuation(index + 3)? as u32;
+            let ch = char::from_u32(code_point).ok_or_else(invalid)?;
+            utf16_units.extend(ch.encode_utf16(&mut [0; 2]).iter());
+            index += 4;
+        } else {
+            return Err(invalid());
+        }
+    }
+
+    Ok(String::from_utf16_lossy(&utf16_units))
+}
+
+/// Encodes a string in Modified UTF-8.
+pub fn encode(value: &str) -> Vec<u8> {
+    // fast path, ASCII without null characters is the same in both encodings
+    if value.bytes().all(|byte| byte != 0 && byte < 0x80) {
+        return value.as_bytes().to_vec();
+    }
+
+    let mut bytes = Vec::with_capacity(value.len() + 2);
+    for unit in value.encode_utf16() {
+        if unit != 0 && unit < 0x80 {
+            bytes.push(unit as u8);
+        } else if unit < 0x800 {
+            bytes.push(0xC0 | (unit >> 6) as u8);
+            bytes.push(0x80 | (unit & 0x3F) as u8);
+        } else {
+            bytes.push(0xE0 | (unit >> 12) as u8);
+            bytes.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+            bytes.push(0x80 | (unit & 0x3F) as u8);
+        }
+    }
+
+    bytes
+}
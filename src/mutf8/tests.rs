@@ -0,0 +1,44 @@
+#[cfg(test)]
+
+use super::*;
+
+#[test]
+fn test_mutf8_ascii() {
+    assert_eq!(encode("minecraft:stone"), b"minecraft:stone".to_vec());
+    assert_eq!(decode(b"minecraft:stone").unwrap(), "minecraft:stone");
+    assert_eq!(decode(b"").unwrap(), "");
+}
+
+#[test]
+fn test_mutf8_null_char() {
+    assert_eq!(encode("a\0b"), vec![b'a', 0xC0, 0x80, b'b']);
+    assert_eq!(decode(&[b'a', 0xC0, 0x80, b'b']).unwrap(), "a\0b");
+}
+
+#[test]
+fn test_mutf8_bmp_chars() {
+    // same as standard UTF-8 up to U+FFFF
+    let value = "Épée ∞ 剣";
+    assert_eq!(encode(value), value.as_bytes().to_vec());
+    assert_eq!(decode(value.as_bytes()).unwrap(), value);
+}
+
+#[test]
+fn test_mutf8_supplementary_char() {
+    // U+1F600 is the surrogate pair D83D DE00, as written by Java
+    let java_bytes = vec![b'<', 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80, b'>'];
+    assert_eq!(encode("<😀>"), java_bytes);
+    assert_eq!(decode(&java_bytes).unwrap(), "<😀>");
+
+    // standard UTF-8 is accepted as well
+    assert_eq!(decode("<😀>".as_bytes()).unwrap(), "<😀>");
+}
+
+#[test]
+fn test_mutf8_invalid() {
+    // lone surrogate is kept as replacement char
+    assert_eq!(decode(&[0xED, 0xA0, 0xBD]).unwrap(), "\u{FFFD}");
+    // truncated sequence and invalid leading byte
+    assert!(matches!(decode(&[b'a', 0xE2, 0x88]), Err(RnbtError::InvalidString(_))));
+    assert!(matches!(decode(&[0xFF]), Err(RnbtError::InvalidString(_))));
+}
@@ -0,0 +1,282 @@
+// ## Author
+// - mrmarkolinus
+//
+// ## Date
+// - 2026-10-16
+//
+// ## File Version
+// - 1.0.2
+//
+// ## Changelog
+// - 1.0.0: Initial version
+// - 1.0.1: Lists are read through the NbtTagList accessors [mrmarkolinus:2026-10-16]
+// - 1.0.2: Added parent and for_each_mut_in_compound, to edit the matched tags [mrmarkolinus:2026-10-16]
+
+//! Paths to reach the tags nested in a compound, e.g. `sections/[0]/block_states/palette/*/Name`.
+//!
+//! A path is a list of segments separated by `/`, each segment is one of:
+//! - `name`: the tag `name` of a compound (or the element at that index, if the name is a number and the tag is a list)
+//! - `*`: every tag of a compound or every element of a list
+//! - `[n]`: the element at index `n` of a list
+//! - `[start:end]`: the elements from `start` (included) to `end` (excluded) of a list, both bounds are optional
+//!
+//! A name can be followed by indexes or slices without a separator: `sections[0]` is the same as `sections/[0]`.
+//! The path is relative to the tag it is applied to, the name of the root compound is not part of it.
+
+use crate::error::RnbtError;
+use crate::nbt_tag::{NbtTag, NbtTagCompound};
+
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+#[cfg(test)]
+mod tests;
+
+pub const PATH_SEPARATOR: char = '/';
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+    Slice {
+        start: Option<usize>,
+        end: Option<usize>,
+    },
+    Wildcard,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NbtPath {
+    segments: Vec<PathSegment>,
+}
+
+impl NbtPath {
+    /// Parses a path, see the module documentation for the grammar. The empty path selects the tag itself.
+    pub fn parse(path: &str) -> Result<Self, RnbtError> {
+        let mut segments = Vec::new();
+        if path.is_empty() {
+            return Ok(Self { segments });
+        }
+
+        for text in path.split(PATH_SEPARATOR) {
+            let invalid = || RnbtError::InvalidPath(path.to_string());
+
+            let (key, mut brackets) = text.split_at(text.find('[').unwrap_or(text.len()));
+            match key {
+                "" if brackets.is_empty() => return Err(invalid()),
+                "" => {},
+                "*" => segments.push(PathSegment::Wildcard),
+                key => segments.push(PathSegment::Key(key.to_string())),
+            }
+
+            while !brackets.is_empty() {
+                let close = brackets.find(']').ok_or_else(invalid)?;
+                segments.push(parse_brackets(&brackets[1..close]).ok_or_else(invalid)?);
+                brackets = &brackets[close + 1..];
+                if !brackets.is_empty() && !brackets.starts_with('[') {
+                    return Err(invalid());
+                }
+            }
+        }
+
+        Ok(Self { segments })
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Splits the path into the path of the parent tags and the last segment, `None` for the empty path.
+    pub fn parent(&self) -> Option<(NbtPath, &PathSegment)> {
+        let (last, parent) = self.segments.split_last()?;
+        Some((NbtPath { segments: parent.to_vec() }, last))
+    }
+
+    /// Returns true if the path selects at most one tag (no wildcards or slices).
+    pub fn is_single(&self) -> bool {
+        self.segments.iter().all(|segment| matches!(segment, PathSegment::Key(_) | PathSegment::Index(_)))
+    }
+
+    /// Returns the tag at the end of the path, or `None` as soon as a segment is missing.
+    ///
+    /// Wildcards and slices never match here, use `select` to follow them.
+    pub fn get<'a>(&self, tag: &'a NbtTag) -> Option<&'a NbtTag> {
+        self.segments.iter().try_fold(tag, |tag, segment| child(tag, segment))
+    }
+
+    /// Same as `get`, starting from a compound.
+    pub fn get_in_compound<'a>(&self, compound: &'a NbtTagCompound) -> Option<&'a NbtTag> {
+        let (first, rest) = self.segments.split_first()?;
+        let PathSegment::Key(key) = first else {
+            return None;
+        };
+        rest.iter().try_fold(compound.values.get(key)?, |tag, segment| child(tag, segment))
+    }
+
+    /// Returns every tag matched by the path, with its concrete path (e.g. `sections/[3]/Y`).
+    ///
+    /// The compound entries matched by a wildcard are visited in name order, so the result is deterministic.
+    pub fn select<'a>(&self, tag: &'a NbtTag) -> Vec<(String, &'a NbtTag)> {
+        let mut matches = Vec::new();
+        select_from(tag, &self.segments, String::new(), &mut matches);
+        matches
+    }
+
+    /// Same as `select`, starting from a compound.
+    pub fn select_in_compound<'a>(&self, compound: &'a NbtTagCompound) -> Vec<(String, &'a NbtTag)> {
+        let mut matches = Vec::new();
+        let Some((first, rest)) = self.segments.split_first() else {
+            return matches;
+        };
+
+        for (key, tag) in compound_children(compound, first) {
+            select_from(tag, rest, key.to_string(), &mut matches);
+        }
+        matches
+    }
+
+    /// Calls `f` on every tag of the compound matched by the path, as `select_in_compound` but mutably
+    /// and in arbitrary order. The empty path matches nothing.
+    pub fn for_each_mut_in_compound<F: FnMut(&mut NbtTag)>(&self, compound: &mut NbtTagCompound, mut f: F) {
+        let Some((first, rest)) = self.segments.split_first() else {
+            return;
+        };
+
+        for tag in compound_children_mut(compound, first) {
+            for_each_mut_from(tag, rest, &mut f);
+        }
+    }
+}
+
+impl FromStr for NbtPath {
+    type Err = RnbtError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        Self::parse(path)
+    }
+}
+
+impl fmt::Display for NbtPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                write!(f, "{}", PATH_SEPARATOR)?;
+            }
+            match segment {
+                PathSegment::Key(key) => write!(f, "{}", key)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+                PathSegment::Slice { start, end } => {
+                    let bound = |bound: &Option<usize>| bound.map(|value| value.to_string()).unwrap_or_default();
+                    write!(f, "[{}:{}]", bound(start), bound(end))?
+                },
+                PathSegment::Wildcard => write!(f, "*")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses the content of a `[...]` segment: an index or a slice.
+fn parse_brackets(content: &str) -> Option<PathSegment> {
+    let parse_bound = |bound: &str| if bound.is_empty() { Some(None) } else { bound.parse::<usize>().ok().map(Some) };
+
+    match content.split_once(':') {
+        Some((start, end)) => Some(PathSegment::Slice { start: parse_bound(start)?, end: parse_bound(end)? }),
+        None => content.parse::<usize>().ok().map(PathSegment::Index),
+    }
+}
+
+/// Returns the single child of a tag selected by a key or an index segment.
+fn child<'a>(tag: &'a NbtTag, segment: &PathSegment) -> Option<&'a NbtTag> {
+    match (tag, segment) {
+        (NbtTag::Compound(compound), PathSegment::Key(key)) => compound.values.get(key),
+        (NbtTag::List(list), PathSegment::Key(key)) => list.get(key.parse::<usize>().ok()?),
+        (NbtTag::List(list), PathSegment::Index(index)) => list.get(*index),
+        _ => None,
+    }
+}
+
+/// Returns the (segment, tag) pairs of a compound matched by a segment, sorted by name for wildcards.
+fn compound_children<'a>(compound: &'a NbtTagCompound, segment: &PathSegment) -> Vec<(String, &'a NbtTag)> {
+    match segment {
+        PathSegment::Key(key) => compound.values.get(key).map(|tag| vec![(key.clone(), tag)]).unwrap_or_default(),
+        PathSegment::Wildcard => {
+            let mut children: Vec<(String, &NbtTag)> = compound.iter().map(|(key, tag)| (key.clone(), tag)).collect();
+            children.sort_by(|a, b| a.0.cmp(&b.0));
+            children
+        },
+        _ => Vec::new(),
+    }
+}
+
+fn compound_children_mut<'a>(compound: &'a mut NbtTagCompound, segment: &PathSegment) -> Vec<&'a mut NbtTag> {
+    match segment {
+        PathSegment::Key(key) => compound.values.get_mut(key).into_iter().collect(),
+        PathSegment::Wildcard => compound.values.values_mut().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the indexes of a list of `len` elements matched by a segment, empty when out of the list.
+pub(crate) fn list_range(segment: &PathSegment, len: usize) -> Range<usize> {
+    let single = |index: usize| index.min(len)..index.saturating_add(1).min(len);
+
+    match segment {
+        PathSegment::Wildcard => 0..len,
+        PathSegment::Index(index) => single(*index),
+        PathSegment::Key(key) => match key.parse::<usize>() {
+            Ok(index) => single(index),
+            Err(_) => 0..0,
+        },
+        PathSegment::Slice { start, end } => {
+            let end = end.unwrap_or(len).min(len);
+            start.unwrap_or(0).min(end)..end
+        },
+    }
+}
+
+fn for_each_mut_from<F: FnMut(&mut NbtTag)>(tag: &mut NbtTag, segments: &[PathSegment], f: &mut F) {
+    let Some((segment, rest)) = segments.split_first() else {
+        f(tag);
+        return;
+    };
+
+    match tag {
+        NbtTag::Compound(compound) => {
+            for child_tag in compound_children_mut(compound, segment) {
+                for_each_mut_from(child_tag, rest, f);
+            }
+        },
+        NbtTag::List(list) => {
+            let range = list_range(segment, list.len());
+            for child_tag in list.values[range].iter_mut() {
+                for_each_mut_from(child_tag, rest, f);
+            }
+        },
+        _ => {},
+    }
+}
+
+fn select_from<'a>(tag: &'a NbtTag, segments: &[PathSegment], path: String, matches: &mut Vec<(String, &'a NbtTag)>) {
+    let Some((segment, rest)) = segments.split_first() else {
+        matches.push((path, tag));
+        return;
+    };
+
+    let join = |child_segment: String| if path.is_empty() { child_segment } else { format!("{}{}{}", path, PATH_SEPARATOR, child_segment) };
+
+    match tag {
+        NbtTag::Compound(compound) => {
+            for (key, child_tag) in compound_children(compound, segment) {
+                select_from(child_tag, rest, join(key), matches);
+            }
+        },
+        NbtTag::List(list) => {
+            for index in list_range(segment, list.len()) {
+                select_from(&list[index], rest, join(format!("[{}]", index)), matches);
+            }
+        },
+        _ => {},
+    }
+}
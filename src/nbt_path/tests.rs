@@ -0,0 +1,164 @@
+#[cfg(test)]
+
+use super::*;
+use crate::nbt_tag::*;
+
+fn string(name: &str, value: &str) -> NbtTag {
+    NbtTag::String(NbtTagString::new(name.to_string(), value.to_string()))
+}
+
+fn palette_entry(block_name: &str) -> NbtTag {
+    let mut entry = NbtTagCompound::new("");
+    entry.values.insert("Name".to_string(), string("Name", block_name));
+    NbtTag::Compound(entry)
+}
+
+/// A chunk with three sections, whose palettes are [air], [stone, dirt] and [bedrock].
+fn sample_chunk() -> NbtTagCompound {
+    let sections = [vec!["minecraft:air"], vec!["minecraft:stone", "minecraft:dirt"], vec!["minecraft:bedrock"]]
+        .into_iter()
+        .enumerate()
+        .map(|(y, names)| {
+            let palette = names.into_iter().map(palette_entry).collect();
+            let mut block_states = NbtTagCompound::new("block_states");
+            block_states.values.insert("palette".to_string(), NbtTag::List(NbtTagList::new("palette".to_string(), NbtTagType::Compound, palette)));
+            let mut section = NbtTagCompound::new("");
+            section.values.insert("Y".to_string(), NbtTag::Byte(NbtTagByte::new("Y".to_string(), y as i8)));
+            section.values.insert("block_states".to_string(), NbtTag::Compound(block_states));
+            NbtTag::Compound(section)
+        })
+        .collect();
+
+    let mut chunk = NbtTagCompound::new("");
+    chunk.values.insert("Status".to_string(), string("Status", "minecraft:full"));
+    chunk.values.insert("sections".to_string(), NbtTag::List(NbtTagList::new("sections".to_string(), NbtTagType::Compound, sections)));
+    chunk
+}
+
+#[test]
+fn test_parse() {
+    let path = NbtPath::parse("sections[1:]/*/palette/[0]/Name").unwrap();
+    assert_eq!(path.segments(), &[
+        PathSegment::Key("sections".to_string()),
+        PathSegment::Slice { start: Some(1), end: None },
+        PathSegment::Wildcard,
+        PathSegment::Key("palette".to_string()),
+        PathSegment::Index(0),
+        PathSegment::Key("Name".to_string()),
+    ]);
+    assert_eq!(path.to_string(), "sections/[1:]/*/palette/[0]/Name");
+    assert!(!path.is_single());
+    assert!(NbtPath::parse("sections/[0]/Y").unwrap().is_single());
+}
+
+#[test]
+fn test_parse_invalid() {
+    for invalid in ["sections//Y", "/sections", "sections/", "sections[0", "sections[a]", "sections[0]Y", "sections[-1]", "[1:2:3]"] {
+        assert_eq!(NbtPath::parse(invalid), Err(RnbtError::InvalidPath(invalid.to_string())), "{:?}", invalid);
+    }
+}
+
+#[test]
+fn test_get_path() {
+    let chunk = sample_chunk();
+
+    assert_eq!(chunk.get_path("Status").and_then(|tag| <&str>::try_from(tag).ok()), Some("minecraft:full"));
+    assert_eq!(chunk.get_path("sections/[1]/block_states/palette/[1]/Name").and_then(|tag| <&str>::try_from(tag).ok()), Some("minecraft:dirt"));
+    assert_eq!(chunk.get_path("sections/2/Y").and_then(|tag| i8::try_from(tag).ok()), Some(2));
+    assert!(std::ptr::eq(chunk.get_path("sections[2]/Y").unwrap(), chunk.get_path("sections/2/Y").unwrap()));
+
+    assert!(chunk.get_path("sections/[3]/Y").is_none());
+    assert!(chunk.get_path("sections/[0]/Missing").is_none());
+    assert!(chunk.get_path("Status/Y").is_none());
+    assert!(chunk.get_path("sections/*/Y").is_none());
+    assert!(chunk.get_path("sections[").is_none());
+}
+
+#[test]
+fn test_contains_path() {
+    let chunk = sample_chunk();
+    assert!(chunk.contains_path("sections/[0]/block_states"));
+    assert!(!chunk.contains_path("Level/Sections"));
+    assert!(!chunk.contains_path("Status/Y"));
+    assert!(!chunk.contains_path("sections/*/Y"));
+    assert!(!chunk.contains_path("sections["));
+
+    let chunk_tag = NbtTag::Compound(chunk);
+    assert!(chunk_tag.contains_path("sections/2/Y"));
+    assert!(!chunk_tag.contains_path("sections/[3]"));
+}
+
+#[test]
+fn test_query_wildcards() {
+    let chunk = sample_chunk();
+
+    let names: Vec<(String, String)> = chunk.query("sections/*/block_states/palette/*/Name").unwrap()
+        .into_iter()
+        .map(|(path, tag)| (path, String::try_from(tag).unwrap()))
+        .collect();
+
+    assert_eq!(names, vec![
+        ("sections/[0]/block_states/palette/[0]/Name".to_string(), "minecraft:air".to_string()),
+        ("sections/[1]/block_states/palette/[0]/Name".to_string(), "minecraft:stone".to_string()),
+        ("sections/[1]/block_states/palette/[1]/Name".to_string(), "minecraft:dirt".to_string()),
+        ("sections/[2]/block_states/palette/[0]/Name".to_string(), "minecraft:bedrock".to_string()),
+    ]);
+
+    // a wildcard on a compound visits the entries in name order
+    let section_keys: Vec<String> = chunk.query("sections/[0]/*").unwrap().into_iter().map(|(path, _)| path).collect();
+    assert_eq!(section_keys, vec!["sections/[0]/Y", "sections/[0]/block_states"]);
+}
+
+#[test]
+fn test_query_slices() {
+    let chunk = sample_chunk();
+    let section_ys = |path: &str| -> Vec<i8> {
+        chunk.query(path).unwrap().into_iter().map(|(_, tag)| i8::try_from(tag).unwrap()).collect()
+    };
+
+    assert_eq!(section_ys("sections[1:]/Y"), vec![1, 2]);
+    assert_eq!(section_ys("sections[:2]/Y"), vec![0, 1]);
+    assert_eq!(section_ys("sections[1:2]/Y"), vec![1]);
+    assert_eq!(section_ys("sections[:]/Y"), vec![0, 1, 2]);
+    assert_eq!(section_ys("sections[2:10]/Y"), vec![2]);
+    assert_eq!(section_ys("sections[5:]/Y"), Vec::<i8>::new());
+    assert_eq!(section_ys("sections[7]/Y"), Vec::<i8>::new());
+
+    assert_eq!(chunk.query("sections[0:").err(), Some(RnbtError::InvalidPath("sections[0:".to_string())));
+}
+
+#[test]
+fn test_query_from_tag() {
+    let chunk = NbtTag::Compound(sample_chunk());
+
+    // the empty path selects the tag itself
+    let root = chunk.query("").unwrap();
+    assert_eq!(root.len(), 1);
+    assert_eq!(root[0].0, "");
+    assert!(std::ptr::eq(root[0].1, &chunk));
+
+    assert_eq!(chunk.query("sections/*/Y").unwrap().len(), 3);
+    assert_eq!(chunk.get_path("sections/[1]/Y").and_then(|tag| i8::try_from(tag).ok()), Some(1));
+}
+
+#[test]
+fn test_for_each_mut_in_compound() {
+    let mut chunk = sample_chunk();
+    let mut count = 0;
+    NbtPath::parse("sections/[1:]/block_states/palette/*/Name").unwrap().for_each_mut_in_compound(&mut chunk, |tag| {
+        tag.set_name("renamed");
+        count += 1;
+    });
+    assert_eq!(count, 3);
+    assert_eq!(chunk.get_path("sections/[1]/block_states/palette/[1]/Name").unwrap().name(), "renamed");
+    assert_eq!(chunk.get_path("sections/[0]/block_states/palette/[0]/Name").unwrap().name(), "Name");
+
+    // out of the list
+    NbtPath::parse("sections/[7]").unwrap().for_each_mut_in_compound(&mut chunk, |_| panic!("no tag expected"));
+
+    let path = NbtPath::parse("sections/[0]/Y").unwrap();
+    let (parent, last) = path.parent().unwrap();
+    assert_eq!(parent.to_string(), "sections/[0]");
+    assert_eq!(last, &PathSegment::Key("Y".to_string()));
+    assert!(NbtPath::parse("").unwrap().parent().is_none());
+}
@@ -5,31 +5,244 @@
 // - 2023-12-17
 //
 // ## File Version
-// - 1.0.2
+// - 1.0.31
 //
 // ## Changelog
 // - 1.0.0: Initial version [caelunshun:2019-07-09]
 // - 1.0.1: Splitted the file_parser logic from the nbt_tag logic [mrmarkolinus:2023-12-17]
 // - 1.0.2: Added support for json-nbt bidirectional conversion [mrmarkolinus:2023-12-17]
+// - 1.0.3: Added type_check and Result-returning try_* accessors, fixed ty() of LongArray [mrmarkolinus:2026-10-16]
+// - 1.0.4: Added list_as_mut and compound_as_mut accessors for editing [mrmarkolinus:2026-10-16]
+// - 1.0.5: Strings are written as Modified UTF-8, fixed the writer (TAG_End of compounds, list elements, byte array length) [mrmarkolinus:2026-10-16]
+// - 1.0.6: Added keys and iter to traverse a compound without using the values map [mrmarkolinus:2026-10-16]
+// - 1.0.7: Added TryFrom conversions of a tag to the native Rust types [mrmarkolinus:2026-10-16]
+// - 1.0.8: Added name and to_snapshot, a canonical text representation for snapshot tests [mrmarkolinus:2026-10-16]
+// - 1.0.9: Added get_path and query to reach nested tags by path [mrmarkolinus:2026-10-16]
+// - 1.0.10: Tags implement Eq and Hash, floats by bit pattern [mrmarkolinus:2026-10-16]
+// - 1.0.11: Added take and replace to move tags out of a compound [mrmarkolinus:2026-10-16]
+// - 1.0.12: Added len, is_empty, get, iter and indexing to NbtTagList [mrmarkolinus:2026-10-16]
+// - 1.0.13: Added is_empty for compounds, lists and arrays [mrmarkolinus:2026-10-16]
+// - 1.0.14: Added prune_empty to remove the empty compounds and lists [mrmarkolinus:2026-10-16]
+// - 1.0.15: Added JsonOptions with longs_as_strings, the longs are read from JSON numbers or strings [mrmarkolinus:2026-10-16]
+// - 1.0.16: Added read_uuid, read_compound_uuid and format_uuid [mrmarkolinus:2026-10-16]
+// - 1.0.17: End is only structural: the writer skips End list elements, from_json drops the End tags [mrmarkolinus:2026-10-16]
+// - 1.0.18: Added infer_schema and SchemaNode, the structure of a compound without the values [mrmarkolinus:2026-10-16]
+// - 1.0.19: Added name, set_name and rename to NbtTagCompound, set_name to NbtTag [mrmarkolinus:2026-10-16]
+// - 1.0.20: Added diff_paths, the paths of the tags differing between two compounds [mrmarkolinus:2026-10-16]
+// - 1.0.21: Added depth, the maximum nesting of lists and compounds in a tag [mrmarkolinus:2026-10-16]
+// - 1.0.22: Added to_cache and from_cache, a compact binary layout faster to read than NBT [mrmarkolinus:2026-10-16]
+// - 1.0.23: Added numeric_eq, comparing any numeric tag to a value [mrmarkolinus:2026-10-16]
+// - 1.0.24: Added to_binary_named and to_binary_unnamed, the root name is omitted by the network protocol [mrmarkolinus:2026-10-16]
+// - 1.0.25: Added contains_path [mrmarkolinus:2026-10-16]
+// - 1.0.26: Added NbtTagList::extend and split_off [mrmarkolinus:2026-10-16]
+// - 1.0.27: Added JsonOptions::non_finite_floats, NaN and infinite Float/Double are written as strings or null [mrmarkolinus:2026-10-16]
+// - 1.0.28: Added apply_rules, Rule and RuleAction, the rule based rewriting of the tags for version migrations [mrmarkolinus:2026-10-16]
+// - 1.0.29: NbtTagCompound implements AsRef<NbtTagCompound> [mrmarkolinus:2026-10-16]
+// - 1.0.30: ChangeType converts the elements of a list only if the path selects all of them [mrmarkolinus:2026-10-16]
+// - 1.0.31: write panics on the strings longer than 65535 bytes instead of truncating their length [mrmarkolinus:2026-10-16]
 
 use byteorder::{BigEndian, WriteBytesExt};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
 use std::io::Write;
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Deserialize, Deserializer};
 use std::fs;
 use std::io::{self, BufWriter, BufReader};
+use std::ops::Index;
 use derive_new::new;
+use crate::error::RnbtError;
+use crate::mutf8;
+use crate::nbt_path::{self, NbtPath, PathSegment};
 
 #[cfg(test)]
 mod tests;
 
+/// The tags that `NbtTagCompound::prune_empty` keeps even when empty: the game expects them in chunks, 
+/// entity chunks, level.dat and player data and may reset or reject the data without them.
+pub const PRUNE_EMPTY_KEEP: &[&str] = &[
+    "Level", "Data", "sections", "Sections", "block_entities", "TileEntities", "Heightmaps", 
+    "structures", "Structures", "References", "starts", "Starts", "Entities", "Inventory", "EnderItems",
+];
+
+/// The relative tolerance of `NbtTag::numeric_eq` on Float and Double tags: enough to compare a Float to the
+/// f64 literal it was saved from (a Float has about 7 significant digits), small for the game values.
+pub const NUMERIC_EQ_EPSILON: f64 = 1e-6;
+
+/// The first byte of the caches written by `NbtTagCompound::to_cache`, changed whenever the cache layout changes.
+pub const CACHE_FORMAT_VERSION: u8 = 1;
+
+
+/// Options of the JSON export (`NbtTagCompound::to_json_with_options`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct JsonOptions {
+    /// Writes the values of Long and LongArray as strings (`"-4172144997902289642"`) instead of numbers.
+    /// 
+    /// JavaScript reads the JSON numbers as doubles, exact only up to 2^53: the seeds, the UUID halves and the packed 
+    /// block states would be silently rounded. `from_json` reads the longs in both forms.
+    pub longs_as_strings: bool,
+    /// How the NaN and infinite values of Float and Double are written, JSON has no number for them.
+    pub non_finite_floats: NonFiniteFloats,
+}
+
+/// The JSON representation of the NaN and infinite Float and Double values (rare, but found in corrupted entities).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NonFiniteFloats {
+    /// Written as the strings `"NaN"`, `"Infinity"` and `"-Infinity"`, read back by `from_json`.
+    #[default]
+    String,
+    /// Written as `null`, for the tools expecting only numbers. The value is lost: `from_json` reads `null` as NaN.
+    Null,
+}
+
+/// Structure of a tag without its values (key -> type, list -> element type), see `NbtTagCompound::infer_schema`.
+/// 
+/// Serialized to JSON as `{"tag_type": "Compound", "fields": {"Pos": {"tag_type": "List", "element": {"tag_type": "Double"}}}}`,
+/// the empty members are omitted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaNode {
+    pub tag_type: NbtTagType,
+    /// The schema of the elements of a list, merged over all the elements. `None` for an empty list and the other types.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element: Option<Box<SchemaNode>>,
+    /// The schemas of the tags of a compound, by name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, SchemaNode>,
+    /// Set on the tags missing from some of the compounds of a list (e.g. `CustomName` in a list of entities).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+}
+
+impl SchemaNode {
+    fn from_tag(tag: &NbtTag) -> Self {
+        match tag {
+            NbtTag::Compound(compound) => compound.infer_schema(),
+            NbtTag::List(list) => {
+                let mut element: Option<Box<SchemaNode>> = None;
+                for value in list.values.iter() {
+                    let value_schema = SchemaNode::from_tag(value);
+                    match element.as_mut() {
+                        Some(element) => element.merge(value_schema),
+                        None => element = Some(Box::new(value_schema)),
+                    }
+                }
+                SchemaNode { tag_type: NbtTagType::List, element, ..Default::default() }
+            },
+            _ => SchemaNode { tag_type: tag.ty(), ..Default::default() },
+        }
+    }
+
+    /// Merges the schema of another element of the same list. On a type conflict the first schema is kept.
+    fn merge(&mut self, other: SchemaNode) {
+        if self.tag_type != other.tag_type {
+            return;
+        }
+
+        match (self.element.as_mut(), other.element) {
+            (Some(element), Some(other_element)) => element.merge(*other_element),
+            (None, Some(other_element)) => self.element = Some(other_element),
+            _ => {},
+        }
+
+        if self.tag_type == NbtTagType::Compound {
+            for (key, field) in self.fields.iter_mut() {
+                if !other.fields.contains_key(key) {
+                    field.optional = true;
+                }
+            }
+            for (key, mut other_field) in other.fields {
+                match self.fields.get_mut(&key) {
+                    Some(field) => {
+                        field.optional |= other_field.optional;
+                        field.merge(other_field);
+                    },
+                    None => {
+                        other_field.optional = true;
+                        self.fields.insert(key, other_field);
+                    },
+                }
+            }
+        }
+    }
+
+    /// Writes the schema as pretty-printed JSON.
+    pub fn to_json<P: AsRef<std::path::Path>>(&self, path: P) -> io::Result<()> {
+        let writer = BufWriter::new(fs::File::create(path)?);
+        serde_json::to_writer_pretty(writer, self)?;
+
+        Ok(())
+    }
+}
+
+/// A rewrite rule of `apply_rules`: `action` is applied to every tag matched by `path` (see `nbt_path`),
+/// e.g. `Rule::new("Level/TileEntities", RuleAction::Rename("block_entities".to_string()))`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub path: NbtPath,
+    pub action: RuleAction,
+}
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+/// What a `Rule` does to the tags matched by its path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuleAction {
+    /// Moves the tag to another key of its compound, see `NbtTagCompound::rename`. A tag already at the new key is replaced.
+    Rename(String),
+    /// Converts a number to another numeric type, or a ByteArray, IntArray or LongArray to another array type.
+    /// The values out of the range of the new type are clamped, the tags of the other types are left unchanged.
+    /// The elements of a list are converted only if the path selects all of them (e.g. `Pos/*`, not `Pos/[0]`),
+    /// and the list takes the new type as element type.
+    ChangeType(NbtTagType),
+    /// Inserts the tag, named after its key, in the compounds where the key is missing.
+    SetDefault(NbtTag),
+}
+
+impl Rule {
+    /// Parses the path of the rule.
+    /// 
+    /// # Errors
+    /// 
+    /// `RnbtError::InvalidPath` if the path is malformed or empty, or if it does not end with a key for `Rename` and `SetDefault`.
+    pub fn new(path: &str, action: RuleAction) -> Result<Self, RnbtError> {
+        let nbt_path = NbtPath::parse(path)?;
+        let last_segment = nbt_path.segments().last();
+        let valid = match action {
+            RuleAction::Rename(_) | RuleAction::SetDefault(_) => matches!(last_segment, Some(PathSegment::Key(_))),
+            RuleAction::ChangeType(_) => last_segment.is_some(),
+        };
+
+        if !valid {
+            return Err(RnbtError::InvalidPath(path.to_string()));
+        }
+        Ok(Self { path: nbt_path, action })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct NbtTagCompound {
     pub name: String,
+    /// The tags of the compound by name.
+    /// 
+    /// Prefer `keys` and `iter` to traverse the compound: the storage may change in the future
+    /// (e.g. to keep the insertion order), the iterators will not.
     pub values: HashMap<String, NbtTag>,
 }
 
+/// Lets the functions reading a list of chunks take the compounds as well as `world::SourcedCompound`.
+impl AsRef<NbtTagCompound> for NbtTagCompound {
+    fn as_ref(&self) -> &NbtTagCompound {
+        self
+    }
+}
+
+/// The entries are hashed in name order, so that equal compounds have the same hash whatever the order of the map.
+impl Hash for NbtTagCompound {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        let mut entries: Vec<(&String, &NbtTag)> = self.values.iter().collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        entries.hash(state);
+    }
+}
+
 
 impl NbtTagCompound {
     pub fn new(name: &str) -> Self {
@@ -39,6 +252,94 @@ impl NbtTagCompound {
         }
     }
 
+    /// Returns the name of the compound, written before its content in the binary format.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Renames the compound. The key of the compound in its parent does not change, see `rename`.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// Moves the tag `key` to `new_key` and renames it accordingly, so that the key and the name of the tag
+    /// stay the same. A tag already at `new_key` is replaced.
+    /// 
+    /// Returns false if there is no tag `key`.
+    pub fn rename(&mut self, key: &str, new_key: &str) -> bool {
+        let Some(mut tag) = self.values.remove(key) else {
+            return false;
+        };
+
+        tag.set_name(new_key);
+        self.values.insert(new_key.to_string(), tag);
+        true
+    }
+
+    /// Returns an iterator over the names of the tags in the compound, in arbitrary order.
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.values.keys()
+    }
+
+    /// Returns an iterator over the (name, tag) pairs of the compound, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &NbtTag)> {
+        self.values.iter()
+    }
+
+    /// Returns the tag at `path` (e.g. `Level/Sections/[0]/Y`), see `NbtTag::get_path`.
+    pub fn get_path(&self, path: &str) -> Option<&NbtTag> {
+        NbtPath::parse(path).ok()?.get_in_compound(self)
+    }
+
+    /// Returns true if there is a tag at `path`, see `NbtTag::contains_path`.
+    pub fn contains_path(&self, path: &str) -> bool {
+        self.get_path(path).is_some()
+    }
+
+    /// Returns every tag matching `path` with its concrete path, see `NbtTag::query`.
+    pub fn query(&self, path: &str) -> Result<Vec<(String, &NbtTag)>, RnbtError> {
+        Ok(NbtPath::parse(path)?.select_in_compound(self))
+    }
+
+    /// Removes the tag `key` from the compound and returns it, moving the whole subtree out without copies.
+    pub fn take(&mut self, key: &str) -> Option<NbtTag> {
+        self.values.remove(key)
+    }
+
+    /// Puts `tag` at `key` and returns the tag it replaces, if any, moved out without copies.
+    /// 
+    /// The tag is stored as is: its name (written to the files) should be `key`.
+    pub fn replace(&mut self, key: &str, tag: NbtTag) -> Option<NbtTag> {
+        self.values.insert(key.to_string(), tag)
+    }
+
+    /// Recursively removes the empty compounds and lists, except the tags named in `PRUNE_EMPTY_KEEP`. 
+    /// Returns the number of removed tags, see `prune_empty_keeping`.
+    pub fn prune_empty(&mut self) -> usize {
+        self.prune_empty_keeping(PRUNE_EMPTY_KEEP)
+    }
+
+    /// Recursively removes the empty compounds and lists, except the tags named in `keep` (at any depth).
+    /// Returns the number of removed tags.
+    /// 
+    /// The contents are pruned first, so a compound holding only empty tags is removed too. 
+    /// Empty arrays are kept, and so are the elements of the lists, whose position may be meaningful 
+    /// (e.g. `PostProcessing` has one list per section): only their contents are pruned.
+    pub fn prune_empty_keeping(&mut self, keep: &[&str]) -> usize {
+        let mut removed_num = 0;
+
+        self.values.retain(|key, tag| {
+            removed_num += tag.prune_empty_contents(keep);
+            let prune = matches!(tag, NbtTag::Compound(_) | NbtTag::List(_)) && tag.is_empty() && !keep.contains(&key.as_str());
+            if prune {
+                removed_num += 1;
+            }
+            !prune
+        });
+
+        removed_num
+    }
+
 /*     pub fn get(&self, name: &str) -> Option<NbtTag> {
         self.values.get(name).cloned()
     }
@@ -47,13 +348,60 @@ impl NbtTagCompound {
         self.values.insert(name.to_string(), value);
     } */
 
+    /// Returns the structure of the compound: the type of every tag, recursively, without the values.
+    /// 
+    /// The elements of a list are described by a single schema: for lists of compounds the keys of all the elements
+    /// are collected, the ones missing from some elements are `optional`. Useful to explore the shape of an unknown file.
+    pub fn infer_schema(&self) -> SchemaNode {
+        let fields = self.values.iter()
+            .map(|(key, tag)| (key.clone(), SchemaNode::from_tag(tag)))
+            .collect();
+
+        SchemaNode { tag_type: NbtTagType::Compound, fields, ..Default::default() }
+    }
+
+    /// Returns the paths (as `get_path` reads them, e.g. `sections/[3]/block_states/data`) of the tags that differ
+    /// from `other`: added, removed, or with another value. Sorted, empty if the compounds have the same content.
+    /// 
+    /// Compounds are compared key by key and lists of the same type and length element by element,
+    /// any other difference is reported at the tag itself. The names of the compounds are not compared.
+    pub fn diff_paths(&self, other: &NbtTagCompound) -> Vec<String> {
+        let mut paths = Vec::new();
+        diff_compounds("", self, other, &mut paths);
+        paths.sort();
+        paths
+    }
+
+    /// Returns the canonical text representation of the compound, see `NbtTag::to_snapshot`.
+    pub fn to_snapshot(&self) -> String {
+        let mut snapshot = String::new();
+        write_snapshot_compound(&mut snapshot, self, 0, true);
+        snapshot
+    }
+
     pub fn to_json<P: AsRef<std::path::Path>>(&self, path: P) -> io::Result<()> {
+        self.to_json_with_options(path, &JsonOptions::default())
+    }
+
+    /// Same as `to_json`, with the given options.
+    pub fn to_json_with_options<P: AsRef<std::path::Path>>(&self, path: P, options: &JsonOptions) -> io::Result<()> {
         // Open a file for writing.
         let file = fs::File::create(path)?;
         let writer = BufWriter::new(file); // Using a BufWriter for more efficient writes.
 
         // Write the pretty-printed JSON to the file.
-        serde_json::to_writer_pretty(writer, &self)?;
+        if options.longs_as_strings || options.non_finite_floats == NonFiniteFloats::Null {
+            let mut json_value = serde_json::to_value(self)?;
+            if options.longs_as_strings {
+                longs_to_strings(&mut json_value);
+            }
+            if options.non_finite_floats == NonFiniteFloats::Null {
+                non_finite_floats_to_null(&mut json_value);
+            }
+            serde_json::to_writer_pretty(writer, &json_value)?;
+        } else {
+            serde_json::to_writer_pretty(writer, &self)?;
+        }
         
         Ok(())
     }
@@ -76,12 +424,55 @@ impl NbtTagCompound {
         let reader = BufReader::new(file); // Wrap the file in a BufReader, since very large file are expected.
 
         // Deserialize the JSON data directly from the stream.
-        let deserialized_nbt = serde_json::from_reader(reader)?;
+        let mut deserialized_nbt: NbtTagCompound = serde_json::from_reader(reader)?;
+        // End only terminates the compounds in the binary format, it is never a value
+        remove_end_tags(&mut deserialized_nbt);
         
         Ok(deserialized_nbt)
 
     }
 
+    /// Encodes the compound in NBT as stored in the files: the root type, the root name, then the content.
+    pub fn to_binary_named(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write(&mut buf, self);
+        buf
+    }
+
+    /// Encodes the compound in NBT without the root name, as sent by the network protocol since 1.20.2.
+    /// Read it back with `ParseOptions::unnamed_root`.
+    pub fn to_binary_unnamed(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_unnamed(&mut buf, self);
+        buf
+    }
+
+    /// Encodes the compound in the cache format: `CACHE_FORMAT_VERSION` followed by the tags serialized with bincode.
+    /// 
+    /// The cache is read back by `from_cache` much faster than the NBT files (no zlib, no Modified UTF-8),
+    /// to store parsed worlds between runs. It is not an interchange format: a cache written by a version
+    /// with another `CACHE_FORMAT_VERSION` is rejected and must be rebuilt from the NBT files.
+    pub fn to_cache(&self) -> Vec<u8> {
+        let mut cache = vec![CACHE_FORMAT_VERSION];
+        bincode::serialize_into(&mut cache, self).expect("serializing a compound to a Vec cannot fail");
+        cache
+    }
+
+    /// Decodes a compound written by `to_cache`.
+    /// 
+    /// # Errors
+    /// 
+    /// `RnbtError::InvalidCache` if the version byte is not `CACHE_FORMAT_VERSION` or the data is malformed.
+    pub fn from_cache(cache: &[u8]) -> Result<Self, RnbtError> {
+        match cache.split_first() {
+            Some((&CACHE_FORMAT_VERSION, data)) => {
+                bincode::deserialize(data).map_err(|err| RnbtError::InvalidCache(err.to_string()))
+            },
+            Some((version, _)) => Err(RnbtError::InvalidCache(format!("format version {}, expected {}", version, CACHE_FORMAT_VERSION))),
+            None => Err(RnbtError::InvalidCache("empty data".to_string())),
+        }
+    }
+
     /* pub fn from_json(&self, path: String) -> PyResult<Self> {
         let path = PathBuf::from(path);
         let file = fs::File::open(&path)
@@ -166,7 +557,12 @@ impl NbtTagType {
 ///
 /// This enum encapsulates all possible NBT tags, each variant holding
 /// data corresponding to its type.
-#[derive(Clone, new, Debug, Serialize, Deserialize)]
+///
+/// Tags are equal when they have the same type, name and content, and can be used as `HashMap` or `HashSet` keys
+/// (e.g. to count the distinct block states of the palettes). Float and Double values are compared and hashed
+/// by bit pattern, unlike `==` on `f32`/`f64`: a NaN is equal to itself (so the equality is an `Eq`), 
+/// NaNs with different payloads are different, and `0.0` is different from `-0.0`.
+#[derive(Clone, new, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum NbtTag {
     End,
     Byte(NbtTagByte),
@@ -191,6 +587,125 @@ impl Default for NbtTag {
 
 impl NbtTag {
 
+    /// Returns a canonical, deterministic text representation of the tag, meant for snapshot tests.
+    /// 
+    /// One tag per line, with its name (omitted for list elements), type and value. Compound entries are sorted by name,
+    /// numbers use the shortest representation that round-trips (`1.0`, `0.1`, `NaN`, `inf`) and strings are quoted and escaped. 
+    /// Two trees with the same content always give the same snapshot, whatever the order of the keys in the file or in memory.
+    /// 
+    /// Example:
+    /// ```text
+    /// "root": Compound {
+    ///   "count": Byte 3
+    ///   "id": String "minecraft:stone"
+    ///   "pos": List<Double> [
+    ///     Double 0.5
+    ///     Double -64.0
+    ///   ]
+    /// }
+    /// ```
+    pub fn to_snapshot(&self) -> String {
+        let mut snapshot = String::new();
+        write_snapshot(&mut snapshot, self, 0, true);
+        snapshot
+    }
+
+    /// Returns the tag at `path`, relative to this tag, or `None` if a segment is missing or the path is malformed.
+    /// 
+    /// The segments are separated by `/`: compound names, or list indexes as `[n]` (or plain `n`), 
+    /// e.g. `sections/[0]/block_states/palette/[1]/Name`. See the `nbt_path` module for the grammar.
+    /// Wildcards and slices select several tags, use `query` for them.
+    pub fn get_path(&self, path: &str) -> Option<&NbtTag> {
+        NbtPath::parse(path).ok()?.get(self)
+    }
+
+    /// Returns true if there is a tag at `path`, relative to this tag, e.g. `contains_path("sections/[0]/block_states")`
+    /// to tell a 1.18+ chunk. The traversal of `get_path` stops at the first missing segment; a malformed path is never found.
+    pub fn contains_path(&self, path: &str) -> bool {
+        self.get_path(path).is_some()
+    }
+
+    /// Returns every tag matching `path` with its concrete path, a `*` matches any compound entry or list element
+    /// and `[start:end]` a range of list elements.
+    /// 
+    /// Example: `query("sections/*/block_states/palette/*/Name")` returns the block names of all the palettes of a chunk,
+    /// with paths like `sections/[2]/block_states/palette/[0]/Name`.
+    pub fn query(&self, path: &str) -> Result<Vec<(String, &NbtTag)>, RnbtError> {
+        Ok(NbtPath::parse(path)?.select(self))
+    }
+
+    /// Returns the name of the tag, empty for list elements and End.
+    pub fn name(&self) -> &str {
+        match self {
+            NbtTag::End => "",
+            NbtTag::Byte(val) => &val.name,
+            NbtTag::Short(val) => &val.name,
+            NbtTag::Int(val) => &val.name,
+            NbtTag::Long(val) => &val.name,
+            NbtTag::Float(val) => &val.name,
+            NbtTag::Double(val) => &val.name,
+            NbtTag::ByteArray(val) => &val.name,
+            NbtTag::String(val) => &val.name,
+            NbtTag::List(val) => &val.name,
+            NbtTag::Compound(val) => &val.name,
+            NbtTag::IntArray(val) => &val.name,
+            NbtTag::LongArray(val) => &val.name,
+        }
+    }
+
+    /// Renames the tag, nothing happens for End. The key of the tag in its compound does not change,
+    /// see `NbtTagCompound::rename`.
+    pub fn set_name(&mut self, name: &str) {
+        let name = name.to_string();
+        match self {
+            NbtTag::End => {},
+            NbtTag::Byte(val) => val.name = name,
+            NbtTag::Short(val) => val.name = name,
+            NbtTag::Int(val) => val.name = name,
+            NbtTag::Long(val) => val.name = name,
+            NbtTag::Float(val) => val.name = name,
+            NbtTag::Double(val) => val.name = name,
+            NbtTag::ByteArray(val) => val.name = name,
+            NbtTag::String(val) => val.name = name,
+            NbtTag::List(val) => val.name = name,
+            NbtTag::Compound(val) => val.name = name,
+            NbtTag::IntArray(val) => val.name = name,
+            NbtTag::LongArray(val) => val.name = name,
+        }
+    }
+
+    /// Returns the maximum nesting of lists and compounds in the tag: 0 for scalars, arrays and End,
+    /// 1 for a compound or a list of scalars (or empty), 2 for a compound holding a compound and so on.
+    /// 
+    /// The same nesting is checked by the parser against `file_parser::MAX_NESTING_DEPTH`.
+    pub fn depth(&self) -> usize {
+        match self {
+            NbtTag::List(list) => 1 + list.values.iter().map(NbtTag::depth).max().unwrap_or(0),
+            NbtTag::Compound(compound) => 1 + compound.values.values().map(NbtTag::depth).max().unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    /// Returns true if the tag is a number (Byte, Short, Int, Long, Float or Double) equal to `value`, whatever its type:
+    /// `Health == 20` matches a Float 20.0 as well as a Double or an Int 20.
+    /// 
+    /// The integers are compared exactly. The floats are equal if they differ by at most `NUMERIC_EQ_EPSILON` times 
+    /// the larger magnitude (at least 1), so a Float matches the f64 it was rounded from. NaN is never equal.
+    /// Returns false for the other tags.
+    pub fn numeric_eq(&self, value: f64) -> bool {
+        let float_eq = |tag_value: f64| (tag_value - value).abs() <= NUMERIC_EQ_EPSILON * tag_value.abs().max(value.abs()).max(1.0);
+
+        match self {
+            NbtTag::Byte(byte) => byte.value as f64 == value,
+            NbtTag::Short(short) => short.value as f64 == value,
+            NbtTag::Int(int) => int.value as f64 == value,
+            NbtTag::Long(long) => value.fract() == 0.0 && long.value as i128 == value as i128 && value.abs() < 2f64.powi(64),
+            NbtTag::Float(float) => float_eq(float.value as f64),
+            NbtTag::Double(double) => float_eq(double.value),
+            _ => false,
+        }
+    }
+
     pub fn ty(&self) -> NbtTagType {
         match &self {
             NbtTag::End => NbtTagType::End,
@@ -205,10 +720,144 @@ impl NbtTag {
             NbtTag::List(_) => NbtTagType::List,
             NbtTag::Compound(_) => NbtTagType::Compound,
             NbtTag::IntArray(_) => NbtTagType::IntArray,
-            NbtTag::LongArray(_) => NbtTagType::End,
+            NbtTag::LongArray(_) => NbtTagType::LongArray,
         }
     } 
 
+    /// Returns true for a compound, a list or an array without elements.
+    /// 
+    /// Scalars (strings included, even empty) and End are never empty.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            NbtTag::ByteArray(val) => val.values.is_empty(),
+            NbtTag::List(val) => val.values.is_empty(),
+            NbtTag::Compound(val) => val.values.is_empty(),
+            NbtTag::IntArray(val) => val.values.is_empty(),
+            NbtTag::LongArray(val) => val.values.is_empty(),
+            _ => false,
+        }
+    }
+
+    fn prune_empty_contents(&mut self, keep: &[&str]) -> usize {
+        match self {
+            NbtTag::Compound(val) => val.prune_empty_keeping(keep),
+            NbtTag::List(val) => val.values.iter_mut().map(|element| element.prune_empty_contents(keep)).sum(),
+            _ => 0,
+        }
+    }
+
+    /// Checks that the tag has the expected type.
+    ///
+    /// Returns `RnbtError::TypeMismatch` describing the expected and the actual type otherwise.
+    pub fn type_check(&self, expected: NbtTagType) -> Result<(), RnbtError> {
+        let found = self.ty();
+        if found == expected {
+            Ok(())
+        } else {
+            Err(RnbtError::TypeMismatch { expected, found })
+        }
+    }
+
+    fn type_mismatch(&self, expected: NbtTagType) -> RnbtError {
+        RnbtError::TypeMismatch { expected, found: self.ty() }
+    }
+
+    pub fn try_byte(&self) -> Result<&NbtTagByte, RnbtError> {
+        if let NbtTag::Byte(x) = self {
+            Ok(x)
+        } else {
+            Err(self.type_mismatch(NbtTagType::Byte))
+        }
+    }
+
+    pub fn try_short(&self) -> Result<&NbtTagShort, RnbtError> {
+        if let NbtTag::Short(x) = self {
+            Ok(x)
+        } else {
+            Err(self.type_mismatch(NbtTagType::Short))
+        }
+    }
+
+    pub fn try_int(&self) -> Result<&NbtTagInt, RnbtError> {
+        if let NbtTag::Int(x) = self {
+            Ok(x)
+        } else {
+            Err(self.type_mismatch(NbtTagType::Int))
+        }
+    }
+
+    pub fn try_long(&self) -> Result<&NbtTagLong, RnbtError> {
+        if let NbtTag::Long(x) = self {
+            Ok(x)
+        } else {
+            Err(self.type_mismatch(NbtTagType::Long))
+        }
+    }
+
+    pub fn try_float(&self) -> Result<&NbtTagFloat, RnbtError> {
+        if let NbtTag::Float(x) = self {
+            Ok(x)
+        } else {
+            Err(self.type_mismatch(NbtTagType::Float))
+        }
+    }
+
+    pub fn try_double(&self) -> Result<&NbtTagDouble, RnbtError> {
+        if let NbtTag::Double(x) = self {
+            Ok(x)
+        } else {
+            Err(self.type_mismatch(NbtTagType::Double))
+        }
+    }
+
+    pub fn try_byte_array(&self) -> Result<&NbtTagByteArray, RnbtError> {
+        if let NbtTag::ByteArray(x) = self {
+            Ok(x)
+        } else {
+            Err(self.type_mismatch(NbtTagType::ByteArray))
+        }
+    }
+
+    pub fn try_string(&self) -> Result<&NbtTagString, RnbtError> {
+        if let NbtTag::String(x) = self {
+            Ok(x)
+        } else {
+            Err(self.type_mismatch(NbtTagType::String))
+        }
+    }
+
+    pub fn try_list(&self) -> Result<&NbtTagList, RnbtError> {
+        if let NbtTag::List(x) = self {
+            Ok(x)
+        } else {
+            Err(self.type_mismatch(NbtTagType::List))
+        }
+    }
+
+    pub fn try_compound(&self) -> Result<&NbtTagCompound, RnbtError> {
+        if let NbtTag::Compound(x) = self {
+            Ok(x)
+        } else {
+            Err(self.type_mismatch(NbtTagType::Compound))
+        }
+    }
+
+    pub fn try_int_array(&self) -> Result<&NbtTagIntArray, RnbtError> {
+        if let NbtTag::IntArray(x) = self {
+            Ok(x)
+        } else {
+            Err(self.type_mismatch(NbtTagType::IntArray))
+        }
+    }
+
+    pub fn try_long_array(&self) -> Result<&NbtTagLongArray, RnbtError> {
+        if let NbtTag::LongArray(x) = self {
+            Ok(x)
+        } else {
+            Err(self.type_mismatch(NbtTagType::LongArray))
+        }
+    }
+
     pub fn byte(&self) -> Option<NbtTagByte> {
         if let NbtTag::Byte(x) = self {
             Some(x.clone())
@@ -289,6 +938,14 @@ impl NbtTag {
         }
     }
 
+    pub fn list_as_mut(&mut self) -> Option<&mut NbtTagList> {
+        if let NbtTag::List(x) = self {
+            Some(x)
+        } else {
+            None
+        }
+    }
+
     pub fn compound(&self) -> Option<NbtTagCompound> {
         if let NbtTag::Compound(x) = self {
             Some(x.clone())
@@ -305,6 +962,14 @@ impl NbtTag {
         }
     }
 
+    pub fn compound_as_mut(&mut self) -> Option<&mut NbtTagCompound> {
+        if let NbtTag::Compound(x) = self {
+            Some(x)
+        } else {
+            None
+        }
+    }
+
     pub fn int_array(&self) -> Option<NbtTagIntArray> {
         if let NbtTag::IntArray(x) = self {
             Some(x.clone())
@@ -333,30 +998,68 @@ impl NbtTag {
 
 
 
-#[derive(Clone, new, Debug, Default, Serialize, Deserialize)]
+/// Conversions of a tag to the native Rust type of its value, e.g. `let age: i32 = (&tag).try_into()?`.
+/// The conversion fails with `RnbtError::TypeMismatch` if the tag has a different type.
+macro_rules! impl_try_from_nbt_tag {
+    ($($native_type:ty => $accessor:ident . $field:ident),* $(,)?) => {
+        $(
+            impl TryFrom<&NbtTag> for $native_type {
+                type Error = RnbtError;
+
+                fn try_from(tag: &NbtTag) -> Result<Self, Self::Error> {
+                    Ok(tag.$accessor()?.$field.to_owned())
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_nbt_tag! {
+    i8 => try_byte.value,
+    i16 => try_short.value,
+    i32 => try_int.value,
+    i64 => try_long.value,
+    f32 => try_float.value,
+    f64 => try_double.value,
+    String => try_string.value,
+    Vec<i8> => try_byte_array.values,
+    Vec<i32> => try_int_array.values,
+    Vec<i64> => try_long_array.values,
+}
+
+impl<'a> TryFrom<&'a NbtTag> for &'a str {
+    type Error = RnbtError;
+
+    fn try_from(tag: &'a NbtTag) -> Result<Self, Self::Error> {
+        Ok(tag.try_string()?.value.as_str())
+    }
+}
+
+#[derive(Clone, new, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NbtTagByte {
     pub name: String,
     pub value: i8,
 }
 
 
-#[derive(Clone, new, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, new, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NbtTagShort {
     pub name: String,
     pub value: i16,
 }
 
 
-#[derive(Clone, new, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, new, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NbtTagInt {
     pub name: String,
     pub value: i32,
 }
 
 
-#[derive(Clone, new, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, new, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NbtTagLong {
     pub name: String,
+    #[serde(deserialize_with = "deserialize_json_long")]
     pub value: i64,
 }
 
@@ -364,68 +1067,601 @@ pub struct NbtTagLong {
 #[derive(Clone, new, Debug, Default, Serialize, Deserialize)]
 pub struct NbtTagFloat {
     pub name: String,
+    #[serde(serialize_with = "serialize_json_float", deserialize_with = "deserialize_json_float")]
     pub value: f32,
 }
 
+/// Compared and hashed by bit pattern, see `NbtTag`.
+impl PartialEq for NbtTagFloat {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.value.to_bits() == other.value.to_bits()
+    }
+}
+
+impl Eq for NbtTagFloat {}
+
+impl Hash for NbtTagFloat {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.value.to_bits().hash(state);
+    }
+}
+
 
 #[derive(Clone, new, Debug, Default, Serialize, Deserialize)]
 pub struct NbtTagDouble {
     pub name: String,
+    #[serde(serialize_with = "serialize_json_double", deserialize_with = "deserialize_json_double")]
     pub value: f64,
 }
 
+/// Compared and hashed by bit pattern, see `NbtTag`.
+impl PartialEq for NbtTagDouble {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.value.to_bits() == other.value.to_bits()
+    }
+}
 
-#[derive(Clone, new, Debug, Default, Serialize, Deserialize)]
+impl Eq for NbtTagDouble {}
+
+impl Hash for NbtTagDouble {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.value.to_bits().hash(state);
+    }
+}
+
+
+#[derive(Clone, new, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NbtTagByteArray {
     pub name: String,
     pub values: Vec<i8>,
 }
 
 
-#[derive(Clone, new, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, new, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NbtTagString {
     pub name: String,
     pub value: String,
 }
 
 
-#[derive(Clone, new, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, new, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NbtTagList {
     pub name: String,
     pub ty: NbtTagType,
     pub values: Vec<NbtTag>,
 }
 
+impl NbtTagList {
+    /// Returns the number of elements of the list.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
 
-#[derive(Clone, new, Debug, Default, Serialize, Deserialize)]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the element at `index`, `None` if out of the list.
+    pub fn get(&self, index: usize) -> Option<&NbtTag> {
+        self.values.get(index)
+    }
+
+    /// Returns an iterator over the elements of the list, in order.
+    pub fn iter(&self) -> impl Iterator<Item = &NbtTag> {
+        self.values.iter()
+    }
+
+    /// Appends the elements of `other` (e.g. to merge two inventories). An empty list takes the type of `other`.
+    /// 
+    /// Returns `RnbtError::TypeMismatch` if both lists have elements of different types, the list is then unchanged.
+    pub fn extend(&mut self, other: NbtTagList) -> Result<(), RnbtError> {
+        if other.values.is_empty() {
+            return Ok(());
+        }
+
+        if self.values.is_empty() {
+            self.ty = other.ty;
+        }
+        else if self.ty != other.ty {
+            return Err(RnbtError::TypeMismatch { expected: self.ty, found: other.ty });
+        }
+
+        self.values.extend(other.values);
+        Ok(())
+    }
+
+    /// Splits the list in two at `at`: the list keeps the elements before `at`, the returned list (with the same
+    /// name and type) holds the others. E.g. to save a long entity list in several chunks.
+    /// 
+    /// Panics if `at` is greater than the length of the list.
+    pub fn split_off(&mut self, at: usize) -> NbtTagList {
+        NbtTagList { name: self.name.clone(), ty: self.ty, values: self.values.split_off(at) }
+    }
+}
+
+/// Panics if `index` is out of the list, use `get` otherwise.
+impl Index<usize> for NbtTagList {
+    type Output = NbtTag;
+
+    fn index(&self, index: usize) -> &NbtTag {
+        &self.values[index]
+    }
+}
+
+
+#[derive(Clone, new, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NbtTagIntArray {
     pub name: String,
     pub values: Vec<i32>,
 }
 
 
-#[derive(Clone, new, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, new, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NbtTagLongArray {
     pub name: String,
+    #[serde(deserialize_with = "deserialize_json_longs")]
     pub values: Vec<i64>,
 }
 
+/// A long read from a JSON number or from a string (`JsonOptions::longs_as_strings`).
+struct JsonLong(i64);
+
+impl<'de> Deserialize<'de> for JsonLong {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct JsonLongVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for JsonLongVisitor {
+            type Value = JsonLong;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a 64-bit integer, as a number or a string")
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<JsonLong, E> {
+                Ok(JsonLong(value))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<JsonLong, E> {
+                i64::try_from(value).map(JsonLong).map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(value), &self))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<JsonLong, E> {
+                value.parse().map(JsonLong).map_err(|_| E::invalid_value(serde::de::Unexpected::Str(value), &self))
+            }
+        }
+
+        // the binary formats (the cache) are not self-describing, the longs are always numbers
+        if !deserializer.is_human_readable() {
+            return i64::deserialize(deserializer).map(JsonLong);
+        }
+        deserializer.deserialize_any(JsonLongVisitor)
+    }
+}
+
+fn deserialize_json_long<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    JsonLong::deserialize(deserializer).map(|json_long| json_long.0)
+}
+
+fn deserialize_json_longs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<i64>, D::Error> {
+    Vec::<JsonLong>::deserialize(deserializer).map(|json_longs| json_longs.into_iter().map(|json_long| json_long.0).collect())
+}
+
+/// Replaces the numbers of the serialized Long (`{"Long": {"value": ...}}`) and LongArray tags with strings.
+fn longs_to_strings(json_value: &mut serde_json::Value) {
+    match json_value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let numbers = match (key.as_str(), value.as_object_mut()) {
+                    ("Long", Some(tag)) => tag.get_mut("value"),
+                    ("LongArray", Some(tag)) => tag.get_mut("values"),
+                    _ => None,
+                };
+
+                match numbers {
+                    Some(serde_json::Value::Array(array)) => array.iter_mut().for_each(number_to_string),
+                    Some(number) => number_to_string(number),
+                    None => longs_to_strings(value),
+                }
+            }
+        },
+        serde_json::Value::Array(array) => array.iter_mut().for_each(longs_to_strings),
+        _ => {},
+    }
+}
+
+fn number_to_string(json_value: &mut serde_json::Value) {
+    if let serde_json::Value::Number(number) = json_value {
+        *json_value = serde_json::Value::String(number.to_string());
+    }
+}
+
+/// The string written for a NaN or infinite value (`NonFiniteFloats::String`), `None` for the finite values.
+fn non_finite_name(value: f64) -> Option<&'static str> {
+    if value.is_nan() {
+        Some("NaN")
+    } else if value == f64::INFINITY {
+        Some("Infinity")
+    } else if value == f64::NEG_INFINITY {
+        Some("-Infinity")
+    } else {
+        None
+    }
+}
+
+fn serialize_json_float<S: serde::Serializer>(value: &f32, serializer: S) -> Result<S::Ok, S::Error> {
+    match non_finite_name(*value as f64) {
+        Some(name) if serializer.is_human_readable() => serializer.serialize_str(name),
+        _ => serializer.serialize_f32(*value),
+    }
+}
+
+fn serialize_json_double<S: serde::Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    match non_finite_name(*value) {
+        Some(name) if serializer.is_human_readable() => serializer.serialize_str(name),
+        _ => serializer.serialize_f64(*value),
+    }
+}
+
+/// A Float or Double read from a JSON number, from the strings of `NonFiniteFloats::String` or from `null` (NaN).
+struct JsonFloat(f64);
+
+impl<'de> Deserialize<'de> for JsonFloat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct JsonFloatVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for JsonFloatVisitor {
+            type Value = JsonFloat;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a number, \"NaN\", \"Infinity\", \"-Infinity\" or null")
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, value: f64) -> Result<JsonFloat, E> {
+                Ok(JsonFloat(value))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<JsonFloat, E> {
+                Ok(JsonFloat(value as f64))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<JsonFloat, E> {
+                Ok(JsonFloat(value as f64))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<JsonFloat, E> {
+                match value {
+                    "NaN" => Ok(JsonFloat(f64::NAN)),
+                    "Infinity" => Ok(JsonFloat(f64::INFINITY)),
+                    "-Infinity" => Ok(JsonFloat(f64::NEG_INFINITY)),
+                    _ => Err(E::invalid_value(serde::de::Unexpected::Str(value), &self)),
+                }
+            }
+
+            fn visit_unit<E: serde::de::Error>(self) -> Result<JsonFloat, E> {
+                Ok(JsonFloat(f64::NAN))
+            }
+        }
+
+        deserializer.deserialize_any(JsonFloatVisitor)
+    }
+}
+
+fn deserialize_json_float<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    // the binary formats (the cache) are not self-describing, the floats are always numbers
+    if !deserializer.is_human_readable() {
+        return f32::deserialize(deserializer);
+    }
+    JsonFloat::deserialize(deserializer).map(|json_float| json_float.0 as f32)
+}
+
+fn deserialize_json_double<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    if !deserializer.is_human_readable() {
+        return f64::deserialize(deserializer);
+    }
+    JsonFloat::deserialize(deserializer).map(|json_float| json_float.0)
+}
+
+/// Replaces the strings of the serialized non-finite Float and Double tags (`{"Float": {"value": "NaN"}}`) with `null`.
+fn non_finite_floats_to_null(json_value: &mut serde_json::Value) {
+    match json_value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match (key.as_str(), value.as_object_mut()) {
+                    ("Float" | "Double", Some(tag)) if tag.get("value").is_some_and(serde_json::Value::is_string) => {
+                        tag.insert("value".to_string(), serde_json::Value::Null);
+                    },
+                    _ => non_finite_floats_to_null(value),
+                }
+            }
+        },
+        serde_json::Value::Array(array) => array.iter_mut().for_each(non_finite_floats_to_null),
+        _ => {},
+    }
+}
+
+
+fn write_snapshot(snapshot: &mut String, value: &NbtTag, indent: usize, write_name: bool) {
+    use std::fmt::Write;
 
+
+    // writing to a String never fails
+    let _ = write!(snapshot, "{:indent$}", "", indent = indent * 2);
+    if write_name {
+        let _ = write!(snapshot, "{:?}: ", value.name());
+    }
+
+    let _ = match value {
+        NbtTag::End => writeln!(snapshot, "End"),
+        NbtTag::Byte(val) => writeln!(snapshot, "Byte {}", val.value),
+        NbtTag::Short(val) => writeln!(snapshot, "Short {}", val.value),
+        NbtTag::Int(val) => writeln!(snapshot, "Int {}", val.value),
+        NbtTag::Long(val) => writeln!(snapshot, "Long {}", val.value),
+        NbtTag::Float(val) => writeln!(snapshot, "Float {:?}", val.value),
+        NbtTag::Double(val) => writeln!(snapshot, "Double {:?}", val.value),
+        NbtTag::ByteArray(val) => writeln!(snapshot, "ByteArray {:?}", val.values),
+        NbtTag::String(val) => writeln!(snapshot, "String {:?}", val.value),
+        NbtTag::IntArray(val) => writeln!(snapshot, "IntArray {:?}", val.values),
+        NbtTag::LongArray(val) => writeln!(snapshot, "LongArray {:?}", val.values),
+        NbtTag::List(val) => {
+            let _ = writeln!(snapshot, "List<{:?}> [", val.ty);
+            for element in &val.values {
+                write_snapshot(snapshot, element, indent + 1, false);
+            }
+            writeln!(snapshot, "{:indent$}]", "", indent = indent * 2)
+        }
+        NbtTag::Compound(val) => {
+            // the name was already written
+            write_snapshot_compound(snapshot, val, indent, false);
+            Ok(())
+        }
+    };
+}
+
+fn write_snapshot_compound(snapshot: &mut String, compound: &NbtTagCompound, indent: usize, write_name: bool) {
+    use std::fmt::Write;
+
+    if write_name {
+        let _ = write!(snapshot, "{:indent$}{:?}: ", "", compound.name, indent = indent * 2);
+    }
+    let _ = writeln!(snapshot, "Compound {{");
+
+    let mut keys: Vec<&String> = compound.keys().collect();
+    keys.sort();
+    for key in keys {
+        write_snapshot(snapshot, &compound.values[key], indent + 1, true);
+    }
+
+    let _ = writeln!(snapshot, "{:indent$}}}", "", indent = indent * 2);
+}
+
+/// Applies the rules to the compound, in order: each rule sees the changes of the previous ones, so a rule set can
+/// rename a tag then change the type of the renamed tag. Returns the number of tags renamed, converted or inserted.
+/// 
+/// The rules matching nothing are ignored: a rule set written for an old format leaves the up to date data unchanged.
+pub fn apply_rules(compound: &mut NbtTagCompound, rules: &[Rule]) -> usize {
+    rules.iter().map(|rule| apply_rule(compound, rule)).sum()
+}
+
+fn apply_rule(compound: &mut NbtTagCompound, rule: &Rule) -> usize {
+    let Some((parent_path, last_segment)) = rule.path.parent() else {
+        return 0;
+    };
+
+    if parent_path.segments().is_empty() {
+        return apply_action_in_compound(compound, last_segment, &rule.action);
+    }
+
+    let mut changes = 0;
+    parent_path.for_each_mut_in_compound(compound, |parent| {
+        changes += match parent {
+            NbtTag::Compound(parent_compound) => apply_action_in_compound(parent_compound, last_segment, &rule.action),
+            NbtTag::List(parent_list) => apply_action_in_list(parent_list, last_segment, &rule.action),
+            _ => 0,
+        };
+    });
+    changes
+}
+
+/// Applies the action to the tags of the compound matched by `segment`.
+fn apply_action_in_compound(compound: &mut NbtTagCompound, segment: &PathSegment, action: &RuleAction) -> usize {
+    match (action, segment) {
+        (RuleAction::Rename(new_key), PathSegment::Key(key)) => usize::from(key != new_key && compound.rename(key, new_key)),
+        (RuleAction::SetDefault(default_tag), PathSegment::Key(key)) => {
+            if compound.values.contains_key(key) {
+                return 0;
+            }
+            let mut tag = default_tag.clone();
+            tag.set_name(key);
+            compound.values.insert(key.clone(), tag);
+            1
+        },
+        (RuleAction::ChangeType(ty), PathSegment::Key(_) | PathSegment::Wildcard) => {
+            let mut changes = 0;
+            for (key, tag) in compound.values.iter_mut() {
+                let matched = match segment {
+                    PathSegment::Key(segment_key) => segment_key == key,
+                    _ => true,
+                };
+                if matched && change_type(tag, *ty) {
+                    changes += 1;
+                }
+            }
+            changes
+        },
+        _ => 0,
+    }
+}
+
+/// Applies the action to the elements of the list matched by `segment`. Only `ChangeType` applies to list elements, all of them.
+fn apply_action_in_list(list: &mut NbtTagList, segment: &PathSegment, action: &RuleAction) -> usize {
+    let RuleAction::ChangeType(ty) = action else {
+        return 0;
+    };
+
+    // the elements of a list share its type: converting only some of them would not be valid NBT
+    let range = nbt_path::list_range(segment, list.len());
+    if range != (0..list.len()) {
+        return 0;
+    }
+
+    let mut changes = 0;
+    for tag in list.values[range].iter_mut() {
+        if change_type(tag, *ty) {
+            changes += 1;
+        }
+    }
+    if changes > 0 && list.values.iter().all(|tag| tag.ty() == *ty) {
+        list.ty = *ty;
+    }
+    changes
+}
+
+/// Converts the tag to `ty`, see `RuleAction::ChangeType`. Returns false if the tag is unchanged.
+fn change_type(tag: &mut NbtTag, ty: NbtTagType) -> bool {
+    if tag.ty() == ty {
+        return false;
+    }
+    match converted_tag(tag, ty) {
+        Some(converted) => {
+            *tag = converted;
+            true
+        },
+        None => false,
+    }
+}
+
+fn converted_tag(tag: &NbtTag, ty: NbtTagType) -> Option<NbtTag> {
+    let name = tag.name().to_string();
+    let float = match tag {
+        NbtTag::Float(val) => Some(val.value as f64),
+        NbtTag::Double(val) => Some(val.value),
+        _ => None,
+    };
+    // the float to integer casts saturate (NaN gives 0)
+    let integer = match tag {
+        NbtTag::Byte(val) => Some(val.value as i64),
+        NbtTag::Short(val) => Some(val.value as i64),
+        NbtTag::Int(val) => Some(val.value as i64),
+        NbtTag::Long(val) => Some(val.value),
+        _ => float.map(|value| value as i64),
+    };
+    let float = float.or(integer.map(|value| value as f64));
+    let array: Option<Vec<i64>> = match tag {
+        NbtTag::ByteArray(val) => Some(val.values.iter().map(|&value| value as i64).collect()),
+        NbtTag::IntArray(val) => Some(val.values.iter().map(|&value| value as i64).collect()),
+        NbtTag::LongArray(val) => Some(val.values.clone()),
+        _ => None,
+    };
+
+    let clamp_i8 = |value: i64| value.clamp(i8::MIN as i64, i8::MAX as i64) as i8;
+    let clamp_i32 = |value: i64| value.clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+
+    Some(match ty {
+        NbtTagType::Byte => NbtTag::Byte(NbtTagByte::new(name, clamp_i8(integer?))),
+        NbtTagType::Short => NbtTag::Short(NbtTagShort::new(name, integer?.clamp(i16::MIN as i64, i16::MAX as i64) as i16)),
+        NbtTagType::Int => NbtTag::Int(NbtTagInt::new(name, clamp_i32(integer?))),
+        NbtTagType::Long => NbtTag::Long(NbtTagLong::new(name, integer?)),
+        NbtTagType::Float => NbtTag::Float(NbtTagFloat::new(name, float? as f32)),
+        NbtTagType::Double => NbtTag::Double(NbtTagDouble::new(name, float?)),
+        NbtTagType::ByteArray => NbtTag::ByteArray(NbtTagByteArray::new(name, array?.into_iter().map(clamp_i8).collect())),
+        NbtTagType::IntArray => NbtTag::IntArray(NbtTagIntArray::new(name, array?.into_iter().map(clamp_i32).collect())),
+        NbtTagType::LongArray => NbtTag::LongArray(NbtTagLongArray::new(name, array?)),
+        _ => return None,
+    })
+}
+
+/// Reads a UUID stored in any of the forms used by Minecraft:
+/// - an IntArray of 4 ints, most significant first (since 1.16, e.g. the `UUID` of entities);
+/// - a String in the canonical hyphenated form (e.g. the `Owner` of some tags before 1.16);
+/// - a Compound holding its UUID, see `read_compound_uuid`.
+/// 
+/// Returns `None` for the other tags.
+pub fn read_uuid(tag: &NbtTag) -> Option<u128> {
+    match tag {
+        NbtTag::IntArray(int_array) => match int_array.values[..] {
+            [most, high, low, least] => Some(((most as u32 as u128) << 96) | ((high as u32 as u128) << 64) | ((low as u32 as u128) << 32) | (least as u32 as u128)),
+            _ => None,
+        },
+        NbtTag::String(uuid_string) => parse_uuid(&uuid_string.value),
+        NbtTag::Compound(compound) => read_compound_uuid(compound),
+        _ => None,
+    }
+}
+
+/// Reads the UUID of an entity or a player compound: the `UUID` IntArray since 1.16, 
+/// the `UUIDMost` and `UUIDLeast` longs before.
+pub fn read_compound_uuid(compound: &NbtTagCompound) -> Option<u128> {
+    if let Some(uuid) = compound.values.get("UUID").and_then(read_uuid) {
+        return Some(uuid);
+    }
+
+    let most = compound.values.get("UUIDMost").and_then(|tag| i64::try_from(tag).ok())?;
+    let least = compound.values.get("UUIDLeast").and_then(|tag| i64::try_from(tag).ok())?;
+    Some(((most as u64 as u128) << 64) | (least as u64 as u128))
+}
+
+/// Formats a UUID in the canonical hyphenated form, e.g. `f81d4fae-7dec-11d0-a765-00a0c91e6bf6`.
+pub fn format_uuid(uuid: u128) -> String {
+    format!("{:08x}-{:04x}-{:04x}-{:04x}-{:012x}", uuid >> 96, (uuid >> 80) & 0xFFFF, (uuid >> 64) & 0xFFFF, (uuid >> 48) & 0xFFFF, uuid & 0xFFFF_FFFF_FFFF)
+}
+
+/// Parses a UUID in the canonical hyphenated form (8-4-4-4-12 hexadecimal digits).
+fn parse_uuid(uuid_string: &str) -> Option<u128> {
+    let groups: Vec<&str> = uuid_string.split('-').collect();
+    let valid_lengths = groups.iter().map(|group| group.len()).eq([8, 4, 4, 4, 12]);
+    if !valid_lengths || !groups.iter().all(|group| group.chars().all(|c| c.is_ascii_hexdigit())) {
+        return None;
+    }
+
+    u128::from_str_radix(&groups.concat(), 16).ok()
+}
+
+/// Removes the End tags from the compound, its nested compounds and lists.
+fn remove_end_tags(compound: &mut NbtTagCompound) {
+    compound.values.retain(|_, tag| !matches!(tag, NbtTag::End));
+    compound.values.values_mut().for_each(remove_nested_end_tags);
+}
+
+fn remove_nested_end_tags(tag: &mut NbtTag) {
+    match tag {
+        NbtTag::Compound(compound) => remove_end_tags(compound),
+        NbtTag::List(list) => {
+            list.values.retain(|element| !matches!(element, NbtTag::End));
+            list.values.iter_mut().for_each(remove_nested_end_tags);
+        },
+        _ => {},
+    }
+}
+
+/// Encodes the compound in `buf`. Panics if a name or a string is longer than 65535 bytes once encoded.
 pub fn write(buf: &mut Vec<u8>, compound: &NbtTagCompound) {
     write_tag_type(buf, NbtTagType::Compound);
     write_tag_name(buf, &compound.name);
     write_compound(buf, compound);
 }
 
+/// Same as `write`, without the root name. The name of `compound` is ignored.
+pub fn write_unnamed(buf: &mut Vec<u8>, compound: &NbtTagCompound) {
+    write_tag_type(buf, NbtTagType::Compound);
+    write_compound(buf, compound);
+}
+
 fn write_compound(buf: &mut Vec<u8>, compound: &NbtTagCompound) {
-    for val in compound.values.values() {
+    // an End value would terminate the compound early
+    for (_, val) in compound.iter().filter(|(_, val)| !matches!(val, NbtTag::End)) {
         write_value(buf, val, true);
     }
+    write_tag_type(buf, NbtTagType::End);
 }
 
+/// Writes a tag. Named tags (compound entries) are prefixed by their type and name,
+/// list elements only by their payload.
 fn write_value(buf: &mut Vec<u8>, value: &NbtTag, write_name: bool) {
-    let ty = value.ty();
-    write_tag_type(buf, ty);
+    if write_name {
+        write_tag_type(buf, value.ty());
+    }
 
     match value {
         NbtTag::End => (),
@@ -470,7 +1706,7 @@ fn write_value(buf: &mut Vec<u8>, value: &NbtTag, write_name: bool) {
                 write_tag_name(buf, &val.name);
             }
 
-            buf.write_i16::<BigEndian>(val.values.len() as i16).unwrap();
+            buf.write_i32::<BigEndian>(val.values.len() as i32).unwrap();
             buf.reserve(val.values.len());
 
             for x in &val.values {
@@ -482,18 +1718,19 @@ fn write_value(buf: &mut Vec<u8>, value: &NbtTag, write_name: bool) {
                 write_tag_name(buf, &val.name);
             }
 
-            buf.write_u16::<BigEndian>(val.value.len() as u16).unwrap();
-            buf.write(val.value.as_bytes()).unwrap();
+            write_string(buf, &val.value);
         }
         NbtTag::List(val) => {
             if write_name {
                 write_tag_name(buf, &val.name);
             }
 
+            // an End element has no payload, counting it would corrupt the list
+            let elements: Vec<&NbtTag> = val.values.iter().filter(|element| !matches!(element, NbtTag::End)).collect();
             write_tag_type(buf, val.ty);
-            buf.write_i32::<BigEndian>(val.values.len() as i32).unwrap();
+            buf.write_i32::<BigEndian>(elements.len() as i32).unwrap();
 
-            for val in &val.values {
+            for val in elements {
                 // Finally, an actual application of recursion
                 write_value(buf, val, false);
             }
@@ -535,10 +1772,45 @@ fn write_value(buf: &mut Vec<u8>, value: &NbtTag, write_name: bool) {
 }
 
 fn write_tag_name(buf: &mut Vec<u8>, s: &str) {
-    buf.write_i16::<BigEndian>(s.len() as i16).unwrap();
-    buf.write(s.as_bytes()).unwrap();
+    write_string(buf, s);
+}
+
+/// Writes a length-prefixed Modified UTF-8 string.
+/// Panics if the encoded string is longer than the 65535 bytes the length prefix can hold.
+fn write_string(buf: &mut Vec<u8>, s: &str) {
+    let bytes = mutf8::encode(s);
+    let len = u16::try_from(bytes.len()).expect("NBT strings are limited to 65535 bytes");
+    buf.write_u16::<BigEndian>(len).unwrap();
+    buf.write_all(&bytes).unwrap();
 }
 
 fn write_tag_type(buf: &mut Vec<u8>, ty: NbtTagType) {
     buf.write_u8(ty.id()).unwrap();
 }
+
+fn diff_compounds(path: &str, old: &NbtTagCompound, new: &NbtTagCompound, paths: &mut Vec<String>) {
+    let child_path = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}/{}", path, key) };
+
+    for (key, old_tag) in old.iter() {
+        match new.values.get(key) {
+            Some(new_tag) => diff_tags(&child_path(key), old_tag, new_tag, paths),
+            None => paths.push(child_path(key)),
+        }
+    }
+    for key in new.keys().filter(|key| !old.values.contains_key(*key)) {
+        paths.push(child_path(key));
+    }
+}
+
+fn diff_tags(path: &str, old: &NbtTag, new: &NbtTag, paths: &mut Vec<String>) {
+    match (old, new) {
+        (NbtTag::Compound(old), NbtTag::Compound(new)) => diff_compounds(path, old, new, paths),
+        (NbtTag::List(old), NbtTag::List(new)) if old.ty == new.ty && old.len() == new.len() => {
+            for (index, (old_element, new_element)) in old.iter().zip(new.iter()).enumerate() {
+                diff_tags(&format!("{}/[{}]", path, index), old_element, new_element, paths);
+            }
+        },
+        _ if old == new => {},
+        _ => paths.push(path.to_string()),
+    }
+}
@@ -2,6 +2,8 @@
 
 use super::*;
 
+use std::fs;
+
 #[test]
 fn test_nbt_tag_type_ids() {
     assert_eq!(NbtTagType::End.id()         ,0);
@@ -37,3 +39,686 @@ fn test_nbt_tag_type_from_id() {
     assert_eq!(NbtTagType::from_id(255), None); // Test an invalid ID
 }
 
+
+#[test]
+fn test_nbt_tag_ty() {
+    assert_eq!(NbtTag::Int(NbtTagInt::new("int".to_string(), 1)).ty(), NbtTagType::Int);
+    assert_eq!(NbtTag::IntArray(NbtTagIntArray::new("ints".to_string(), vec![1, 2])).ty(), NbtTagType::IntArray);
+    assert_eq!(NbtTag::LongArray(NbtTagLongArray::new("longs".to_string(), vec![1, 2])).ty(), NbtTagType::LongArray);
+    assert_eq!(NbtTag::End.ty(), NbtTagType::End);
+}
+
+#[test]
+fn test_nbt_tag_is_empty() {
+    assert!(NbtTag::Compound(NbtTagCompound::new("compound")).is_empty());
+    assert!(NbtTag::List(NbtTagList::new("list".to_string(), NbtTagType::End, vec![])).is_empty());
+    assert!(NbtTag::ByteArray(NbtTagByteArray::new("bytes".to_string(), vec![])).is_empty());
+    assert!(NbtTag::IntArray(NbtTagIntArray::new("ints".to_string(), vec![])).is_empty());
+    assert!(NbtTag::LongArray(NbtTagLongArray::new("longs".to_string(), vec![])).is_empty());
+
+    let mut compound = NbtTagCompound::new("compound");
+    compound.values.insert("int".to_string(), NbtTag::Int(NbtTagInt::new("int".to_string(), 0)));
+    assert!(!NbtTag::Compound(compound).is_empty());
+    assert!(!NbtTag::List(NbtTagList::new("list".to_string(), NbtTagType::Int, vec![NbtTag::Int(NbtTagInt::new(String::new(), 0))])).is_empty());
+    assert!(!NbtTag::LongArray(NbtTagLongArray::new("longs".to_string(), vec![0])).is_empty());
+
+    assert!(!NbtTag::Int(NbtTagInt::new("int".to_string(), 0)).is_empty());
+    assert!(!NbtTag::String(NbtTagString::new("string".to_string(), String::new())).is_empty());
+    assert!(!NbtTag::End.is_empty());
+}
+
+#[test]
+fn test_nbt_compound_prune_empty() {
+    let empty_compound = |name: &str| NbtTag::Compound(NbtTagCompound::new(name));
+    let empty_list = |name: &str| NbtTag::List(NbtTagList::new(name.to_string(), NbtTagType::End, vec![]));
+
+    // nested holds only empty tags: it becomes empty and is removed too
+    let mut nested = NbtTagCompound::new("nested");
+    nested.values.insert("empty".to_string(), empty_compound("empty"));
+    nested.values.insert("list".to_string(), empty_list("list"));
+
+    // the empty element of a list is kept, its content pruned
+    let mut element = NbtTagCompound::new("");
+    element.values.insert("empty".to_string(), empty_compound("empty"));
+    element.values.insert("int".to_string(), NbtTag::Int(NbtTagInt::new("int".to_string(), 1)));
+    let list = NbtTagList::new("elements".to_string(), NbtTagType::List, vec![empty_list(""), NbtTag::Compound(element)]);
+
+    let mut root = NbtTagCompound::new("");
+    root.values.insert("nested".to_string(), NbtTag::Compound(nested));
+    root.values.insert("elements".to_string(), NbtTag::List(list));
+    root.values.insert("longs".to_string(), NbtTag::LongArray(NbtTagLongArray::new("longs".to_string(), vec![])));
+    root.values.insert("sections".to_string(), empty_list("sections"));
+    root.values.insert("kept".to_string(), empty_compound("kept"));
+
+    let mut pruned = root.clone();
+    assert_eq!(pruned.prune_empty(), 5);
+    let mut keys: Vec<&String> = pruned.keys().collect();
+    keys.sort();
+    assert_eq!(keys, vec!["elements", "longs", "sections"]);
+    let elements = pruned.values["elements"].list_as_ref().unwrap();
+    assert_eq!(elements.len(), 2);
+    assert!(elements[0].is_empty());
+    assert_eq!(elements[1].compound_as_ref().unwrap().keys().collect::<Vec<_>>(), vec!["int"]);
+    assert_eq!(pruned.prune_empty(), 0);
+
+    assert_eq!(root.prune_empty_keeping(&["kept", "list"]), 3);
+    assert!(root.values.contains_key("kept"));
+    assert!(!root.values.contains_key("sections"));
+    assert_eq!(root.values["nested"].compound_as_ref().unwrap().keys().collect::<Vec<_>>(), vec!["list"]);
+}
+
+#[test]
+fn test_nbt_tag_type_check() {
+    let tag = NbtTag::Float(NbtTagFloat::new("float".to_string(), 0.5));
+
+    assert!(tag.type_check(NbtTagType::Float).is_ok());
+    assert_eq!(tag.type_check(NbtTagType::Double), 
+               Err(RnbtError::TypeMismatch { expected: NbtTagType::Double, found: NbtTagType::Float }));
+}
+
+#[test]
+fn test_nbt_tag_try_accessors() {
+    let tag = NbtTag::String(NbtTagString::new("name".to_string(), "minecraft:stone".to_string()));
+
+    assert_eq!(tag.try_string().unwrap().value, "minecraft:stone");
+    assert_eq!(tag.try_byte().unwrap_err(), 
+               RnbtError::TypeMismatch { expected: NbtTagType::Byte, found: NbtTagType::String });
+    assert!(tag.try_compound().is_err());
+
+    let tag = NbtTag::LongArray(NbtTagLongArray::new("data".to_string(), vec![1, 2, 3]));
+    assert_eq!(tag.try_long_array().unwrap().values, vec![1, 2, 3]);
+    assert_eq!(tag.try_int_array().unwrap_err(), 
+               RnbtError::TypeMismatch { expected: NbtTagType::IntArray, found: NbtTagType::LongArray });
+}
+
+#[test]
+fn test_nbt_json_longs_as_strings() {
+    let json_path = std::env::temp_dir().join(format!("fastnbt_json_longs_{}.json", std::process::id()));
+
+    let mut compound = NbtTagCompound::new("root");
+    compound.values.insert("seed".to_string(), NbtTag::Long(NbtTagLong::new("seed".to_string(), -4172144997902289642)));
+    compound.values.insert("data".to_string(), NbtTag::LongArray(NbtTagLongArray::new("data".to_string(), vec![i64::MAX, 1])));
+    // an entry named as a tag type is not mistaken for a tag
+    compound.values.insert("Long".to_string(), NbtTag::Int(NbtTagInt::new("Long".to_string(), 5)));
+
+    compound.to_json(&json_path).unwrap();
+    let json = fs::read_to_string(&json_path).unwrap();
+    assert!(json.contains("-4172144997902289642") && !json.contains("\"-4172144997902289642\""));
+
+    compound.to_json_with_options(&json_path, &JsonOptions { longs_as_strings: true, ..Default::default() }).unwrap();
+    let json = fs::read_to_string(&json_path).unwrap();
+    assert!(json.contains("\"-4172144997902289642\""));
+    assert!(json.contains("\"9223372036854775807\""));
+    assert!(json.contains("\"value\": 5"));
+
+    // the longs are read back from both forms
+    assert_eq!(NbtTagCompound::from_json(&json_path).unwrap(), compound);
+    fs::remove_file(&json_path).unwrap();
+
+    let invalid_long = r#"{"name": "", "values": {"seed": {"Long": {"name": "seed", "value": "12a"}}}}"#;
+    assert!(serde_json::from_str::<NbtTagCompound>(invalid_long).is_err());
+}
+
+#[test]
+fn test_nbt_json_non_finite_floats() {
+    let json_path = std::env::temp_dir().join(format!("fastnbt_json_non_finite_{}.json", std::process::id()));
+
+    let mut compound = NbtTagCompound::new("root");
+    compound.values.insert("max".to_string(), NbtTag::Double(NbtTagDouble::new("max".to_string(), f64::INFINITY)));
+    compound.values.insert("min".to_string(), NbtTag::Float(NbtTagFloat::new("min".to_string(), f32::NEG_INFINITY)));
+    compound.values.insert("pos".to_string(), NbtTag::Double(NbtTagDouble::new("pos".to_string(), 0.5)));
+
+    compound.to_json(&json_path).unwrap();
+    let json = fs::read_to_string(&json_path).unwrap();
+    assert!(json.contains("\"Infinity\"") && json.contains("\"-Infinity\"") && json.contains("0.5"));
+    assert!(serde_json::from_str::<serde_json::Value>(&json).is_ok());
+    assert_eq!(NbtTagCompound::from_json(&json_path).unwrap(), compound);
+
+    compound.to_json_with_options(&json_path, &JsonOptions { non_finite_floats: NonFiniteFloats::Null, ..Default::default() }).unwrap();
+    let json = fs::read_to_string(&json_path).unwrap();
+    assert!(!json.contains("Infinity") && json.contains("null"));
+    // null is read back as NaN
+    let read_back = NbtTagCompound::from_json(&json_path).unwrap();
+    assert!(read_back.values["max"].try_double().unwrap().value.is_nan());
+    assert_eq!(read_back.values["pos"], compound.values["pos"]);
+    fs::remove_file(&json_path).unwrap();
+
+    // the cache keeps the values
+    assert_eq!(NbtTagCompound::from_cache(&compound.to_cache()).unwrap(), compound);
+
+    let invalid_double = r#"{"name": "", "values": {"max": {"Double": {"name": "max", "value": "Inf"}}}}"#;
+    assert!(serde_json::from_str::<NbtTagCompound>(invalid_double).is_err());
+}
+
+#[test]
+fn test_read_uuid() {
+    const UUID: u128 = 0xf81d4fae_7dec_11d0_a765_00a0c91e6bf6;
+    let uuid_int_array = NbtTag::IntArray(NbtTagIntArray::new("UUID".to_string(), vec![-132296786, 2112623056, -1486552928, -920753162]));
+
+    assert_eq!(read_uuid(&uuid_int_array), Some(UUID));
+    assert_eq!(format_uuid(UUID), "f81d4fae-7dec-11d0-a765-00a0c91e6bf6");
+    assert_eq!(format_uuid(1), "00000000-0000-0000-0000-000000000001");
+    assert_eq!(read_uuid(&NbtTag::String(NbtTagString::new("Owner".to_string(), "F81D4FAE-7DEC-11D0-A765-00A0C91E6BF6".to_string()))), Some(UUID));
+
+    // an entity since 1.16, and before
+    let mut entity = NbtTagCompound::new("");
+    entity.values.insert("UUID".to_string(), uuid_int_array);
+    assert_eq!(read_uuid(&NbtTag::Compound(entity)), Some(UUID));
+    let mut legacy_entity = NbtTagCompound::new("");
+    legacy_entity.values.insert("UUIDMost".to_string(), NbtTag::Long(NbtTagLong::new("UUIDMost".to_string(), -568210367123287600)));
+    legacy_entity.values.insert("UUIDLeast".to_string(), NbtTag::Long(NbtTagLong::new("UUIDLeast".to_string(), -6384696206158828554)));
+    assert_eq!(read_compound_uuid(&legacy_entity), Some(UUID));
+
+    legacy_entity.values.remove("UUIDLeast");
+    assert_eq!(read_compound_uuid(&legacy_entity), None);
+    assert_eq!(read_uuid(&NbtTag::IntArray(NbtTagIntArray::new("UUID".to_string(), vec![1, 2, 3]))), None);
+    assert_eq!(read_uuid(&NbtTag::String(NbtTagString::new("Owner".to_string(), "Steve".to_string()))), None);
+    assert_eq!(read_uuid(&NbtTag::String(NbtTagString::new("Owner".to_string(), "f81d4fae-7dec-11d0-a765-00a0c91e6bfg".to_string()))), None);
+    assert_eq!(read_uuid(&NbtTag::Long(NbtTagLong::new("UUID".to_string(), 1))), None);
+}
+
+#[test]
+fn test_nbt_end_tags_are_not_written_or_read() {
+    // End values can only be built by hand, the writer skips them
+    let mut compound = NbtTagCompound::new("root");
+    compound.values.insert("end".to_string(), NbtTag::End);
+    compound.values.insert("ints".to_string(), NbtTag::List(NbtTagList::new("ints".to_string(), NbtTagType::Int, 
+        vec![NbtTag::Int(NbtTagInt::new("".to_string(), 1)), NbtTag::End, NbtTag::Int(NbtTagInt::new("".to_string(), 2))])));
+
+    let mut buf = Vec::new();
+    write(&mut buf, &compound);
+    let parsed = crate::file_parser::parse_nbt_bytes(&buf).unwrap();
+    assert_eq!(parsed.keys().collect::<Vec<_>>(), vec!["ints"]);
+    assert_eq!(parsed.values["ints"].list_as_ref().unwrap().len(), 2);
+
+    // nor read from JSON
+    let json_path = std::env::temp_dir().join(format!("fastnbt_json_end_{}.json", std::process::id()));
+    compound.to_json(&json_path).unwrap();
+    let from_json = NbtTagCompound::from_json(&json_path).unwrap();
+    fs::remove_file(&json_path).unwrap();
+    assert_eq!(from_json, parsed);
+}
+
+#[test]
+fn test_nbt_write_roundtrip() {
+    let mut compound = NbtTagCompound::new("root");
+    compound.values.insert("name".to_string(), NbtTag::String(NbtTagString::new("name".to_string(), "Sword of 😀\0".to_string())));
+    compound.values.insert("bytes".to_string(), NbtTag::ByteArray(NbtTagByteArray::new("bytes".to_string(), vec![1, -2, 3])));
+    compound.values.insert("ints".to_string(), NbtTag::List(NbtTagList::new("ints".to_string(), NbtTagType::Int, 
+        vec![NbtTag::Int(NbtTagInt::new("".to_string(), 7)), NbtTag::Int(NbtTagInt::new("".to_string(), -7))])));
+    let mut nested = NbtTagCompound::new("nested ∞");
+    nested.values.insert("long".to_string(), NbtTag::Long(NbtTagLong::new("long".to_string(), i64::MIN)));
+    compound.values.insert("nested ∞".to_string(), NbtTag::Compound(nested));
+
+    let mut buf = Vec::new();
+    write(&mut buf, &compound);
+
+    // the supplementary char is written as a surrogate pair
+    assert!(buf.windows(6).any(|bytes| bytes == [0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80]));
+
+    let parsed = crate::file_parser::parse_nbt_bytes(&buf).unwrap();
+    assert_eq!(parsed.name, "root");
+    assert_eq!(parsed.values.get("name").unwrap().try_string().unwrap().value, "Sword of 😀\0");
+    assert_eq!(parsed.values.get("bytes").unwrap().try_byte_array().unwrap().values, vec![1, -2, 3]);
+    let ints = parsed.values.get("ints").unwrap().try_list().unwrap();
+    assert_eq!(ints.values.iter().map(|tag| tag.try_int().unwrap().value).collect::<Vec<_>>(), vec![7, -7]);
+    let nested = parsed.values.get("nested ∞").unwrap().try_compound().unwrap();
+    assert_eq!(nested.values.get("long").unwrap().try_long().unwrap().value, i64::MIN);
+}
+
+#[test]
+fn test_nbt_write_longest_string() {
+    let mut compound = NbtTagCompound::new("root");
+    compound.values.insert("text".to_string(), NbtTag::String(NbtTagString::new("text".to_string(), "a".repeat(65535))));
+
+    let mut buf = Vec::new();
+    write(&mut buf, &compound);
+    let parsed = crate::file_parser::parse_nbt_bytes(&buf).unwrap();
+    assert_eq!(parsed, compound);
+}
+
+#[test]
+#[should_panic(expected = "NBT strings are limited to 65535 bytes")]
+fn test_nbt_write_string_too_long() {
+    // the length prefix would be truncated to 0
+    let mut compound = NbtTagCompound::new("root");
+    compound.values.insert("text".to_string(), NbtTag::String(NbtTagString::new("text".to_string(), "a".repeat(65536))));
+
+    let mut buf = Vec::new();
+    write(&mut buf, &compound);
+}
+
+#[test]
+fn test_nbt_compound_iterators() {
+    let mut compound = NbtTagCompound::new("root");
+    compound.values.insert("a".to_string(), NbtTag::Byte(NbtTagByte::new("a".to_string(), 1)));
+    compound.values.insert("b".to_string(), NbtTag::Int(NbtTagInt::new("b".to_string(), 2)));
+
+    let mut keys: Vec<&String> = compound.keys().collect();
+    keys.sort();
+    assert_eq!(keys, vec!["a", "b"]);
+
+    let mut pairs: Vec<(&String, NbtTagType)> = compound.iter().map(|(key, tag)| (key, tag.ty())).collect();
+    pairs.sort_by(|left, right| left.0.cmp(right.0));
+    assert_eq!(pairs, vec![(&"a".to_string(), NbtTagType::Byte), (&"b".to_string(), NbtTagType::Int)]);
+
+    assert_eq!(NbtTagCompound::new("empty").iter().count(), 0);
+}
+
+#[test]
+fn test_nbt_tag_try_from_native() {
+    let tag = NbtTag::Int(NbtTagInt::new("age".to_string(), 42));
+    let age: i32 = (&tag).try_into().unwrap();
+    assert_eq!(age, 42);
+    assert_eq!(i64::try_from(&tag).unwrap_err(), RnbtError::TypeMismatch { expected: NbtTagType::Long, found: NbtTagType::Int });
+
+    assert_eq!(i8::try_from(&NbtTag::Byte(NbtTagByte::new("".to_string(), -1))).unwrap(), -1);
+    assert_eq!(i16::try_from(&NbtTag::Short(NbtTagShort::new("".to_string(), 300))).unwrap(), 300);
+    assert_eq!(i64::try_from(&NbtTag::Long(NbtTagLong::new("".to_string(), i64::MAX))).unwrap(), i64::MAX);
+    assert_eq!(f32::try_from(&NbtTag::Float(NbtTagFloat::new("".to_string(), 0.5))).unwrap(), 0.5);
+    assert_eq!(f64::try_from(&NbtTag::Double(NbtTagDouble::new("".to_string(), -0.25))).unwrap(), -0.25);
+}
+
+#[test]
+fn test_nbt_tag_try_from_string_and_arrays() {
+    let tag = NbtTag::String(NbtTagString::new("id".to_string(), "minecraft:stone".to_string()));
+    assert_eq!(String::try_from(&tag).unwrap(), "minecraft:stone");
+    let id: &str = (&tag).try_into().unwrap();
+    assert_eq!(id, "minecraft:stone");
+    assert!(Vec::<i32>::try_from(&tag).is_err());
+
+    assert_eq!(Vec::<i8>::try_from(&NbtTag::ByteArray(NbtTagByteArray::new("".to_string(), vec![1, 2]))).unwrap(), vec![1, 2]);
+    assert_eq!(Vec::<i32>::try_from(&NbtTag::IntArray(NbtTagIntArray::new("".to_string(), vec![3, 4]))).unwrap(), vec![3, 4]);
+    let longs = NbtTag::LongArray(NbtTagLongArray::new("".to_string(), vec![5]));
+    assert_eq!(Vec::<i64>::try_from(&longs).unwrap(), vec![5]);
+    assert_eq!(String::try_from(&longs).unwrap_err(), RnbtError::TypeMismatch { expected: NbtTagType::String, found: NbtTagType::LongArray });
+}
+
+#[test]
+fn test_nbt_tag_to_snapshot() {
+    let mut compound = NbtTagCompound::new("root");
+    compound.values.insert("id".to_string(), NbtTag::String(NbtTagString::new("id".to_string(), "minecraft:\"stone\"".to_string())));
+    compound.values.insert("count".to_string(), NbtTag::Byte(NbtTagByte::new("count".to_string(), 3)));
+    compound.values.insert("pos".to_string(), NbtTag::List(NbtTagList::new("pos".to_string(), NbtTagType::Double, 
+        vec![NbtTag::Double(NbtTagDouble::new("".to_string(), 0.5)), NbtTag::Double(NbtTagDouble::new("".to_string(), -64.0))])));
+    let mut nested = NbtTagCompound::new("tag");
+    nested.values.insert("damage".to_string(), NbtTag::Float(NbtTagFloat::new("damage".to_string(), 1.0)));
+    nested.values.insert("colors".to_string(), NbtTag::IntArray(NbtTagIntArray::new("colors".to_string(), vec![1, -2])));
+    compound.values.insert("tag".to_string(), NbtTag::Compound(nested));
+
+    let expected = concat!(
+        "\"root\": Compound {\n",
+        "  \"count\": Byte 3\n",
+        "  \"id\": String \"minecraft:\\\"stone\\\"\"\n",
+        "  \"pos\": List<Double> [\n",
+        "    Double 0.5\n",
+        "    Double -64.0\n",
+        "  ]\n",
+        "  \"tag\": Compound {\n",
+        "    \"colors\": IntArray [1, -2]\n",
+        "    \"damage\": Float 1.0\n",
+        "  }\n",
+        "}\n",
+    );
+    assert_eq!(compound.to_snapshot(), expected);
+    assert_eq!(NbtTag::Compound(compound.clone()).to_snapshot(), expected);
+}
+
+#[test]
+fn test_nbt_tag_to_snapshot_order_independent() {
+    // the same content inserted in a different order gives the same snapshot
+    let names = ["a", "b", "c", "d", "e", "f", "g", "h"];
+    let mut forward = NbtTagCompound::new("");
+    for (value, name) in names.iter().enumerate() {
+        forward.values.insert(name.to_string(), NbtTag::Int(NbtTagInt::new(name.to_string(), value as i32)));
+    }
+    let mut backward = NbtTagCompound::new("");
+    for (value, name) in names.iter().enumerate().rev() {
+        backward.values.insert(name.to_string(), NbtTag::Int(NbtTagInt::new(name.to_string(), value as i32)));
+    }
+
+    assert_eq!(forward.to_snapshot(), backward.to_snapshot());
+}
+
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn test_nbt_tag_eq_hash_float_bits() {
+    let float = |value: f32| NbtTag::Float(NbtTagFloat::new("f".to_string(), value));
+    let double = |value: f64| NbtTag::Double(NbtTagDouble::new("d".to_string(), value));
+
+    assert_eq!(float(f32::NAN), float(f32::NAN));
+    assert_eq!(hash_of(&float(f32::NAN)), hash_of(&float(f32::NAN)));
+    assert_eq!(double(f64::INFINITY), double(f64::INFINITY));
+    assert_ne!(float(0.0), float(-0.0));
+    assert_ne!(double(0.0), double(-0.0));
+    assert_ne!(double(f64::NAN), double(-f64::NAN));
+
+    // same value, different name or type
+    assert_ne!(float(1.0), NbtTag::Float(NbtTagFloat::new("g".to_string(), 1.0)));
+    assert_ne!(NbtTag::Int(NbtTagInt::new("".to_string(), 1)), NbtTag::Long(NbtTagLong::new("".to_string(), 1)));
+}
+
+#[test]
+fn test_nbt_tag_hash_map_keys() {
+    // counting the distinct palette entries, whatever the insertion order of the properties
+    let block_state = |properties: &[(&str, &str)]| {
+        let mut props = NbtTagCompound::new("Properties");
+        for (name, value) in properties {
+            props.values.insert(name.to_string(), NbtTag::String(NbtTagString::new(name.to_string(), value.to_string())));
+        }
+        let mut entry = NbtTagCompound::new("");
+        entry.values.insert("Name".to_string(), NbtTag::String(NbtTagString::new("Name".to_string(), "minecraft:repeater".to_string())));
+        entry.values.insert("Properties".to_string(), NbtTag::Compound(props));
+        NbtTag::Compound(entry)
+    };
+
+    let palette = [
+        block_state(&[("delay", "1"), ("facing", "west")]),
+        block_state(&[("facing", "west"), ("delay", "1")]),
+        block_state(&[("delay", "2"), ("facing", "west")]),
+    ];
+    assert_eq!(hash_of(&palette[0]), hash_of(&palette[1]));
+
+    let mut counts = HashMap::<&NbtTag, usize>::new();
+    for entry in palette.iter() {
+        *counts.entry(entry).or_default() += 1;
+    }
+    assert_eq!(counts.len(), 2);
+    assert_eq!(counts[&palette[0]], 2);
+}
+
+#[test]
+fn test_nbt_tag_compound_take_replace() {
+    let mut level = NbtTagCompound::new("Level");
+    level.values.insert("Sections".to_string(), NbtTag::List(NbtTagList::new("Sections".to_string(), NbtTagType::Compound, vec![NbtTag::Compound(NbtTagCompound::new(""))])));
+    let mut chunk = NbtTagCompound::new("");
+    chunk.values.insert("Level".to_string(), NbtTag::Compound(level));
+
+    // move the pre-1.18 Level/Sections to the root as sections
+    let Some(NbtTag::Compound(mut level)) = chunk.take("Level") else { panic!("Level is not a compound") };
+    assert!(chunk.take("Level").is_none());
+    let Some(NbtTag::List(mut sections)) = level.take("Sections") else { panic!("Sections is not a list") };
+    sections.name = "sections".to_string();
+    assert!(chunk.replace("sections", NbtTag::List(sections)).is_none());
+    assert_eq!(chunk.get_path("sections").map(|tag| tag.name()), Some("sections"));
+
+    let old_sections = chunk.replace("sections", NbtTag::List(NbtTagList::new("sections".to_string(), NbtTagType::Compound, Vec::new())));
+    assert_eq!(old_sections.and_then(|tag| tag.list()).map(|list| list.values.len()), Some(1));
+    assert_eq!(chunk.keys().collect::<Vec<_>>(), vec!["sections"]);
+}
+
+#[test]
+fn test_nbt_tag_list_accessors() {
+    let values = (0..3).map(|i| NbtTag::Int(NbtTagInt::new("".to_string(), i * 10))).collect();
+    let list = NbtTagList::new("ints".to_string(), NbtTagType::Int, values);
+
+    assert_eq!(list.len(), 3);
+    assert!(!list.is_empty());
+    assert_eq!(list.get(1).and_then(|tag| i32::try_from(tag).ok()), Some(10));
+    assert!(list.get(3).is_none());
+    assert_eq!(i32::try_from(&list[2]).unwrap(), 20);
+    assert_eq!(list.iter().map(|tag| i32::try_from(tag).unwrap()).collect::<Vec<_>>(), vec![0, 10, 20]);
+
+    assert!(NbtTagList::default().is_empty());
+}
+
+#[test]
+fn test_nbt_tag_list_extend_split_off() {
+    let ints = |values: Vec<i32>| values.into_iter().map(|value| NbtTag::Int(NbtTagInt::new("".to_string(), value))).collect();
+    let mut list = NbtTagList::new("ints".to_string(), NbtTagType::Int, ints(vec![1, 2]));
+
+    list.extend(NbtTagList::new("other".to_string(), NbtTagType::Int, ints(vec![3, 4, 5]))).unwrap();
+    assert_eq!(list, NbtTagList::new("ints".to_string(), NbtTagType::Int, ints(vec![1, 2, 3, 4, 5])));
+    list.extend(NbtTagList::new("empty".to_string(), NbtTagType::End, Vec::new())).unwrap();
+    assert_eq!(list.len(), 5);
+
+    let strings = NbtTagList::new("strings".to_string(), NbtTagType::String, vec![NbtTag::String(NbtTagString::new("".to_string(), "a".to_string()))]);
+    assert_eq!(list.extend(strings.clone()), Err(RnbtError::TypeMismatch { expected: NbtTagType::Int, found: NbtTagType::String }));
+    assert_eq!(list.len(), 5);
+
+    // an empty list takes the type of the elements
+    let mut empty = NbtTagList::new("empty".to_string(), NbtTagType::End, Vec::new());
+    empty.extend(strings.clone()).unwrap();
+    assert_eq!(empty.ty, NbtTagType::String);
+
+    let tail = list.split_off(3);
+    assert_eq!(list, NbtTagList::new("ints".to_string(), NbtTagType::Int, ints(vec![1, 2, 3])));
+    assert_eq!(tail, NbtTagList::new("ints".to_string(), NbtTagType::Int, ints(vec![4, 5])));
+    assert!(list.split_off(3).is_empty());
+}
+
+#[test]
+#[should_panic]
+fn test_nbt_tag_list_index_out_of_bounds() {
+    let list = NbtTagList::new("empty".to_string(), NbtTagType::End, Vec::new());
+    let _ = &list[0];
+}
+
+#[test]
+fn test_nbt_compound_infer_schema() {
+    let entity = |name: Option<&str>| {
+        let mut entity = NbtTagCompound::new("");
+        let pos = vec![NbtTag::Double(NbtTagDouble::new("".to_string(), 0.5)); 3];
+        entity.values.insert("Pos".to_string(), NbtTag::List(NbtTagList::new("Pos".to_string(), NbtTagType::Double, pos)));
+        if let Some(name) = name {
+            entity.values.insert("CustomName".to_string(), NbtTag::String(NbtTagString::new("CustomName".to_string(), name.to_string())));
+        }
+        NbtTag::Compound(entity)
+    };
+
+    let mut root = NbtTagCompound::new("");
+    root.values.insert("DataVersion".to_string(), NbtTag::Int(NbtTagInt::new("DataVersion".to_string(), 3465)));
+    root.values.insert("Entities".to_string(), NbtTag::List(NbtTagList::new("Entities".to_string(), NbtTagType::Compound, vec![entity(None), entity(Some("Bob"))])));
+    root.values.insert("Empty".to_string(), NbtTag::List(NbtTagList::new("Empty".to_string(), NbtTagType::End, vec![])));
+
+    let schema = root.infer_schema();
+    assert_eq!(schema.tag_type, NbtTagType::Compound);
+    assert_eq!(schema.fields.keys().collect::<Vec<_>>(), vec!["DataVersion", "Empty", "Entities"]);
+    assert_eq!(schema.fields["DataVersion"].tag_type, NbtTagType::Int);
+    assert_eq!(schema.fields["Empty"].element, None);
+
+    // the keys of the elements are merged, CustomName is only in one of them
+    let element = schema.fields["Entities"].element.as_deref().unwrap();
+    assert_eq!(element.tag_type, NbtTagType::Compound);
+    assert!(element.fields["CustomName"].optional);
+    assert!(!element.fields["Pos"].optional);
+    assert_eq!(element.fields["Pos"].element.as_ref().unwrap().tag_type, NbtTagType::Double);
+
+    // the JSON has no values and reads back to the same schema
+    let json_path = std::env::temp_dir().join(format!("fastnbt_schema_{}.json", std::process::id()));
+    schema.to_json(&json_path).unwrap();
+    let json = fs::read_to_string(&json_path).unwrap();
+    assert!(!json.contains("3465") && !json.contains("Bob"));
+    assert_eq!(serde_json::from_str::<SchemaNode>(&json).unwrap(), schema);
+    fs::remove_file(&json_path).unwrap();
+}
+
+#[test]
+fn test_nbt_compound_name_and_rename() {
+    let mut compound = NbtTagCompound::new("root");
+    assert_eq!(compound.name(), "root");
+    compound.set_name("Data");
+    assert_eq!(compound.name(), "Data");
+
+    compound.values.insert("Old".to_string(), NbtTag::Int(NbtTagInt::new("Old".to_string(), 1)));
+    compound.values.insert("New".to_string(), NbtTag::Int(NbtTagInt::new("New".to_string(), 2)));
+
+    // the key and the name of the tag move together, the tag already at the new key is replaced
+    assert!(compound.rename("Old", "New"));
+    assert_eq!(compound.values.len(), 1);
+    assert_eq!(compound.values["New"], NbtTag::Int(NbtTagInt::new("New".to_string(), 1)));
+    assert!(!compound.rename("Old", "Other"));
+
+    // the written name follows
+    let mut written = Vec::new();
+    write(&mut written, &compound);
+    assert_eq!(&written[..9], &[10, 0, 4, b'D', b'a', b't', b'a', 3, 0]);
+    assert_eq!(&written[9..12], &[3, b'N', b'e']);
+
+    let mut end = NbtTag::End;
+    end.set_name("ignored");
+    assert_eq!(end.name(), "");
+}
+
+#[test]
+fn test_nbt_compound_diff_paths() {
+    let mut section = NbtTagCompound::new("");
+    section.values.insert("Y".to_string(), NbtTag::Byte(NbtTagByte::new("Y".to_string(), 0)));
+    let sections = NbtTagList { name: "sections".to_string(), ty: NbtTagType::Compound, values: vec![NbtTag::Compound(section)] };
+
+    let mut old = NbtTagCompound::new("");
+    old.values.insert("sections".to_string(), NbtTag::List(sections));
+    old.values.insert("Status".to_string(), NbtTag::String(NbtTagString::new("Status".to_string(), "full".to_string())));
+    old.values.insert("Removed".to_string(), NbtTag::Int(NbtTagInt::new("Removed".to_string(), 1)));
+    assert!(old.diff_paths(&old.clone()).is_empty());
+
+    let mut new = old.clone();
+    new.values.remove("Removed");
+    new.values.insert("Added".to_string(), NbtTag::Int(NbtTagInt::new("Added".to_string(), 1)));
+    if let Some(NbtTag::List(sections)) = new.values.get_mut("sections") {
+        if let NbtTag::Compound(section) = &mut sections.values[0] {
+            section.values.insert("Y".to_string(), NbtTag::Byte(NbtTagByte::new("Y".to_string(), 1)));
+        }
+    }
+
+    assert_eq!(old.diff_paths(&new), vec!["Added", "Removed", "sections/[0]/Y"]);
+}
+
+#[test]
+fn test_nbt_tag_depth() {
+    let int = NbtTag::Int(NbtTagInt::new("i".to_string(), 1));
+    assert_eq!(int.depth(), 0);
+    assert_eq!(NbtTag::LongArray(NbtTagLongArray::new("a".to_string(), vec![1, 2])).depth(), 0);
+    assert_eq!(NbtTag::Compound(NbtTagCompound::new("empty")).depth(), 1);
+
+    let mut inner = NbtTagCompound::new("inner");
+    inner.values.insert("i".to_string(), int.clone());
+    let mut outer = NbtTagCompound::new("outer");
+    outer.values.insert("i".to_string(), int.clone());
+    outer.values.insert("inner".to_string(), NbtTag::Compound(inner.clone()));
+    assert_eq!(NbtTag::Compound(inner.clone()).depth(), 1);
+    assert_eq!(NbtTag::Compound(outer.clone()).depth(), 2);
+
+    // the deepest branch counts: a list of [compound, list of compound holding outer]
+    let nested_list = NbtTagList::new("".to_string(), NbtTagType::Compound, vec![NbtTag::Compound(outer)]);
+    let list = NbtTagList::new("l".to_string(), NbtTagType::List, vec![NbtTag::List(NbtTagList::new("".to_string(), NbtTagType::End, Vec::new())), NbtTag::List(nested_list)]);
+    assert_eq!(NbtTag::List(list).depth(), 4);
+}
+
+#[test]
+fn test_nbt_compound_cache() {
+    let mut inner = NbtTagCompound::new("inner");
+    inner.values.insert("name".to_string(), NbtTag::String(NbtTagString::new("name".to_string(), "é\0".to_string())));
+    inner.values.insert("nan".to_string(), NbtTag::Double(NbtTagDouble::new("nan".to_string(), f64::NAN)));
+    let mut compound = NbtTagCompound::new("root");
+    compound.values.insert("seed".to_string(), NbtTag::Long(NbtTagLong::new("seed".to_string(), -4172144997902289642)));
+    compound.values.insert("data".to_string(), NbtTag::LongArray(NbtTagLongArray::new("data".to_string(), vec![i64::MIN, 1])));
+    compound.values.insert("items".to_string(), NbtTag::List(NbtTagList::new("items".to_string(), NbtTagType::Compound, vec![NbtTag::Compound(inner)])));
+
+    let cache = compound.to_cache();
+    assert_eq!(cache[0], CACHE_FORMAT_VERSION);
+    assert_eq!(NbtTagCompound::from_cache(&cache).unwrap(), compound);
+
+    let mut other_version = cache.clone();
+    other_version[0] = CACHE_FORMAT_VERSION + 1;
+    assert!(matches!(NbtTagCompound::from_cache(&other_version), Err(RnbtError::InvalidCache(_))));
+    assert!(matches!(NbtTagCompound::from_cache(&cache[..cache.len() - 1]), Err(RnbtError::InvalidCache(_))));
+    assert!(matches!(NbtTagCompound::from_cache(&[]), Err(RnbtError::InvalidCache(_))));
+}
+
+#[test]
+fn test_nbt_tag_numeric_eq() {
+    assert!(NbtTag::Float(NbtTagFloat::new("Health".to_string(), 20.0)).numeric_eq(20.0));
+    assert!(NbtTag::Double(NbtTagDouble::new("Health".to_string(), 20.0)).numeric_eq(20.0));
+    assert!(NbtTag::Int(NbtTagInt::new("Health".to_string(), 20)).numeric_eq(20.0));
+    assert!(NbtTag::Byte(NbtTagByte::new("Count".to_string(), -3)).numeric_eq(-3.0));
+    assert!(NbtTag::Short(NbtTagShort::new("Fire".to_string(), 300)).numeric_eq(300.0));
+
+    // a Float is rounded from the f64 literal, the tolerance is relative
+    assert!(NbtTag::Float(NbtTagFloat::new("Speed".to_string(), 0.1)).numeric_eq(0.1));
+    assert!(NbtTag::Double(NbtTagDouble::new("Pos".to_string(), 1.0e9 + 0.5)).numeric_eq(1.0e9));
+    assert!(!NbtTag::Float(NbtTagFloat::new("Health".to_string(), 19.5)).numeric_eq(20.0));
+    assert!(!NbtTag::Float(NbtTagFloat::new("Health".to_string(), f32::NAN)).numeric_eq(f64::NAN));
+
+    // the integers are exact
+    assert!(!NbtTag::Int(NbtTagInt::new("Health".to_string(), 20)).numeric_eq(20.000001));
+    assert!(NbtTag::Long(NbtTagLong::new("Seed".to_string(), -4172144997902289920)).numeric_eq(-4172144997902289920.0));
+    assert!(!NbtTag::Long(NbtTagLong::new("Seed".to_string(), 1)).numeric_eq(1.5));
+    assert!(!NbtTag::Long(NbtTagLong::new("Seed".to_string(), i64::MAX)).numeric_eq(f64::INFINITY));
+
+    assert!(!NbtTag::String(NbtTagString::new("Health".to_string(), "20".to_string())).numeric_eq(20.0));
+}
+
+#[test]
+fn test_apply_rules() {
+    let float = |value: f32| NbtTag::Float(NbtTagFloat::new("".to_string(), value));
+    let mut entity = NbtTagCompound::new("");
+    entity.values.insert("Pos".to_string(), NbtTag::List(NbtTagList::new("Pos".to_string(), NbtTagType::Float, vec![float(0.5), float(64.0), float(-3.5)])));
+    let mut level = NbtTagCompound::new("Level");
+    level.values.insert("TileEntities".to_string(), NbtTag::List(NbtTagList::new("TileEntities".to_string(), NbtTagType::Compound, vec![])));
+    level.values.insert("Entities".to_string(), NbtTag::List(NbtTagList::new("Entities".to_string(), NbtTagType::Compound, vec![NbtTag::Compound(entity)])));
+    level.values.insert("xPos".to_string(), NbtTag::Long(NbtTagLong::new("xPos".to_string(), i64::MAX)));
+    let mut chunk = NbtTagCompound::new("");
+    chunk.values.insert("Level".to_string(), NbtTag::Compound(level));
+
+    let status = NbtTag::String(NbtTagString::new("".to_string(), "minecraft:full".to_string()));
+    let rules = vec![
+        Rule::new("Level/TileEntities", RuleAction::Rename("block_entities".to_string())).unwrap(),
+        Rule::new("Level/block_entities", RuleAction::ChangeType(NbtTagType::Int)).unwrap(),
+        Rule::new("Level/Entities/*/Pos/*", RuleAction::ChangeType(NbtTagType::Double)).unwrap(),
+        Rule::new("Level/xPos", RuleAction::ChangeType(NbtTagType::Int)).unwrap(),
+        Rule::new("Level/Status", RuleAction::SetDefault(status)).unwrap(),
+        Rule::new("Level/Missing/Key", RuleAction::Rename("key".to_string())).unwrap(),
+    ];
+    // the list is not a number, it is left unchanged
+    assert_eq!(apply_rules(&mut chunk, &rules), 1 + 3 + 1 + 1);
+
+    let block_entities = chunk.get_path("Level/block_entities").unwrap();
+    assert_eq!(block_entities.name(), "block_entities");
+    assert_eq!(block_entities.ty(), NbtTagType::List);
+    assert!(!chunk.contains_path("Level/TileEntities"));
+    let pos = chunk.get_path("Level/Entities/[0]/Pos").unwrap().list_as_ref().unwrap();
+    assert_eq!(pos.ty, NbtTagType::Double);
+    assert_eq!(pos[2], NbtTag::Double(NbtTagDouble::new("".to_string(), -3.5)));
+    assert_eq!(chunk.get_path("Level/xPos"), Some(&NbtTag::Int(NbtTagInt::new("xPos".to_string(), i32::MAX))));
+    assert_eq!(chunk.get_path("Level/Status").unwrap().name(), "Status");
+
+    // applying the rules again changes nothing
+    assert_eq!(apply_rules(&mut chunk, &rules), 0);
+}
+
+#[test]
+fn test_apply_rules_list_elements() {
+    let float = |value: f32| NbtTag::Float(NbtTagFloat::new("".to_string(), value));
+    let mut entity = NbtTagCompound::new("");
+    entity.values.insert("Pos".to_string(), NbtTag::List(NbtTagList::new("Pos".to_string(), NbtTagType::Float, vec![float(0.5), float(64.0), float(-3.5)])));
+
+    // a part of the list is left unchanged, the elements must keep the type of the list
+    for path in ["Pos/[0]", "Pos/[1:]", "Pos/2"] {
+        assert_eq!(apply_rules(&mut entity, &[Rule::new(path, RuleAction::ChangeType(NbtTagType::Double)).unwrap()]), 0);
+    }
+    assert_eq!(entity.get_path("Pos").unwrap().list_as_ref().unwrap().ty, NbtTagType::Float);
+
+    assert_eq!(apply_rules(&mut entity, &[Rule::new("Pos[:]", RuleAction::ChangeType(NbtTagType::Double)).unwrap()]), 3);
+    let pos = entity.get_path("Pos").unwrap().list_as_ref().unwrap();
+    assert_eq!(pos.ty, NbtTagType::Double);
+    assert!(pos.values.iter().all(|tag| tag.ty() == NbtTagType::Double));
+
+    // the converted list is written and read back as valid NBT
+    let parsed = crate::file_parser::parse_bytes(&entity.to_binary_named()).unwrap();
+    assert_eq!(parsed, NbtTag::Compound(entity));
+}
+
+#[test]
+fn test_rule_new_invalid() {
+    assert_eq!(Rule::new("", RuleAction::ChangeType(NbtTagType::Int)).unwrap_err(), RnbtError::InvalidPath("".to_string()));
+    assert!(Rule::new("Level/*", RuleAction::Rename("key".to_string())).is_err());
+    assert!(Rule::new("Level/Entities[0]", RuleAction::SetDefault(NbtTag::End)).is_err());
+    assert!(Rule::new("Level/[", RuleAction::ChangeType(NbtTagType::Int)).is_err());
+    assert!(Rule::new("Level/*", RuleAction::ChangeType(NbtTagType::Int)).is_ok());
+}
@@ -0,0 +1,388 @@
+// ## Author
+// - mrmarkolinus
+//
+// ## Date
+// - 2026-10-16
+//
+// ## File Version
+// - 1.0.8
+//
+// ## Changelog
+// - 1.0.0: Initial version, python bindings moved from lib.rs and gated behind the "python" feature
+// - 1.0.1: Added load_all_dimensions to load_binary [mrmarkolinus:2026-10-16]
+// - 1.0.2: Added load_metadata_only and the level.dat getters [mrmarkolinus:2026-10-16]
+// - 1.0.3: The compounds are converted by reference, without deep copies, under a single GIL acquisition [mrmarkolinus:2026-10-16]
+// - 1.0.4: Added the typed conversion (NbtValue), keeping the NBT type of the values [mrmarkolinus:2026-10-16]
+// - 1.0.5: End tags are not converted, End is only structural [mrmarkolinus:2026-10-16]
+// - 1.0.6: Added chunks_with_coords, the chunks with their region and chunk coordinates [mrmarkolinus:2026-10-16]
+// - 1.0.7: Added search_blocks_np, the positions of the blocks as (x, y, z) tuples [mrmarkolinus:2026-10-16]
+// - 1.0.8: chunks_with_coords reads the coordinates from the SourcedCompound of each chunk [mrmarkolinus:2026-10-16]
+
+use crate::{blocks, nbt_tag, world};
+use crate::{McWorldDescriptor, WorldLoadOptions};
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use pyo3::prelude::*;
+use pyo3::wrap_pyfunction;
+use pyo3::types::{PyDict, PyList};
+use log::info;
+
+#[pymodule]
+fn fastnbt(_py: Python, m: &PyModule) -> PyResult<()> {
+    pyo3_log::init();
+    m.add_class::<PyMcWorldDescriptor>()?;
+    m.add_class::<PyNbtTag>()?;
+    m.add_class::<PyNbtValue>()?;
+    m.add_class::<blocks::MinecraftBlock>()?;
+    m.add_class::<blocks::Coordinates>()?;
+    
+    m.add_function(wrap_pyfunction!(load_binary, m)?)?;
+    m.add_function(wrap_pyfunction!(load_metadata_only, m)?)?;
+    m.add_function(wrap_pyfunction!(py_log, m)?)?;
+
+    Ok(())
+}
+#[pyfunction]
+fn py_log(message: String)  {
+    info!("{}", message);
+}
+
+/// Loads a world, a region file or a NBT file. With `typed`, the values of the dictionaries are `NbtValue`s, 
+/// which keep the NBT type of each value (plain Python values otherwise).
+#[pyfunction]
+#[pyo3(signature = (input_path, skip_non_full_chunks = false, load_all_dimensions = false, typed = false))]
+fn load_binary(input_path: String, skip_non_full_chunks: bool, load_all_dimensions: bool, typed: bool) -> PyResult<PyMcWorldDescriptor> {   
+    let path_buf = PathBuf::from(input_path);
+    let load_options = WorldLoadOptions { skip_non_full_chunks, load_all_dimensions, ..Default::default() };
+    let mc_world = McWorldDescriptor::new_with_options(path_buf, load_options)?; 
+    PyMcWorldDescriptor::new_with_types(mc_world, typed)
+}
+
+/// Reads only level.dat of a world, the chunks are not loaded (tag_compounds_list is empty).
+#[pyfunction]
+fn load_metadata_only(input_path: String) -> PyResult<PyMcWorldDescriptor> {
+    let mc_world = McWorldDescriptor::open_metadata_only(PathBuf::from(input_path))?;
+    PyMcWorldDescriptor::new(mc_world)
+}
+
+/// A chunk returned to python by `chunks_with_coords`: (region_x, region_z, chunk_x, chunk_z, dict).
+pub type PyChunkWithCoords = (i32, i32, i32, i32, Py<PyDict>);
+
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct PyMcWorldDescriptor {
+    mc_world_descriptor: McWorldDescriptor,
+    /// The dictionaries hold `NbtValue`s, see `load_binary`.
+    typed: bool,
+    //TEST
+    #[pyo3(get, set)]
+    pub tag_compounds_list: Vec::<Py<PyDict>>
+}
+
+#[pymethods]
+impl PyMcWorldDescriptor {
+    #[new]
+    pub fn new(rust_mc_world_descriptor: McWorldDescriptor) -> PyResult<Self> {
+        Self::new_with_types(rust_mc_world_descriptor, false)
+    }
+
+    pub fn to_json(&self, path: String) -> PyResult<()> {
+        self.mc_world_descriptor.to_json(path).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("{}", e)))
+    }
+
+    pub fn get_mc_version(&self) -> String {
+        self.mc_world_descriptor.get_mc_version()
+    }
+
+    pub fn get_level_name(&self) -> Option<String> {
+        self.mc_world_descriptor.metadata().and_then(|metadata| metadata.level_name()).map(str::to_string)
+    }
+
+    pub fn get_seed(&self) -> Option<i64> {
+        self.mc_world_descriptor.metadata().and_then(|metadata| metadata.seed())
+    }
+
+    pub fn search_compound(&self, key: &str) -> PyResult<(bool, Vec::<Py<PyDict>>)> {
+        
+        let mut py_tag_list = Vec::<Py<PyDict>>::new();
+
+        let (compound_found, compound_tag_list) = self.mc_world_descriptor.search_compound(key, false);
+        
+        if compound_found {
+            for item in compound_tag_list {
+                py_tag_list.push(PyNbtTag::from_compound_with_types(item, self.typed)?.python_dict);
+            }
+            Ok((true, py_tag_list))
+        } else {
+            Ok((false, py_tag_list))
+        }
+
+    }
+
+    pub fn search_blocks(&self, block_resource_location: Vec::<String>) -> HashMap::<String, Vec::<blocks::MinecraftBlock>> {
+        self.mc_world_descriptor.search_blocks(block_resource_location)
+    }
+
+    /// Returns the loaded chunks as `(region_x, region_z, chunk_x, chunk_z, dict)` tuples, in the order of `tag_compounds_list`.
+    /// The compounds that are not chunks (e.g. a structure file) are skipped.
+    pub fn chunks_with_coords(&self) -> PyResult<Vec<PyChunkWithCoords>> {
+        let mut chunks = Vec::new();
+
+        for chunk_compound in self.mc_world_descriptor.tag_compounds_list.iter() {
+            let Some((chunk_x, chunk_z)) = chunk_compound.chunk_coords else { continue };
+
+            // a chunk is always in the region containing its coordinates, also when not read from a region file
+            let (region_x, region_z) = chunk_compound.region_coords
+                .unwrap_or((chunk_x.div_euclid(world::REGION_CHUNKS_SIDE), chunk_z.div_euclid(world::REGION_CHUNKS_SIDE)));
+            let py_dict = PyNbtTag::from_compound_with_types(chunk_compound, self.typed)?.python_dict;
+            chunks.push((region_x, region_z, chunk_x, chunk_z, py_dict));
+        }
+
+        Ok(chunks)
+    }
+
+    /// Same as `search_blocks`, returning only the positions as `(x, y, z)` tuples, ready for `numpy.array` 
+    /// or `pandas.DataFrame` without iterating the `MinecraftBlock` objects.
+    pub fn search_blocks_np(&self, block_resource_location: Vec::<String>) -> HashMap::<String, Vec::<(i32, i32, i32)>> {
+        self.mc_world_descriptor.search_blocks(block_resource_location).into_iter()
+            .map(|(block_name, blocks)| (block_name, blocks.iter().map(|block| (block.coord.x, block.coord.y, block.coord.z)).collect()))
+            .collect()
+    }
+
+    pub fn search_blocks_with_props(&self, block_filters: Vec::<(String, Vec::<(String, String)>)>) -> HashMap::<String, Vec::<blocks::MinecraftBlock>> {
+        self.mc_world_descriptor.search_blocks_with_props(block_filters)
+    }
+
+}
+
+impl PyMcWorldDescriptor {
+    /// Converts the compounds of the world into dictionaries, holding `NbtValue`s when `typed`.
+    pub fn new_with_types(rust_mc_world_descriptor: McWorldDescriptor, typed: bool) -> PyResult<Self> {
+
+        let mut py_tag_list = Vec::<Py<PyDict>>::new();
+        
+        for item in rust_mc_world_descriptor.tag_compounds_list.iter() {
+            py_tag_list.push(PyNbtTag::from_compound_with_types(item, typed)?.python_dict)
+        }
+
+        Ok(PyMcWorldDescriptor{ 
+            mc_world_descriptor: rust_mc_world_descriptor, 
+            typed,
+            tag_compounds_list: py_tag_list 
+        })
+    }
+}
+
+
+/* #[derive(Clone, Debug)]
+pub struct SerializablePyDict(Py<PyDict>);
+
+impl SerializablePyDict {
+    pub fn get_py_dict(&self) -> &Py<PyDict> {
+        &self.0
+    }
+}
+
+impl IntoPy<Py<PyAny>> for SerializablePyDict {
+    fn into_py(self, py: Python) -> Py<PyAny> {
+        self.0.into_py(py)
+    }
+}
+
+impl ToPyObject for SerializablePyDict {
+    fn to_object(&self, py: Python) -> PyObject {
+        self.0.to_object(py) // Delegate to Py<PyDict>'s implementation
+    }
+}
+
+impl FromPyObject<'_> for SerializablePyDict {
+    fn extract(ob: &'_ PyAny) -> PyResult<Self> {
+        let py_dict: Py<PyDict> = ob.extract()?; // Extract as Py<PyDict> using PyDict's FromPyObject
+        Ok(SerializablePyDict(py_dict)) // Wrap in SerializablePyDict
+    }
+}
+
+impl Serialize for SerializablePyDict {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Python::with_gil(|py| {
+            let dict: &PyDict = self.0.as_ref(py);
+            let mut rust_dict = HashMap::new();
+
+            for (key, value) in dict.into_iter() {
+                let key_str = key.extract::<String>().map_err(serde::ser::Error::custom)?;
+                let value_str = value.extract::<PyNbtTag>().map_err(serde::ser::Error::custom)?;
+                rust_dict.insert(key_str, value_str);
+            }
+
+            let mut map = serializer.serialize_map(Some(rust_dict.len()))?;
+            for (k, v) in rust_dict {
+                map.serialize_entry(&k, &v.ser_python_dict)?;
+            }
+            map.end()
+        })
+    }
+} */
+
+/// A value with its NBT type, produced by the typed conversion (`load_binary(..., typed=True)`): 
+/// Python code can tell a Byte from an Int or a Long and write the value back with its type.
+/// 
+/// `repr` gives e.g. `NbtByte(5)`.
+#[pyclass(name = "NbtValue", get_all)]
+#[derive(Clone, Debug)]
+pub struct PyNbtValue {
+    /// The NBT type: "Byte", "Short", "Int", "Long", "Float", "Double", "ByteArray", "String", "List", "IntArray", "LongArray" or "End".
+    pub tag_type: String,
+    /// The value as a Python int, float, str or list.
+    pub value: PyObject,
+    /// The NBT type of the elements of a List (known even when the list is empty), None for the other types.
+    pub element_type: Option<String>,
+}
+
+#[pymethods]
+impl PyNbtValue {
+    #[new]
+    #[pyo3(signature = (tag_type, value, element_type = None))]
+    fn new(tag_type: String, value: PyObject, element_type: Option<String>) -> Self {
+        Self { tag_type, value, element_type }
+    }
+
+    fn __repr__(&self, py: Python) -> PyResult<String> {
+        Ok(format!("Nbt{}({})", self.tag_type, self.value.as_ref(py).repr()?))
+    }
+}
+
+#[pyclass(get_all)]
+#[derive(Clone, Debug)]
+pub struct PyNbtTag {
+    //pub nbt_tag: &'a NbtTag,
+    pub python_dict: Py<PyDict>,
+    //pub ser_python_dict: SerializablePyDict
+}
+
+//https://github.com/PyO3/pyo3/pull/3582 
+impl PyNbtTag {
+
+    pub fn new(nbt_tag: &nbt_tag::NbtTag) -> PyResult<Self> {
+        let python_dict = Python::with_gil(|py| Self::to_python_dictionary(py, nbt_tag, false))?;
+        //let ser_py_dict = Self::to_ser_python_dictionary(python_dict);
+        Ok(Self {
+            //python_dict,
+            python_dict
+        })
+    }
+
+    /// Same as `new` for a compound, e.g. a chunk of `McWorldDescriptor::tag_compounds_list`.
+    /// 
+    /// The compound is read by reference: wrapping it in a `NbtTag` would need a deep copy of the whole tree
+    /// (about 47 ms for the 992 chunks of a region, in release mode) only to be converted and dropped.
+    pub fn from_compound(tag_compound: &nbt_tag::NbtTagCompound) -> PyResult<Self> {
+        Self::from_compound_with_types(tag_compound, false)
+    }
+
+    /// Same as `from_compound`, with the values wrapped in `NbtValue` when `typed`, see `to_python_dictionary`.
+    pub fn from_compound_with_types(tag_compound: &nbt_tag::NbtTagCompound, typed: bool) -> PyResult<Self> {
+        let python_dict = Python::with_gil(|py| Self::compound_to_python_dictionary(py, tag_compound, typed))?;
+        Ok(Self { python_dict })
+    }
+
+    /* fn to_ser_python_dictionary(py_dict: Py<PyDict>) -> SerializablePyDict {
+        SerializablePyDict(py_dict)
+    } */
+
+    /// Converts a tag into a python dictionary {tag name: tag value}.
+    /// 
+    /// With `typed`, the values (compounds excepted) are wrapped in `NbtValue`, which keeps their NBT type.
+    /// 
+    /// Every failure (a tag whose content does not match its type, or a python error while filling
+    /// the dictionary) is returned as a python exception instead of panicking the interpreter.
+    /// The nested tags are converted with the GIL already held, without acquiring it again for each of them.
+    fn to_python_dictionary(py: Python, nbt_tag: &nbt_tag::NbtTag, typed: bool) -> PyResult<Py<PyDict>> {
+        let dict: Py<PyDict> = PyDict::new(py).into();
+
+        let (name, value): (&str, PyObject) = match nbt_tag.ty() {
+            // End only terminates the compounds in the files, it has no value
+            nbt_tag::NbtTagType::End => return Ok(dict),
+            nbt_tag::NbtTagType::Byte => {
+                let tag_byte = nbt_tag.try_byte()?;
+                (&tag_byte.name, tag_byte.value.into_py(py))
+            },
+            nbt_tag::NbtTagType::Short => {
+                let tag_short = nbt_tag.try_short()?;
+                (&tag_short.name, tag_short.value.into_py(py))
+            },
+            nbt_tag::NbtTagType::Int => {
+                let tag_int = nbt_tag.try_int()?;
+                (&tag_int.name, tag_int.value.into_py(py))
+            },
+            nbt_tag::NbtTagType::Long => {
+                let tag_long = nbt_tag.try_long()?;
+                (&tag_long.name, tag_long.value.into_py(py))
+            },
+            nbt_tag::NbtTagType::Float => {
+                let tag_float = nbt_tag.try_float()?;
+                (&tag_float.name, tag_float.value.into_py(py))
+            },
+            nbt_tag::NbtTagType::Double => {
+                let tag_double = nbt_tag.try_double()?;
+                (&tag_double.name, tag_double.value.into_py(py))
+            },
+            nbt_tag::NbtTagType::ByteArray => {
+                let tag_byte_array = nbt_tag.try_byte_array()?;
+                (&tag_byte_array.name, tag_byte_array.values.to_object(py))
+            },
+            nbt_tag::NbtTagType::String => {
+                let tag_string = nbt_tag.try_string()?;
+                (&tag_string.name, tag_string.value.to_object(py))
+            },
+            nbt_tag::NbtTagType::List => {
+                let tag_list = nbt_tag.try_list()?;
+                let empty_object_array: &[PyObject] = &[];
+                let py_list: &PyList = PyList::new(py, empty_object_array);
+
+                for list_element in tag_list.values.iter().filter(|list_element| !matches!(list_element, nbt_tag::NbtTag::End)) {
+                    py_list.append(Self::to_python_dictionary(py, list_element, typed)?)?;
+                }
+
+                (&tag_list.name, py_list.into_py(py))
+            },
+            nbt_tag::NbtTagType::Compound => {
+                return Self::compound_to_python_dictionary(py, nbt_tag.try_compound()?, typed);
+            },
+            nbt_tag::NbtTagType::IntArray => {
+                let tag_int_array = nbt_tag.try_int_array()?;
+                (&tag_int_array.name, tag_int_array.values.to_object(py))
+            },
+            nbt_tag::NbtTagType::LongArray => {
+                let tag_long_array = nbt_tag.try_long_array()?;
+                (&tag_long_array.name, tag_long_array.values.to_object(py))
+            }
+        };
+
+        if typed {
+            let element_type = nbt_tag.list_as_ref().map(|tag_list| format!("{:?}", tag_list.ty));
+            let typed_value = PyNbtValue { tag_type: format!("{:?}", nbt_tag.ty()), value, element_type };
+            dict.as_ref(py).set_item(name, Py::new(py, typed_value)?)?;
+        } else {
+            dict.as_ref(py).set_item(name, value)?;
+        }
+
+        Ok(dict)
+    }
+
+    /// Converts a compound into a python dictionary {compound name: {entry name: entry dictionary}}.
+    fn compound_to_python_dictionary(py: Python, tag_compound: &nbt_tag::NbtTagCompound, typed: bool) -> PyResult<Py<PyDict>> {
+        let dict: Py<PyDict> = PyDict::new(py).into();
+        let py_dict: &PyDict = PyDict::new(py);
+
+        for (key, value) in tag_compound.iter().filter(|(_, value)| !matches!(value, nbt_tag::NbtTag::End)) {
+            py_dict.set_item(key, Self::to_python_dictionary(py, value, typed)?)?;
+        }
+
+        dict.as_ref(py).set_item(&tag_compound.name, py_dict)?;
+        Ok(dict)
+    }
+}
@@ -5,44 +5,255 @@
 // - 2023-12-17
 //
 // ## File Version
-// - 1.0.0
+// - 1.0.15
 //
 // ## Changelog
 // - 1.0.0: Initial version
+// - 1.0.1: Added region coordinates parsed from the file name [mrmarkolinus:2026-10-16]
+// - 1.0.2: Added absolute chunk coordinates from the region position [mrmarkolinus:2026-10-16]
+// - 1.0.3: Added from_bytes and new_async (feature "async") [mrmarkolinus:2026-10-16]
+// - 1.0.4: Chunk parse errors are propagated with their cause [mrmarkolinus:2026-10-16]
+// - 1.0.5: Added present_chunks and read_chunk to decode a single chunk [mrmarkolinus:2026-10-16]
+// - 1.0.6: Added verify to scan the chunks for corruption, chunk bounds are checked before reading [mrmarkolinus:2026-10-16]
+// - 1.0.7: Added repair to rewrite a region without its corrupt chunks [mrmarkolinus:2026-10-16]
+// - 1.0.8: Added from_reader [mrmarkolinus:2026-10-16]
+// - 1.0.9: from_reader and open_seekable read each chunk from its sectors on demand [mrmarkolinus:2026-10-16]
+// - 1.0.10: The region file keeps the path it was read from [mrmarkolinus:2026-10-16]
+// - 1.0.11: Added write, to save edited chunks in their slots [mrmarkolinus:2026-10-16]
+// - 1.0.12: Added Compression, the zlib level of write and compact [mrmarkolinus:2026-10-16]
+// - 1.0.13: Added parse options of the chunks, to prune them while decoding [mrmarkolinus:2026-10-16]
+// - 1.0.14: Added first_compounds, decoding only the first chunks [mrmarkolinus:2026-10-16]
+// - 1.0.15: read_chunk returns the chunk as stored, added first_indexed_compounds [mrmarkolinus:2026-10-16]
 
 use crate::file_parser;
+use crate::chunk_format;
+use crate::error::RnbtError;
 use crate::nbt_tag::*;
 use crate::generic_bin::*;
 
-use std::io;
-use std::path::PathBuf;
+use std::borrow::Cow;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use flate2::write::ZlibEncoder;
+
+#[cfg(test)]
+mod tests;
 
 const HEADER_LENGTH: usize = 4096;
 const CHUNK_HEADER_LENGTH: usize = 4;
 const CHUNK_HEADER_COMPRESSION: usize = CHUNK_HEADER_LENGTH + 1;
+/// The location table and the timestamp table, the chunk data starts after them.
+const REGION_HEADERS_LENGTH: usize = 2 * HEADER_LENGTH;
+/// The number of sectors of a chunk is a single byte of the location table.
+const MAX_CHUNK_SECTORS: usize = 255;
+
+/// The zlib compression level of the chunks written by `RegionFile::write_with_compression` and `RegionFile::compact_with_compression`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    /// Level 1, for frequent saves.
+    Fast,
+    /// Level 6, as Minecraft.
+    #[default]
+    Default,
+    /// Level 9, the smallest files, for archival.
+    Best,
+}
+
+impl Compression {
+    /// Returns the zlib compression level.
+    pub fn level(&self) -> u32 {
+        match self {
+            Compression::Fast => 1,
+            Compression::Default => 6,
+            Compression::Best => 9,
+        }
+    }
+}
+
+/// A corrupt chunk found by `RegionFile::verify`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChunkIssue {
+    /// The index (x + z * 32) of the chunk in the region header.
+    pub index: usize,
+    /// The absolute (x, z) chunk coordinates, if the region coordinates are known.
+    pub chunk_coords: Option<(i32, i32)>,
+    pub kind: ChunkIssueKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChunkIssueKind {
+    /// The sectors given by the region header are not in the chunk data area of the file (offset and size in bytes).
+    SectorsOutOfBounds { offset: usize, size: usize, file_len: usize },
+    /// The length at the start of the chunk data is zero or larger than its sectors (both in bytes).
+    InvalidLength { length: usize, sectors_size: usize },
+    /// The chunk data cannot be decompressed, because of an unknown compression or a corrupt stream.
+    DecompressionFailed(String),
+    /// The decompressed chunk data is not valid NBT.
+    InvalidNbt(RnbtError),
+    /// The sectors of the chunk cannot be read from the source of the region (`RegionFile::from_reader`).
+    ReadFailed(String),
+}
+
+/// The outcome of `RegionFile::repair`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RepairReport {
+    /// The number of chunks written to the repaired region.
+    pub kept_chunks: usize,
+    /// The corrupt chunks left out, now ungenerated, with the reason.
+    pub dropped_chunks: Vec<ChunkIssue>,
+}
+
+impl fmt::Display for ChunkIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChunkIssueKind::SectorsOutOfBounds { offset, size, file_len } => write!(f, "Chunk sectors out of bounds: {} bytes at offset {}, file length {}", size, offset, file_len),
+            ChunkIssueKind::InvalidLength { length, sectors_size } => write!(f, "Invalid chunk length: {} bytes in {} bytes of sectors", length, sectors_size),
+            ChunkIssueKind::DecompressionFailed(message) => write!(f, "Chunk decompression failed: {}", message),
+            ChunkIssueKind::InvalidNbt(error) => write!(f, "Invalid chunk NBT: {}", error),
+            ChunkIssueKind::ReadFailed(message) => write!(f, "Chunk read failed: {}", message),
+        }
+    }
+}
+
+impl fmt::Display for ChunkIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.chunk_coords {
+            Some((chunk_x, chunk_z)) => write!(f, "Chunk {}, {} (index {}): {}", chunk_x, chunk_z, self.index, self.kind),
+            None => write!(f, "Chunk index {}: {}", self.index, self.kind),
+        }
+    }
+}
+
+/// Any seekable source of a region file.
+trait ReadSeek: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> ReadSeek for T {}
+
+/// Where the chunks of a region are read from.
+enum RegionSource {
+    /// The whole file, loaded in memory.
+    Memory(GenericBinFile),
+    /// A seekable source, the sectors of a chunk are read when the chunk is.
+    Reader { reader: Mutex<Box<dyn ReadSeek>>, len: usize },
+}
+
+impl RegionSource {
+    fn len(&self) -> usize {
+        match self {
+            RegionSource::Memory(bin_content) => bin_content.get_raw_data().len(),
+            RegionSource::Reader { len, .. } => *len,
+        }
+    }
+
+    /// Returns `len` bytes at `offset`, which must be inside the source.
+    fn read_at(&self, offset: usize, len: usize) -> io::Result<Cow<'_, [u8]>> {
+        match self {
+            RegionSource::Memory(bin_content) => Ok(Cow::Borrowed(&bin_content.get_raw_data()[offset..offset + len])),
+            RegionSource::Reader { reader, .. } => {
+                // a panic while reading leaves the reader usable, the position is set again at each read
+                let mut reader = reader.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                reader.seek(SeekFrom::Start(offset as u64))?;
+                let mut data = vec![0u8; len];
+                reader.read_exact(&mut data)?;
+                Ok(Cow::Owned(data))
+            },
+        }
+    }
+}
 
 pub struct RegionFile {
-    bin_content: GenericBinFile,
+    source: RegionSource,
+    /// The location and timestamp tables (the timestamps may be missing in truncated files).
+    header: Vec<u8>,
     num_chunks: usize,
     chunk_offsets: Vec<(u32, u32)>,
+    region_coords: Option<(i32, i32)>,
+    /// The path of the file, None if the region was not read from a file.
+    file_path: Option<PathBuf>,
+    /// The options parsing the chunks, see `set_parse_options`.
+    parse_options: file_parser::ParseOptions,
     //chunks_as_nbt: Vec<NbtTagCompound>,
 }
 
 impl RegionFile {
     pub fn new(file_path: PathBuf) -> io::Result<Self> {
-        let generic_bin = GenericBinFile::new(file_path, FileType::Region)?;
-        let mut region_file = RegionFile { bin_content: generic_bin, num_chunks: 0, chunk_offsets: Vec::new() };
+        let region_coords = parse_region_coords(&file_path);
+        let generic_bin = GenericBinFile::new(file_path.clone(), FileType::Region)?;
+        Self::from_source(RegionSource::Memory(generic_bin), region_coords).map(|region_file| region_file.with_file_path(file_path))
+    }
 
-        //let region_fp = FileParser::new(&file_path, ReadMode::EntireFile, FileType::Region);
-        let region_content = region_file.bin_content.get_raw_data();
+    /// Creates a RegionFile from the content of a region file already loaded in memory.
+    /// 
+    /// The region coordinates cannot be derived from a file name, so they are given by the caller (if known).
+    pub fn from_bytes(raw_data: Vec<u8>, region_coords: Option<(i32, i32)>) -> io::Result<Self> {
+        Self::from_source(RegionSource::Memory(GenericBinFile::from_bytes(raw_data)), region_coords)
+    }
 
-        let header = match Self::read_header(&region_content)
+    /// Creates a RegionFile from any seekable source, e.g. a `Cursor<Vec<u8>>` built by a test or an open file.
+    /// 
+    /// Only the header is read here: each chunk seeks to its sectors and reads them on demand, nothing else is kept
+    /// in memory. The sector offsets of the header are absolute, the source is read from its start whatever its position.
+    pub fn from_reader<R: Read + Seek + Send + 'static>(mut reader: R, region_coords: Option<(i32, i32)>) -> io::Result<Self> {
+        let len = reader.seek(SeekFrom::End(0))? as usize;
+        let source = RegionSource::Reader { reader: Mutex::new(Box::new(reader)), len };
+        Self::from_source(source, region_coords)
+    }
+
+    /// Opens a region file for random access: only the header is read, each chunk is read from the file
+    /// when it is, so that the memory used does not depend on the size of the region. See `from_reader`.
+    pub fn open_seekable(file_path: PathBuf) -> io::Result<Self> {
+        let region_coords = parse_region_coords(&file_path);
+        Self::from_reader(BufReader::new(File::open(&file_path)?), region_coords).map(|region_file| region_file.with_file_path(file_path))
+    }
+
+    /// Asynchronous version of `new`: the file is read with `tokio::fs` and the header is parsed in the blocking thread pool.
+    #[cfg(feature = "async")]
+    pub async fn new_async(file_path: PathBuf) -> io::Result<Self> {
+        let region_coords = parse_region_coords(&file_path);
+        let raw_data = tokio::fs::read(&file_path).await?;
+        
+        tokio::task::spawn_blocking(move || Self::from_bytes(raw_data, region_coords))
+            .await
+            .map_err(io::Error::other)?
+            .map(|region_file| region_file.with_file_path(file_path))
+    }
+
+    fn with_file_path(self, file_path: PathBuf) -> Self {
+        RegionFile { file_path: Some(file_path), ..self }
+    }
+
+    /// Sets the options parsing the chunks read afterwards, e.g. `keep_only` to decode only a part of each chunk.
+    /// `verify` and `repair` always parse the whole chunks.
+    pub fn set_parse_options(&mut self, parse_options: file_parser::ParseOptions) {
+        self.parse_options = parse_options;
+    }
+
+    pub fn parse_options(&self) -> &file_parser::ParseOptions {
+        &self.parse_options
+    }
+
+    /// Returns the path of the region file, None if it was created from bytes or a reader.
+    pub fn file_path(&self) -> Option<&Path> {
+        self.file_path.as_deref()
+    }
+
+    fn from_source(source: RegionSource, region_coords: Option<(i32, i32)>) -> io::Result<Self> {
+        let headers_length = source.len().min(REGION_HEADERS_LENGTH);
+        let region_headers = source.read_at(0, headers_length)?.into_owned();
+        let mut region_file = RegionFile { source, header: region_headers, num_chunks: 0, chunk_offsets: Vec::new(), region_coords, file_path: None, parse_options: file_parser::ParseOptions::default() };
+
+        //let region_fp = FileParser::new(&file_path, ReadMode::EntireFile, FileType::Region);
+        let header = match Self::read_header(&region_file.header)
         {
             Ok(h) => h,
             Err(e) => return Err(io::Error::new(io::ErrorKind::Other, e)),
         };
 
-        let offsets = Self::parse_chunk_offsets(&header);
+        let offsets = Self::parse_chunk_offsets(header);
         let num_chunks = offsets.len();
 
         region_file.chunk_offsets = offsets;
@@ -56,13 +267,52 @@ impl RegionFile {
         self.num_chunks
     }
 
+    /// Returns the (x, z) coordinates of the region, parsed from the file name `r.X.Z.mca`.
+    /// 
+    /// Returns `None` if the file does not follow the region naming convention.
+    pub fn region_coords(&self) -> Option<(i32, i32)> {
+        self.region_coords
+    }
+
+    /// Returns the absolute (x, z) chunk coordinates of the chunk stored at `index` in the region header.
+    /// 
+    /// Returns `None` if the region coordinates are unknown or the index is out of the region.
+    pub fn chunk_coords(&self, index: usize) -> Option<(i32, i32)> {
+        if index >= self.num_chunks {
+            return None;
+        }
+
+        let chunk_pos = chunk_format::get_chunk_coordinates_from_region(self.region_coords?, index);
+        Some((chunk_pos.x, chunk_pos.z))
+    }
+
     pub fn to_compounds_list(&self) -> std::io::Result<Vec<NbtTagCompound>> {
         let chunks_as_nbt = self.process_all_chunks()?;
         Ok(chunks_as_nbt)
     }
+
+    /// Same as `to_compounds_list`, decoding only the first `max_chunks` present chunks in the order of the header.
+    pub fn first_compounds(&self, max_chunks: usize) -> io::Result<Vec<NbtTagCompound>> {
+        let indexed_chunks = self.first_indexed_compounds(max_chunks)?;
+        Ok(indexed_chunks.into_iter().map(|(_, chunk_compound)| chunk_compound).collect())
+    }
+
+    /// Same as `first_compounds`, each chunk with its index in the region header (see `chunk_coords`).
+    /// The chunks are returned as stored, also when they miss their `xPos` and `zPos`.
+    pub fn first_indexed_compounds(&self, max_chunks: usize) -> io::Result<Vec<(usize, NbtTagCompound)>> {
+        let mut chunks_list = Vec::new();
+
+        for index in self.present_chunks().into_iter().take(max_chunks) {
+            if let Some(chunk_compound) = self.read_chunk(index)? {
+                chunks_list.push((index, chunk_compound));
+            }
+        }
+
+        Ok(chunks_list)
+    }
     
     
-    fn read_header(region_content: &Vec<u8>) -> Result<&[u8], &'static str> {
+    fn read_header(region_content: &[u8]) -> Result<&[u8], &'static str> {
         if region_content.len() >= HEADER_LENGTH {
             Ok(&region_content[..HEADER_LENGTH])
         } 
@@ -82,68 +332,260 @@ impl RegionFile {
             .collect()
     }
 
+    /// Returns the header indices (x + z * 32) of the chunks present in the region file.
+    pub fn present_chunks(&self) -> Vec<usize> {
+        (0..self.num_chunks)
+            .filter(|index| self.chunk_offsets[*index].0 != 0)
+            .collect()
+    }
+
+    /// Decodes the single chunk stored at `index` in the region header, without touching the other chunks.
+    /// 
+    /// Returns `Ok(None)` if the chunk has not been generated (or the index is out of the region).
+    pub fn read_chunk(&self, index: usize) -> io::Result<Option<NbtTagCompound>> {
+        if index >= self.num_chunks || self.chunk_offsets[index].0 == 0 {
+            return Ok(None); // Skip if the chunk is not present
+        }
+
+        let chunk_data = self.read_and_decompress_chunk(index)?;
+        let chunk_compound = file_parser::parse_nbt_bytes_with_options(&chunk_data, &self.parse_options)?;
+
+        Ok(Some(chunk_compound))
+    }
+
     /// Public method to process the region file.
     fn process_all_chunks(&self) -> io::Result<Vec<NbtTagCompound>> {
 
         let mut processed_chunks_list = Vec::new();
 
+        for index in self.present_chunks() {
+            if let Some(chunk_compound) = self.read_chunk(index)? {
+                processed_chunks_list.push(chunk_compound);
+            }
+        }
+
+        Ok(processed_chunks_list)
+    }
+
+    /// Checks every present chunk of the region: its sectors are inside the file, its length fits its sectors,
+    /// the data decompresses and parses as NBT. Nothing is modified.
+    /// 
+    /// Returns the issues found, one per corrupt chunk, empty if the region is sound.
+    pub fn verify(&self) -> Vec<ChunkIssue> {
+        self.present_chunks()
+            .into_iter()
+            .filter_map(|index| {
+                let kind = self.verify_chunk(index).err()?;
+                Some(ChunkIssue { index, chunk_coords: self.chunk_coords(index), kind })
+            })
+            .collect()
+    }
+
+    /// Writes the region to `out` without the chunks that fail `verify`, which the game will generate again.
+    /// The sound chunks are copied as stored, with their timestamps. `out` may be the region file itself,
+    /// since the whole region is read before writing.
+    /// 
+    /// Returns which chunks were dropped and why, each one is also logged as a warning.
+    pub fn repair(&self, out: PathBuf) -> io::Result<RepairReport> {
+        let dropped_chunks = self.verify();
+        let mut repaired_data = vec![0u8; REGION_HEADERS_LENGTH];
+        let mut kept_chunks = 0;
+
+        for index in self.present_chunks() {
+            if dropped_chunks.iter().any(|issue| issue.index == index) {
+                continue;
+            }
+
+            let (offset, size) = (self.chunk_offsets[index].0 as usize, self.chunk_offsets[index].1 as usize);
+            Self::append_chunk_sectors(&mut repaired_data, index, &self.source.read_at(offset, size)?, self.stored_timestamp(index));
+            kept_chunks += 1;
+        }
+
+        for issue in dropped_chunks.iter() {
+            log::warn!("{}: dropped {}", out.display(), issue);
+        }
+        std::fs::write(&out, repaired_data)?;
+
+        Ok(RepairReport { kept_chunks, dropped_chunks })
+    }
+
+    /// Writes the region to `out` with the given chunks (index in the region header, compound) in their slots,
+    /// zlib-compressed and with the current time as timestamp. The other chunks are copied as stored.
+    /// `out` may be the region file itself, since the whole region is read before writing.
+    /// 
+    /// Fails if a stored chunk has its sectors outside of the file, see `repair`.
+    pub fn write(&self, out: PathBuf, chunks: &[(usize, &NbtTagCompound)]) -> io::Result<()> {
+        self.write_with_compression(out, chunks, Compression::default())
+    }
+
+    /// Same as `write`, compressing the given chunks with the `compression` level.
+    pub fn write_with_compression(&self, out: PathBuf, chunks: &[(usize, &NbtTagCompound)], compression: Compression) -> io::Result<()> {
+        if let Some((index, _)) = chunks.iter().find(|(index, _)| *index >= self.num_chunks) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid chunk index {}", index)));
+        }
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs() as u32).unwrap_or(0);
+        let to_io_error = |issue: ChunkIssueKind| io::Error::new(io::ErrorKind::InvalidData, issue.to_string());
+        let mut region_data = vec![0u8; REGION_HEADERS_LENGTH];
+
         for index in 0..self.num_chunks {
-            let (offset, _) = self.chunk_offsets[index];    
-            if offset == 0 { 
-                continue; // Skip if the chunk is not present
+            if let Some((_, chunk)) = chunks.iter().rev().find(|(chunk_index, _)| *chunk_index == index) {
+                Self::append_chunk_sectors(&mut region_data, index, &Self::encode_chunk(chunk, compression)?, timestamp.to_be_bytes().as_slice());
+            }
+            else if self.chunk_offsets[index].0 != 0 {
+                let chunk_sectors = self.chunk_sectors(index).map_err(to_io_error)?;
+                Self::append_chunk_sectors(&mut region_data, index, &chunk_sectors, self.stored_timestamp(index));
             }
-            
+        }
+
+        std::fs::write(&out, region_data)
+    }
+
+    /// Writes the region to `out` with every chunk decompressed and compressed again with zlib, keeping the timestamps,
+    /// and without the unused sectors left by the chunks that grew or moved. The NBT data of the chunks is unchanged.
+    /// `out` may be the region file itself, since the whole region is read before writing.
+    /// 
+    /// Fails if a chunk cannot be decompressed, see `repair`.
+    pub fn compact(&self, out: PathBuf) -> io::Result<()> {
+        self.compact_with_compression(out, Compression::default())
+    }
+
+    /// Same as `compact`, compressing the chunks with the `compression` level.
+    pub fn compact_with_compression(&self, out: PathBuf, compression: Compression) -> io::Result<()> {
+        let mut region_data = vec![0u8; REGION_HEADERS_LENGTH];
+
+        for index in self.present_chunks() {
             let chunk_data = self.read_and_decompress_chunk(index)?;
-            let chunk_nbt = file_parser::parse_bytes(&chunk_data)
-                .map_err(|_| io::Error::new(io::ErrorKind::Other, "Parse error"))?;
+            Self::append_chunk_sectors(&mut region_data, index, &Self::compress_chunk_data(&chunk_data, compression)?, self.stored_timestamp(index));
+        }
+
+        std::fs::write(&out, region_data)
+    }
+
+    /// Compresses a chunk with zlib into its sectors: the length, the compression method, the data and the padding.
+    fn encode_chunk(chunk: &NbtTagCompound, compression: Compression) -> io::Result<Vec<u8>> {
+        let mut nbt_data = Vec::new();
+        write(&mut nbt_data, chunk);
+        Self::compress_chunk_data(&nbt_data, compression)
+    }
+
+    /// Compresses the NBT data of a chunk with zlib into its sectors, see `encode_chunk`.
+    fn compress_chunk_data(nbt_data: &[u8], compression: Compression) -> io::Result<Vec<u8>> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::new(compression.level()));
+        encoder.write_all(nbt_data)?;
+        let compressed_data = encoder.finish()?;
+
+        let mut chunk_sectors = ((compressed_data.len() + 1) as u32).to_be_bytes().to_vec();
+        chunk_sectors.push(CompressionType::Zlib as u8);
+        chunk_sectors.extend_from_slice(&compressed_data);
+        chunk_sectors.resize(chunk_sectors.len().div_ceil(HEADER_LENGTH) * HEADER_LENGTH, 0);
 
-            //TODO: remove unwrap and handle errors
-            processed_chunks_list.push(chunk_nbt.compound().unwrap());
+        // Minecraft moves the larger chunks to separate .mcc files, which are not supported
+        if chunk_sectors.len() / HEADER_LENGTH > MAX_CHUNK_SECTORS {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Chunk larger than the 255 sectors of a region slot"));
         }
 
-        Ok(processed_chunks_list)
+        Ok(chunk_sectors)
     }
 
-    /// Reads a chunk from the file based on the provided offset and size.
+    /// Returns the timestamp of the chunk at `index` in the header, empty if the timestamp table is missing.
+    fn stored_timestamp(&self, index: usize) -> &[u8] {
+        self.header.get(HEADER_LENGTH + index * 4..HEADER_LENGTH + index * 4 + 4).unwrap_or(&[])
+    }
+
+    /// Appends the sectors of the chunk at `index` to the region data, pointing its location at them.
+    fn append_chunk_sectors(region_data: &mut Vec<u8>, index: usize, chunk_sectors: &[u8], timestamp: &[u8]) {
+        let sector = region_data.len() / HEADER_LENGTH;
+        let location = index * 4;
+        region_data[location..location + 3].copy_from_slice(&(sector as u32).to_be_bytes()[1..]);
+        region_data[location + 3] = (chunk_sectors.len() / HEADER_LENGTH) as u8;
+        if timestamp.len() == 4 {
+            region_data[HEADER_LENGTH + location..HEADER_LENGTH + location + 4].copy_from_slice(timestamp);
+        }
+
+        region_data.extend_from_slice(chunk_sectors);
+    }
+
+    fn verify_chunk(&self, index: usize) -> Result<(), ChunkIssueKind> {
+        let chunk_sectors = self.chunk_sectors(index)?;
+        let (chunk_payload, chunk_compression_method) = Self::chunk_payload(&chunk_sectors)?;
+        let chunk_data = decompress(chunk_payload, chunk_compression_method)
+            .map_err(|e| ChunkIssueKind::DecompressionFailed(e.to_string()))?;
+        file_parser::parse_bytes(&chunk_data).map_err(ChunkIssueKind::InvalidNbt)?;
+
+        Ok(())
+    }
+
+    /// Returns the sectors given by the region header for the chunk stored at `index`, checking that they are inside the file.
+    fn chunk_sectors(&self, index: usize) -> Result<Cow<'_, [u8]>, ChunkIssueKind> {
+        let (offset, size) = (self.chunk_offsets[index].0 as usize, self.chunk_offsets[index].1 as usize);
+        let file_len = self.source.len();
+
+        if offset < REGION_HEADERS_LENGTH || offset + size > file_len {
+            return Err(ChunkIssueKind::SectorsOutOfBounds { offset, size, file_len });
+        }
+
+        self.source.read_at(offset, size).map_err(|e| ChunkIssueKind::ReadFailed(e.to_string()))
+    }
+
+    /// Returns the compressed data and the compression method in the sectors of a chunk,
+    /// checking that they are inside the sectors.
     /// 
     /// https://minecraft.fandom.com/wiki/Region_file_format
     /// 
-    /// A Chunk is always represented as 4096 bytes.
-    /// The first 4 bytes (big endian) represent the actual length of the chunk.
+    /// A Chunk is stored in sectors of 4096 bytes.
+    /// The first 4 bytes (big endian) represent the length of the rest of the chunk data.
     /// The fifth byte is the compression method (usually zlib)
-    /// The rest x bytes (where x is the u32 of the first 4 bytes) are the actual chunk data, which is compressed.
+    /// The rest bytes are the actual chunk data, which is compressed.
     /// 
+    fn chunk_payload(chunk_data: &[u8]) -> Result<(&[u8], u8), ChunkIssueKind> {
+        let size = chunk_data.len();
+
+        let real_chunk_len = match chunk_data.get(..CHUNK_HEADER_LENGTH) {
+            Some(bytes) => u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize,
+            None => 0,
+        };
+        // the length counts the compression method byte too
+        if real_chunk_len == 0 || CHUNK_HEADER_LENGTH + real_chunk_len > size {
+            return Err(ChunkIssueKind::InvalidLength { length: real_chunk_len, sectors_size: size });
+        }
+
+        Ok((&chunk_data[CHUNK_HEADER_COMPRESSION..CHUNK_HEADER_LENGTH + real_chunk_len], chunk_data[CHUNK_HEADER_LENGTH]))
+    }
+
+    /// Reads and decompresses the chunk stored at `index` in the region header.
     fn read_and_decompress_chunk(&self, index: usize) -> io::Result<Vec<u8>> {
-        if index < self.chunk_offsets.len() {
-            let (offset, size) = self.chunk_offsets[index];
-            let raw_data = self.bin_content.get_raw_data();
-
-            if (offset as usize) < raw_data.len() && (offset as usize) + (size as usize) <= raw_data.len() {
-                let chunk_data = &raw_data[offset as usize..(offset as usize) + (size as usize)];
-
-                let real_chunk_len_slice = &chunk_data[..CHUNK_HEADER_LENGTH];
-
-                if real_chunk_len_slice.len() == 4 {
-                    let bytes = [real_chunk_len_slice[0], real_chunk_len_slice[1], real_chunk_len_slice[2], real_chunk_len_slice[3]];
-                    
-                    let real_chunk_len = u32::from_be_bytes(bytes) as usize;
-                    let chunk_compression_method = &chunk_data[CHUNK_HEADER_LENGTH..CHUNK_HEADER_COMPRESSION];
-                    let chunk_payload = &chunk_data[CHUNK_HEADER_COMPRESSION..CHUNK_HEADER_COMPRESSION + real_chunk_len];
-
-                    //Self::decode_binary_data(chunk_payload, chunk_compression_method)
-                    self.bin_content.decode_binary_data(chunk_payload, chunk_compression_method)
-                }
-                else {
-                    Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid or Unsupported chunk header length"))
-                }
-                
-            } else {
-                Err(io::Error::new(io::ErrorKind::InvalidInput, "Chunk offset/size out of bounds"))
-            }
-        } else {
-            Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid chunk index"))
+        if index >= self.chunk_offsets.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid chunk index"));
         }
+
+        let to_io_error = |issue: ChunkIssueKind| io::Error::new(io::ErrorKind::InvalidInput, issue.to_string());
+        let chunk_sectors = self.chunk_sectors(index).map_err(to_io_error)?;
+        let (chunk_payload, chunk_compression_method) = Self::chunk_payload(&chunk_sectors).map_err(to_io_error)?;
+        decompress(chunk_payload, chunk_compression_method)
     }
     
 }
 
+/// Parses the region coordinates from a region file name.
+/// 
+/// https://minecraft.fandom.com/wiki/Region_file_format
+/// 
+/// Region files are named `r.X.Z.mca` (or `r.X.Z.mcr` for the old McRegion format), where X and Z are the
+/// region coordinates. A region contains 32x32 chunks, so the region X, Z is the chunk X, Z divided by 32 (floor).
+pub fn parse_region_coords(file_path: &Path) -> Option<(i32, i32)> {
+    let file_name = file_path.file_name()?.to_str()?;
+    let mut parts = file_name.split('.');
+
+    if parts.next()? != "r" {
+        return None;
+    }
+
+    let x = parts.next()?.parse::<i32>().ok()?;
+    let z = parts.next()?.parse::<i32>().ok()?;
+
+    match (parts.next(), parts.next()) {
+        (Some("mca"), None) | (Some("mcr"), None) => Some((x, z)),
+        _ => None,
+    }
+}
@@ -0,0 +1,309 @@
+#[cfg(test)]
+
+use super::*;
+
+#[test]
+fn test_parse_region_coords() {
+    assert_eq!(parse_region_coords(Path::new("r.0.0.mca")), Some((0, 0)));
+    assert_eq!(parse_region_coords(Path::new("r.-1.2.mca")), Some((-1, 2)));
+    assert_eq!(parse_region_coords(Path::new("world/region/r.12.-34.mca")), Some((12, -34)));
+    assert_eq!(parse_region_coords(Path::new("r.5.-6.mcr")), Some((5, -6)));
+}
+
+#[test]
+fn test_parse_region_coords_invalid() {
+    assert_eq!(parse_region_coords(Path::new("bigtest.nbt")), None);
+    assert_eq!(parse_region_coords(Path::new("r.0.mca")), None);
+    assert_eq!(parse_region_coords(Path::new("r.a.0.mca")), None);
+    assert_eq!(parse_region_coords(Path::new("x.0.0.mca")), None);
+    assert_eq!(parse_region_coords(Path::new("r.0.0.mca.bak")), None);
+    assert_eq!(parse_region_coords(Path::new("r.0.0.json")), None);
+}
+
+#[test]
+fn test_region_file_coords() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+
+    let region_file = RegionFile::new(path).unwrap();
+    assert_eq!(region_file.region_coords(), Some((-1, 0)));
+}
+
+#[test]
+fn test_region_file_chunk_coords() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+
+    let region_file = RegionFile::new(path).unwrap();
+    assert_eq!(region_file.chunk_coords(0), Some((-32, 0)));
+    assert_eq!(region_file.chunk_coords(1), Some((-31, 0)));
+    assert_eq!(region_file.chunk_coords(32), Some((-32, 1)));
+    assert_eq!(region_file.chunk_coords(1023), Some((-1, 31)));
+    assert_eq!(region_file.chunk_coords(1024), None);
+}
+
+#[test]
+fn test_region_file_read_chunk() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+
+    let region_file = RegionFile::new(path).unwrap();
+    let present_chunks = region_file.present_chunks();
+    assert_eq!(present_chunks.len(), region_file.to_compounds_list().unwrap().len());
+
+    let index = present_chunks[0];
+    let chunk = region_file.read_chunk(index).unwrap().unwrap();
+    let chunk_pos = chunk_format::get_chunk_coordinates(&chunk);
+    assert_eq!(region_file.chunk_coords(index), Some((chunk_pos.x, chunk_pos.z)));
+
+    let missing_index = (0..1024).find(|index| !present_chunks.contains(index));
+    if let Some(missing_index) = missing_index {
+        assert!(region_file.read_chunk(missing_index).unwrap().is_none());
+    }
+    assert!(region_file.read_chunk(1024).unwrap().is_none());
+}
+
+#[test]
+fn test_region_file_read_chunk_as_stored() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+
+    // the position is pruned, it is not added back from the slot
+    let mut region_file = RegionFile::new(path).unwrap();
+    region_file.set_parse_options(file_parser::ParseOptions { keep_only: vec!["DataVersion".to_string()], ..Default::default() });
+    let (index, chunk) = region_file.first_indexed_compounds(1).unwrap().remove(0);
+    assert_eq!(index, region_file.present_chunks()[0]);
+    assert_eq!(chunk.values.keys().collect::<Vec<_>>(), vec!["DataVersion"]);
+    assert!(!chunk_format::has_chunk_coordinates(&chunk));
+    assert!(region_file.chunk_coords(index).is_some());
+}
+
+/// The stored data of a chunk: length, compression method (zlib) and the compressed NBT.
+fn zlib_chunk_data(chunk: &NbtTagCompound) -> Vec<u8> {
+    use std::io::Write;
+
+    let mut nbt = Vec::new();
+    write(&mut nbt, chunk);
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(&nbt).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut chunk_data = ((compressed.len() + 1) as u32).to_be_bytes().to_vec();
+    chunk_data.push(2);
+    chunk_data.extend(compressed);
+    chunk_data
+}
+
+/// A region whose chunks are stored one after the other from the sector 2, as (index, stored data).
+fn region_bytes(chunks: &[(usize, Vec<u8>)]) -> Vec<u8> {
+    let mut region = vec![0u8; 2 * HEADER_LENGTH];
+    for (index, chunk_data) in chunks {
+        let sector = region.len() / HEADER_LENGTH;
+        let sectors_num = chunk_data.len().div_ceil(HEADER_LENGTH);
+        region[index * 4..index * 4 + 4].copy_from_slice(&[(sector >> 16) as u8, (sector >> 8) as u8, sector as u8, sectors_num as u8]);
+        region.extend(chunk_data);
+        region.resize((sector + sectors_num) * HEADER_LENGTH, 0);
+    }
+    region
+}
+
+#[test]
+fn test_region_file_verify_sound() {
+    let mut chunk = NbtTagCompound::new("");
+    chunk.values.insert("DataVersion".to_string(), NbtTag::Int(NbtTagInt::new("DataVersion".to_string(), 3465)));
+    let region = region_bytes(&[(0, zlib_chunk_data(&chunk)), (33, zlib_chunk_data(&chunk))]);
+
+    let region_file = RegionFile::from_bytes(region, Some((0, 0))).unwrap();
+    assert!(region_file.verify().is_empty());
+    assert_eq!(region_file.to_compounds_list().unwrap().len(), 2);
+}
+
+#[test]
+fn test_region_file_verify_corrupt() {
+    let chunk = NbtTagCompound::new("");
+    let mut bad_compression = zlib_chunk_data(&chunk);
+    bad_compression[4] = 9;
+    let mut bad_stream = zlib_chunk_data(&chunk);
+    bad_stream[7] ^= 0xFF;
+    let mut too_long = zlib_chunk_data(&chunk);
+    too_long[..4].copy_from_slice(&5000u32.to_be_bytes());
+    let mut not_nbt = 3u32.to_be_bytes().to_vec();
+    not_nbt.extend([0, 0xFF, 0x01]);
+
+    let mut region = region_bytes(&[(0, zlib_chunk_data(&chunk)), (1, bad_compression), (2, bad_stream), (3, too_long), (4, not_nbt)]);
+    // a header entry pointing past the end of the file
+    region[5 * 4..5 * 4 + 4].copy_from_slice(&[0, 0, 200, 1]);
+
+    let region_file = RegionFile::from_bytes(region.clone(), Some((1, -1))).unwrap();
+    let issues = region_file.verify();
+
+    assert_eq!(issues.iter().map(|issue| issue.index).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+    assert_eq!(issues[0].chunk_coords, Some((33, -32)));
+    assert!(matches!(issues[0].kind, ChunkIssueKind::DecompressionFailed(_)));
+    assert!(matches!(issues[1].kind, ChunkIssueKind::DecompressionFailed(_)));
+    assert_eq!(issues[2].kind, ChunkIssueKind::InvalidLength { length: 5000, sectors_size: 4096 });
+    assert!(matches!(issues[3].kind, ChunkIssueKind::InvalidNbt(_)));
+    assert_eq!(issues[4].kind, ChunkIssueKind::SectorsOutOfBounds { offset: 200 * 4096, size: 4096, file_len: region.len() });
+    assert_eq!(issues[4].to_string(), format!("Chunk 37, -32 (index 5): Chunk sectors out of bounds: 4096 bytes at offset 819200, file length {}", region.len()));
+
+    // reading the corrupt chunks fails without panicking
+    assert!(region_file.read_chunk(0).unwrap().is_some());
+    assert!((1..=5).all(|index| region_file.read_chunk(index).is_err()));
+
+    // same issues when the chunks are read from a seekable source
+    let reader_file = RegionFile::from_reader(std::io::Cursor::new(region), Some((1, -1))).unwrap();
+    assert_eq!(reader_file.verify(), issues);
+}
+
+#[test]
+fn test_region_file_repair() {
+    let mut chunk = NbtTagCompound::new("");
+    chunk.values.insert("DataVersion".to_string(), NbtTag::Int(NbtTagInt::new("DataVersion".to_string(), 3465)));
+    let mut bad_stream = zlib_chunk_data(&chunk);
+    bad_stream[7] ^= 0xFF;
+
+    // a big chunk spanning two sectors, after a corrupt one
+    let mut big_chunk = chunk.clone();
+    big_chunk.values.insert("Padding".to_string(), NbtTag::ByteArray(NbtTagByteArray::new("Padding".to_string(), (0..6000u32).scan(1u32, |seed, _| { *seed = seed.wrapping_mul(1103515245).wrapping_add(12345); Some((*seed >> 16) as i8) }).collect())));
+    let mut region = region_bytes(&[(0, bad_stream), (1, zlib_chunk_data(&big_chunk)), (2, zlib_chunk_data(&chunk))]);
+    for index in 0..3 {
+        region[HEADER_LENGTH + index * 4..HEADER_LENGTH + index * 4 + 4].copy_from_slice(&(1700000000 + index as u32).to_be_bytes());
+    }
+    region[3 * 4..3 * 4 + 4].copy_from_slice(&[0, 0, 200, 1]);
+
+    let out_path = std::env::temp_dir().join(format!("fastnbt_region_repair_{}.mca", std::process::id()));
+    let report = RegionFile::from_bytes(region, Some((0, 0))).unwrap().repair(out_path.clone()).unwrap();
+    assert_eq!(report.kept_chunks, 2);
+    assert_eq!(report.dropped_chunks.iter().map(|issue| issue.index).collect::<Vec<_>>(), vec![0, 3]);
+
+    let repaired = std::fs::read(&out_path).unwrap();
+    std::fs::remove_file(&out_path).unwrap();
+    assert_eq!(&repaired[HEADER_LENGTH + 4..HEADER_LENGTH + 12], &[1700000001u32.to_be_bytes(), 1700000002u32.to_be_bytes()].concat());
+    assert_eq!(&repaired[HEADER_LENGTH..HEADER_LENGTH + 4], &[0, 0, 0, 0]);
+    assert_eq!(&repaired[4..12], &[0, 0, 2, 2, 0, 0, 4, 1]);
+
+    let repaired_file = RegionFile::from_bytes(repaired, Some((0, 0))).unwrap();
+    assert!(repaired_file.verify().is_empty());
+    assert_eq!(repaired_file.present_chunks(), vec![1, 2]);
+    assert_eq!(repaired_file.read_chunk(1).unwrap().unwrap().values.get("Padding"), big_chunk.values.get("Padding"));
+}
+
+#[test]
+fn test_region_file_write() {
+    let mut chunk = NbtTagCompound::new("");
+    chunk.values.insert("DataVersion".to_string(), NbtTag::Int(NbtTagInt::new("DataVersion".to_string(), 3465)));
+    let mut region = region_bytes(&[(0, zlib_chunk_data(&chunk)), (2, zlib_chunk_data(&chunk))]);
+    region[HEADER_LENGTH..HEADER_LENGTH + 4].copy_from_slice(&1700000000u32.to_be_bytes());
+    let region_file = RegionFile::from_bytes(region.clone(), Some((0, 0))).unwrap();
+
+    // the chunk 2 is replaced, the chunk 5 is added
+    let mut edited_chunk = chunk.clone();
+    edited_chunk.values.insert("Edited".to_string(), NbtTag::Byte(NbtTagByte::new("Edited".to_string(), 1)));
+    let out_path = std::env::temp_dir().join(format!("fastnbt_region_write_{}.mca", std::process::id()));
+    region_file.write(out_path.clone(), &[(2, &edited_chunk), (5, &edited_chunk)]).unwrap();
+
+    let written = std::fs::read(&out_path).unwrap();
+    std::fs::remove_file(&out_path).unwrap();
+    assert_eq!(written.len() % HEADER_LENGTH, 0);
+    // the untouched chunk is copied with its timestamp
+    assert_eq!(&written[HEADER_LENGTH..HEADER_LENGTH + 4], &1700000000u32.to_be_bytes());
+    assert_eq!(&written[2 * HEADER_LENGTH..3 * HEADER_LENGTH], &region[2 * HEADER_LENGTH..3 * HEADER_LENGTH]);
+    assert_ne!(&written[HEADER_LENGTH + 8..HEADER_LENGTH + 12], &[0, 0, 0, 0]);
+
+    let written_file = RegionFile::from_bytes(written, Some((0, 0))).unwrap();
+    assert!(written_file.verify().is_empty());
+    assert_eq!(written_file.present_chunks(), vec![0, 2, 5]);
+    assert_eq!(written_file.read_chunk(0).unwrap().unwrap().values.get("Edited"), None);
+    assert_eq!(written_file.read_chunk(2).unwrap().unwrap().values["Edited"], edited_chunk.values["Edited"]);
+    assert_eq!(written_file.read_chunk(5).unwrap().unwrap().values["Edited"], edited_chunk.values["Edited"]);
+
+    assert!(region_file.write(out_path, &[(1024, &chunk)]).is_err());
+}
+
+#[test]
+fn test_region_file_compact() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+    let region_file = RegionFile::new(path).unwrap();
+    let chunks = region_file.to_compounds_list().unwrap();
+
+    let fast_path = std::env::temp_dir().join(format!("fastnbt_region_compact_fast_{}.mca", std::process::id()));
+    let best_path = std::env::temp_dir().join(format!("fastnbt_region_compact_best_{}.mca", std::process::id()));
+    region_file.compact_with_compression(fast_path.clone(), Compression::Fast).unwrap();
+    region_file.compact_with_compression(best_path.clone(), Compression::Best).unwrap();
+    let fast_len = std::fs::metadata(&fast_path).unwrap().len();
+    let best_len = std::fs::metadata(&best_path).unwrap().len();
+    assert!(best_len <= fast_len);
+
+    for compacted_path in [fast_path, best_path] {
+        let compacted_file = RegionFile::new(compacted_path.clone()).unwrap();
+        std::fs::remove_file(&compacted_path).unwrap();
+        assert!(compacted_file.verify().is_empty());
+        assert_eq!(compacted_file.present_chunks(), region_file.present_chunks());
+        assert_eq!(compacted_file.stored_timestamp(0), region_file.stored_timestamp(0));
+        assert_eq!(compacted_file.to_compounds_list().unwrap(), chunks);
+    }
+
+    assert_eq!(Compression::default().level(), 6);
+}
+
+#[test]
+fn test_region_file_from_reader() {
+    let mut chunk = NbtTagCompound::new("");
+    chunk.values.insert("DataVersion".to_string(), NbtTag::Int(NbtTagInt::new("DataVersion".to_string(), 3465)));
+
+    // a cursor just written is at its end: the region is still read from the start
+    let mut cursor = std::io::Cursor::new(Vec::new());
+    std::io::Write::write_all(&mut cursor, &region_bytes(&[(5, zlib_chunk_data(&chunk))])).unwrap();
+
+    let region_file = RegionFile::from_reader(cursor, Some((-1, 0))).unwrap();
+    assert_eq!(region_file.present_chunks(), vec![5]);
+    let read_chunk = region_file.read_chunk(5).unwrap().unwrap();
+    assert_eq!(read_chunk.values["DataVersion"], chunk.values["DataVersion"]);
+    assert_eq!(region_file.chunk_coords(5), Some((-27, 0)));
+
+    assert!(RegionFile::from_reader(std::io::Cursor::new(vec![0u8; 100]), None).is_err());
+}
+
+/// A reader counting the bytes read from it.
+struct CountingReader {
+    inner: std::io::Cursor<Vec<u8>>,
+    bytes_read: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl Read for CountingReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.bytes_read.fetch_add(read, std::sync::atomic::Ordering::Relaxed);
+        Ok(read)
+    }
+}
+
+impl Seek for CountingReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn test_region_file_from_reader_on_demand() {
+    let mut chunk = NbtTagCompound::new("");
+    chunk.values.insert("DataVersion".to_string(), NbtTag::Int(NbtTagInt::new("DataVersion".to_string(), 3465)));
+    let region = region_bytes(&(0..32).map(|index| (index, zlib_chunk_data(&chunk))).collect::<Vec<_>>());
+
+    let bytes_read = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let reader = CountingReader { inner: std::io::Cursor::new(region.clone()), bytes_read: bytes_read.clone() };
+    let region_file = RegionFile::from_reader(reader, Some((0, 0))).unwrap();
+    assert_eq!(bytes_read.load(std::sync::atomic::Ordering::Relaxed), REGION_HEADERS_LENGTH);
+    assert_eq!(region_file.present_chunks().len(), 32);
+
+    // only the sector of the chunk is read
+    assert!(region_file.read_chunk(17).unwrap().is_some());
+    assert_eq!(bytes_read.load(std::sync::atomic::Ordering::Relaxed), REGION_HEADERS_LENGTH + HEADER_LENGTH);
+
+    // the repair copies the sectors from the source as well
+    let out_path = std::env::temp_dir().join(format!("fastnbt_region_repair_reader_{}.mca", std::process::id()));
+    assert_eq!(region_file.repair(out_path.clone()).unwrap().kept_chunks, 32);
+    assert_eq!(std::fs::read(&out_path).unwrap(), region);
+    std::fs::remove_file(&out_path).unwrap();
+}
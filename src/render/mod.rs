@@ -0,0 +1,230 @@
+// ## Author
+// - mrmarkolinus
+//
+// ## Date
+// - 2026-10-16
+//
+// ## File Version
+// - 1.0.4
+//
+// ## Changelog
+// - 1.0.0: Initial version
+// - 1.0.1: Added custom block color palettes, unknown blocks are magenta [mrmarkolinus:2026-10-16]
+// - 1.0.2: The rendering functions take any AsRef<NbtTagCompound> [mrmarkolinus:2026-10-16]
+// - 1.0.3: The chunks are placed with ChunkPosition [mrmarkolinus:2026-10-16]
+// - 1.0.4: topdown_blocks returns None for a grid larger than chunk_format::MAX_MAP_PIXELS [mrmarkolinus:2026-10-16]
+
+//! Top-down rendering of the loaded chunks, one pixel per block column colored by the topmost non-air block,
+//! like the in-game maps.
+
+use crate::chunk_format::{self, ChunkPosition};
+use crate::nbt_tag::NbtTagCompound;
+
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod tests;
+
+/// Color of the columns without a chunk or without blocks.
+pub const EMPTY_COLUMN_COLOR: [u8; 3] = [0, 0, 0];
+/// Color of the blocks missing from the color palette, magenta to be easily spotted.
+pub const UNKNOWN_BLOCK_COLOR: [u8; 3] = [255, 0, 255];
+
+/// Built-in colors of the most common surface blocks, close to the colors of the in-game maps.
+pub const BLOCK_COLORS: &[(&str, [u8; 3])] = &[
+    ("minecraft:grass_block", [95, 159, 53]),
+    ("minecraft:short_grass", [110, 170, 60]),
+    ("minecraft:grass", [110, 170, 60]),
+    ("minecraft:tall_grass", [110, 170, 60]),
+    ("minecraft:fern", [100, 150, 60]),
+    ("minecraft:dirt", [134, 96, 67]),
+    ("minecraft:coarse_dirt", [119, 85, 59]),
+    ("minecraft:podzol", [91, 63, 24]),
+    ("minecraft:mycelium", [111, 99, 105]),
+    ("minecraft:moss_block", [89, 109, 45]),
+    ("minecraft:stone", [125, 125, 125]),
+    ("minecraft:cobblestone", [122, 122, 122]),
+    ("minecraft:andesite", [136, 136, 137]),
+    ("minecraft:granite", [149, 103, 85]),
+    ("minecraft:diorite", [188, 188, 188]),
+    ("minecraft:deepslate", [80, 80, 82]),
+    ("minecraft:bedrock", [85, 85, 85]),
+    ("minecraft:gravel", [136, 126, 126]),
+    ("minecraft:sand", [219, 207, 163]),
+    ("minecraft:sandstone", [216, 203, 155]),
+    ("minecraft:red_sand", [190, 102, 33]),
+    ("minecraft:clay", [160, 166, 179]),
+    ("minecraft:terracotta", [152, 94, 67]),
+    ("minecraft:water", [63, 118, 228]),
+    ("minecraft:lava", [207, 92, 15]),
+    ("minecraft:ice", [145, 183, 253]),
+    ("minecraft:packed_ice", [141, 180, 250]),
+    ("minecraft:snow", [249, 254, 254]),
+    ("minecraft:snow_block", [249, 254, 254]),
+    ("minecraft:oak_leaves", [60, 120, 30]),
+    ("minecraft:birch_leaves", [80, 128, 50]),
+    ("minecraft:spruce_leaves", [60, 95, 60]),
+    ("minecraft:jungle_leaves", [48, 140, 20]),
+    ("minecraft:acacia_leaves", [75, 130, 30]),
+    ("minecraft:dark_oak_leaves", [50, 100, 25]),
+    ("minecraft:oak_log", [109, 85, 50]),
+    ("minecraft:oak_planks", [162, 130, 78]),
+    ("minecraft:netherrack", [97, 38, 38]),
+    ("minecraft:end_stone", [219, 222, 158]),
+    ("minecraft:coal_ore", [70, 70, 70]),
+    ("minecraft:iron_ore", [216, 175, 147]),
+    ("minecraft:copper_ore", [124, 66, 44]),
+    ("minecraft:gold_ore", [252, 238, 75]),
+    ("minecraft:redstone_ore", [170, 0, 0]),
+    ("minecraft:lapis_ore", [31, 67, 140]),
+    ("minecraft:diamond_ore", [93, 236, 245]),
+    ("minecraft:emerald_ore", [23, 221, 98]),
+];
+
+/// The topmost non-air block of each column of the loaded chunks, see `topdown_blocks`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TopdownBlocks {
+    /// The world X coordinate of the leftmost column.
+    pub min_block_x: i32,
+    /// The world Z coordinate of the top row.
+    pub min_block_z: i32,
+    pub width: u32,
+    pub height: u32,
+    /// The block names row by row (Z), left to right (X). `None` for the columns without a chunk or without blocks.
+    pub blocks: Vec<Option<String>>,
+}
+
+/// Returns true for the air blocks, which are transparent in a top-down view.
+pub fn is_air(block_name: &str) -> bool {
+    matches!(block_name, "minecraft:air" | "minecraft:cave_air" | "minecraft:void_air")
+}
+
+/// Returns the built-in color of a block (`BLOCK_COLORS`), `UNKNOWN_BLOCK_COLOR` if the block is not listed.
+pub fn default_block_color(block_name: &str) -> [u8; 3] {
+    BLOCK_COLORS.iter()
+        .find(|(name, _)| *name == block_name)
+        .map(|(_, color)| *color)
+        .unwrap_or(UNKNOWN_BLOCK_COLOR)
+}
+
+/// Returns the built-in colors (`BLOCK_COLORS`) as a palette, the starting point to theme a map:
+/// insert or replace the colors of some blocks and render with `render_topdown_with_palette`.
+pub fn default_block_palette() -> HashMap<String, [u8; 3]> {
+    BLOCK_COLORS.iter()
+        .map(|(name, color)| (name.to_string(), *color))
+        .collect()
+}
+
+/// Returns the color of a block in a palette keyed by full resource location (e.g. `minecraft:stone`),
+/// `UNKNOWN_BLOCK_COLOR` if the block is not in the palette.
+pub fn palette_block_color(palette: &HashMap<String, [u8; 3]>, block_name: &str) -> [u8; 3] {
+    palette.get(block_name).copied().unwrap_or(UNKNOWN_BLOCK_COLOR)
+}
+
+/// Finds the topmost non-air block of each column of the given chunks, placed by their world coordinates
+/// on a grid covering all of them.
+///
+/// Returns `None` if there are no chunks, or if the grid would have more than `chunk_format::MAX_MAP_PIXELS` columns.
+pub fn topdown_blocks<C: ChunkPosition>(tag_compounds_list: &[C]) -> Option<TopdownBlocks> {
+    let min_chunk_x = tag_compounds_list.iter().map(|chunk| chunk.chunk_coordinates().x).min()?;
+    let max_chunk_x = tag_compounds_list.iter().map(|chunk| chunk.chunk_coordinates().x).max()?;
+    let min_chunk_z = tag_compounds_list.iter().map(|chunk| chunk.chunk_coordinates().z).min()?;
+    let max_chunk_z = tag_compounds_list.iter().map(|chunk| chunk.chunk_coordinates().z).max()?;
+
+    let (width, height) = chunk_format::map_size((min_chunk_x, min_chunk_z), (max_chunk_x, max_chunk_z))?;
+    let mut blocks = vec![None; width * height];
+
+    for chunk in tag_compounds_list.iter() {
+        let chunk_pos = chunk.chunk_coordinates();
+        let origin_x = ((chunk_pos.x - min_chunk_x) * 16) as usize;
+        let origin_z = ((chunk_pos.z - min_chunk_z) * 16) as usize;
+
+        for (column_index, block_name) in chunk_top_blocks(chunk.as_ref()).into_iter().enumerate() {
+            if block_name.is_some() {
+                blocks[(origin_z + column_index / 16) * width + origin_x + column_index % 16] = block_name;
+            }
+        }
+    }
+
+    Some(TopdownBlocks {
+        min_block_x: min_chunk_x * 16,
+        min_block_z: min_chunk_z * 16,
+        width: width as u32,
+        height: height as u32,
+        blocks,
+    })
+}
+
+/// Returns the topmost non-air block of each of the 256 columns of a chunk, in ZX order.
+///
+/// The sections are scanned from the highest, so that the lower sections are decoded only for the columns still empty.
+pub fn chunk_top_blocks(chunk_compound: &NbtTagCompound) -> Vec<Option<String>> {
+    let mut top_blocks = vec![None; chunk_format::CHUNK_COLUMNS_NUM];
+    let Some(sections_list) = chunk_format::get_sections_list(chunk_compound) else {
+        return top_blocks;
+    };
+    let data_version = chunk_format::get_data_version(chunk_compound).unwrap_or(chunk_format::BLOCK_STATES_DATA_VERSION);
+
+    let mut sections: Vec<_> = sections_list.values.iter()
+        .filter_map(|section| Some((chunk_format::get_section_y(section)?, section)))
+        .collect();
+    sections.sort_by_key(|(y, _)| std::cmp::Reverse(*y));
+
+    let mut empty_columns = chunk_format::CHUNK_COLUMNS_NUM;
+    for (_, section) in sections {
+        if empty_columns == 0 {
+            break;
+        }
+        let Some(block_states_tag) = chunk_format::find_block_states_in_section(section) else {
+            continue;
+        };
+        let (Some(palette_list), data_array) = chunk_format::find_palette_in_block_states(block_states_tag) else {
+            continue;
+        };
+
+        let palette_names: Vec<Option<String>> = palette_list.values.iter()
+            .map(|block_tag| block_tag.compound_as_ref()
+                .and_then(|block| block.values.get("Name"))
+                .and_then(|name| String::try_from(name).ok())
+                .filter(|name| !is_air(name)))
+            .collect();
+        if palette_names.iter().all(|name| name.is_none()) {
+            continue;
+        }
+
+        let palette_ids = chunk_format::unpack_block_states(data_array.map(|data| data.as_slice()).unwrap_or(&[]), palette_names.len(), data_version);
+        for (column_index, top_block) in top_blocks.iter_mut().enumerate().filter(|(_, top_block)| top_block.is_none()) {
+            // the blocks are in YZX order, the column is the ZX part of the index
+            let column_top = (0..16).rev()
+                .filter_map(|y| palette_names.get(palette_ids[y * chunk_format::CHUNK_COLUMNS_NUM + column_index] as usize))
+                .find_map(|name| name.clone());
+            if column_top.is_some() {
+                *top_block = column_top;
+                empty_columns -= 1;
+            }
+        }
+    }
+
+    top_blocks
+}
+
+/// Renders the given chunks seen from above, coloring each column with `block_color` of its topmost non-air block.
+/// The columns without a chunk or without blocks are `EMPTY_COLUMN_COLOR`. Requires the "image" feature.
+///
+/// Returns `None` if there are no chunks or they are too far apart, see `topdown_blocks`.
+#[cfg(feature = "image")]
+pub fn render_topdown<C: ChunkPosition, F: Fn(&str) -> [u8; 3]>(tag_compounds_list: &[C], block_color: F) -> Option<image::RgbImage> {
+    let topdown = topdown_blocks(tag_compounds_list)?;
+    let pixels = topdown.blocks.iter()
+        .flat_map(|block_name| block_name.as_deref().map(&block_color).unwrap_or(EMPTY_COLUMN_COLOR))
+        .collect();
+
+    image::RgbImage::from_raw(topdown.width, topdown.height, pixels)
+}
+
+/// Same as `render_topdown`, with the colors of a user palette (see `default_block_palette`).
+/// The blocks missing from the palette are `UNKNOWN_BLOCK_COLOR`. Requires the "image" feature.
+#[cfg(feature = "image")]
+pub fn render_topdown_with_palette<C: ChunkPosition>(tag_compounds_list: &[C], palette: &HashMap<String, [u8; 3]>) -> Option<image::RgbImage> {
+    render_topdown(tag_compounds_list, |block_name| palette_block_color(palette, block_name))
+}
@@ -0,0 +1,127 @@
+#[cfg(test)]
+
+use super::*;
+use crate::nbt_tag::*;
+
+/// A section with the given palette, every block being `block_at(x, y, z)` (a palette index).
+fn section(y: i8, palette: &[&str], block_at: impl Fn(usize, usize, usize) -> u16) -> NbtTag {
+    let palette_tags = palette.iter().map(|name| {
+        let mut entry = NbtTagCompound::new("");
+        entry.values.insert("Name".to_string(), NbtTag::String(NbtTagString::new("Name".to_string(), name.to_string())));
+        NbtTag::Compound(entry)
+    }).collect();
+    let palette_ids: Vec<u16> = (0..chunk_format::SECTION_BLOCKS_NUM)
+        .map(|block_index| block_at(block_index % 16, block_index / 256, (block_index / 16) % 16))
+        .collect();
+
+    let mut block_states = NbtTagCompound::new("block_states");
+    block_states.values.insert("palette".to_string(), NbtTag::List(NbtTagList::new("palette".to_string(), NbtTagType::Compound, palette_tags)));
+    let data = chunk_format::pack_block_states(&palette_ids, palette.len(), chunk_format::BLOCK_STATES_DATA_VERSION);
+    if !data.is_empty() {
+        block_states.values.insert("data".to_string(), NbtTag::LongArray(NbtTagLongArray::new("data".to_string(), data)));
+    }
+
+    let mut section = NbtTagCompound::new("");
+    section.values.insert("Y".to_string(), NbtTag::Byte(NbtTagByte::new("Y".to_string(), y)));
+    section.values.insert("block_states".to_string(), NbtTag::Compound(block_states));
+    NbtTag::Compound(section)
+}
+
+fn chunk(chunk_x: i32, chunk_z: i32, sections: Vec<NbtTag>) -> NbtTagCompound {
+    let mut chunk = NbtTagCompound::new("");
+    for (name, value) in [("xPos", chunk_x), ("zPos", chunk_z), ("DataVersion", 3465)] {
+        chunk.values.insert(name.to_string(), NbtTag::Int(NbtTagInt::new(name.to_string(), value)));
+    }
+    chunk.values.insert("sections".to_string(), NbtTag::List(NbtTagList::new("sections".to_string(), NbtTagType::Compound, sections)));
+    chunk
+}
+
+/// Stone up to y = 3 in the section 0, a grass block on top of the column x = 5, z = 7,
+/// water at y = 17 (section 1) on the column x = 0, z = 0, an air section above.
+fn sample_chunk(chunk_x: i32, chunk_z: i32) -> NbtTagCompound {
+    chunk(chunk_x, chunk_z, vec![
+        section(0, &["minecraft:air", "minecraft:stone", "minecraft:grass_block"], |x, y, z| match y {
+            0..=3 => 1,
+            4 if x == 5 && z == 7 => 2,
+            _ => 0,
+        }),
+        section(2, &["minecraft:air"], |_, _, _| 0),
+        section(1, &["minecraft:cave_air", "minecraft:water"], |x, y, z| if x == 0 && z == 0 && y == 1 { 1 } else { 0 }),
+    ])
+}
+
+#[test]
+fn test_chunk_top_blocks() {
+    let top_blocks = chunk_top_blocks(&sample_chunk(0, 0));
+
+    assert_eq!(top_blocks.len(), 256);
+    assert_eq!(top_blocks[0].as_deref(), Some("minecraft:water"));
+    assert_eq!(top_blocks[7 * 16 + 5].as_deref(), Some("minecraft:grass_block"));
+    assert_eq!(top_blocks[1].as_deref(), Some("minecraft:stone"));
+    assert!(top_blocks.iter().all(|block| block.is_some()));
+
+    // a chunk with only air has no top blocks
+    let air_chunk = chunk(0, 0, vec![section(0, &["minecraft:air"], |_, _, _| 0)]);
+    assert!(chunk_top_blocks(&air_chunk).iter().all(|block| block.is_none()));
+}
+
+#[test]
+fn test_topdown_blocks_stitching() {
+    let topdown = topdown_blocks(&[sample_chunk(-1, 0), sample_chunk(0, 1)]).unwrap();
+
+    assert_eq!((topdown.min_block_x, topdown.min_block_z), (-16, 0));
+    assert_eq!((topdown.width, topdown.height), (32, 32));
+    let block = |x: usize, z: usize| topdown.blocks[z * 32 + x].as_deref();
+    assert_eq!(block(0, 0), Some("minecraft:water"));
+    assert_eq!(block(16 + 5, 16 + 7), Some("minecraft:grass_block"));
+    // no chunk at (0, 0) and (-1, 1)
+    assert_eq!(block(20, 3), None);
+    assert_eq!(block(3, 20), None);
+
+    assert!(topdown_blocks::<NbtTagCompound>(&[]).is_none());
+    assert!(topdown_blocks(&[sample_chunk(0, 0), sample_chunk(1000, 1000)]).is_none());
+}
+
+#[test]
+fn test_default_block_color() {
+    assert_eq!(default_block_color("minecraft:water"), [63, 118, 228]);
+    assert_eq!(default_block_color("mymod:custom_block"), UNKNOWN_BLOCK_COLOR);
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn test_render_topdown() {
+    let image = render_topdown(&[sample_chunk(0, 0)], default_block_color).unwrap();
+    assert_eq!(image.dimensions(), (16, 16));
+    assert_eq!(image.get_pixel(0, 0).0, default_block_color("minecraft:water"));
+    assert_eq!(image.get_pixel(5, 7).0, default_block_color("minecraft:grass_block"));
+
+    // the colors can be overridden
+    let ores = render_topdown(&[sample_chunk(0, 0)], |name| if name == "minecraft:water" { [255, 0, 0] } else { [0, 0, 0] }).unwrap();
+    assert_eq!(ores.get_pixel(0, 0).0, [255, 0, 0]);
+    assert_eq!(ores.get_pixel(1, 0).0, [0, 0, 0]);
+}
+
+#[test]
+fn test_block_palette() {
+    let mut palette = default_block_palette();
+    assert_eq!(palette.len(), BLOCK_COLORS.len());
+    assert_eq!(palette_block_color(&palette, "minecraft:stone"), default_block_color("minecraft:stone"));
+
+    palette.insert("minecraft:stone".to_string(), [1, 2, 3]);
+    assert_eq!(palette_block_color(&palette, "minecraft:stone"), [1, 2, 3]);
+    assert_eq!(palette_block_color(&palette, "mymod:custom_block"), [255, 0, 255]);
+    assert_eq!(palette_block_color(&HashMap::new(), "minecraft:stone"), UNKNOWN_BLOCK_COLOR);
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn test_render_topdown_with_palette() {
+    let palette = HashMap::from([("minecraft:grass_block".to_string(), [0, 200, 0])]);
+    let image = render_topdown_with_palette(&[sample_chunk(0, 0)], &palette).unwrap();
+
+    assert_eq!(image.get_pixel(5, 7).0, [0, 200, 0]);
+    // stone and water are not in the palette
+    assert_eq!(image.get_pixel(1, 0).0, UNKNOWN_BLOCK_COLOR);
+    assert_eq!(image.get_pixel(0, 0).0, UNKNOWN_BLOCK_COLOR);
+}
@@ -0,0 +1,333 @@
+// ## Author
+// - mrmarkolinus
+//
+// ## Date
+// - 2026-10-16
+//
+// ## File Version
+// - 1.0.4
+//
+// ## Changelog
+// - 1.0.0: Initial version
+// - 1.0.1: Added MapItem::to_rgba, the raw RGBA buffer of a map [mrmarkolinus:2026-10-16]
+// - 1.0.2: Added read_command_storage, the storages of the /data command [mrmarkolinus:2026-10-16]
+// - 1.0.3: Added read_next_map_id [mrmarkolinus:2026-10-16]
+// - 1.0.4: read_next_map_id returns an InvalidData error instead of overflowing after i32::MAX [mrmarkolinus:2026-10-16]
+
+//! The saved data of the `data` folder of a world: the scoreboard, the maps, the map id counter and the command storage.
+//!
+//! https://minecraft.wiki/w/Scoreboard#NBT_format, https://minecraft.wiki/w/Map_item_format,
+//! https://minecraft.wiki/w/Command_storage_format
+//!
+//! Each file is a gzip compressed NBT file, whose root compound holds a `data` compound with the content.
+
+use crate::generic_bin::{FileType, GenericBinFile};
+use crate::nbt_tag::{NbtTag, NbtTagCompound};
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+pub const DATA_FOLDER_NAME: &str = "data";
+pub const SCOREBOARD_FILE_NAME: &str = "scoreboard.dat";
+pub const IDCOUNTS_FILE_NAME: &str = "idcounts.dat";
+/// The prefix of the command storage files, followed by the namespace of the storages (`command_storage_<namespace>.dat`).
+pub const COMMAND_STORAGE_FILE_PREFIX: &str = "command_storage_";
+
+/// The width and the height of a map, in pixels.
+pub const MAP_SIZE: usize = 128;
+
+/// The colors of the map color ids, from `colors` / 4 (the shade is `colors` % 4), in Java Edition 1.20.
+/// The color id 0 is transparent.
+pub const MAP_BASE_COLORS: [[u8; 3]; 62] = [
+    [0, 0, 0], [127, 178, 56], [247, 233, 163], [199, 199, 199], [255, 0, 0], [160, 160, 255], [167, 167, 167], [0, 124, 0],
+    [255, 255, 255], [164, 168, 184], [151, 109, 77], [112, 112, 112], [64, 64, 255], [143, 119, 72], [255, 252, 245], [216, 127, 51],
+    [178, 76, 216], [102, 153, 216], [229, 229, 51], [127, 204, 25], [242, 127, 165], [76, 76, 76], [153, 153, 153], [76, 127, 153],
+    [127, 63, 178], [51, 76, 178], [102, 76, 51], [102, 127, 51], [153, 51, 51], [25, 25, 25], [250, 238, 77], [92, 219, 213],
+    [74, 128, 255], [0, 217, 58], [129, 86, 49], [112, 2, 0], [209, 177, 161], [159, 82, 36], [149, 87, 108], [112, 108, 138],
+    [186, 133, 36], [103, 117, 53], [160, 77, 78], [57, 41, 35], [135, 107, 98], [87, 92, 92], [122, 73, 88], [76, 62, 92],
+    [76, 50, 35], [76, 82, 42], [142, 60, 46], [37, 22, 16], [189, 48, 49], [148, 63, 97], [92, 25, 29], [22, 126, 134],
+    [58, 142, 140], [86, 44, 62], [20, 180, 133], [100, 100, 100], [216, 175, 147], [127, 167, 150],
+];
+
+/// The brightness of the 4 shades of a map color, out of 255.
+const MAP_SHADE_MULTIPLIERS: [u32; 4] = [180, 220, 255, 135];
+
+/// A scoreboard objective.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Objective {
+    pub name: String,
+    /// The criterion updating the scores, e.g. `dummy` or `minecraft.mined:minecraft.stone`.
+    pub criteria: String,
+    /// The name shown in game, a JSON text component.
+    pub display_name: String,
+}
+
+/// The score of an entity or a player (by name) for an objective.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Score {
+    /// The player name, or the UUID of the entity.
+    pub owner: String,
+    pub objective: String,
+    pub score: i32,
+}
+
+/// The content of `scoreboard.dat`.
+#[derive(Clone, Debug, Default)]
+pub struct Scoreboard {
+    /// The `data` compound of `scoreboard.dat`, with the teams and the display slots too.
+    pub data: NbtTagCompound,
+}
+
+impl Scoreboard {
+    /// Reads `data/scoreboard.dat` of a world folder.
+    pub fn read(world_path: &Path) -> io::Result<Self> {
+        Self::from_root(read_saved_data(&world_path.join(DATA_FOLDER_NAME).join(SCOREBOARD_FILE_NAME))?)
+    }
+
+    /// Builds the scoreboard from the root compound of `scoreboard.dat`.
+    pub fn from_root(root: NbtTagCompound) -> io::Result<Self> {
+        Ok(Self { data: take_data_compound(root, SCOREBOARD_FILE_NAME)? })
+    }
+
+    pub fn objectives(&self) -> Vec<Objective> {
+        compounds_of(&self.data, "Objectives")
+            .map(|objective| Objective {
+                name: string_of(objective, "Name").unwrap_or_default(),
+                criteria: string_of(objective, "CriteriaName").unwrap_or_default(),
+                display_name: string_of(objective, "DisplayName").unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// Returns all the scores, of all the objectives.
+    pub fn scores(&self) -> Vec<Score> {
+        compounds_of(&self.data, "PlayerScores")
+            .map(|score| Score {
+                owner: string_of(score, "Name").unwrap_or_default(),
+                objective: string_of(score, "Objective").unwrap_or_default(),
+                score: score.values.get("Score").and_then(|tag| i32::try_from(tag).ok()).unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// Returns the scores of the objective `objective_name`.
+    pub fn objective_scores(&self, objective_name: &str) -> Vec<Score> {
+        self.scores().into_iter().filter(|score| score.objective == objective_name).collect()
+    }
+}
+
+/// A map item (`data/map_<id>.dat`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MapItem {
+    /// The map id, from the file name.
+    pub id: i32,
+    /// The zoom level, from 0 (1 block per pixel) to 4 (16 x 16 blocks per pixel).
+    pub scale: i8,
+    /// The dimension resource location, e.g. `minecraft:overworld` (converted from the numeric ids before 1.16).
+    pub dimension: String,
+    /// The block coordinates of the center of the map.
+    pub x_center: i32,
+    pub z_center: i32,
+    /// The map is locked in a cartography table.
+    pub locked: bool,
+    /// The color id of each pixel, row by row (`MAP_SIZE` x `MAP_SIZE`), see `pixels`.
+    pub colors: Vec<u8>,
+}
+
+impl MapItem {
+    /// Builds the map `id` from the root compound of its file.
+    pub fn from_root(id: i32, root: NbtTagCompound) -> io::Result<Self> {
+        let data = take_data_compound(root, "map")?;
+        let int = |name: &str| data.values.get(name).and_then(|tag| i32::try_from(tag).ok());
+
+        let dimension = match data.values.get("dimension") {
+            Some(NbtTag::String(dimension)) => dimension.value.clone(),
+            Some(tag) => match i32::try_from(tag).ok() {
+                Some(-1) => "minecraft:the_nether".to_string(),
+                Some(1) => "minecraft:the_end".to_string(),
+                _ => "minecraft:overworld".to_string(),
+            },
+            None => String::new(),
+        };
+        let colors = match data.values.get("colors") {
+            Some(NbtTag::ByteArray(colors)) => colors.values.iter().map(|color| *color as u8).collect(),
+            _ => Vec::new(),
+        };
+
+        Ok(Self {
+            id,
+            scale: data.values.get("scale").and_then(|tag| i8::try_from(tag).ok()).unwrap_or_default(),
+            dimension,
+            x_center: int("xCenter").unwrap_or_default(),
+            z_center: int("zCenter").unwrap_or_default(),
+            locked: data.values.get("locked").and_then(|tag| i8::try_from(tag).ok()).is_some_and(|locked| locked != 0),
+            colors,
+        })
+    }
+
+    /// Returns the RGBA color of each pixel, row by row. Unknown color ids and the missing pixels are transparent.
+    pub fn pixels(&self) -> Vec<[u8; 4]> {
+        (0..MAP_SIZE * MAP_SIZE)
+            .map(|pixel_index| map_color(self.colors.get(pixel_index).copied().unwrap_or(0)))
+            .collect()
+    }
+
+    /// Returns the RGBA bytes of the map, 4 per pixel, row by row (`MAP_SIZE` x `MAP_SIZE` x 4 bytes),
+    /// ready for any image encoder. See `to_image` with the "image" feature.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        self.pixels().into_iter().flatten().collect()
+    }
+
+    /// Returns the map as an image of `MAP_SIZE` x `MAP_SIZE` pixels. Requires the "image" feature.
+    #[cfg(feature = "image")]
+    pub fn to_image(&self) -> image::RgbaImage {
+        image::RgbaImage::from_raw(MAP_SIZE as u32, MAP_SIZE as u32, self.to_rgba()).expect("the pixels fill the map")
+    }
+}
+
+/// Returns the RGBA color of a map color id (base color * 4 + shade), transparent for 0 to 3 and the unknown ids.
+pub fn map_color(color_id: u8) -> [u8; 4] {
+    let base_color = (color_id / 4) as usize;
+    match MAP_BASE_COLORS.get(base_color) {
+        Some(rgb) if base_color != 0 => {
+            let shade = |channel: u8| (channel as u32 * MAP_SHADE_MULTIPLIERS[(color_id % 4) as usize] / 255) as u8;
+            [shade(rgb[0]), shade(rgb[1]), shade(rgb[2]), 255]
+        },
+        _ => [0, 0, 0, 0],
+    }
+}
+
+/// Reads all the maps of a world folder, sorted by id. A world without `data` folder has no maps.
+/// 
+/// An unreadable map file is skipped with a warning, so that a broken map does not hide the others.
+pub fn read_maps(world_path: &Path) -> io::Result<Vec<MapItem>> {
+    let data_path = world_path.join(DATA_FOLDER_NAME);
+    if !data_path.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut maps = Vec::new();
+    for entry in fs::read_dir(&data_path)? {
+        let map_path = entry?.path();
+        let Some(id) = map_id(&map_path) else { continue };
+
+        match read_saved_data(&map_path).and_then(|root| MapItem::from_root(id, root)) {
+            Ok(map) => maps.push(map),
+            Err(e) => log::warn!("{}: {}", map_path.display(), e),
+        }
+    }
+
+    maps.sort_by_key(|map| map.id);
+    Ok(maps)
+}
+
+/// Returns the id of the last map created in a world (`data/idcounts.dat`), `None` if no map was ever created.
+pub fn read_last_map_id(world_path: &Path) -> io::Result<Option<i32>> {
+    let idcounts_path = world_path.join(DATA_FOLDER_NAME).join(IDCOUNTS_FILE_NAME);
+    if !idcounts_path.is_file() {
+        return Ok(None);
+    }
+
+    let data = take_data_compound(read_saved_data(&idcounts_path)?, IDCOUNTS_FILE_NAME)?;
+    Ok(data.values.get("map").and_then(|tag| i32::try_from(tag).ok()))
+}
+
+/// Returns the id of the next map of a world (`data/idcounts.dat`): the last map id plus one, 0 if the file 
+/// has no map counter yet. `None` if the file is absent.
+/// 
+/// Returns an `InvalidData` error if the last map id is `i32::MAX`, no map can follow it.
+pub fn read_next_map_id(world_path: &Path) -> io::Result<Option<i32>> {
+    let idcounts_path = world_path.join(DATA_FOLDER_NAME).join(IDCOUNTS_FILE_NAME);
+    if !idcounts_path.is_file() {
+        return Ok(None);
+    }
+
+    match read_last_map_id(world_path)? {
+        None => Ok(Some(0)),
+        Some(last_map_id) => last_map_id.checked_add(1).map(Some)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("the last map id {} has no next id", last_map_id))),
+    }
+}
+
+/// Reads the storages of the `/data` command (`data/command_storage_<namespace>.dat`) of a world folder, 
+/// `None` if the world has none.
+/// 
+/// The storages of all the namespaces are returned in a single compound, keyed by their full id 
+/// as in `/data get storage <namespace>:<path>`. An unreadable file is skipped with a warning.
+pub fn read_command_storage(world_path: &Path) -> io::Result<Option<NbtTagCompound>> {
+    let data_path = world_path.join(DATA_FOLDER_NAME);
+    if !data_path.is_dir() {
+        return Ok(None);
+    }
+
+    let mut storage_paths: Vec<(String, PathBuf)> = fs::read_dir(&data_path)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| Some((command_storage_namespace(&entry.path())?.to_string(), entry.path())))
+        .collect();
+    if storage_paths.is_empty() {
+        return Ok(None);
+    }
+    storage_paths.sort();
+
+    let mut command_storage = NbtTagCompound::new("");
+    for (namespace, storage_path) in storage_paths {
+        let contents = read_saved_data(&storage_path)
+            .and_then(|root| take_data_compound(root, &format!("{}{}.dat", COMMAND_STORAGE_FILE_PREFIX, namespace)));
+        match contents.map(|mut data| data.values.remove("contents")) {
+            Ok(Some(NbtTag::Compound(contents))) => {
+                for (path, mut storage) in contents.values {
+                    let id = format!("{}:{}", namespace, path);
+                    storage.set_name(&id);
+                    command_storage.values.insert(id, storage);
+                }
+            },
+            Ok(_) => log::warn!("{}: no contents compound", storage_path.display()),
+            Err(e) => log::warn!("{}: {}", storage_path.display(), e),
+        }
+    }
+
+    Ok(Some(command_storage))
+}
+
+/// Returns the namespace of a command storage file from its name (`command_storage_<namespace>.dat`), `None` for the other files.
+fn command_storage_namespace(file_path: &Path) -> Option<&str> {
+    file_path.file_name()?.to_str()?
+        .strip_prefix(COMMAND_STORAGE_FILE_PREFIX)?
+        .strip_suffix(".dat")
+        .filter(|namespace| !namespace.is_empty())
+}
+
+/// Returns the id of a map file from its name (`map_<id>.dat`), `None` for the other files.
+fn map_id(file_path: &Path) -> Option<i32> {
+    file_path.file_name()?.to_str()?
+        .strip_prefix("map_")?
+        .strip_suffix(".dat")?
+        .parse().ok()
+}
+
+fn read_saved_data(file_path: &Path) -> io::Result<NbtTagCompound> {
+    GenericBinFile::new(PathBuf::from(file_path), FileType::Nbt)?.to_tag_compound()
+}
+
+fn take_data_compound(mut root: NbtTagCompound, file_name: &str) -> io::Result<NbtTagCompound> {
+    match root.values.remove("data") {
+        Some(NbtTag::Compound(data)) => Ok(data),
+        _ => Err(io::Error::new(io::ErrorKind::InvalidData, format!("{} has no data compound", file_name))),
+    }
+}
+
+fn compounds_of<'a>(compound: &'a NbtTagCompound, list_name: &str) -> impl Iterator<Item = &'a NbtTagCompound> {
+    compound.values.get(list_name)
+        .and_then(|tag| tag.list_as_ref())
+        .into_iter()
+        .flat_map(|list| list.iter())
+        .filter_map(|tag| tag.compound_as_ref())
+}
+
+fn string_of(compound: &NbtTagCompound, name: &str) -> Option<String> {
+    compound.values.get(name).and_then(|tag| <&str>::try_from(tag).ok()).map(str::to_string)
+}
@@ -0,0 +1,129 @@
+#[cfg(test)]
+
+use super::*;
+use crate::nbt_tag::*;
+
+fn string_tag(name: &str, value: &str) -> NbtTag {
+    NbtTag::String(NbtTagString::new(name.to_string(), value.to_string()))
+}
+
+fn with_data(data: NbtTagCompound) -> NbtTagCompound {
+    let mut root = NbtTagCompound::new("");
+    root.values.insert("data".to_string(), NbtTag::Compound(data));
+    root
+}
+
+fn sample_scoreboard() -> NbtTagCompound {
+    let mut objective = NbtTagCompound::new("");
+    objective.values.insert("Name".to_string(), string_tag("Name", "deaths"));
+    objective.values.insert("CriteriaName".to_string(), string_tag("CriteriaName", "deathCount"));
+    objective.values.insert("DisplayName".to_string(), string_tag("DisplayName", "{\"text\":\"Deaths\"}"));
+
+    let score = |owner: &str, objective: &str, value: i32| {
+        let mut score = NbtTagCompound::new("");
+        score.values.insert("Name".to_string(), string_tag("Name", owner));
+        score.values.insert("Objective".to_string(), string_tag("Objective", objective));
+        score.values.insert("Score".to_string(), NbtTag::Int(NbtTagInt::new("Score".to_string(), value)));
+        NbtTag::Compound(score)
+    };
+
+    let mut data = NbtTagCompound::new("data");
+    data.values.insert("Objectives".to_string(), NbtTag::List(NbtTagList::new("Objectives".to_string(), NbtTagType::Compound, vec![NbtTag::Compound(objective)])));
+    data.values.insert("PlayerScores".to_string(), NbtTag::List(NbtTagList::new("PlayerScores".to_string(), NbtTagType::Compound, 
+        vec![score("Steve", "deaths", 3), score("Alex", "kills", 7), score("Alex", "deaths", 1)])));
+    with_data(data)
+}
+
+#[test]
+fn test_scoreboard() {
+    let scoreboard = Scoreboard::from_root(sample_scoreboard()).unwrap();
+
+    assert_eq!(scoreboard.objectives(), vec![Objective {
+        name: "deaths".to_string(),
+        criteria: "deathCount".to_string(),
+        display_name: "{\"text\":\"Deaths\"}".to_string(),
+    }]);
+    assert_eq!(scoreboard.scores().len(), 3);
+    assert_eq!(scoreboard.objective_scores("deaths").iter().map(|score| (score.owner.as_str(), score.score)).collect::<Vec<_>>(), 
+        vec![("Steve", 3), ("Alex", 1)]);
+
+    assert!(Scoreboard::from_root(NbtTagCompound::new("")).is_err());
+    assert!(Scoreboard::from_root(with_data(NbtTagCompound::new("data"))).unwrap().objectives().is_empty());
+}
+
+#[test]
+fn test_map_item() {
+    let mut data = NbtTagCompound::new("data");
+    data.values.insert("scale".to_string(), NbtTag::Byte(NbtTagByte::new("scale".to_string(), 2)));
+    data.values.insert("dimension".to_string(), string_tag("dimension", "minecraft:the_nether"));
+    data.values.insert("xCenter".to_string(), NbtTag::Int(NbtTagInt::new("xCenter".to_string(), 64)));
+    data.values.insert("zCenter".to_string(), NbtTag::Int(NbtTagInt::new("zCenter".to_string(), -192)));
+    data.values.insert("locked".to_string(), NbtTag::Byte(NbtTagByte::new("locked".to_string(), 1)));
+    let mut colors = vec![0i8; MAP_SIZE * MAP_SIZE];
+    colors[1] = 4 * 12 + 2;   // water, normal shade
+    colors[MAP_SIZE] = (4 * 61 + 3) as u8 as i8; // glow lichen, darkest shade
+    data.values.insert("colors".to_string(), NbtTag::ByteArray(NbtTagByteArray::new("colors".to_string(), colors)));
+
+    let map = MapItem::from_root(5, with_data(data.clone())).unwrap();
+    assert_eq!((map.id, map.scale, map.dimension.as_str(), map.x_center, map.z_center, map.locked), (5, 2, "minecraft:the_nether", 64, -192, true));
+
+    let pixels = map.pixels();
+    assert_eq!(pixels.len(), MAP_SIZE * MAP_SIZE);
+    assert_eq!(pixels[0], [0, 0, 0, 0]);
+    assert_eq!(pixels[1], [64, 64, 255, 255]);
+    assert_eq!(pixels[MAP_SIZE], [67, 88, 79, 255]);
+
+    // the dimension was a number before 1.16
+    data.values.insert("dimension".to_string(), NbtTag::Int(NbtTagInt::new("dimension".to_string(), 1)));
+    assert_eq!(MapItem::from_root(5, with_data(data)).unwrap().dimension, "minecraft:the_end");
+}
+
+#[test]
+fn test_map_item_to_rgba() {
+    let mut colors = vec![0u8; MAP_SIZE * MAP_SIZE];
+    colors[MAP_SIZE + 2] = 4 * 8 + 2;   // snow, normal shade, at x = 2 of the second row
+    let map = MapItem { colors, ..Default::default() };
+
+    let rgba = map.to_rgba();
+    assert_eq!(rgba.len(), MAP_SIZE * MAP_SIZE * 4);
+    assert_eq!(rgba[..4], [0, 0, 0, 0]);
+    let pixel_offset = (MAP_SIZE + 2) * 4;
+    assert_eq!(rgba[pixel_offset..pixel_offset + 4], [255, 255, 255, 255]);
+
+    // a map without colors is transparent
+    assert!(MapItem::default().to_rgba().iter().all(|byte| *byte == 0));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn test_map_item_to_image() {
+    let map = MapItem { colors: vec![4 * 12 + 2; MAP_SIZE * MAP_SIZE], ..Default::default() };
+
+    let image = map.to_image();
+    assert_eq!(image.dimensions(), (MAP_SIZE as u32, MAP_SIZE as u32));
+    assert_eq!(image.get_pixel(127, 127).0, [64, 64, 255, 255]);
+}
+
+#[test]
+fn test_map_color() {
+    assert_eq!(map_color(3), [0, 0, 0, 0]);
+    assert_eq!(map_color(4 * 8 + 2), [255, 255, 255, 255]);
+    assert_eq!(map_color(4 * 8), [180, 180, 180, 255]);
+    assert_eq!(map_color(4 * 8 + 1), [220, 220, 220, 255]);
+    assert_eq!(map_color(255), [0, 0, 0, 0]);
+}
+
+#[test]
+fn test_map_id() {
+    assert_eq!(map_id(Path::new("world/data/map_0.dat")), Some(0));
+    assert_eq!(map_id(Path::new("world/data/map_123.dat")), Some(123));
+    assert_eq!(map_id(Path::new("world/data/idcounts.dat")), None);
+    assert_eq!(map_id(Path::new("world/data/map_x.dat")), None);
+}
+
+#[test]
+fn test_command_storage_namespace() {
+    assert_eq!(command_storage_namespace(Path::new("world/data/command_storage_mymap.dat")), Some("mymap"));
+    assert_eq!(command_storage_namespace(Path::new("world/data/command_storage_.dat")), None);
+    assert_eq!(command_storage_namespace(Path::new("world/data/map_0.dat")), None);
+}
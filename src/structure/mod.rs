@@ -0,0 +1,201 @@
+// ## Author
+// - mrmarkolinus
+//
+// ## Date
+// - 2026-10-16
+//
+// ## File Version
+// - 1.0.1
+//
+// ## Changelog
+// - 1.0.0: Initial version
+// - 1.0.1: entities skips the entities whose pos is not 3 numbers, instead of panicking [mrmarkolinus:2026-10-16]
+
+//! The structure files (`.nbt`) saved by the structure blocks and `/structure`, and used by the game for the generated structures.
+//!
+//! https://minecraft.wiki/w/Structure_file
+//!
+//! Unlike the chunks the blocks are not packed: `blocks` lists every block with its `pos` (relative to the structure origin)
+//! and its `state`, an index in the `palette` (or in the first palette of `palettes` for the structures with random variants,
+//! such as the shipwrecks). The air blocks are usually missing, they are the structure voids.
+
+use crate::blocks::{Coordinates, MinecraftBlock};
+use crate::chunk_format::BlockState;
+use crate::generic_bin::{FileType, GenericBinFile};
+use crate::nbt_tag::{NbtTag, NbtTagCompound, NbtTagList};
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+/// A block of a structure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructureBlock<'a> {
+    /// The position relative to the structure origin, from 0 to `size` - 1.
+    pub pos: Coordinates,
+    pub state: BlockState<'a>,
+    /// The block entity data (chest content, sign text...), without its position.
+    pub nbt: Option<&'a NbtTagCompound>,
+}
+
+/// An entity of a structure.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StructureEntity<'a> {
+    /// The exact position relative to the structure origin.
+    pub pos: [f64; 3],
+    /// The block the entity is in, relative to the structure origin.
+    pub block_pos: Coordinates,
+    /// The entity data, with its `id`.
+    pub nbt: Option<&'a NbtTagCompound>,
+}
+
+/// A structure file.
+#[derive(Clone, Debug, Default)]
+pub struct Structure {
+    pub root: NbtTagCompound,
+}
+
+impl Structure {
+    /// Reads a structure file, usually gzip compressed.
+    pub fn read(file_path: &Path) -> io::Result<Self> {
+        Self::from_root(GenericBinFile::new(PathBuf::from(file_path), FileType::Nbt)?.to_tag_compound()?)
+    }
+
+    /// Builds the structure from the root compound of a structure file, see `is_structure`.
+    pub fn from_root(root: NbtTagCompound) -> io::Result<Self> {
+        if !is_structure(&root) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Not a structure: size, blocks or palette missing"));
+        }
+
+        Ok(Self { root })
+    }
+
+    pub fn data_version(&self) -> Option<i32> {
+        self.root.values.get("DataVersion").and_then(|tag| i32::try_from(tag).ok())
+    }
+
+    /// Returns the dimensions of the structure in blocks, along x, y and z.
+    pub fn size(&self) -> Coordinates {
+        int_triple(self.root.values.get("size")).unwrap_or(Coordinates { x: 0, y: 0, z: 0 })
+    }
+
+    /// Returns the palette of the blocks, the first one if the structure has several variants.
+    pub fn palette(&self) -> Vec<BlockState<'_>> {
+        palette_of(&self.root)
+    }
+
+    /// Returns the blocks of the structure, in the order they are saved.
+    /// The blocks whose state is not in the palette are skipped.
+    pub fn blocks(&self) -> impl Iterator<Item = StructureBlock<'_>> {
+        let palette = self.palette();
+        compounds_of(self.root.values.get("blocks")).filter_map(move |block| {
+            let state = palette.get(usize::try_from(state_of(block)?).ok()?)?.clone();
+            Some(StructureBlock { pos: int_triple(block.values.get("pos"))?, state, nbt: compound_of(block, "nbt") })
+        })
+    }
+
+    /// Returns the entities of the structure. The entities without a position of 3 numbers are skipped.
+    pub fn entities(&self) -> impl Iterator<Item = StructureEntity<'_>> {
+        compounds_of(self.root.values.get("entities")).filter_map(|entity| {
+            let pos = entity.values.get("pos")?.list_as_ref()?.iter()
+                .map(|tag| f64::try_from(tag).ok())
+                .collect::<Option<Vec<f64>>>()?;
+            let pos = <[f64; 3]>::try_from(pos).ok()?;
+            let block_pos = int_triple(entity.values.get("blockPos"))
+                .unwrap_or_else(|| Coordinates::new(pos.iter().map(|value| value.floor() as i32).collect()));
+            Some(StructureEntity { pos, block_pos, nbt: compound_of(entity, "nbt") })
+        })
+    }
+
+    /// Searches the blocks by resource location, see `search_blocks`.
+    pub fn search_blocks(&self, block_resource_location: &[String]) -> HashMap<String, Vec<MinecraftBlock>> {
+        search_blocks(&self.root, block_resource_location)
+    }
+}
+
+/// Returns true if the compound is the root of a structure file: it has a `size`, a `blocks` list and a `palette` or `palettes`.
+pub fn is_structure(root: &NbtTagCompound) -> bool {
+    root.values.contains_key("size")
+        && root.values.get("blocks").is_some_and(|tag| tag.list_as_ref().is_some())
+        && (root.values.contains_key("palette") || root.values.contains_key("palettes"))
+}
+
+/// Searches the blocks of a structure by resource location, as `McWorldDescriptor::search_blocks` does for the chunks.
+/// 
+/// The coordinates are relative to the structure origin, and the chunk of each block is the 16 x 16 x 16 cube it is in.
+/// Empty if `root` is not a structure.
+pub fn search_blocks(root: &NbtTagCompound, block_resource_location: &[String]) -> HashMap<String, Vec<MinecraftBlock>> {
+    let mut blocks_found = HashMap::<String, Vec<MinecraftBlock>>::new();
+    if !is_structure(root) {
+        return blocks_found;
+    }
+
+    // the palette entries of the searched blocks, by state index
+    let searched_states: HashMap<i32, BlockState> = palette_of(root).into_iter()
+        .enumerate()
+        .filter(|(_, state)| block_resource_location.iter().any(|name| name == state.name))
+        .map(|(index, state)| (index as i32, state))
+        .collect();
+
+    for block in compounds_of(root.values.get("blocks")) {
+        let Some(state) = state_of(block).and_then(|state| searched_states.get(&state)) else {
+            continue;
+        };
+        let Some(pos) = int_triple(block.values.get("pos")) else {
+            continue;
+        };
+
+        let properties = state.properties.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect();
+        let chunk = vec![pos.x.div_euclid(16), pos.y.div_euclid(16), pos.z.div_euclid(16)];
+        blocks_found.entry(state.name.to_string())
+            .or_default()
+            .push(MinecraftBlock::new(state.name.to_string(), vec![pos.x, pos.y, pos.z], chunk, properties));
+    }
+
+    blocks_found
+}
+
+fn palette_of(root: &NbtTagCompound) -> Vec<BlockState<'_>> {
+    let palette_list: Option<&NbtTagList> = match root.values.get("palette") {
+        Some(palette) => palette.list_as_ref(),
+        None => root.values.get("palettes")
+            .and_then(|tag| tag.list_as_ref())
+            .and_then(|palettes| palettes.iter().next())
+            .and_then(|tag| tag.list_as_ref()),
+    };
+
+    palette_list.into_iter()
+        .flat_map(|list| list.iter())
+        .map(|entry| BlockState::from_palette_entry(entry).unwrap_or_default())
+        .collect()
+}
+
+fn state_of(block: &NbtTagCompound) -> Option<i32> {
+    block.values.get("state").and_then(|tag| i32::try_from(tag).ok())
+}
+
+/// Reads a list of 3 ints (`size`, `pos`, `blockPos`).
+fn int_triple(tag: Option<&NbtTag>) -> Option<Coordinates> {
+    let values = tag?.list_as_ref()?.iter()
+        .map(|tag| i32::try_from(tag).ok())
+        .collect::<Option<Vec<i32>>>()?;
+
+    if values.len() != 3 {
+        return None;
+    }
+    Some(Coordinates::new(values))
+}
+
+fn compound_of<'a>(compound: &'a NbtTagCompound, name: &str) -> Option<&'a NbtTagCompound> {
+    compound.values.get(name)?.compound_as_ref()
+}
+
+fn compounds_of(tag: Option<&NbtTag>) -> impl Iterator<Item = &NbtTagCompound> {
+    tag.and_then(|tag| tag.list_as_ref())
+        .into_iter()
+        .flat_map(|list| list.iter())
+        .filter_map(|tag| tag.compound_as_ref())
+}
@@ -0,0 +1,130 @@
+#[cfg(test)]
+
+use super::*;
+use crate::nbt_tag::*;
+
+fn int_list(name: &str, values: [i32; 3]) -> NbtTag {
+    let values = values.iter().map(|value| NbtTag::Int(NbtTagInt::new("".to_string(), *value))).collect();
+    NbtTag::List(NbtTagList::new(name.to_string(), NbtTagType::Int, values))
+}
+
+fn string(name: &str, value: &str) -> NbtTag {
+    NbtTag::String(NbtTagString::new(name.to_string(), value.to_string()))
+}
+
+fn palette_entry(name: &str, properties: &[(&str, &str)]) -> NbtTag {
+    let mut entry = NbtTagCompound::new("");
+    entry.values.insert("Name".to_string(), string("Name", name));
+    if !properties.is_empty() {
+        let mut properties_compound = NbtTagCompound::new("Properties");
+        for (key, value) in properties {
+            properties_compound.values.insert(key.to_string(), string(key, value));
+        }
+        entry.values.insert("Properties".to_string(), NbtTag::Compound(properties_compound));
+    }
+    NbtTag::Compound(entry)
+}
+
+fn block(pos: [i32; 3], state: i32, nbt: Option<NbtTagCompound>) -> NbtTag {
+    let mut block = NbtTagCompound::new("");
+    block.values.insert("pos".to_string(), int_list("pos", pos));
+    block.values.insert("state".to_string(), NbtTag::Int(NbtTagInt::new("state".to_string(), state)));
+    if let Some(nbt) = nbt {
+        block.values.insert("nbt".to_string(), NbtTag::Compound(nbt));
+    }
+    NbtTag::Compound(block)
+}
+
+fn list(name: &str, values: Vec<NbtTag>) -> NbtTag {
+    NbtTag::List(NbtTagList::new(name.to_string(), NbtTagType::Compound, values))
+}
+
+/// A 3 x 2 x 1 structure: stone, a chest and a log on the ground, an armor stand above the stone.
+fn structure_root() -> NbtTagCompound {
+    let mut chest = NbtTagCompound::new("nbt");
+    chest.values.insert("id".to_string(), string("id", "minecraft:chest"));
+
+    let mut armor_stand = NbtTagCompound::new("nbt");
+    armor_stand.values.insert("id".to_string(), string("id", "minecraft:armor_stand"));
+    let mut entity = NbtTagCompound::new("");
+    let pos = [0.5, 1.0, 0.5].iter().map(|value| NbtTag::Double(NbtTagDouble::new("".to_string(), *value))).collect();
+    entity.values.insert("pos".to_string(), NbtTag::List(NbtTagList::new("pos".to_string(), NbtTagType::Double, pos)));
+    entity.values.insert("blockPos".to_string(), int_list("blockPos", [0, 1, 0]));
+    entity.values.insert("nbt".to_string(), NbtTag::Compound(armor_stand));
+
+    let mut root = NbtTagCompound::new("");
+    root.values.insert("DataVersion".to_string(), NbtTag::Int(NbtTagInt::new("DataVersion".to_string(), 3465)));
+    root.values.insert("size".to_string(), int_list("size", [3, 2, 1]));
+    root.values.insert("palette".to_string(), list("palette", vec![
+        palette_entry("minecraft:stone", &[]),
+        palette_entry("minecraft:chest", &[("facing", "north")]),
+        palette_entry("minecraft:oak_log", &[("axis", "x")]),
+    ]));
+    root.values.insert("blocks".to_string(), list("blocks", vec![
+        block([0, 0, 0], 0, None),
+        block([1, 0, 0], 1, Some(chest)),
+        block([2, 0, 0], 2, None),
+        // a state out of the palette is skipped
+        block([2, 1, 0], 7, None),
+    ]));
+    root.values.insert("entities".to_string(), list("entities", vec![NbtTag::Compound(entity)]));
+    root
+}
+
+#[test]
+fn test_structure_blocks_and_entities() {
+    let structure = Structure::from_root(structure_root()).unwrap();
+    assert_eq!(structure.data_version(), Some(3465));
+    assert_eq!(structure.size(), Coordinates::new(vec![3, 2, 1]));
+    assert_eq!(structure.palette().len(), 3);
+
+    let blocks: Vec<StructureBlock> = structure.blocks().collect();
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[1].pos, Coordinates::new(vec![1, 0, 0]));
+    assert_eq!(blocks[1].state.name, "minecraft:chest");
+    assert_eq!(blocks[1].state.property("facing"), Some("north"));
+    assert_eq!(blocks[1].nbt.and_then(|nbt| nbt.values.get("id")), Some(&string("id", "minecraft:chest")));
+    assert!(blocks[0].nbt.is_none());
+
+    let entities: Vec<StructureEntity> = structure.entities().collect();
+    assert_eq!(entities.len(), 1);
+    assert_eq!(entities[0].pos, [0.5, 1.0, 0.5]);
+    assert_eq!(entities[0].block_pos, Coordinates::new(vec![0, 1, 0]));
+
+    assert!(Structure::from_root(NbtTagCompound::new("")).is_err());
+}
+
+#[test]
+fn test_structure_entity_malformed_pos() {
+    // a position of 2 numbers and no blockPos: the entity is skipped, the block position is not guessed
+    let mut entity = NbtTagCompound::new("");
+    let pos = [0.5, 1.0].iter().map(|value| NbtTag::Double(NbtTagDouble::new("".to_string(), *value))).collect();
+    entity.values.insert("pos".to_string(), NbtTag::List(NbtTagList::new("pos".to_string(), NbtTagType::Double, pos)));
+    let mut root = structure_root();
+    root.values.insert("entities".to_string(), list("entities", vec![NbtTag::Compound(entity)]));
+
+    let structure = Structure::from_root(root).unwrap();
+    assert_eq!(structure.entities().count(), 0);
+}
+
+#[test]
+fn test_structure_palettes_and_search() {
+    // the structures with variants have several palettes, the first one is used
+    let mut root = structure_root();
+    let palette = root.values.remove("palette").unwrap();
+    let mut variant = palette.clone();
+    if let NbtTag::List(variant) = &mut variant {
+        variant.values[0] = palette_entry("minecraft:granite", &[]);
+    }
+    root.values.insert("palettes".to_string(), NbtTag::List(NbtTagList::new("palettes".to_string(), NbtTagType::List, vec![palette, variant])));
+    assert!(is_structure(&root));
+
+    let blocks_found = search_blocks(&root, &["minecraft:oak_log".to_string(), "minecraft:stone".to_string(), "minecraft:granite".to_string()]);
+    assert_eq!(blocks_found.len(), 2);
+    let log = &blocks_found["minecraft:oak_log"][0];
+    assert_eq!((log.coord.x, log.coord.y, log.coord.z), (2, 0, 0));
+    assert_eq!(log.properties.get("axis").map(String::as_str), Some("x"));
+    assert_eq!(blocks_found["minecraft:stone"].len(), 1);
+
+    assert!(search_blocks(&NbtTagCompound::new(""), &["minecraft:stone".to_string()]).is_empty());
+}
@@ -0,0 +1,834 @@
+// ## Author
+// - mrmarkolinus
+//
+// ## Date
+// - 2026-10-16
+//
+// ## File Version
+// - 1.0.17
+//
+// ## Changelog
+// - 1.0.0: Initial version
+// - 1.0.1: Added CompoundSource and SourcedCompound [mrmarkolinus:2026-10-16]
+// - 1.0.2: Added RegionFileSet for random access to the chunks of a world [mrmarkolinus:2026-10-16]
+// - 1.0.3: Added World, caching the most recently used region files [mrmarkolinus:2026-10-16]
+// - 1.0.4: Added is_session_locked [mrmarkolinus:2026-10-16]
+// - 1.0.5: Added LazyChunks, decoding the chunks on first access [mrmarkolinus:2026-10-16]
+// - 1.0.6: Added list_region_files and RegionFileSet::region_files [mrmarkolinus:2026-10-16]
+// - 1.0.7: RegionFileSet opens the regions with open_seekable, reading only the requested chunks [mrmarkolinus:2026-10-16]
+// - 1.0.8: Added diff_chunks and ChunkChange, comparing the chunks of two worlds [mrmarkolinus:2026-10-16]
+// - 1.0.9: Added SkippedFile [mrmarkolinus:2026-10-16]
+// - 1.0.10: CompoundSource holds the path of the file and the chunk coordinates [mrmarkolinus:2026-10-16]
+// - 1.0.11: Added ChunkHandle, marking a chunk as modified when edited [mrmarkolinus:2026-10-16]
+// - 1.0.12: Added Dimension::id [mrmarkolinus:2026-10-16]
+// - 1.0.13: Added Edition, detected from the world folder [mrmarkolinus:2026-10-16]
+// - 1.0.14: SourcedCompound dereferences to its compound, it is the element of McWorldDescriptor::tag_compounds_list [mrmarkolinus:2026-10-16]
+// - 1.0.15: SourcedCompound holds the modified flag of its compound, ChunkHandle sets it [mrmarkolinus:2026-10-16]
+// - 1.0.16: SourcedCompound shares its compound with the snapshots, copied on the first edit [mrmarkolinus:2026-10-16]
+// - 1.0.17: SourcedCompound implements ChunkPosition with the coordinates of its source [mrmarkolinus:2026-10-16]
+
+use crate::blocks;
+use crate::chunk_format;
+use crate::level_dat::{self, LEVEL_DAT_FILE_NAME, SESSION_LOCK_FILE_NAME};
+use crate::nbt_tag::{NbtTag, NbtTagCompound, NbtTagType};
+use crate::region::{self, RegionFile};
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Read;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use rayon::prelude::*;
+
+/// Number of chunks along each side of a region.
+pub const REGION_CHUNKS_SIDE: i32 = 32;
+/// Number of blocks along each side of a chunk.
+pub const CHUNK_BLOCKS_SIDE: i32 = 16;
+/// Number of region files kept open by default by `World`.
+pub const DEFAULT_REGION_CACHE_SIZE: usize = 16;
+/// The folder of a Bedrock Edition world holding the LevelDB database of the chunks.
+pub const BEDROCK_DB_FOLDER_NAME: &str = "db";
+/// The first bytes of a gzip file, as the `level.dat` of Java Edition.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+#[cfg(test)]
+mod tests;
+
+/// The dimensions of a Minecraft world. Each one has its own region folder in the world folder.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Dimension {
+    #[default]
+    Overworld,
+    Nether,
+    End,
+}
+
+impl Dimension {
+    /// All the dimensions, in the order they are loaded.
+    pub const ALL: [Dimension; 3] = [Dimension::Overworld, Dimension::Nether, Dimension::End];
+
+    /// Returns the identifier of the dimension in the game, e.g. `minecraft:the_nether`.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Dimension::Overworld => "minecraft:overworld",
+            Dimension::Nether => "minecraft:the_nether",
+            Dimension::End => "minecraft:the_end",
+        }
+    }
+
+    /// Returns the folder of the dimension relative to the world folder ("" for the Overworld).
+    pub fn folder(&self) -> &'static str {
+        match self {
+            Dimension::Overworld => "",
+            Dimension::Nether => "DIM-1",
+            Dimension::End => "DIM1",
+        }
+    }
+
+    /// Returns the path of the region folder of the dimension in the given world folder.
+    pub fn region_path(&self, world_path: &Path) -> PathBuf {
+        world_path.join(self.folder()).join("region")
+    }
+
+    /// Guesses the dimension of a region file from its path (e.g. `world/DIM-1/region/r.0.0.mca` is in the Nether).
+    /// Files outside a dimension folder are considered in the Overworld.
+    pub fn from_region_file_path(file_path: &Path) -> Dimension {
+        let dimension_folder = file_path.parent()
+            .filter(|region_path| region_path.file_name().is_some_and(|name| name == "region"))
+            .and_then(|region_path| region_path.parent())
+            .and_then(|dimension_path| dimension_path.file_name())
+            .and_then(|name| name.to_str());
+
+        match dimension_folder {
+            Some("DIM-1") => Dimension::Nether,
+            Some("DIM1") => Dimension::End,
+            _ => Dimension::Overworld,
+        }
+    }
+}
+
+/// Where a loaded compound comes from.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompoundSource {
+    pub dimension: Dimension,
+    /// The coordinates of the region file, None if the compound was not loaded from a region file
+    /// (or the file name is not r.X.Z.mca).
+    pub region_coords: Option<(i32, i32)>,
+    /// The file the compound was read from, None for the compounds built in memory.
+    pub file_path: Option<PathBuf>,
+    /// The (x, z) coordinates of the chunk, None if the compound is not a chunk. For a chunk read from a region file
+    /// they are the coordinates of its slot, also if the chunk does not store its `xPos` and `zPos`.
+    pub chunk_coords: Option<(i32, i32)>,
+}
+
+/// A compound together with its source, as returned by `McWorldDescriptor::load_sourced_compounds`
+/// and held by `McWorldDescriptor::tag_compounds_list`. It dereferences to the compound.
+/// 
+/// The source is needed to interpret the coordinates (a Nether block at x is at 8x in the Overworld)
+/// and to write a chunk back to its region file.
+/// 
+/// Two sourced compounds are equal if their sources and compounds are, whether they were modified or not.
+#[derive(Clone, Debug, Default)]
+pub struct SourcedCompound {
+    pub dimension: Dimension,
+    pub region_coords: Option<(i32, i32)>,
+    pub file_path: Option<PathBuf>,
+    pub chunk_coords: Option<(i32, i32)>,
+    /// Shared with the snapshots of the world until modified, see `McWorldDescriptor::snapshot`.
+    compound: Arc<NbtTagCompound>,
+    /// The compound was modified since loaded or saved, see `McWorldDescriptor::mark_dirty`.
+    pub(crate) dirty: bool,
+}
+
+impl SourcedCompound {
+    pub fn new(source: CompoundSource, compound: NbtTagCompound) -> Self {
+        SourcedCompound { 
+            dimension: source.dimension, 
+            region_coords: source.region_coords, 
+            file_path: source.file_path,
+            chunk_coords: source.chunk_coords,
+            compound: Arc::new(compound),
+            dirty: false,
+        }
+    }
+
+    pub fn source(&self) -> CompoundSource {
+        CompoundSource { 
+            dimension: self.dimension, 
+            region_coords: self.region_coords, 
+            file_path: self.file_path.clone(), 
+            chunk_coords: self.chunk_coords,
+        }
+    }
+
+    /// Returns the compound, copied only if it is shared with a snapshot.
+    pub fn into_compound(self) -> NbtTagCompound {
+        Arc::unwrap_or_clone(self.compound)
+    }
+}
+
+impl PartialEq for SourcedCompound {
+    fn eq(&self, other: &Self) -> bool {
+        self.dimension == other.dimension 
+            && self.region_coords == other.region_coords
+            && self.file_path == other.file_path
+            && self.chunk_coords == other.chunk_coords
+            && self.compound == other.compound
+    }
+}
+
+impl Deref for SourcedCompound {
+    type Target = NbtTagCompound;
+
+    fn deref(&self) -> &NbtTagCompound {
+        &self.compound
+    }
+}
+
+impl DerefMut for SourcedCompound {
+    /// Copies the compound first if it is shared with a snapshot.
+    fn deref_mut(&mut self) -> &mut NbtTagCompound {
+        Arc::make_mut(&mut self.compound)
+    }
+}
+
+impl AsRef<NbtTagCompound> for SourcedCompound {
+    fn as_ref(&self) -> &NbtTagCompound {
+        &self.compound
+    }
+}
+
+impl chunk_format::ChunkPosition for SourcedCompound {
+    /// The x and z of the source (the slot of a chunk read from a region file), the position stored in the chunk otherwise.
+    fn chunk_coordinates(&self) -> blocks::Coordinates {
+        let mut coordinates = chunk_format::get_chunk_coordinates(&self.compound);
+        if let Some((chunk_x, chunk_z)) = self.chunk_coords {
+            coordinates.x = chunk_x;
+            coordinates.z = chunk_z;
+        }
+        coordinates
+    }
+}
+
+/// A file of a world not loaded because it cannot be read, see `WorldLoadOptions::skip_corrupt`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SkippedFile {
+    pub path: PathBuf,
+    /// The description of the read or parse error.
+    pub error: String,
+}
+
+/// A loaded chunk open for editing, see `McWorldDescriptor::chunk_handle`.
+/// 
+/// The edits through `set`, `remove`, `set_block` and `compound_mut` mark the chunk as modified,
+/// so that `McWorldDescriptor::save` writes it back to its region file.
+#[derive(Debug)]
+pub struct ChunkHandle<'a> {
+    /// Copied on the first edit if shared with a snapshot.
+    compound: &'a mut Arc<NbtTagCompound>,
+    /// The modified flag of the chunk in `McWorldDescriptor::tag_compounds_list`.
+    dirty: &'a mut bool,
+    /// None if the chunk was not read from a region file and cannot be saved.
+    position: Option<(Dimension, i32, i32)>,
+}
+
+impl<'a> ChunkHandle<'a> {
+    pub(crate) fn new(sourced_compound: &'a mut SourcedCompound, position: Option<(Dimension, i32, i32)>) -> Self {
+        ChunkHandle { compound: &mut sourced_compound.compound, dirty: &mut sourced_compound.dirty, position }
+    }
+
+    pub fn compound(&self) -> &NbtTagCompound {
+        self.compound
+    }
+
+    /// Returns the compound to edit it directly, marking the chunk as modified.
+    pub fn compound_mut(&mut self) -> &mut NbtTagCompound {
+        self.mark_dirty();
+        Arc::make_mut(self.compound)
+    }
+
+    /// Returns the (dimension, chunk x, chunk z) of the chunk, None if it cannot be saved.
+    pub fn position(&self) -> Option<(Dimension, i32, i32)> {
+        self.position
+    }
+
+    pub fn get(&self, key: &str) -> Option<&NbtTag> {
+        self.compound.values.get(key)
+    }
+
+    /// Puts `tag` at `key` of the chunk root, renamed to `key`, and returns the tag it replaces.
+    pub fn set(&mut self, key: &str, mut tag: NbtTag) -> Option<NbtTag> {
+        self.mark_dirty();
+        tag.set_name(key);
+        Arc::make_mut(self.compound).replace(key, tag)
+    }
+
+    /// Removes the tag `key` from the chunk root. The chunk is marked as modified only if the tag existed.
+    pub fn remove(&mut self, key: &str) -> Option<NbtTag> {
+        if !self.compound.values.contains_key(key) {
+            return None;
+        }
+
+        self.mark_dirty();
+        Arc::make_mut(self.compound).take(key)
+    }
+
+    /// Sets a block of the chunk, see `chunk_format::set_block`.
+    /// Returns false, leaving the chunk unchanged, if it has no section at height y.
+    pub fn set_block(&mut self, x: i32, y: i32, z: i32, block_resource_location: &str) -> bool {
+        let changed = chunk_format::set_block(Arc::make_mut(self.compound), x, y, z, block_resource_location);
+        if changed {
+            self.mark_dirty();
+        }
+        changed
+    }
+
+    /// Returns whether the chunk was modified since loaded or saved.
+    pub fn is_dirty(&self) -> bool {
+        *self.dirty
+    }
+
+    fn mark_dirty(&mut self) {
+        *self.dirty = true;
+    }
+}
+
+/// Returns the (x, z) coordinates of the chunk containing the block at (x, z).
+pub fn block_to_chunk_coords(block_x: i32, block_z: i32) -> (i32, i32) {
+    (block_x.div_euclid(CHUNK_BLOCKS_SIDE), block_z.div_euclid(CHUNK_BLOCKS_SIDE))
+}
+
+/// Returns the (x, z) coordinates of the region containing the chunk at (x, z).
+pub fn chunk_to_region_coords(chunk_x: i32, chunk_z: i32) -> (i32, i32) {
+    (chunk_x.div_euclid(REGION_CHUNKS_SIDE), chunk_z.div_euclid(REGION_CHUNKS_SIDE))
+}
+
+/// Returns the index in the region header (x + z * 32) of the chunk at (x, z).
+pub fn chunk_index_in_region(chunk_x: i32, chunk_z: i32) -> usize {
+    (chunk_x.rem_euclid(REGION_CHUNKS_SIDE) + chunk_z.rem_euclid(REGION_CHUNKS_SIDE) * REGION_CHUNKS_SIDE) as usize
+}
+
+/// Lists the region files of a region folder with their (x, z) region coordinates, parsed from the file names.
+/// The files are not opened. The other files are ignored.
+/// 
+/// The files are sorted by z, then x (row by row, as the chunks of a region).
+pub fn list_region_files(region_path: &Path) -> io::Result<Vec<(i32, i32, PathBuf)>> {
+    let mut region_files = Vec::new();
+    for entry in std::fs::read_dir(region_path)? {
+        let file_path = entry?.path();
+        if let Some((region_x, region_z)) = region::parse_region_coords(&file_path) {
+            region_files.push((region_x, region_z, file_path));
+        }
+    }
+
+    region_files.sort_by(|a, b| (a.1, a.0, &a.2).cmp(&(b.1, b.0, &b.2)));
+    Ok(region_files)
+}
+
+/// The region files of a dimension of a world folder, opened on demand.
+/// 
+/// Unlike `McWorldDescriptor`, nothing is loaded upfront: each query opens only the region file
+/// containing the requested chunk and decodes only that chunk.
+#[derive(Clone, Debug)]
+pub struct RegionFileSet {
+    world_path: PathBuf,
+    dimension: Dimension,
+}
+
+impl RegionFileSet {
+    /// Creates the set of the Overworld region files of the world folder.
+    pub fn new(world_path: PathBuf) -> Self {
+        Self::with_dimension(world_path, Dimension::Overworld)
+    }
+
+    pub fn with_dimension(world_path: PathBuf, dimension: Dimension) -> Self {
+        RegionFileSet { world_path, dimension }
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        self.dimension
+    }
+
+    /// Returns the path of the region file at the given region coordinates (the file may not exist).
+    pub fn region_file_path(&self, region_x: i32, region_z: i32) -> PathBuf {
+        self.dimension.region_path(&self.world_path).join(format!("r.{}.{}.mca", region_x, region_z))
+    }
+
+    /// Lists the region files of the dimension without opening them, see `list_region_files`.
+    /// Empty if the dimension has no region folder.
+    pub fn region_files(&self) -> io::Result<Vec<(i32, i32, PathBuf)>> {
+        let region_path = self.dimension.region_path(&self.world_path);
+        if !region_path.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        list_region_files(&region_path)
+    }
+
+    /// Opens the region file at the given region coordinates, `Ok(None)` if it does not exist.
+    /// Only the header is read, the chunks are read from the file when requested (see `RegionFile::open_seekable`).
+    pub fn open_region(&self, region_x: i32, region_z: i32) -> io::Result<Option<RegionFile>> {
+        let file_path = self.region_file_path(region_x, region_z);
+        if !file_path.is_file() {
+            return Ok(None);
+        }
+
+        RegionFile::open_seekable(file_path).map(Some)
+    }
+
+    /// Returns the chunk at the given chunk coordinates, `Ok(None)` if it has not been generated.
+    pub fn chunk_at(&self, chunk_x: i32, chunk_z: i32) -> io::Result<Option<NbtTagCompound>> {
+        let (region_x, region_z) = chunk_to_region_coords(chunk_x, chunk_z);
+        match self.open_region(region_x, region_z)? {
+            Some(region_file) => region_file.read_chunk(chunk_index_in_region(chunk_x, chunk_z)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the chunk containing the block at the given (x, z) world coordinates, `Ok(None)` if it has not been generated.
+    pub fn chunk_at_block(&self, x: i32, z: i32) -> io::Result<Option<NbtTagCompound>> {
+        let (chunk_x, chunk_z) = block_to_chunk_coords(x, z);
+        self.chunk_at(chunk_x, chunk_z)
+    }
+}
+
+/// A world opened for random access, keeping the most recently used region files open.
+/// 
+/// Repeated queries in the same area (e.g. a map viewer requesting chunks while the user pans) 
+/// do not reopen the region files. When more than `cache_size` regions are open, 
+/// the least recently used one is dropped.
+pub struct World {
+    region_file_set: RegionFileSet,
+    cache_size: usize,
+    region_cache: HashMap<(i32, i32), RegionFile>,
+    /// Region coordinates from the least to the most recently used.
+    lru_order: VecDeque<(i32, i32)>,
+}
+
+impl World {
+    /// Opens the Overworld of the world folder, caching up to `DEFAULT_REGION_CACHE_SIZE` regions.
+    pub fn new(world_path: PathBuf) -> Self {
+        Self::with_cache_size(RegionFileSet::new(world_path), DEFAULT_REGION_CACHE_SIZE)
+    }
+
+    /// Opens the region files of `region_file_set` caching up to `cache_size` regions (at least 1).
+    pub fn with_cache_size(region_file_set: RegionFileSet, cache_size: usize) -> Self {
+        World { 
+            region_file_set, 
+            cache_size: cache_size.max(1), 
+            region_cache: HashMap::new(), 
+            lru_order: VecDeque::new() 
+        }
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        self.region_file_set.dimension()
+    }
+
+    pub fn cache_size(&self) -> usize {
+        self.cache_size
+    }
+
+    /// Changes the maximum number of cached regions (at least 1), dropping the least recently used ones if needed.
+    pub fn set_cache_size(&mut self, cache_size: usize) {
+        self.cache_size = cache_size.max(1);
+        self.evict_regions();
+    }
+
+    /// Returns the number of region files currently open.
+    pub fn cached_regions_num(&self) -> usize {
+        self.region_cache.len()
+    }
+
+    /// Returns true if the region file at the given region coordinates is open.
+    pub fn is_region_cached(&self, region_x: i32, region_z: i32) -> bool {
+        self.region_cache.contains_key(&(region_x, region_z))
+    }
+
+    /// Returns the region file at the given region coordinates, opening it if it is not cached. 
+    /// `Ok(None)` if the region does not exist.
+    pub fn region(&mut self, region_x: i32, region_z: i32) -> io::Result<Option<&RegionFile>> {
+        let region_coords = (region_x, region_z);
+
+        if self.region_cache.contains_key(&region_coords) {
+            self.lru_order.retain(|coords| *coords != region_coords);
+        }
+        else {
+            let Some(region_file) = self.region_file_set.open_region(region_x, region_z)? else {
+                return Ok(None);
+            };
+            self.region_cache.insert(region_coords, region_file);
+        }
+        self.lru_order.push_back(region_coords);
+        self.evict_regions();
+
+        Ok(self.region_cache.get(&region_coords))
+    }
+
+    /// Returns the chunk at the given chunk coordinates, `Ok(None)` if it has not been generated.
+    pub fn chunk_at(&mut self, chunk_x: i32, chunk_z: i32) -> io::Result<Option<NbtTagCompound>> {
+        let (region_x, region_z) = chunk_to_region_coords(chunk_x, chunk_z);
+        match self.region(region_x, region_z)? {
+            Some(region_file) => region_file.read_chunk(chunk_index_in_region(chunk_x, chunk_z)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the chunk containing the block at the given (x, z) world coordinates, `Ok(None)` if it has not been generated.
+    pub fn chunk_at_block(&mut self, x: i32, z: i32) -> io::Result<Option<NbtTagCompound>> {
+        let (chunk_x, chunk_z) = block_to_chunk_coords(x, z);
+        self.chunk_at(chunk_x, chunk_z)
+    }
+
+    fn evict_regions(&mut self) {
+        while self.lru_order.len() > self.cache_size {
+            if let Some(region_coords) = self.lru_order.pop_front() {
+                self.region_cache.remove(&region_coords);
+            }
+        }
+    }
+}
+
+/// A chunk kept compressed in its region file until it is accessed, then kept decoded.
+#[derive(Clone)]
+struct LazyChunk {
+    region_file: Arc<RegionFile>,
+    /// The index of the chunk in the region header.
+    index: usize,
+    /// The decoded chunk, `None` if it is skipped (see `LazyChunks::new`).
+    decoded: OnceLock<Option<NbtTagCompound>>,
+}
+
+/// The chunks of region files read in memory but not decoded, keyed by (dimension, chunk x, chunk z).
+/// 
+/// Each chunk is decompressed and parsed on its first access, and then kept decoded: opening a world is
+/// about as fast as reading its files, and a query pays only for the chunks it touches.
+/// This is the storage of `McWorldDescriptor` in `LoadMode::Lazy`.
+#[derive(Clone, Default)]
+pub struct LazyChunks {
+    chunks: HashMap<(Dimension, i32, i32), LazyChunk>,
+    skip_non_full_chunks: bool,
+}
+
+impl LazyChunks {
+    /// Creates an empty set. With `skip_non_full_chunks` the chunks not fully generated are skipped
+    /// when decoded, as if they were missing (see `WorldLoadOptions::skip_non_full_chunks`).
+    pub fn new(skip_non_full_chunks: bool) -> Self {
+        LazyChunks { chunks: HashMap::new(), skip_non_full_chunks }
+    }
+
+    /// Adds the chunks present in a region file, without decoding them.
+    /// 
+    /// Returns the number of chunks added, 0 if the region coordinates are unknown (the chunks could not be keyed).
+    pub fn add_region(&mut self, dimension: Dimension, region_file: RegionFile) -> usize {
+        if region_file.region_coords().is_none() {
+            return 0;
+        }
+
+        let present_chunks = region_file.present_chunks();
+        let region_file = Arc::new(region_file);
+        for index in present_chunks.iter() {
+            if let Some((chunk_x, chunk_z)) = region_file.chunk_coords(*index) {
+                let lazy_chunk = LazyChunk { region_file: Arc::clone(&region_file), index: *index, decoded: OnceLock::new() };
+                self.chunks.insert((dimension, chunk_x, chunk_z), lazy_chunk);
+            }
+        }
+
+        present_chunks.len()
+    }
+
+    /// Returns the number of chunks, decoded or not.
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Returns the number of chunks already decoded.
+    pub fn decoded_num(&self) -> usize {
+        self.chunks.values().filter(|chunk| chunk.decoded.get().is_some()).count()
+    }
+
+    /// Returns the (x, z) coordinates of the chunks of a dimension, sorted by z and x.
+    pub fn chunk_coords(&self, dimension: Dimension) -> Vec<(i32, i32)> {
+        let mut chunk_coords: Vec<(i32, i32)> = self.chunks.keys()
+            .filter(|(chunk_dimension, _, _)| *chunk_dimension == dimension)
+            .map(|(_, chunk_x, chunk_z)| (*chunk_x, *chunk_z))
+            .collect();
+        chunk_coords.sort_by_key(|(chunk_x, chunk_z)| (*chunk_z, *chunk_x));
+        chunk_coords
+    }
+
+    /// Returns the chunk at the given chunk coordinates, decoding it on the first access.
+    /// `Ok(None)` if the chunk is missing or skipped.
+    /// 
+    /// A chunk failing to decode returns the error, and is decoded again on the next access.
+    pub fn chunk_at(&self, dimension: Dimension, chunk_x: i32, chunk_z: i32) -> io::Result<Option<&NbtTagCompound>> {
+        let Some(lazy_chunk) = self.chunks.get(&(dimension, chunk_x, chunk_z)) else {
+            return Ok(None);
+        };
+
+        if let Some(decoded) = lazy_chunk.decoded.get() {
+            return Ok(decoded.as_ref());
+        }
+        let decoded = Self::decode_chunk(&lazy_chunk.region_file, lazy_chunk.index, self.skip_non_full_chunks)?;
+        Ok(lazy_chunk.decoded.get_or_init(|| decoded).as_ref())
+    }
+
+    /// Decodes all the chunks (in parallel), ordered by dimension, then by region and index in the region.
+    /// The chunks already decoded are copied, the skipped chunks are left out.
+    pub fn to_sourced_compounds(&self) -> io::Result<Vec<SourcedCompound>> {
+        let mut lazy_chunks: Vec<(&(Dimension, i32, i32), &LazyChunk)> = self.chunks.iter().collect();
+        lazy_chunks.sort_by_key(|((dimension, _, _), lazy_chunk)| {
+            (Dimension::ALL.iter().position(|d| d == dimension), lazy_chunk.region_file.region_coords(), lazy_chunk.index)
+        });
+
+        let decoded_chunks = lazy_chunks.into_par_iter()
+            .map(|((dimension, chunk_x, chunk_z), lazy_chunk)| {
+                let source = CompoundSource { 
+                    dimension: *dimension, 
+                    region_coords: lazy_chunk.region_file.region_coords(), 
+                    file_path: lazy_chunk.region_file.file_path().map(Path::to_path_buf),
+                    chunk_coords: Some((*chunk_x, *chunk_z)),
+                };
+                let compound = match lazy_chunk.decoded.get() {
+                    Some(decoded) => decoded.clone(),
+                    None => Self::decode_chunk(&lazy_chunk.region_file, lazy_chunk.index, self.skip_non_full_chunks)?,
+                };
+                Ok(compound.map(|compound| SourcedCompound::new(source, compound)))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        Ok(decoded_chunks.into_iter().flatten().collect())
+    }
+
+    fn decode_chunk(region_file: &RegionFile, index: usize, skip_non_full_chunks: bool) -> io::Result<Option<NbtTagCompound>> {
+        let chunk = region_file.read_chunk(index)?;
+        Ok(chunk.filter(|chunk| !skip_non_full_chunks || chunk_format::is_chunk_fully_generated(chunk)))
+    }
+}
+
+impl fmt::Debug for LazyChunks {
+    // the region files are not printed, they hold the raw content of the files
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LazyChunks")
+            .field("chunks", &self.len())
+            .field("decoded", &self.decoded_num())
+            .field("skip_non_full_chunks", &self.skip_non_full_chunks)
+            .finish()
+    }
+}
+
+/// The edition of Minecraft that saved a world.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Edition {
+    /// Region files (`region/*.mca`) and a gzip compressed big-endian `level.dat`.
+    Java,
+    /// A LevelDB database (`db/`) and an uncompressed little-endian `level.dat`. Only `level.dat` can be read.
+    Bedrock,
+    /// Neither layout was recognized.
+    #[default]
+    Unknown,
+}
+
+impl Edition {
+    /// Detects the edition from the layout of a world folder, or from the content of a single file
+    /// (region files and gzip compressed files are Java Edition, a `level.dat` with the Bedrock header is Bedrock Edition).
+    /// 
+    /// Only the folder entries and the first bytes of `level.dat` are read.
+    pub fn detect(path: &Path) -> Edition {
+        if path.is_dir() {
+            if path.join(BEDROCK_DB_FOLDER_NAME).is_dir() {
+                return Edition::Bedrock;
+            }
+            if Dimension::Overworld.region_path(path).is_dir() {
+                return Edition::Java;
+            }
+            return Self::detect_file(&path.join(LEVEL_DAT_FILE_NAME));
+        }
+
+        if region::parse_region_coords(path).is_some() {
+            return Edition::Java;
+        }
+        Self::detect_file(path)
+    }
+
+    fn detect_file(path: &Path) -> Edition {
+        let Ok(mut file) = File::open(path) else { return Edition::Unknown };
+        let mut header = [0u8; level_dat::BEDROCK_HEADER_LENGTH + 1];
+        if file.read_exact(&mut header).is_err() {
+            return Edition::Unknown;
+        }
+
+        if header.starts_with(&GZIP_MAGIC) {
+            return Edition::Java;
+        }
+
+        // the header holds the length of the rest of the file, so check it against the size of the file
+        let length = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as u64;
+        let file_length = file.metadata().map(|metadata| metadata.len()).unwrap_or_default();
+        let root_type = NbtTagType::from_id(header[level_dat::BEDROCK_HEADER_LENGTH]);
+        if length + level_dat::BEDROCK_HEADER_LENGTH as u64 == file_length && root_type == Some(NbtTagType::Compound) {
+            return Edition::Bedrock;
+        }
+        Edition::Unknown
+    }
+}
+
+/// Returns true if the world is open in a game or a server, which keep `session.lock` locked while running (since 1.16).
+/// Reading a world in use may give torn data, since the game writes the region files at any time.
+/// 
+/// Returns false if the world has no `session.lock`.
+pub fn is_session_locked(world_path: &Path) -> io::Result<bool> {
+    let lock_path = world_path.join(SESSION_LOCK_FILE_NAME);
+    if !lock_path.is_file() {
+        return Ok(false);
+    }
+
+    let lock_file = OpenOptions::new().read(true).write(true).open(lock_path)?;
+    is_file_locked(&lock_file)
+}
+
+/// Java locks the file with `fcntl`, which on Linux is not seen by `flock` (used by `File::try_lock`): 
+/// ask the kernel for a conflicting `fcntl` lock instead.
+#[cfg(unix)]
+fn is_file_locked(file: &File) -> io::Result<bool> {
+    use std::os::unix::io::AsRawFd;
+
+    // l_start and l_len 0 cover the whole file
+    // SAFETY: flock is a plain C struct, all zeroes is a valid value
+    let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+    lock.l_type = libc::F_WRLCK as _;
+    lock.l_whence = libc::SEEK_SET as _;
+
+    // SAFETY: the file descriptor is open for the lifetime of `file` and `lock` is a valid flock struct
+    if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETLK, &mut lock) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(lock.l_type != libc::F_UNLCK as libc::c_short)
+}
+
+#[cfg(not(unix))]
+fn is_file_locked(file: &File) -> io::Result<bool> {
+    match file.try_lock() {
+        Ok(()) => {
+            file.unlock()?;
+            Ok(false)
+        },
+        Err(std::fs::TryLockError::WouldBlock) => Ok(true),
+        Err(std::fs::TryLockError::Error(e)) => Err(e),
+    }
+}
+
+/// The keys of a chunk ignored by default when comparing two worlds: the game updates them every time
+/// the chunk is loaded, even if nothing changed in it.
+pub const DIFF_IGNORED_KEYS: [&str; 2] = ["LastUpdate", "InhabitedTime"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChunkChangeKind {
+    /// The chunk is only in the new world.
+    Added,
+    /// The chunk is only in the old world.
+    Removed,
+    Changed,
+}
+
+/// A chunk that differs between two worlds, see `diff_chunks`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkChange {
+    pub dimension: Dimension,
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    pub kind: ChunkChangeKind,
+    /// The paths of the tags that differ in a changed chunk (see `NbtTagCompound::diff_paths`), 
+    /// only with `DiffOptions::changed_paths`.
+    pub changed_paths: Vec<String>,
+}
+
+/// Options of `diff_chunks`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffOptions {
+    /// Compares the changed chunks tag by tag to fill `ChunkChange::changed_paths`, slower.
+    pub changed_paths: bool,
+    /// The keys of the chunk (or of its `Level` compound before 1.18) left out of the comparison, `DIFF_IGNORED_KEYS` by default.
+    pub ignored_keys: Vec<String>,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        DiffOptions { changed_paths: false, ignored_keys: DIFF_IGNORED_KEYS.iter().map(|key| key.to_string()).collect() }
+    }
+}
+
+/// Compares the chunks of two worlds, matched by dimension and chunk coordinates.
+/// 
+/// The chunks are compared by a hash of their content, so that an unchanged chunk is never compared tag by tag.
+/// The changes are sorted by dimension, then z and x.
+pub fn diff_chunks(old_chunks: &HashMap<(Dimension, i32, i32), &NbtTagCompound>, 
+                   new_chunks: &HashMap<(Dimension, i32, i32), &NbtTagCompound>, 
+                   options: &DiffOptions) -> Vec<ChunkChange> {
+    let chunk_change = |(dimension, chunk_x, chunk_z): (Dimension, i32, i32), kind, changed_paths| {
+        ChunkChange { dimension, chunk_x, chunk_z, kind, changed_paths }
+    };
+
+    let mut changes: Vec<ChunkChange> = old_chunks.par_iter()
+        .filter_map(|(position, old_chunk)| {
+            let Some(new_chunk) = new_chunks.get(position) else {
+                return Some(chunk_change(*position, ChunkChangeKind::Removed, Vec::new()));
+            };
+            if chunk_content_hash(old_chunk, &options.ignored_keys) == chunk_content_hash(new_chunk, &options.ignored_keys) {
+                return None;
+            }
+
+            let changed_paths = match options.changed_paths {
+                true => old_chunk.diff_paths(new_chunk).into_iter()
+                    .filter(|path| !is_ignored_path(path, &options.ignored_keys))
+                    .collect(),
+                false => Vec::new(),
+            };
+            Some(chunk_change(*position, ChunkChangeKind::Changed, changed_paths))
+        })
+        .collect();
+
+    changes.extend(new_chunks.keys()
+        .filter(|position| !old_chunks.contains_key(*position))
+        .map(|position| chunk_change(*position, ChunkChangeKind::Added, Vec::new())));
+
+    changes.sort_by_key(|change| (change.dimension as u8, change.chunk_z, change.chunk_x));
+    changes
+}
+
+/// Hashes the content of a chunk without the ignored keys, at the root and in `Level`.
+fn chunk_content_hash(chunk: &NbtTagCompound, ignored_keys: &[String]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hash_without_keys(chunk, ignored_keys, &mut hasher);
+    hasher.finish()
+}
+
+fn hash_without_keys(compound: &NbtTagCompound, ignored_keys: &[String], hasher: &mut DefaultHasher) {
+    let mut entries: Vec<(&String, &NbtTag)> = compound.iter()
+        .filter(|(key, _)| !ignored_keys.contains(key))
+        .collect();
+    entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+    for (key, tag) in entries {
+        key.hash(hasher);
+        match tag {
+            NbtTag::Compound(level) if key == "Level" => hash_without_keys(level, ignored_keys, hasher),
+            _ => tag.hash(hasher),
+        }
+    }
+}
+
+fn is_ignored_path(path: &str, ignored_keys: &[String]) -> bool {
+    let key = path.strip_prefix("Level/").unwrap_or(path);
+    ignored_keys.iter().any(|ignored_key| ignored_key == key)
+}
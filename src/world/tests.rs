@@ -0,0 +1,144 @@
+#[cfg(test)]
+
+use super::*;
+
+#[test]
+fn test_dimension_region_path() {
+    let world_path = Path::new("saves/world");
+    assert_eq!(Dimension::Overworld.region_path(world_path), Path::new("saves/world/region"));
+    assert_eq!(Dimension::Nether.region_path(world_path), Path::new("saves/world/DIM-1/region"));
+    assert_eq!(Dimension::End.region_path(world_path), Path::new("saves/world/DIM1/region"));
+}
+
+#[test]
+fn test_dimension_from_region_file_path() {
+    assert_eq!(Dimension::from_region_file_path(Path::new("world/region/r.0.0.mca")), Dimension::Overworld);
+    assert_eq!(Dimension::from_region_file_path(Path::new("world/DIM-1/region/r.0.0.mca")), Dimension::Nether);
+    assert_eq!(Dimension::from_region_file_path(Path::new("world/DIM1/region/r.-1.0.mca")), Dimension::End);
+    assert_eq!(Dimension::from_region_file_path(Path::new("DIM1/r.0.0.mca")), Dimension::Overworld);
+    assert_eq!(Dimension::from_region_file_path(Path::new("r.0.0.mca")), Dimension::Overworld);
+}
+
+#[test]
+fn test_block_to_chunk_coords() {
+    assert_eq!(block_to_chunk_coords(0, 0), (0, 0));
+    assert_eq!(block_to_chunk_coords(15, 16), (0, 1));
+    assert_eq!(block_to_chunk_coords(-1, -16), (-1, -1));
+    assert_eq!(block_to_chunk_coords(-17, 31), (-2, 1));
+}
+
+#[test]
+fn test_chunk_to_region_coords() {
+    assert_eq!(chunk_to_region_coords(0, 31), (0, 0));
+    assert_eq!(chunk_to_region_coords(32, -1), (1, -1));
+    assert_eq!(chunk_to_region_coords(-32, -33), (-1, -2));
+    assert_eq!(chunk_index_in_region(0, 0), 0);
+    assert_eq!(chunk_index_in_region(-1, 0), 31);
+    assert_eq!(chunk_index_in_region(33, -1), 1 + 31 * 32);
+}
+
+#[test]
+fn test_region_file_set_path() {
+    let region_file_set = RegionFileSet::with_dimension(PathBuf::from("world"), Dimension::End);
+    assert_eq!(region_file_set.region_file_path(-1, 2), Path::new("world/DIM1/region/r.-1.2.mca"));
+}
+
+#[test]
+fn test_list_region_files() {
+    let world_path = std::env::temp_dir().join(format!("fastnbt_region_files_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&world_path);
+    let region_path = Dimension::Overworld.region_path(&world_path);
+    std::fs::create_dir_all(&region_path).unwrap();
+    for file_name in ["r.0.0.mca", "r.-1.0.mca", "r.3.-2.mca", "r.0.1.mcr", "r.0.0.mca.bak", "level.dat"] {
+        std::fs::write(region_path.join(file_name), []).unwrap();
+    }
+
+    let region_files = RegionFileSet::new(world_path.clone()).region_files().unwrap();
+    assert_eq!(region_files, vec![
+        (3, -2, region_path.join("r.3.-2.mca")),
+        (-1, 0, region_path.join("r.-1.0.mca")),
+        (0, 0, region_path.join("r.0.0.mca")),
+        (0, 1, region_path.join("r.0.1.mcr")),
+    ]);
+
+    // a dimension not generated yet
+    assert!(RegionFileSet::with_dimension(world_path.clone(), Dimension::Nether).region_files().unwrap().is_empty());
+    assert!(list_region_files(&world_path.join("missing")).is_err());
+
+    std::fs::remove_dir_all(&world_path).unwrap();
+}
+
+#[test]
+fn test_is_session_locked() {
+    let world_path = std::env::temp_dir().join(format!("fastnbt_session_lock_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&world_path);
+    std::fs::create_dir_all(&world_path).unwrap();
+
+    // no session.lock, as in the worlds older than 1.16 or copied without it
+    assert!(!is_session_locked(&world_path).unwrap());
+
+    // a session.lock left by a closed game
+    std::fs::write(world_path.join(SESSION_LOCK_FILE_NAME), [0xE2, 0x98, 0x83]).unwrap();
+    assert!(!is_session_locked(&world_path).unwrap());
+
+    std::fs::remove_dir_all(&world_path).unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_is_session_locked_by_other_file_description() {
+    use std::os::unix::io::AsRawFd;
+
+    let world_path = std::env::temp_dir().join(format!("fastnbt_session_lock_held_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&world_path);
+    std::fs::create_dir_all(&world_path).unwrap();
+    std::fs::write(world_path.join(SESSION_LOCK_FILE_NAME), [0xE2, 0x98, 0x83]).unwrap();
+
+    // an open file description lock conflicts with the fcntl locks even in the same process, like a running game
+    let game_file = OpenOptions::new().read(true).write(true).open(world_path.join(SESSION_LOCK_FILE_NAME)).unwrap();
+    // SAFETY: flock is a plain C struct, all zeroes is a valid value
+    let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+    lock.l_type = libc::F_WRLCK as libc::c_short;
+    lock.l_whence = libc::SEEK_SET as libc::c_short;
+    // SAFETY: the file descriptor is open for the lifetime of `game_file` and `lock` is a valid flock struct
+    assert_ne!(unsafe { libc::fcntl(game_file.as_raw_fd(), libc::F_OFD_SETLK, &lock) }, -1);
+
+    assert!(is_session_locked(&world_path).unwrap());
+
+    drop(game_file);
+    assert!(!is_session_locked(&world_path).unwrap());
+    std::fs::remove_dir_all(&world_path).unwrap();
+}
+
+#[test]
+fn test_lazy_chunks() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+    let region_file = RegionFile::new(path.clone()).unwrap();
+    let present_chunks = region_file.present_chunks();
+    let (chunk_x, chunk_z) = region_file.chunk_coords(present_chunks[0]).unwrap();
+
+    let mut lazy_chunks = LazyChunks::new(false);
+    assert_eq!(lazy_chunks.add_region(Dimension::Overworld, RegionFile::new(path.clone()).unwrap()), present_chunks.len());
+    assert_eq!(lazy_chunks.len(), present_chunks.len());
+    assert_eq!(lazy_chunks.decoded_num(), 0);
+    assert_eq!(lazy_chunks.chunk_coords(Dimension::Overworld).len(), present_chunks.len());
+    assert!(lazy_chunks.chunk_coords(Dimension::Nether).is_empty());
+
+    // only the accessed chunk is decoded, once
+    let chunk = lazy_chunks.chunk_at(Dimension::Overworld, chunk_x, chunk_z).unwrap().unwrap();
+    assert_eq!(Some(chunk), region_file.read_chunk(present_chunks[0]).unwrap().as_ref());
+    assert_eq!(lazy_chunks.decoded_num(), 1);
+    assert!(std::ptr::eq(chunk, lazy_chunks.chunk_at(Dimension::Overworld, chunk_x, chunk_z).unwrap().unwrap()));
+    assert!(lazy_chunks.chunk_at(Dimension::Nether, chunk_x, chunk_z).unwrap().is_none());
+    assert!(lazy_chunks.chunk_at(Dimension::Overworld, 0, 0).unwrap().is_none());
+
+    let sourced_compounds = lazy_chunks.to_sourced_compounds().unwrap();
+    assert_eq!(sourced_compounds.len(), present_chunks.len());
+    assert_eq!(*sourced_compounds[0], *chunk);
+    assert_eq!(sourced_compounds[0].region_coords, Some((-1, 0)));
+
+    // the chunks of a region without coordinates cannot be keyed
+    let raw_data = std::fs::read(&path).unwrap();
+    assert_eq!(lazy_chunks.add_region(Dimension::Overworld, RegionFile::from_bytes(raw_data, None).unwrap()), 0);
+}
@@ -0,0 +1,40 @@
+//! Helpers shared by the integration tests.
+use fastnbt::Dimension;
+use fastnbt::level_dat::LEVEL_DAT_FILE_NAME;
+use fastnbt::nbt_tag::{self, NbtTag, NbtTagCompound, NbtTagString};
+use std::fs;
+use std::path::PathBuf;
+
+/// Creates a world in the target temporary directory, with the test region file `r.-1.0.mca` in each of `dimensions`.
+/// `extra_regions` are the region files of `tests/resources` also copied in each dimension.
+/// With a `version_name`, the world has a level.dat holding the version and the world name as level name.
+pub fn create_test_world(world_name: &str, dimensions: &[Dimension], extra_regions: &[&str], version_name: Option<&str>) -> PathBuf {
+    let resources_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/resources");
+    let world_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(world_name);
+    let _ = fs::remove_dir_all(&world_path);
+
+    for dimension in dimensions {
+        let region_path = dimension.region_path(&world_path);
+        fs::create_dir_all(&region_path).unwrap();
+        fs::copy(resources_path.join("test_world/r.-1.0.mca"), region_path.join("r.-1.0.mca")).unwrap();
+        for region in extra_regions {
+            fs::copy(resources_path.join(region), region_path.join(region)).unwrap();
+        }
+    }
+
+    if let Some(version_name) = version_name {
+        let mut version = NbtTagCompound::new("Version");
+        version.values.insert("Name".to_string(), NbtTag::String(NbtTagString::new("Name".to_string(), version_name.to_string())));
+        let mut data = NbtTagCompound::new("Data");
+        data.values.insert("LevelName".to_string(), NbtTag::String(NbtTagString::new("LevelName".to_string(), world_name.to_string())));
+        data.values.insert("Version".to_string(), NbtTag::Compound(version));
+        let mut root = NbtTagCompound::new("");
+        root.values.insert("Data".to_string(), NbtTag::Compound(data));
+
+        let mut level_dat = Vec::new();
+        nbt_tag::write(&mut level_dat, &root);
+        fs::write(world_path.join(LEVEL_DAT_FILE_NAME), level_dat).unwrap();
+    }
+
+    world_path
+}
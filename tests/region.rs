@@ -30,3 +30,16 @@ fn read_region_file() {
     );
     assert_eq!(c.get("shortTest").unwrap().short().unwrap().value, 32767);
 }
+
+#[test]
+fn region_open_seekable() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+
+    let in_memory = fastnbt::region::RegionFile::new(path.clone()).unwrap();
+    let seekable = fastnbt::region::RegionFile::open_seekable(path).unwrap();
+
+    assert_eq!(seekable.region_coords(), Some((-1, 0)));
+    assert_eq!(seekable.present_chunks(), in_memory.present_chunks());
+    assert_eq!(seekable.to_compounds_list().unwrap(), in_memory.to_compounds_list().unwrap());
+}
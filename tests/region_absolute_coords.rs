@@ -0,0 +1,43 @@
+//! Tests that the block search reports world-absolute coordinates
+//! for a region with negative coordinates (r.-1.0).
+use fastnbt::McWorldDescriptor;
+use fastnbt::chunk_format;
+use std::path::PathBuf;
+
+#[test]
+fn region_negative_absolute_coords() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+
+    let mc_world = McWorldDescriptor::new(path).unwrap();
+
+    // r.-1.0 contains the chunks from x = -32 to x = -1 and from z = 0 to z = 31
+    for chunk in mc_world.tag_compounds_list.iter() {
+        let chunk_pos = chunk_format::get_chunk_coordinates(chunk);
+        assert!((-32..0).contains(&chunk_pos.x));
+        assert!((0..32).contains(&chunk_pos.z));
+    }
+
+    let block_positions = mc_world.search_blocks(vec!["minecraft:repeater".to_string(), "minecraft:lever".to_string()]);
+    assert!(!block_positions.is_empty());
+
+    for block in block_positions.values().flatten() {
+        // the blocks are in the negative x region, -512 <= x < 0
+        assert!((-512..0).contains(&block.coord.x));
+        assert!((0..512).contains(&block.coord.z));
+
+        // the block belongs to the reported chunk and section
+        assert_eq!(block.coord.x.div_euclid(16), block.chunk.coord.x);
+        assert_eq!(block.coord.y.div_euclid(16), block.chunk.coord.y);
+        assert_eq!(block.coord.z.div_euclid(16), block.chunk.coord.z);
+    }
+}
+
+#[test]
+fn chunk_coordinates_from_region() {
+    let chunk_pos = chunk_format::get_chunk_coordinates_from_region((-1, 2), 0);
+    assert_eq!((chunk_pos.x, chunk_pos.z), (-32, 64));
+    
+    let chunk_pos = chunk_format::get_chunk_coordinates_from_region((-1, 2), 31 + 32 * 31);
+    assert_eq!((chunk_pos.x, chunk_pos.z), (-1, 95));
+}
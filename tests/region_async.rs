@@ -0,0 +1,37 @@
+//! Tests the asynchronous loading of a region file,
+//! comparing it with the synchronous loading.
+#![cfg(feature = "async")]
+use fastnbt::McWorldDescriptor;
+use fastnbt::region::RegionFile;
+use std::path::PathBuf;
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(future)
+}
+
+#[test]
+fn region_async() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+
+    let mc_world = McWorldDescriptor::new(path.clone()).unwrap();
+    let mc_world_async = block_on(McWorldDescriptor::new_async(path.clone())).unwrap();
+    
+    assert_eq!(mc_world_async.tag_compounds_list.len(), mc_world.tag_compounds_list.len());
+    assert_eq!(mc_world_async.search_blocks(vec!["minecraft:repeater".to_string()]).get("minecraft:repeater").unwrap().len(),
+               mc_world.search_blocks(vec!["minecraft:repeater".to_string()]).get("minecraft:repeater").unwrap().len());
+
+    let region_file = block_on(RegionFile::new_async(path)).unwrap();
+    assert_eq!(region_file.region_coords(), Some((-1, 0)));
+}
+
+#[test]
+fn bigtest_async() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/bigtest.nbt");
+
+    let mc_world = block_on(McWorldDescriptor::new_async(path)).unwrap();
+    let c = mc_world.tag_compounds_list.first().unwrap();
+    
+    assert_eq!(c.values.get("intTest").unwrap().int().unwrap().value, 2147483647);
+}
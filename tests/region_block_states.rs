@@ -0,0 +1,35 @@
+//! Tests that the block states of every section of the test world region file
+//! are unpacked and packed back to the same data array.
+use fastnbt::chunk_format;
+use fastnbt::McWorldDescriptor;
+use std::path::PathBuf;
+
+#[test]
+fn region_block_states_roundtrip() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+
+    let mc_world = McWorldDescriptor::new(path).unwrap();
+    let mut sections_with_data = 0;
+
+    for chunk in mc_world.tag_compounds_list.iter() {
+        let data_version = chunk.values.get("DataVersion").unwrap().try_int().unwrap().value;
+        let sections = chunk.values.get("sections").unwrap().try_list().unwrap();
+
+        for section in sections.values.iter() {
+            let Some(block_states_tag) = chunk_format::find_block_states_in_section(section) else {
+                continue;
+            };
+            let (Some(palette_list), Some(long_array)) = chunk_format::find_palette_in_block_states(block_states_tag) else {
+                continue;
+            };
+
+            let palette_ids = chunk_format::unpack_block_states(long_array, palette_list.values.len(), data_version);
+            assert!(palette_ids.iter().all(|palette_id| (*palette_id as usize) < palette_list.values.len()));
+            assert_eq!(&chunk_format::pack_block_states(&palette_ids, palette_list.values.len(), data_version), long_array);
+            sections_with_data += 1;
+        }
+    }
+
+    assert!(sections_with_data > 0);
+}
@@ -0,0 +1,38 @@
+//! Tests the chunk status filtering using the test world region file,
+//! which contains both fully generated chunks and proto-chunks.
+use fastnbt::{McWorldDescriptor, WorldLoadOptions};
+use fastnbt::chunk_format;
+use std::path::PathBuf;
+
+#[test]
+fn region_skip_non_full_chunks() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+
+    let mc_world = McWorldDescriptor::new(path.clone()).unwrap();
+    let full_chunks = mc_world.tag_compounds_list.iter()
+                                .filter(|chunk| chunk_format::chunk_status(chunk).as_deref() == Some("minecraft:full"))
+                                .count();
+    
+    assert!(full_chunks > 0);
+    assert!(full_chunks < mc_world.tag_compounds_list.len());
+
+    let load_options = WorldLoadOptions { skip_non_full_chunks: true, ..Default::default() };
+    let mc_world_full = McWorldDescriptor::new_with_options(path, load_options).unwrap();
+    
+    assert_eq!(mc_world_full.tag_compounds_list.len(), full_chunks);
+    assert!(mc_world_full.tag_compounds_list.iter().all(|chunk| chunk_format::is_chunk_fully_generated(chunk)));
+}
+
+#[test]
+fn region_chunk_status_pre_1_18() {
+    // r.0.0.mca stores the status without the namespace
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/r.0.0.mca");
+
+    let mc_world = McWorldDescriptor::new(path).unwrap();
+    let chunk = mc_world.tag_compounds_list.first().unwrap();
+    
+    assert_eq!(chunk_format::chunk_status(chunk), Some("full".to_string()));
+    assert!(chunk_format::is_chunk_fully_generated(chunk));
+}
@@ -0,0 +1,29 @@
+//! Tests the block count restricted to a box, against the positions found by the block search.
+use fastnbt::McWorldDescriptor;
+use fastnbt::blocks::Coordinates;
+use std::path::PathBuf;
+
+#[test]
+fn region_count_blocks_in_box() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+
+    let mc_world = McWorldDescriptor::new(path).unwrap();
+
+    // a box across several chunks and the border of the bedrock layer (from x = -352), not aligned to them
+    let (min, max) = (Coordinates::new(vec![-360, -64, 13]), Coordinates::new(vec![-300, -59, 70]));
+    let blocks_count = mc_world.count_blocks_in_box(min.clone(), max.clone());
+    assert_eq!(blocks_count.values().sum::<u64>(), 61 * 6 * 58);
+
+    let block_names = vec!["minecraft:bedrock".to_string(), "minecraft:repeater".to_string()];
+    for (block_name, blocks) in mc_world.search_blocks(block_names) {
+        let in_box = blocks.iter()
+            .filter(|block| (min.x..=max.x).contains(&block.coord.x) && (min.y..=max.y).contains(&block.coord.y) && (min.z..=max.z).contains(&block.coord.z))
+            .count() as u64;
+        assert_eq!(blocks_count.get(&block_name).copied().unwrap_or_default(), in_box, "{}", block_name);
+    }
+    assert!(blocks_count["minecraft:bedrock"] > 0);
+
+    // the corners can be swapped
+    assert_eq!(mc_world.count_blocks_in_box(max, min), blocks_count);
+}
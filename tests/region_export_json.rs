@@ -0,0 +1,72 @@
+//! Tests the export of each loaded compound to its own JSON file.
+use fastnbt::McWorldDescriptor;
+use fastnbt::chunk_format;
+use fastnbt::nbt_tag::{JsonOptions, NbtTagCompound};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn region_export_each_to_json() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/resources/test_world/r.-1.0.mca");
+    let mc_world = McWorldDescriptor::new(path).unwrap();
+
+    let export_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("region_export_each_to_json");
+    let _ = fs::remove_dir_all(&export_path);
+    mc_world.export_each_to_json(export_path.clone()).unwrap();
+
+    assert_eq!(fs::read_dir(&export_path).unwrap().count(), mc_world.tag_compounds_list.len());
+
+    let chunk = &mc_world.tag_compounds_list[0];
+    let chunk_pos = chunk_format::get_chunk_coordinates(chunk);
+    let file_name = format!("r.-1.0_c.{}.{}.json", chunk_pos.x, chunk_pos.z);
+    let exported = NbtTagCompound::from_json(export_path.join(file_name)).unwrap();
+    assert_eq!(exported.to_snapshot(), chunk.to_snapshot());
+}
+
+#[test]
+fn region_export_each_to_json_longs_as_strings() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/resources/test_world/r.-1.0.mca");
+    let mc_world = McWorldDescriptor::new(path).unwrap();
+
+    let export_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("region_export_each_to_json_longs_as_strings");
+    let _ = fs::remove_dir_all(&export_path);
+    mc_world.export_each_to_json_with_options(export_path.clone(), &JsonOptions { longs_as_strings: true, ..Default::default() }).unwrap();
+
+    // the heightmaps are packed in longs, exported as strings and read back exactly
+    let chunk = mc_world.tag_compounds_list.iter()
+        .find(|chunk| chunk_format::ChunkAccessor::new(chunk).heightmaps().is_some_and(|heightmaps| !heightmaps.values.is_empty()))
+        .unwrap();
+    let chunk_pos = chunk_format::get_chunk_coordinates(chunk);
+    let file_path = export_path.join(format!("r.-1.0_c.{}.{}.json", chunk_pos.x, chunk_pos.z));
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&file_path).unwrap()).unwrap();
+    let long_values = long_array_values(&json);
+    assert!(!long_values.is_empty());
+    assert!(long_values.iter().all(|value| value.is_string()));
+    let exported = NbtTagCompound::from_json(file_path).unwrap();
+    assert_eq!(exported.to_snapshot(), chunk.to_snapshot());
+}
+
+/// Returns the values of all the LongArray tags of a JSON export.
+fn long_array_values(json: &serde_json::Value) -> Vec<&serde_json::Value> {
+    match json {
+        serde_json::Value::Object(map) => map.iter()
+            .flat_map(|(key, value)| match (key.as_str(), value.get("values").and_then(|values| values.as_array())) {
+                ("LongArray", Some(values)) => values.iter().collect(),
+                _ => long_array_values(value),
+            })
+            .collect(),
+        serde_json::Value::Array(array) => array.iter().flat_map(long_array_values).collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[test]
+fn empty_world_to_json() {
+    let mc_world = McWorldDescriptor::from_compounds(Vec::new(), String::new());
+    let json_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("empty_world_to_json.json");
+    let _ = fs::remove_file(&json_path);
+
+    assert_eq!(mc_world.to_json(&json_path).unwrap_err().kind(), std::io::ErrorKind::NotFound);
+    assert_eq!(mc_world.to_json_with_options(&json_path, &JsonOptions::default()).unwrap_err().kind(), std::io::ErrorKind::NotFound);
+    assert!(!json_path.exists());
+}
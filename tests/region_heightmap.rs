@@ -0,0 +1,24 @@
+//! Tests the heightmap decoding on a real region file.
+use fastnbt::McWorldDescriptor;
+use fastnbt::chunk_format;
+use std::path::PathBuf;
+
+#[test]
+fn region_heightmap_grayscale() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/resources/test_world/r.-1.0.mca");
+    let mc_world = McWorldDescriptor::new(path).unwrap();
+
+    let full_chunk = mc_world.tag_compounds_list.iter()
+        .find(|chunk| chunk_format::is_chunk_fully_generated(chunk))
+        .unwrap();
+    let heights = chunk_format::get_heightmap(full_chunk, "WORLD_SURFACE").unwrap();
+    // the surface of a generated overworld chunk is between the bottom and the top of the world (384 blocks)
+    assert!(heights.iter().all(|height| *height > 0 && *height <= 384));
+
+    let grayscale = chunk_format::heightmap_grayscale(&mc_world.tag_compounds_list, "WORLD_SURFACE").unwrap();
+    // the region r.-1.0 covers the blocks from x = -512 and z = 0
+    assert!(grayscale.min_block_x >= -512 && grayscale.min_block_x < 0);
+    assert!(grayscale.min_block_z >= 0 && grayscale.min_block_z < 512);
+    assert_eq!(grayscale.pixels.len(), (grayscale.width * grayscale.height) as usize);
+    assert!(grayscale.width <= 512 && grayscale.height <= 512);
+}
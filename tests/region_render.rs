@@ -0,0 +1,18 @@
+//! Tests the top-down rendering of a real region file. Requires the "image" feature.
+#![cfg(feature = "image")]
+use fastnbt::McWorldDescriptor;
+use fastnbt::render;
+use std::path::PathBuf;
+
+#[test]
+fn region_render_topdown() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/resources/test_world/r.-1.0.mca");
+    let mc_world = McWorldDescriptor::new(path).unwrap();
+
+    let image = mc_world.render_topdown().unwrap();
+    let topdown = render::topdown_blocks(&mc_world.tag_compounds_list).unwrap();
+    assert_eq!(image.dimensions(), (topdown.width, topdown.height));
+
+    // a generated region has some columns with a known surface block
+    assert!(image.pixels().any(|pixel| pixel.0 != render::EMPTY_COLUMN_COLOR && pixel.0 != render::UNKNOWN_BLOCK_COLOR));
+}
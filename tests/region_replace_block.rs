@@ -0,0 +1,49 @@
+//! Tests the replacement of a block with another one
+//! in all the chunks of the test world region file.
+use fastnbt::McWorldDescriptor;
+use std::path::PathBuf;
+
+#[test]
+fn region_replace_block() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+
+    let mut mc_world = McWorldDescriptor::new(path).unwrap();
+
+    let searched_blocks = vec!["minecraft:repeater".to_string(), "minecraft:stone".to_string()];
+    let block_positions = mc_world.search_blocks(searched_blocks.clone());
+    let repeaters_num = block_positions.get("minecraft:repeater").unwrap().len();
+    let stones_num = block_positions.get("minecraft:stone").unwrap().len();
+    assert!(repeaters_num > 0);
+
+    let replaced = mc_world.replace_block("minecraft:repeater", "minecraft:stone");
+    assert_eq!(replaced, repeaters_num);
+
+    let block_positions = mc_world.search_blocks(searched_blocks);
+    assert!(!block_positions.contains_key("minecraft:repeater"));
+    
+    let stones = block_positions.get("minecraft:stone").unwrap();
+    assert_eq!(stones.len(), stones_num + repeaters_num);
+    // stone has no block-state properties, the repeater ones are dropped
+    assert!(stones.iter().all(|block| block.properties.is_empty()));
+
+    // nothing left to replace
+    assert_eq!(mc_world.replace_block("minecraft:repeater", "minecraft:stone"), 0);
+}
+
+#[test]
+fn region_replace_block_keeps_properties() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+
+    let mut mc_world = McWorldDescriptor::new(path).unwrap();
+    let levers = mc_world.search_blocks(vec!["minecraft:lever".to_string()]).remove("minecraft:lever").unwrap();
+
+    // the target block is not in the world, the properties are kept as they are
+    let replaced = mc_world.replace_block("minecraft:lever", "mymod:custom_lever");
+    assert_eq!(replaced, levers.len());
+    
+    let custom_levers = mc_world.search_blocks(vec!["mymod:custom_lever".to_string()]).remove("mymod:custom_lever").unwrap();
+    assert_eq!(custom_levers.len(), levers.len());
+    assert_eq!(custom_levers[0].properties, levers[0].properties);
+}
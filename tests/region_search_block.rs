@@ -1,6 +1,7 @@
 //! Tests the library using the `bigtest.nbt` file provided
 //! by Mojang.
 use fastnbt::McWorldDescriptor;
+use fastnbt::blocks::{self, ResourceLocation};
 use std::path::PathBuf;
 
 #[test]
@@ -22,3 +23,82 @@ fn region_search_blocks() {
                                                                         "minecraft:piston".to_string(),]);
     print!("End");
 }
+
+#[test]
+fn region_search_blocks_resource_locations() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+    let mc_world = McWorldDescriptor::new(path).unwrap();
+
+    let repeaters = mc_world.search_blocks(vec!["minecraft:repeater"]).remove("minecraft:repeater").unwrap();
+    assert!(!repeaters.is_empty());
+
+    // the namespace defaults to minecraft
+    let short_name = mc_world.search_blocks(vec!["repeater"]);
+    assert_eq!(short_name.get("minecraft:repeater").map(|blocks| blocks.len()), Some(repeaters.len()));
+
+    let resource_location = ResourceLocation::new("minecraft:repeater").unwrap();
+    let typed = mc_world.search_blocks(vec![resource_location]);
+    assert_eq!(typed.get("minecraft:repeater").map(|blocks| blocks.len()), Some(repeaters.len()));
+
+    // malformed resource locations are skipped
+    let malformed = mc_world.search_blocks(vec!["minecraft:Repeater".to_string()]);
+    assert!(malformed.is_empty());
+}
+
+#[test]
+fn region_search_blocks_report() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+    let mc_world = McWorldDescriptor::new(path).unwrap();
+
+    let report = mc_world.search_blocks_report(vec!["minecraft:repeater", "minecraft:daimond_ore", "minecraft:Lever"]);
+
+    assert!(!report.blocks["minecraft:repeater"].is_empty());
+    assert!(report.blocks["minecraft:daimond_ore"].is_empty());
+    assert_eq!(report.not_in_palette, vec!["minecraft:daimond_ore".to_string()]);
+    assert_eq!(report.invalid, vec!["minecraft:Lever".to_string()]);
+}
+
+#[test]
+fn region_search_blocks_multi() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+
+    let mc_world = McWorldDescriptor::new(path).unwrap();
+    let single = mc_world.search_blocks(vec!["minecraft:lever"]);
+    let worlds = vec![mc_world.clone(), McWorldDescriptor::default(), mc_world];
+
+    let found = blocks::search_blocks_multi(&worlds, vec!["lever".to_string(), "minecraft:Lever".to_string()]);
+    assert_eq!(found.keys().collect::<Vec<_>>(), vec!["minecraft:lever"]);
+
+    let levers = &found["minecraft:lever"];
+    let levers_num = single["minecraft:lever"].len();
+    assert!(levers_num > 0);
+    assert_eq!(levers.len(), 2 * levers_num);
+    assert!(levers[..levers_num].iter().all(|(world_index, _)| *world_index == 0));
+    assert!(levers[levers_num..].iter().all(|(world_index, _)| *world_index == 2));
+    assert_eq!(levers[0].1.coord, single["minecraft:lever"][0].coord);
+}
+
+
+#[test]
+fn region_search_blocks_sorted() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+    let mc_world = McWorldDescriptor::new(path).unwrap();
+
+    let options = blocks::SearchOptions { sort_by_position: true };
+    let sorted = mc_world.search_blocks_with_options(vec!["minecraft:repeater", "minecraft:lever"], &options);
+    let unsorted = mc_world.search_blocks(vec!["minecraft:repeater", "minecraft:lever"]);
+
+    for (resource_location, blocks) in sorted.iter() {
+        let positions: Vec<(i32, i32, i32)> = blocks.iter().map(|block| (block.coord.x, block.coord.y, block.coord.z)).collect();
+        assert!(positions.windows(2).all(|pair| pair[0] <= pair[1]), "{}", resource_location);
+
+        // same blocks, only the order changes
+        let mut expected: Vec<(i32, i32, i32)> = unsorted[resource_location].iter().map(|block| (block.coord.x, block.coord.y, block.coord.z)).collect();
+        expected.sort();
+        assert_eq!(positions, expected);
+    }
+}
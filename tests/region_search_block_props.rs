@@ -0,0 +1,35 @@
+//! Tests the block search with block-state property filters
+//! using the test world region file.
+use fastnbt::McWorldDescriptor;
+use std::path::PathBuf;
+
+#[test]
+fn region_search_blocks_with_props() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/resources/test_world/r.-1.0.mca");
+
+    let mc_world = McWorldDescriptor::new(path).unwrap();
+
+    let all_repeaters = mc_world.search_blocks(vec!["minecraft:repeater".to_string()]);
+    let all_repeaters = all_repeaters.get("minecraft:repeater").unwrap();
+
+    // No properties: every state of the block is a match
+    let unfiltered = mc_world.search_blocks_with_props(vec![("minecraft:repeater".to_string(), vec![])]);
+    assert_eq!(unfiltered.get("minecraft:repeater").unwrap().len(), all_repeaters.len());
+
+    // Only the repeaters facing west
+    let west_repeaters = mc_world.search_blocks_with_props(vec![
+                                                    ("minecraft:repeater".to_string(), vec![("facing".to_string(), "west".to_string())])]);
+    let west_repeaters = west_repeaters.get("minecraft:repeater").unwrap();
+    let expected = all_repeaters.iter().filter(|block| block.properties.get("facing").map(String::as_str) == Some("west")).count();
+    
+    assert!(expected > 0);
+    assert!(expected < all_repeaters.len());
+    assert_eq!(west_repeaters.len(), expected);
+    assert!(west_repeaters.iter().all(|block| block.properties.get("facing").map(String::as_str) == Some("west")));
+
+    // Unspecified properties are wildcards, a non existing value matches nothing
+    let no_match = mc_world.search_blocks_with_props(vec![
+                                                    ("minecraft:repeater".to_string(), vec![("facing".to_string(), "up".to_string())])]);
+    assert!(!no_match.contains_key("minecraft:repeater"));
+}
@@ -0,0 +1,57 @@
+//! Tests the integrity scan and the repair of a region file.
+use fastnbt::region::{ChunkIssueKind, RegionFile};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn region_verify() {
+    let region_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/resources/test_world/r.-1.0.mca");
+
+    let region_file = RegionFile::new(region_path.clone()).unwrap();
+    assert!(region_file.verify().is_empty());
+
+    // overwrite the start of the compressed data of the first chunk
+    let mut region = fs::read(&region_path).unwrap();
+    let index = region_file.present_chunks()[0];
+    let offset = (u32::from_be_bytes([0, region[index * 4], region[index * 4 + 1], region[index * 4 + 2]]) * 4096) as usize;
+    region[offset + 5..offset + 15].fill(0xFF);
+    let corrupt_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("r.-1.0.mca");
+    fs::write(&corrupt_path, &region).unwrap();
+
+    let issues = RegionFile::new(corrupt_path.clone()).unwrap().verify();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].index, index);
+    assert_eq!(issues[0].chunk_coords, region_file.chunk_coords(index));
+    assert!(matches!(issues[0].kind, ChunkIssueKind::DecompressionFailed(_)));
+
+    // the file is only read
+    assert_eq!(fs::read(&corrupt_path).unwrap(), region);
+}
+
+#[test]
+fn region_repair() {
+    let region_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/resources/test_world/r.-1.0.mca");
+    let region_file = RegionFile::new(region_path.clone()).unwrap();
+    let present_chunks = region_file.present_chunks();
+
+    // break the length of the last chunk
+    let mut region = fs::read(&region_path).unwrap();
+    let index = *present_chunks.last().unwrap();
+    let offset = (u32::from_be_bytes([0, region[index * 4], region[index * 4 + 1], region[index * 4 + 2]]) * 4096) as usize;
+    region[offset..offset + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+    let corrupt_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("region_repair").join("r.-1.0.mca");
+    fs::create_dir_all(corrupt_path.parent().unwrap()).unwrap();
+    fs::write(&corrupt_path, &region).unwrap();
+
+    // repair in place
+    let report = RegionFile::new(corrupt_path.clone()).unwrap().repair(corrupt_path.clone()).unwrap();
+    assert_eq!(report.kept_chunks, present_chunks.len() - 1);
+    assert_eq!(report.dropped_chunks.len(), 1);
+    assert_eq!(report.dropped_chunks[0].index, index);
+    assert!(matches!(report.dropped_chunks[0].kind, ChunkIssueKind::InvalidLength { .. }));
+
+    let repaired_file = RegionFile::new(corrupt_path).unwrap();
+    assert!(repaired_file.verify().is_empty());
+    assert_eq!(repaired_file.present_chunks(), present_chunks[..present_chunks.len() - 1]);
+    assert_eq!(repaired_file.read_chunk(present_chunks[0]).unwrap(), region_file.read_chunk(present_chunks[0]).unwrap());
+}
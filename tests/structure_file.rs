@@ -0,0 +1,58 @@
+//! Tests the loading and the block search of the structure files (`.nbt`).
+use fastnbt::McWorldDescriptor;
+use fastnbt::nbt_tag::{self, NbtTag, NbtTagCompound, NbtTagInt, NbtTagList, NbtTagString, NbtTagType};
+use fastnbt::structure::Structure;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+fn int_list(name: &str, values: [i32; 3]) -> NbtTag {
+    let values = values.iter().map(|value| NbtTag::Int(NbtTagInt::new("".to_string(), *value))).collect();
+    NbtTag::List(NbtTagList::new(name.to_string(), NbtTagType::Int, values))
+}
+
+/// A 2 x 1 x 1 structure of a diamond block and a gold block.
+fn write_structure(file_path: &PathBuf) {
+    let palette = ["minecraft:diamond_block", "minecraft:gold_block"].iter().map(|name| {
+        let mut entry = NbtTagCompound::new("");
+        entry.values.insert("Name".to_string(), NbtTag::String(NbtTagString::new("Name".to_string(), name.to_string())));
+        NbtTag::Compound(entry)
+    }).collect();
+    let blocks = (0..2).map(|x| {
+        let mut block = NbtTagCompound::new("");
+        block.values.insert("pos".to_string(), int_list("pos", [x, 0, 0]));
+        block.values.insert("state".to_string(), NbtTag::Int(NbtTagInt::new("state".to_string(), x)));
+        NbtTag::Compound(block)
+    }).collect();
+
+    let mut root = NbtTagCompound::new("");
+    root.values.insert("size".to_string(), int_list("size", [2, 1, 1]));
+    root.values.insert("palette".to_string(), NbtTag::List(NbtTagList::new("palette".to_string(), NbtTagType::Compound, palette)));
+    root.values.insert("blocks".to_string(), NbtTag::List(NbtTagList::new("blocks".to_string(), NbtTagType::Compound, blocks)));
+    root.values.insert("entities".to_string(), NbtTag::List(NbtTagList::new("entities".to_string(), NbtTagType::End, vec![])));
+
+    let mut nbt = Vec::new();
+    nbt_tag::write(&mut nbt, &root);
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(&nbt).unwrap();
+    fs::write(file_path, encoder.finish().unwrap()).unwrap();
+}
+
+#[test]
+fn structure_file_search_blocks() {
+    let file_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("structure_file_search_blocks.nbt");
+    write_structure(&file_path);
+
+    let structure = Structure::read(&file_path).unwrap();
+    assert_eq!((structure.size().x, structure.size().y, structure.size().z), (2, 1, 1));
+    assert_eq!(structure.blocks().count(), 2);
+    assert_eq!(structure.entities().count(), 0);
+
+    // search_blocks works over the loaded structure files
+    let mc_world = McWorldDescriptor::new(file_path).unwrap();
+    let blocks_found = mc_world.search_blocks(vec!["minecraft:gold_block", "minecraft:stone"]);
+    let gold_blocks = &blocks_found["minecraft:gold_block"];
+    assert_eq!(gold_blocks.len(), 1);
+    assert_eq!((gold_blocks[0].coord.x, gold_blocks[0].coord.y, gold_blocks[0].coord.z), (1, 0, 0));
+    assert!(!blocks_found.contains_key("minecraft:stone"));
+}
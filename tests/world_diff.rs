@@ -0,0 +1,57 @@
+//! Tests the comparison of the chunks of two worlds.
+use fastnbt::{Dimension, McWorldDescriptor};
+use fastnbt::nbt_tag::{NbtTag, NbtTagLong};
+use fastnbt::world::{ChunkChange, ChunkChangeKind, DiffOptions};
+use std::path::PathBuf;
+
+fn load_test_region() -> McWorldDescriptor {
+    let region_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/resources/test_world/r.-1.0.mca");
+    McWorldDescriptor::new(region_path).unwrap()
+}
+
+fn chunk_position(mc_world: &McWorldDescriptor, index: usize) -> (i32, i32) {
+    let coordinates = fastnbt::chunk_format::get_chunk_coordinates(&mc_world.tag_compounds_list[index]);
+    (coordinates.x, coordinates.z)
+}
+
+fn set_long(mc_world: &mut McWorldDescriptor, index: usize, key: &str, value: i64) {
+    mc_world.tag_compounds_list[index].values.insert(key.to_string(), NbtTag::Long(NbtTagLong::new(key.to_string(), value)));
+}
+
+#[test]
+fn world_diff_chunks() {
+    let old_world = load_test_region();
+    let mut new_world = load_test_region();
+    assert!(old_world.diff_world(&new_world).is_empty());
+
+    // LastUpdate is ignored, a new key is a change and the last chunk is removed
+    set_long(&mut new_world, 0, "LastUpdate", 1);
+    set_long(&mut new_world, 1, "Changed", 1);
+    let changed_position = chunk_position(&new_world, 1);
+    let removed_position = chunk_position(&old_world, old_world.tag_compounds_list.len() - 1);
+    new_world.tag_compounds_list.pop();
+
+    let changes = old_world.diff_world(&new_world);
+    assert_eq!(changes.len(), 2);
+    assert!(changes.contains(&ChunkChange {
+        dimension: Dimension::Overworld, chunk_x: changed_position.0, chunk_z: changed_position.1,
+        kind: ChunkChangeKind::Changed, changed_paths: Vec::new(),
+    }));
+    assert!(changes.contains(&ChunkChange {
+        dimension: Dimension::Overworld, chunk_x: removed_position.0, chunk_z: removed_position.1,
+        kind: ChunkChangeKind::Removed, changed_paths: Vec::new(),
+    }));
+
+    // the other way around the chunk is added
+    let changes = new_world.diff_world(&old_world);
+    assert!(changes.iter().any(|change| change.kind == ChunkChangeKind::Added 
+        && (change.chunk_x, change.chunk_z) == removed_position));
+
+    let options = DiffOptions { changed_paths: true, ..DiffOptions::default() };
+    let changes = old_world.diff_world_with_options(&new_world, &options);
+    let changed = changes.iter().find(|change| change.kind == ChunkChangeKind::Changed).unwrap();
+    assert_eq!(changed.changed_paths, vec!["Changed"]);
+
+    let options = DiffOptions { ignored_keys: Vec::new(), ..DiffOptions::default() };
+    assert_eq!(old_world.diff_world_with_options(&new_world, &options).len(), 3);
+}
@@ -0,0 +1,132 @@
+//! Tests the loading of the Nether and the End of a world folder
+//! built from the test region files.
+mod common;
+
+use fastnbt::{Dimension, McWorldDescriptor, WorldLoadOptions};
+use std::path::PathBuf;
+use common::create_test_world;
+
+#[test]
+fn world_overworld_only_by_default() {
+    let world_path = create_test_world("world_overworld_only", &[Dimension::Overworld, Dimension::Nether], &[], None);
+
+    let mc_world = McWorldDescriptor::new(world_path).unwrap();
+    assert_eq!(mc_world.loaded_dimensions(), vec![Dimension::Overworld]);
+    assert_eq!(mc_world.dimension(Dimension::Overworld).len(), mc_world.tag_compounds_list.len());
+    assert!(mc_world.dimension(Dimension::Nether).is_empty());
+}
+
+#[test]
+fn world_all_dimensions() {
+    let world_path = create_test_world("world_all_dimensions", &[Dimension::Overworld, Dimension::Nether], &[], None);
+
+    let load_options = WorldLoadOptions { load_all_dimensions: true, ..Default::default() };
+    let mc_world = McWorldDescriptor::new_with_options(world_path, load_options).unwrap();
+
+    // the End folder does not exist and is skipped
+    assert_eq!(mc_world.loaded_dimensions(), vec![Dimension::Overworld, Dimension::Nether]);
+    let overworld_chunks = mc_world.dimension(Dimension::Overworld).len();
+    assert!(overworld_chunks > 0);
+    assert_eq!(mc_world.dimension(Dimension::Nether).len(), overworld_chunks);
+    assert!(mc_world.dimension(Dimension::End).is_empty());
+    assert_eq!(mc_world.tag_compounds_list.len(), 2 * overworld_chunks);
+
+    assert_eq!(mc_world.chunk_count(), 2 * overworld_chunks);
+    assert_eq!(mc_world.dimension_chunk_count(Dimension::Nether), overworld_chunks);
+    assert_eq!(mc_world.dimension_chunk_count(Dimension::End), 0);
+
+    // editing the list moves the compounds, the dimensions are still found by their source
+    let mut edited_world = mc_world.clone();
+    let nether_chunk = edited_world.tag_compounds_list.pop().unwrap();
+    edited_world.tag_compounds_list.insert(0, nether_chunk);
+    edited_world.tag_compounds_list.pop();
+    assert_eq!(edited_world.loaded_dimensions(), vec![Dimension::Nether, Dimension::Overworld]);
+    assert_eq!(edited_world.dimension(Dimension::Nether).len(), overworld_chunks - 1);
+    assert_eq!(edited_world.dimension(Dimension::Overworld).len(), overworld_chunks);
+    edited_world.tag_compounds_list.retain(|sourced_compound| sourced_compound.dimension != Dimension::Overworld);
+    assert!(edited_world.dimension(Dimension::Overworld).is_empty());
+    assert_eq!(edited_world.loaded_dimensions(), vec![Dimension::Nether]);
+
+    // a single .nbt file holds no chunk
+    let nbt_world = McWorldDescriptor::new(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/resources/bigtest.nbt")).unwrap();
+    assert_eq!(nbt_world.tag_compounds_list.len(), 1);
+    assert_eq!(nbt_world.chunk_count(), 0);
+}
+
+#[test]
+fn world_single_file_dimension() {
+    let world_path = create_test_world("world_single_file", &[Dimension::Overworld, Dimension::Nether], &[], None);
+
+    let mc_world = McWorldDescriptor::new(Dimension::Nether.region_path(&world_path).join("r.-1.0.mca")).unwrap();
+    assert_eq!(mc_world.loaded_dimensions(), vec![Dimension::Nether]);
+    assert_eq!(mc_world.dimension(Dimension::Nether).len(), mc_world.tag_compounds_list.len());
+}
+
+#[test]
+fn world_compound_sources() {
+    let world_path = create_test_world("world_compound_sources", &[Dimension::Overworld, Dimension::Nether], &[], None);
+
+    let load_options = WorldLoadOptions { load_all_dimensions: true, ..Default::default() };
+    let mc_world = McWorldDescriptor::new_with_options(world_path.clone(), load_options.clone()).unwrap();
+
+    let first_nether_index = mc_world.dimension(Dimension::Overworld).len();
+    assert_eq!(mc_world.compound_dimension(0), Some(Dimension::Overworld));
+    assert_eq!(mc_world.compound_dimension(first_nether_index), Some(Dimension::Nether));
+    assert_eq!(mc_world.compound_source(first_nether_index).unwrap().region_coords, Some((-1, 0)));
+    assert_eq!(mc_world.compound_dimension(mc_world.tag_compounds_list.len()), None);
+
+    let sourced_compounds = McWorldDescriptor::load_sourced_compounds(world_path, &load_options).unwrap();
+    assert_eq!(sourced_compounds.len(), mc_world.tag_compounds_list.len());
+    assert!(sourced_compounds.iter().all(|sourced_compound| sourced_compound.region_coords == Some((-1, 0))));
+    assert_eq!(sourced_compounds.iter().filter(|sourced_compound| sourced_compound.dimension == Dimension::Nether).count(), 
+               mc_world.dimension(Dimension::Nether).len());
+}
+
+#[test]
+fn world_compound_source_files() {
+    let world_path = create_test_world("world_compound_source_files", &[Dimension::Overworld, Dimension::Nether], &[], None);
+    let overworld_file = Dimension::Overworld.region_path(&world_path).join("r.-1.0.mca");
+    let nether_file = Dimension::Nether.region_path(&world_path).join("r.-1.0.mca");
+
+    let load_options = WorldLoadOptions { load_all_dimensions: true, ..Default::default() };
+    let mc_world = McWorldDescriptor::new_with_options(world_path.clone(), load_options.clone()).unwrap();
+    assert_eq!(mc_world.source_files(), vec![overworld_file.as_path(), nether_file.as_path()]);
+
+    let first_nether_index = mc_world.dimension(Dimension::Overworld).len();
+    let source = mc_world.compound_source(first_nether_index).unwrap();
+    assert_eq!(source.file_path.as_deref(), Some(nether_file.as_path()));
+    let coordinates = fastnbt::chunk_format::get_chunk_coordinates(&mc_world.tag_compounds_list[first_nether_index]);
+    assert_eq!(source.chunk_coords, Some((coordinates.x, coordinates.z)));
+    assert_eq!(fastnbt::world::chunk_to_region_coords(coordinates.x, coordinates.z), (-1, 0));
+
+    // the lazy chunks keep their source once decoded
+    let load_options = WorldLoadOptions { load_mode: fastnbt::LoadMode::Lazy, ..load_options };
+    let mut lazy_world = McWorldDescriptor::new_with_options(world_path, load_options).unwrap();
+    lazy_world.decode_all().unwrap();
+    assert_eq!(lazy_world.source_files(), mc_world.source_files());
+    assert!((0..lazy_world.tag_compounds_list.len()).all(|index| lazy_world.compound_source(index).unwrap().chunk_coords.is_some()));
+}
+
+#[test]
+fn world_as_tag() {
+    let world_path = create_test_world("world_as_tag", &[Dimension::Overworld, Dimension::Nether], &[], None);
+
+    let load_options = WorldLoadOptions { load_all_dimensions: true, ..Default::default() };
+    let mc_world = McWorldDescriptor::new_with_options(world_path, load_options).unwrap();
+    let world_tag = mc_world.as_tag();
+    let world_compound = world_tag.compound_as_ref().unwrap();
+
+    let mut dimension_names: Vec<&str> = world_compound.keys().map(|name| name.as_str()).collect();
+    dimension_names.sort();
+    assert_eq!(dimension_names, vec!["minecraft:overworld", "minecraft:the_nether"]);
+
+    let overworld = world_compound.values["minecraft:overworld"].compound_as_ref().unwrap();
+    assert_eq!(overworld.values.len(), mc_world.dimension(Dimension::Overworld).len());
+    let (chunk_x, chunk_z) = mc_world.compound_source(0).unwrap().chunk_coords.unwrap();
+    let chunk_key = format!("{},{}", chunk_x, chunk_z);
+    let chunk_tag = world_tag.get_path(&format!("minecraft:the_nether/{}", chunk_key)).unwrap();
+    assert_eq!(chunk_tag.name(), chunk_key);
+
+    let data_versions = world_tag.query("minecraft:overworld/*/DataVersion").unwrap();
+    assert_eq!(data_versions.len(), overworld.values.len());
+}
@@ -0,0 +1,173 @@
+//! Tests a descriptor built in memory from generated chunks.
+use fastnbt::{chunk_format, CompoundSource, Dimension, McWorldDescriptor, SourcedCompound};
+use fastnbt::blocks::Coordinates;
+use fastnbt::nbt_tag::*;
+
+/// A chunk with a single section at y = 0, all air but one diamond block at the local position (3, 2, 4).
+fn diamond_chunk(chunk_x: i32, chunk_z: i32) -> NbtTagCompound {
+    let palette_tags = ["minecraft:air", "minecraft:diamond_block"].iter().map(|name| {
+        let mut entry = NbtTagCompound::new("");
+        entry.values.insert("Name".to_string(), NbtTag::String(NbtTagString::new("Name".to_string(), name.to_string())));
+        NbtTag::Compound(entry)
+    }).collect();
+    let mut palette_ids = vec![0u16; chunk_format::SECTION_BLOCKS_NUM];
+    palette_ids[2 * 256 + 4 * 16 + 3] = 1;
+
+    let mut block_states = NbtTagCompound::new("block_states");
+    block_states.values.insert("palette".to_string(), NbtTag::List(NbtTagList::new("palette".to_string(), NbtTagType::Compound, palette_tags)));
+    let data = chunk_format::pack_block_states(&palette_ids, 2, chunk_format::BLOCK_STATES_DATA_VERSION);
+    block_states.values.insert("data".to_string(), NbtTag::LongArray(NbtTagLongArray::new("data".to_string(), data)));
+
+    let mut section = NbtTagCompound::new("");
+    section.values.insert("Y".to_string(), NbtTag::Byte(NbtTagByte::new("Y".to_string(), 0)));
+    section.values.insert("block_states".to_string(), NbtTag::Compound(block_states));
+
+    let mut chunk = NbtTagCompound::new("");
+    for (name, value) in [("xPos", chunk_x), ("zPos", chunk_z), ("DataVersion", 3465)] {
+        chunk.values.insert(name.to_string(), NbtTag::Int(NbtTagInt::new(name.to_string(), value)));
+    }
+    chunk.values.insert("sections".to_string(), NbtTag::List(NbtTagList::new("sections".to_string(), NbtTagType::Compound, vec![NbtTag::Compound(section)])));
+    chunk
+}
+
+#[test]
+fn world_from_compounds() {
+    let mc_world = McWorldDescriptor::from_compounds(vec![diamond_chunk(0, 0), diamond_chunk(1, -1)], "1.20.1".to_string());
+
+    assert_eq!(mc_world.get_mc_version(), "1.20.1");
+    assert_eq!(mc_world.tag_compounds_list.len(), 2);
+    assert_eq!(mc_world.compound_dimension(1), Some(Dimension::Overworld));
+    assert_eq!(mc_world.dimension(Dimension::Overworld).len(), 2);
+    assert!(mc_world.metadata().is_none());
+
+    let found = mc_world.search_blocks(vec!["minecraft:diamond_block".to_string()]);
+    let mut coords: Vec<(i32, i32, i32)> = found["minecraft:diamond_block"].iter()
+        .map(|block| (block.coord.x, block.coord.y, block.coord.z))
+        .collect();
+    coords.sort();
+    assert_eq!(coords, vec![(3, 2, 4), (19, 2, -12)]);
+
+    let chunk = mc_world.chunk_at(Dimension::Overworld, 1, -1).unwrap().unwrap();
+    assert_eq!(chunk, &*mc_world.tag_compounds_list[1]);
+}
+
+/// A chunk with the structures starting in it, with their bounding box, and the references to the structures
+/// started in other chunks.
+fn structures_chunk(chunk_x: i32, chunk_z: i32, starts: &[(&str, [i32; 6])], references: &[(&str, (i32, i32))]) -> NbtTagCompound {
+    let mut starts_compound = NbtTagCompound::new("starts");
+    for (id, bounding_box) in starts {
+        let mut start = NbtTagCompound::new(id);
+        start.values.insert("id".to_string(), NbtTag::String(NbtTagString::new("id".to_string(), id.to_string())));
+        start.values.insert("ChunkX".to_string(), NbtTag::Int(NbtTagInt::new("ChunkX".to_string(), chunk_x)));
+        start.values.insert("ChunkZ".to_string(), NbtTag::Int(NbtTagInt::new("ChunkZ".to_string(), chunk_z)));
+        start.values.insert("BB".to_string(), NbtTag::IntArray(NbtTagIntArray::new("BB".to_string(), bounding_box.to_vec())));
+        starts_compound.values.insert(id.to_string(), NbtTag::Compound(start));
+    }
+    let mut references_compound = NbtTagCompound::new("References");
+    for (id, (start_x, start_z)) in references {
+        let packed_position = ((*start_z as i64) << 32) | (*start_x as u32 as i64);
+        references_compound.values.insert(id.to_string(), NbtTag::LongArray(NbtTagLongArray::new(id.to_string(), vec![packed_position])));
+    }
+
+    let mut structures = NbtTagCompound::new("structures");
+    structures.values.insert("starts".to_string(), NbtTag::Compound(starts_compound));
+    structures.values.insert("References".to_string(), NbtTag::Compound(references_compound));
+
+    let mut chunk = diamond_chunk(chunk_x, chunk_z);
+    chunk.values.insert("structures".to_string(), NbtTag::Compound(structures));
+    chunk
+}
+
+#[test]
+fn world_nearest_structure() {
+    let village_box = [100, 64, 20, 140, 80, 60];
+    let mc_world = McWorldDescriptor::from_compounds(vec![
+        structures_chunk(6, 1, &[("minecraft:village_plains", village_box)], &[]),
+        structures_chunk(-3, 0, &[("minecraft:mineshaft", [-48, 10, 0, -20, 40, 30])], &[]),
+        // the start chunk (-20, -20) of this village is not loaded
+        structures_chunk(-19, -20, &[], &[("minecraft:village_plains", (-20, -20)), ("minecraft:mineshaft", (-3, 0))]),
+    ], "1.20.1".to_string());
+
+    let origin = Coordinates::new(vec![0, 70, 0]);
+    assert_eq!(mc_world.nearest_structure("minecraft:village_plains", origin.clone()), 
+               Some(("minecraft:village_plains".to_string(), Coordinates::new(vec![104, 64, 24]))));
+    assert_eq!(mc_world.nearest_structure("minecraft:mineshaft", origin.clone()), 
+               Some(("minecraft:mineshaft".to_string(), Coordinates::new(vec![-40, 10, 8]))));
+
+    let far_origin = Coordinates::new(vec![-300, 70, -300]);
+    assert_eq!(mc_world.nearest_structure("minecraft:village_plains", far_origin), 
+               Some(("minecraft:village_plains".to_string(), Coordinates::new(vec![-312, 70, -312]))));
+    assert_eq!(mc_world.nearest_structure("minecraft:stronghold", origin.clone()), None);
+
+    // a structure of another dimension, closer to the origin, is only found in its dimension
+    let mut mc_world = mc_world;
+    let nether_source = CompoundSource { dimension: Dimension::Nether, chunk_coords: Some((0, 0)), ..CompoundSource::default() };
+    let nether_chunk = structures_chunk(0, 0, &[("minecraft:village_plains", [0, 32, 0, 16, 40, 16])], &[]);
+    mc_world.tag_compounds_list.push(SourcedCompound::new(nether_source, nether_chunk));
+    assert_eq!(mc_world.nearest_structure("minecraft:village_plains", origin.clone()), 
+               Some(("minecraft:village_plains".to_string(), Coordinates::new(vec![104, 64, 24]))));
+    assert_eq!(mc_world.nearest_structure_in(Dimension::Nether, "minecraft:village_plains", origin.clone()), 
+               Some(("minecraft:village_plains".to_string(), Coordinates::new(vec![8, 32, 8]))));
+    assert_eq!(mc_world.nearest_structure_in(Dimension::End, "minecraft:village_plains", origin), None);
+}
+
+/// An entity with its id, its position and the given tags.
+fn entity(id: &str, pos: [f64; 3], tags: Vec<NbtTag>) -> NbtTag {
+    let mut entity = NbtTagCompound::new("");
+    entity.values.insert("id".to_string(), NbtTag::String(NbtTagString::new("id".to_string(), id.to_string())));
+    let pos_list = pos.iter().map(|value| NbtTag::Double(NbtTagDouble::new(String::new(), *value))).collect();
+    entity.values.insert("Pos".to_string(), NbtTag::List(NbtTagList::new("Pos".to_string(), NbtTagType::Double, pos_list)));
+    for tag in tags {
+        entity.values.insert(tag.name().to_string(), tag);
+    }
+    NbtTag::Compound(entity)
+}
+
+#[test]
+fn world_find_entities_where() {
+    // an entities region chunk (since 1.17): the Entities list at the root
+    let mut entities_chunk = NbtTagCompound::new("");
+    entities_chunk.values.insert("Position".to_string(), NbtTag::IntArray(NbtTagIntArray::new("Position".to_string(), vec![0, 0])));
+    let entities = vec![
+        entity("minecraft:zombie", [1.5, 64.0, 2.5], vec![NbtTag::Float(NbtTagFloat::new("Health".to_string(), 20.0))]),
+        entity("minecraft:wolf", [3.5, 64.0, 2.5], vec![
+            NbtTag::Float(NbtTagFloat::new("Health".to_string(), 8.0)),
+            NbtTag::String(NbtTagString::new("CustomName".to_string(), "{\"text\":\"Rex\"}".to_string())),
+        ]),
+        entity("minecraft:pig", [5.5, 64.0, 2.5], vec![NbtTag::Double(NbtTagDouble::new("Health".to_string(), 20.0))]),
+    ];
+    entities_chunk.values.insert("Entities".to_string(), NbtTag::List(NbtTagList::new("Entities".to_string(), NbtTagType::Compound, entities)));
+    let mc_world = McWorldDescriptor::from_compounds(vec![diamond_chunk(0, 0), entities_chunk], "1.20.1".to_string());
+
+    let named = mc_world.find_entities_where(|entity| entity.values.contains_key("CustomName"));
+    assert_eq!(named.len(), 1);
+    assert_eq!(named[0].values["id"], NbtTag::String(NbtTagString::new("id".to_string(), "minecraft:wolf".to_string())));
+
+    // Float and Double health alike
+    let full_health = mc_world.find_entities_where(|entity| entity.values.get("Health").is_some_and(|health| health.numeric_eq(20.0)));
+    assert_eq!(full_health.len(), 2);
+
+    assert_eq!(mc_world.find_entities_where(|_| true).len(), 3);
+    assert!(mc_world.find_entities_where(|entity| entity.values.contains_key("Owner")).is_empty());
+}
+
+#[test]
+fn world_search_by_tag() {
+    let mut chunk = diamond_chunk(1, 0);
+    for (x, y, z, block_name) in [(1, 1, 1, "minecraft:deepslate_iron_ore"), (2, 1, 1, "minecraft:coal_ore"), (5, 5, 5, "minecraft:oak_log")] {
+        assert!(chunk_format::set_block(&mut chunk, x, y, z, block_name));
+    }
+    let mc_world = McWorldDescriptor::from_compounds(vec![chunk], "1.20.1".to_string());
+
+    let ores = mc_world.search_by_tag("ores");
+    let mut ore_names: Vec<&String> = ores.iter().filter(|(_, blocks)| !blocks.is_empty()).map(|(name, _)| name).collect();
+    ore_names.sort();
+    assert_eq!(ore_names, vec!["minecraft:coal_ore", "minecraft:deepslate_iron_ore"]);
+    assert_eq!(ores["minecraft:coal_ore"][0].coord, Coordinates::new(vec![18, 1, 1]));
+
+    let logs = mc_world.search_by_tag("minecraft:logs");
+    assert_eq!(logs.values().map(|blocks| blocks.len()).sum::<usize>(), 1);
+    assert_eq!(logs["minecraft:oak_log"][0].coord, Coordinates::new(vec![21, 5, 5]));
+
+    assert!(mc_world.search_by_tag("minecraft:unknown").is_empty());
+}
@@ -0,0 +1,43 @@
+//! Tests the loading of a world keeping only some tags of the chunks.
+mod common;
+
+use fastnbt::{Dimension, LoadMode, McWorldDescriptor, WorldLoadOptions};
+use fastnbt::blocks::MinecraftBlock;
+use fastnbt::nbt_tag::{NbtTag, NbtTagInt};
+use std::collections::HashMap;
+use common::create_test_world;
+
+#[test]
+fn world_keep_only_blocks() {
+    let world_path = create_test_world("world_keep_only_blocks", &[Dimension::Overworld], &[], None);
+    let full_world = McWorldDescriptor::new(world_path.clone()).unwrap();
+
+    let keep_only = vec!["block_states".to_string(), "Y".to_string()];
+    for load_mode in [LoadMode::Eager, LoadMode::Lazy] {
+        let load_options = WorldLoadOptions { keep_only: keep_only.clone(), load_mode, ..Default::default() };
+        let mut pruned_world = McWorldDescriptor::new_with_options(world_path.clone(), load_options).unwrap();
+        pruned_world.decode_all().unwrap();
+        assert_eq!(pruned_world.chunk_count(), full_world.chunk_count());
+
+        // everything but the blocks is dropped, the chunk position comes from the region slot
+        let chunk = &pruned_world.tag_compounds_list[0];
+        let chunk_keys: Vec<&str> = chunk.keys().map(|key| key.as_str()).collect();
+        assert_eq!(chunk_keys, vec!["sections"]);
+        assert!(chunk.chunk_coords.is_some());
+
+        let blocks = vec!["minecraft:bedrock"];
+        let pruned_found = pruned_world.search_blocks(blocks.clone());
+        let full_found = full_world.search_blocks(blocks);
+        assert!(!full_found["minecraft:bedrock"].is_empty());
+        let block_coords = |found: &HashMap<String, Vec<MinecraftBlock>>| {
+            let mut coords: Vec<(i32, i32, i32)> = found["minecraft:bedrock"].iter().map(|block| (block.coord.x, block.coord.y, block.coord.z)).collect();
+            coords.sort();
+            coords
+        };
+        assert_eq!(block_coords(&pruned_found), block_coords(&full_found));
+
+        // the pruned chunks would overwrite the full ones
+        pruned_world.chunk_handle(0).unwrap().set("Edited", NbtTag::Int(NbtTagInt::new(String::new(), 1)));
+        assert!(pruned_world.save().is_err());
+    }
+}
@@ -0,0 +1,52 @@
+//! Tests the lazy loading of a world, decoding the chunks on first access.
+mod common;
+
+use fastnbt::{Dimension, LoadMode, McWorldDescriptor, WorldLoadOptions};
+use common::create_test_world;
+
+#[test]
+fn world_lazy_chunk_at() {
+    let world_path = create_test_world("world_lazy_chunk_at", &[Dimension::Overworld, Dimension::Nether], &[], None);
+    let eager_world = McWorldDescriptor::new(world_path.clone()).unwrap();
+
+    let load_options = WorldLoadOptions { load_mode: LoadMode::Lazy, load_all_dimensions: true, ..Default::default() };
+    let lazy_world = McWorldDescriptor::new_with_options(world_path, load_options).unwrap();
+    assert!(lazy_world.is_lazy());
+    assert!(lazy_world.tag_compounds_list.is_empty());
+    assert_eq!(lazy_world.lazy_chunks().len(), 2 * eager_world.tag_compounds_list.len());
+    assert_eq!(lazy_world.lazy_chunks().decoded_num(), 0);
+
+    let (chunk_x, chunk_z) = lazy_world.lazy_chunks().chunk_coords(Dimension::Overworld)[0];
+    let lazy_chunk = lazy_world.chunk_at(Dimension::Overworld, chunk_x, chunk_z).unwrap();
+    assert!(lazy_chunk.is_some());
+    assert_eq!(lazy_chunk, eager_world.chunk_at(Dimension::Overworld, chunk_x, chunk_z).unwrap());
+    assert_eq!(lazy_world.lazy_chunks().decoded_num(), 1);
+
+    assert!(eager_world.chunk_at(Dimension::Nether, chunk_x, chunk_z).unwrap().is_none());
+    assert!(lazy_world.chunk_at(Dimension::Nether, chunk_x, chunk_z).unwrap().is_some());
+}
+
+#[test]
+fn world_lazy_decode_all() {
+    let world_path = create_test_world("world_lazy_decode_all", &[Dimension::Overworld, Dimension::Nether], &[], None);
+    let load_options = WorldLoadOptions { load_all_dimensions: true, skip_non_full_chunks: true, ..Default::default() };
+    let eager_world = McWorldDescriptor::new_with_options(world_path.clone(), load_options.clone()).unwrap();
+
+    let load_options = WorldLoadOptions { load_mode: LoadMode::Lazy, ..load_options };
+    let mut lazy_world = McWorldDescriptor::new_with_options(world_path, load_options).unwrap();
+    let (chunk_x, chunk_z) = lazy_world.lazy_chunks().chunk_coords(Dimension::Nether)[0];
+    lazy_world.chunk_at(Dimension::Nether, chunk_x, chunk_z).unwrap();
+    assert_eq!(lazy_world.chunk_count(), lazy_world.lazy_chunks().len());
+
+    lazy_world.decode_all().unwrap();
+    assert_eq!(lazy_world.chunk_count(), eager_world.chunk_count());
+    assert!(!lazy_world.is_lazy());
+    assert_eq!(lazy_world.tag_compounds_list.len(), eager_world.tag_compounds_list.len());
+    assert_eq!(lazy_world.loaded_dimensions(), vec![Dimension::Overworld, Dimension::Nether]);
+    assert_eq!(lazy_world.dimension(Dimension::Nether).len(), eager_world.dimension(Dimension::Nether).len());
+
+    let blocks = vec!["minecraft:bedrock"];
+    let lazy_found = lazy_world.search_blocks(blocks.clone());
+    let eager_found = eager_world.search_blocks(blocks);
+    assert_eq!(lazy_found["minecraft:bedrock"].len(), eager_found["minecraft:bedrock"].len());
+}
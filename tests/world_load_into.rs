@@ -0,0 +1,54 @@
+//! Tests the incremental loading of several files and worlds into one descriptor.
+mod common;
+
+use fastnbt::{Dimension, McWorldDescriptor, UNKNOWN_VERSION};
+use std::path::PathBuf;
+use common::create_test_world;
+
+#[test]
+fn world_load_into_region_files() {
+    let world_path = create_test_world("world_load_into_region_files", &[Dimension::Overworld, Dimension::Nether], &[], Some("1.20.1"));
+    let nether_region_path = Dimension::Nether.region_path(&world_path).join("r.-1.0.mca");
+    let overworld_region_path = Dimension::Overworld.region_path(&world_path).join("r.-1.0.mca");
+
+    let mut mc_world = McWorldDescriptor::new(nether_region_path).unwrap();
+    let region_chunks_num = mc_world.tag_compounds_list.len();
+    assert_eq!(mc_world.get_mc_version(), UNKNOWN_VERSION);
+
+    assert_eq!(mc_world.load_into(overworld_region_path).unwrap(), region_chunks_num);
+    assert_eq!(mc_world.tag_compounds_list.len(), 2 * region_chunks_num);
+    // the compounds of each dimension stay together, in the dimensions order
+    assert_eq!(mc_world.loaded_dimensions(), vec![Dimension::Overworld, Dimension::Nether]);
+    assert_eq!(mc_world.dimension(Dimension::Overworld).len(), region_chunks_num);
+    assert_eq!(mc_world.compound_source(region_chunks_num).map(|source| source.region_coords), Some(Some((-1, 0))));
+
+    // a plain nbt file
+    let bigtest_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/resources/bigtest.nbt");
+    assert_eq!(mc_world.load_into(bigtest_path).unwrap(), 1);
+    assert_eq!(mc_world.dimension(Dimension::Overworld).len(), region_chunks_num + 1);
+
+    // a failed load leaves the descriptor unchanged
+    assert!(mc_world.load_into(world_path.join("missing.mca")).is_err());
+    assert_eq!(mc_world.tag_compounds_list.len(), 2 * region_chunks_num + 1);
+}
+
+#[test]
+fn world_load_into_metadata() {
+    let first_world_path = create_test_world("world_load_into_first", &[Dimension::Overworld, Dimension::Nether], &[], Some("1.20.1"));
+    let second_world_path = create_test_world("world_load_into_second", &[Dimension::Overworld, Dimension::Nether], &[], Some("1.19.4"));
+
+    // the metadata come from the first world with a level.dat
+    let mut mc_world = McWorldDescriptor::new(Dimension::Overworld.region_path(&first_world_path).join("r.-1.0.mca")).unwrap();
+    let region_chunks_num = mc_world.tag_compounds_list.len();
+    assert!(mc_world.metadata().is_none());
+    mc_world.load_into(first_world_path).unwrap();
+    assert_eq!(mc_world.get_mc_version(), "1.20.1");
+    assert_eq!(mc_world.metadata().and_then(|metadata| metadata.level_name()), Some("world_load_into_first"));
+
+    // and are kept when another world is added
+    mc_world.load_into(second_world_path).unwrap();
+    assert_eq!(mc_world.get_mc_version(), "1.20.1");
+    assert_eq!(mc_world.metadata().and_then(|metadata| metadata.level_name()), Some("world_load_into_first"));
+    // only the Overworld of the worlds is loaded by default
+    assert_eq!(mc_world.tag_compounds_list.len(), 3 * region_chunks_num);
+}
@@ -0,0 +1,102 @@
+//! Tests the reading of level.dat, alone or with the chunks of a world folder.
+mod common;
+
+use fastnbt::{Dimension, Edition, McWorldDescriptor, SessionLockCheck, WorldLoadOptions};
+use fastnbt::level_dat::{ICON_FILE_NAME, LEVEL_DAT_FILE_NAME, SESSION_LOCK_FILE_NAME};
+use std::fs;
+use std::path::PathBuf;
+use common::create_test_world;
+
+#[test]
+fn world_open_metadata_only() {
+    let world_path = create_test_world("world_open_metadata_only", &[Dimension::Overworld], &[], Some("1.20.1"));
+
+    let mc_world = McWorldDescriptor::open_metadata_only(world_path).unwrap();
+    assert!(mc_world.tag_compounds_list.is_empty());
+    assert_eq!(mc_world.metadata().and_then(|metadata| metadata.level_name()), Some("world_open_metadata_only"));
+    assert_eq!(mc_world.get_mc_version(), "1.20.1");
+}
+
+#[test]
+fn world_load_with_metadata() {
+    let world_path = create_test_world("world_load_with_metadata", &[Dimension::Overworld], &[], Some("1.20.1"));
+
+    let mc_world = McWorldDescriptor::new(world_path.clone()).unwrap();
+    assert!(!mc_world.tag_compounds_list.is_empty());
+    assert_eq!(mc_world.metadata().and_then(|metadata| metadata.level_name()), Some("world_load_with_metadata"));
+    assert_eq!(mc_world.get_mc_version(), "1.20.1");
+
+    // level.dat is optional for the chunks
+    fs::remove_file(world_path.join(LEVEL_DAT_FILE_NAME)).unwrap();
+    let mc_world = McWorldDescriptor::new(world_path.clone()).unwrap();
+    assert!(mc_world.metadata().is_none());
+    assert!(McWorldDescriptor::open_metadata_only(world_path).is_err());
+}
+
+#[test]
+fn world_summary() {
+    let world_path = create_test_world("world_summary", &[Dimension::Overworld], &[], Some("1.20.1"));
+    fs::write(world_path.join(ICON_FILE_NAME), [0x89, b'P', b'N', b'G']).unwrap();
+
+    let summary = McWorldDescriptor::open_metadata_only(world_path.clone()).unwrap().world_summary().unwrap();
+    assert_eq!(summary.name, "world_summary");
+    assert_eq!(summary.version_name.as_deref(), Some("1.20.1"));
+    assert_eq!(summary.seed, None);
+    assert!(summary.has_icon);
+
+    // the level.dat file can be given directly too
+    let summary = McWorldDescriptor::open_metadata_only(world_path.join(LEVEL_DAT_FILE_NAME)).unwrap().world_summary().unwrap();
+    assert_eq!(summary.name, "world_summary");
+    assert!(summary.has_icon);
+
+    fs::remove_file(world_path.join(LEVEL_DAT_FILE_NAME)).unwrap();
+    let error = McWorldDescriptor::new(world_path).unwrap().world_summary().unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn world_load_checking_session_lock() {
+    let world_path = create_test_world("world_load_checking_session_lock", &[Dimension::Overworld], &[], Some("1.20.1"));
+    // the session.lock of a closed game is not locked
+    fs::write(world_path.join(SESSION_LOCK_FILE_NAME), [0xE2, 0x98, 0x83]).unwrap();
+
+    let load_options = WorldLoadOptions { session_lock_check: SessionLockCheck::Fail, ..Default::default() };
+    let mc_world = McWorldDescriptor::new_with_options(world_path, load_options).unwrap();
+    assert!(!mc_world.tag_compounds_list.is_empty());
+}
+
+#[test]
+fn world_edition() {
+    let world_path = create_test_world("world_edition_java", &[Dimension::Overworld], &[], Some("1.20.1"));
+    assert_eq!(Edition::detect(&world_path), Edition::Java);
+    assert_eq!(Edition::detect(&world_path.join("region/r.-1.0.mca")), Edition::Java);
+    assert_eq!(McWorldDescriptor::new(world_path).unwrap().edition(), Edition::Java);
+
+    // a Bedrock world: the chunks in a LevelDB database and a little-endian level.dat after an 8 bytes header
+    let world_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("world_edition_bedrock");
+    let _ = fs::remove_dir_all(&world_path);
+    fs::create_dir_all(world_path.join("db")).unwrap();
+
+    let mut nbt = vec![10, 0, 0, 8, 9, 0];
+    nbt.extend(b"LevelName");
+    nbt.extend(7u16.to_le_bytes());
+    nbt.extend(b"Bedrock");
+    nbt.push(0);
+    let mut level_dat = 10i32.to_le_bytes().to_vec();
+    level_dat.extend((nbt.len() as u32).to_le_bytes());
+    level_dat.extend(nbt);
+    fs::write(world_path.join(LEVEL_DAT_FILE_NAME), level_dat).unwrap();
+
+    assert_eq!(Edition::detect(&world_path.join(LEVEL_DAT_FILE_NAME)), Edition::Bedrock);
+    fs::remove_dir(world_path.join("db")).unwrap();
+    assert_eq!(Edition::detect(&world_path), Edition::Bedrock);
+    fs::create_dir(world_path.join("db")).unwrap();
+
+    // only level.dat is read
+    let mc_world = McWorldDescriptor::new(world_path).unwrap();
+    assert_eq!(mc_world.edition(), Edition::Bedrock);
+    assert!(mc_world.tag_compounds_list.is_empty());
+    assert_eq!(mc_world.metadata().and_then(|metadata| metadata.level_name()), Some("Bedrock"));
+
+    assert_eq!(McWorldDescriptor::from_compounds(Vec::new(), String::new()).edition(), Edition::Unknown);
+}
@@ -0,0 +1,36 @@
+//! Tests the loading of only the first compounds of a world, as a preview.
+mod common;
+
+use fastnbt::{Dimension, LoadMode, McWorldDescriptor, WorldLoadOptions};
+use fastnbt::chunk_format;
+use common::create_test_world;
+
+#[test]
+fn world_max_compounds() {
+    let world_path = create_test_world("world_max_compounds", &[Dimension::Overworld, Dimension::Nether], &[], None);
+    let full_world = McWorldDescriptor::new(world_path.clone()).unwrap();
+    let region_chunks_num = full_world.tag_compounds_list.len();
+
+    // the first chunks of the Overworld region, the Nether is not read
+    let load_options = WorldLoadOptions { load_all_dimensions: true, max_compounds: Some(10), ..Default::default() };
+    let preview_world = McWorldDescriptor::new_with_options(world_path.clone(), load_options).unwrap();
+    assert_eq!(preview_world.tag_compounds_list, full_world.tag_compounds_list[..10]);
+    assert_eq!(preview_world.loaded_dimensions(), vec![Dimension::Overworld]);
+
+    // the chunks not fully generated do not count
+    let full_chunks: Vec<_> = full_world.tag_compounds_list.iter().filter(|chunk| chunk_format::is_chunk_fully_generated(chunk)).cloned().collect();
+    let load_options = WorldLoadOptions { skip_non_full_chunks: true, max_compounds: Some(full_chunks.len()), ..Default::default() };
+    let preview_world = McWorldDescriptor::new_with_options(world_path.clone(), load_options).unwrap();
+    assert_eq!(preview_world.tag_compounds_list, full_chunks);
+
+    // the lazy region files are not cut, but the next files are not read
+    let load_options = WorldLoadOptions { load_all_dimensions: true, max_compounds: Some(10), load_mode: LoadMode::Lazy, ..Default::default() };
+    let preview_world = McWorldDescriptor::new_with_options(world_path.clone(), load_options).unwrap();
+    assert_eq!(preview_world.chunk_count(), region_chunks_num);
+    assert_eq!(preview_world.dimension_chunk_count(Dimension::Nether), 0);
+
+    // a cap above the world size loads everything
+    let load_options = WorldLoadOptions { load_all_dimensions: true, max_compounds: Some(10 * region_chunks_num), ..Default::default() };
+    let preview_world = McWorldDescriptor::new_with_options(world_path, load_options).unwrap();
+    assert_eq!(preview_world.chunk_count(), 2 * region_chunks_num);
+}
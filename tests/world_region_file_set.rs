@@ -0,0 +1,79 @@
+//! Tests the random access to the chunks of a world folder
+//! built from the test region file.
+mod common;
+
+use fastnbt::chunk_format;
+use fastnbt::world::{RegionFileSet, World};
+use fastnbt::{Dimension, McWorldDescriptor};
+use common::create_test_world;
+
+#[test]
+fn world_chunk_at_block() {
+    let world_path = create_test_world("world_chunk_at_block", &[Dimension::Overworld], &["r.0.0.mca"], None);
+    let region_file_set = RegionFileSet::new(world_path.clone());
+
+    let region_file = region_file_set.open_region(-1, 0).unwrap().unwrap();
+    let index = region_file.present_chunks()[0];
+    let (chunk_x, chunk_z) = region_file.chunk_coords(index).unwrap();
+
+    // any block of the chunk resolves to the same chunk
+    for (offset_x, offset_z) in [(0, 0), (15, 15), (7, 3)] {
+        let chunk = region_file_set.chunk_at_block(chunk_x * 16 + offset_x, chunk_z * 16 + offset_z).unwrap().unwrap();
+        let chunk_pos = chunk_format::get_chunk_coordinates(&chunk);
+        assert_eq!((chunk_pos.x, chunk_pos.z), (chunk_x, chunk_z));
+    }
+}
+
+#[test]
+fn world_chunk_at_block_missing_region() {
+    let world_path = create_test_world("world_chunk_at_block_missing", &[Dimension::Overworld], &["r.0.0.mca"], None);
+
+    // region r.5.5 does not exist
+    assert!(RegionFileSet::new(world_path.clone()).chunk_at_block(5 * 512, 5 * 512).unwrap().is_none());
+    // the Nether is not generated
+    assert!(RegionFileSet::with_dimension(world_path, Dimension::Nether).chunk_at_block(-10, 10).unwrap().is_none());
+}
+
+#[test]
+fn world_region_cache() {
+    let world_path = create_test_world("world_region_cache", &[Dimension::Overworld], &["r.0.0.mca"], None);
+    let mut world = World::with_cache_size(RegionFileSet::new(world_path), 1);
+    assert_eq!(world.cache_size(), 1);
+
+    let index = world.region(-1, 0).unwrap().unwrap().present_chunks()[0];
+    let (chunk_x, chunk_z) = world.region(-1, 0).unwrap().unwrap().chunk_coords(index).unwrap();
+    assert!(world.chunk_at_block(chunk_x * 16, chunk_z * 16).unwrap().is_some());
+    assert!(world.is_region_cached(-1, 0));
+
+    // opening another region drops the least recently used one
+    assert!(world.region(0, 0).unwrap().is_some());
+    assert!(world.is_region_cached(0, 0));
+    assert!(!world.is_region_cached(-1, 0));
+    assert_eq!(world.cached_regions_num(), 1);
+
+    // missing regions are not cached
+    assert!(world.region(5, 5).unwrap().is_none());
+    assert!(world.is_region_cached(0, 0));
+
+    world.set_cache_size(2);
+    world.region(-1, 0).unwrap();
+    world.region(0, 0).unwrap();
+    assert_eq!(world.cached_regions_num(), 2);
+
+    // the most recently used region is kept when the cache shrinks
+    world.set_cache_size(0);
+    assert_eq!(world.cache_size(), 1);
+    assert!(world.is_region_cached(0, 0));
+    assert!(!world.is_region_cached(-1, 0));
+}
+
+#[test]
+fn world_region_files() {
+    let world_path = create_test_world("world_region_files", &[Dimension::Overworld], &["r.0.0.mca"], None);
+    let region_path = Dimension::Overworld.region_path(&world_path);
+
+    let region_files = McWorldDescriptor::region_files(&world_path).unwrap();
+    assert_eq!(region_files, vec![(-1, 0, region_path.join("r.-1.0.mca")), (0, 0, region_path.join("r.0.0.mca"))]);
+    assert_eq!(McWorldDescriptor::region_files(&region_path).unwrap(), region_files);
+    assert!(McWorldDescriptor::region_files(&world_path.join("missing")).is_err());
+}
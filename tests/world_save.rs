@@ -0,0 +1,165 @@
+//! Tests writing the edited chunks of a world back to its region files.
+mod common;
+
+use fastnbt::{Dimension, McWorldDescriptor};
+use fastnbt::blocks::Coordinates;
+use fastnbt::region::Compression;
+use fastnbt::nbt_tag::{NbtTag, NbtTagCompound, NbtTagInt};
+use std::fs;
+use std::path::PathBuf;
+use common::create_test_world;
+
+#[test]
+fn world_save_dirty_chunks() {
+    let world_path = create_test_world("world_save_dirty_chunks", &[Dimension::Overworld], &[], None);
+    let region_path = Dimension::Overworld.region_path(&world_path).join("r.-1.0.mca");
+    let mut mc_world = McWorldDescriptor::new(world_path.clone()).unwrap();
+
+    // nothing modified, nothing written
+    let original_region = fs::read(&region_path).unwrap();
+    mc_world.save().unwrap();
+    assert_eq!(fs::read(&region_path).unwrap(), original_region);
+
+    let chunk = mc_world.compound_mut(1).unwrap();
+    chunk.values.insert("Edited".to_string(), NbtTag::Int(NbtTagInt::new("Edited".to_string(), 42)));
+    assert!(mc_world.is_dirty(1));
+    assert!(!mc_world.is_dirty(0));
+    let edited_chunk = mc_world.tag_compounds_list[1].clone();
+    let edited_source = mc_world.compound_source(1).unwrap().clone();
+
+    mc_world.save().unwrap();
+    assert!(!mc_world.is_dirty(1));
+
+    let saved_world = McWorldDescriptor::new(world_path).unwrap();
+    assert_eq!(saved_world.tag_compounds_list.len(), mc_world.tag_compounds_list.len());
+    let (chunk_x, chunk_z) = edited_source.chunk_coords.unwrap();
+    let saved_index = (0..saved_world.tag_compounds_list.len())
+        .find(|index| saved_world.compound_source(*index).unwrap().chunk_coords == Some((chunk_x, chunk_z)))
+        .unwrap();
+    assert_eq!(saved_world.tag_compounds_list[saved_index], edited_chunk);
+    assert!(saved_world.diff_world(&mc_world).is_empty());
+}
+
+#[test]
+fn world_save_replaced_blocks() {
+    let world_path = create_test_world("world_save_replaced_blocks", &[Dimension::Overworld], &[], None);
+    let mut mc_world = McWorldDescriptor::new(world_path.clone()).unwrap();
+
+    let replaced = mc_world.replace_block("minecraft:repeater", "minecraft:comparator");
+    assert!(replaced > 0);
+    assert!((0..mc_world.tag_compounds_list.len()).any(|index| mc_world.is_dirty(index)));
+    mc_world.save().unwrap();
+
+    let saved_world = McWorldDescriptor::new(world_path).unwrap();
+    assert!(saved_world.search_blocks(vec!["minecraft:repeater".to_string()]).values().all(|blocks| blocks.is_empty()));
+    assert!(!saved_world.search_blocks(vec!["minecraft:comparator".to_string()]).values().all(|blocks| blocks.is_empty()));
+
+    // a compound read from a single .nbt file cannot be saved
+    let mut nbt_world = McWorldDescriptor::new(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/resources/bigtest.nbt")).unwrap();
+    assert!(!nbt_world.mark_dirty(0));
+}
+
+#[test]
+fn world_save_chunk_handle() {
+    let world_path = create_test_world("world_save_chunk_handle", &[Dimension::Overworld], &[], None);
+    let mut mc_world = McWorldDescriptor::new(world_path.clone()).unwrap();
+    let (chunk_x, chunk_z) = mc_world.compound_source(0).unwrap().chunk_coords.unwrap();
+
+    let mut chunk = mc_world.chunk_handle_at(Dimension::Overworld, chunk_x, chunk_z).unwrap();
+    assert_eq!(chunk.position(), Some((Dimension::Overworld, chunk_x, chunk_z)));
+    assert!(chunk.remove("NotInTheChunk").is_none());
+    assert!(!chunk.is_dirty());
+
+    let block_y = (-4..20).map(|section_y| section_y * 16)
+        .find(|block_y| chunk.set_block(3, *block_y, 5, "minecraft:diamond_block"))
+        .unwrap();
+    assert!(chunk.is_dirty());
+    assert!(chunk.set("Edited", NbtTag::Int(NbtTagInt::new(String::new(), 42))).is_none());
+    assert!(mc_world.is_dirty(0));
+    assert!(!mc_world.is_dirty(1));
+    assert!(mc_world.chunk_handle_at(Dimension::Nether, chunk_x, chunk_z).is_none());
+
+    mc_world.save_with_compression(Compression::Best).unwrap();
+    assert!(!mc_world.chunk_handle(0).unwrap().is_dirty());
+
+    let mut saved_world = McWorldDescriptor::new(world_path).unwrap();
+    let saved_chunk = saved_world.chunk_handle_at(Dimension::Overworld, chunk_x, chunk_z).unwrap();
+    assert_eq!(saved_chunk.get("Edited"), Some(&NbtTag::Int(NbtTagInt::new("Edited".to_string(), 42))));
+    let diamonds = saved_world.search_blocks(vec!["minecraft:diamond_block".to_string()]);
+    let diamond = &diamonds["minecraft:diamond_block"][0];
+    assert_eq!(diamond.coord, Coordinates::new(vec![chunk_x * 16 + 3, block_y, chunk_z * 16 + 5]));
+}
+
+#[test]
+fn world_snapshot_restore() {
+    let world_path = create_test_world("world_snapshot_restore", &[Dimension::Overworld], &[], None);
+    let mut mc_world = McWorldDescriptor::new(world_path).unwrap();
+    let original_world = mc_world.clone();
+
+    let snapshot = mc_world.snapshot();
+    assert_eq!(snapshot.len(), mc_world.tag_compounds_list.len());
+    let original_chunk: *const NbtTagCompound = &*mc_world.tag_compounds_list[0];
+
+    // the edited chunk is copied, the snapshot keeps the original one
+    mc_world.chunk_handle(0).unwrap().set("Edited", NbtTag::Int(NbtTagInt::new(String::new(), 42)));
+    assert!(!std::ptr::eq(&*mc_world.tag_compounds_list[0], original_chunk));
+    mc_world.replace_block("minecraft:repeater", "minecraft:comparator");
+    assert!(!mc_world.diff_world(&original_world).is_empty());
+
+    mc_world.restore(snapshot);
+    assert!(std::ptr::eq(&*mc_world.tag_compounds_list[0], original_chunk));
+    assert!(mc_world.diff_world(&original_world).is_empty());
+    assert_eq!(mc_world.tag_compounds_list, original_world.tag_compounds_list);
+    assert!((0..mc_world.tag_compounds_list.len()).all(|index| !mc_world.is_dirty(index)));
+}
+
+#[test]
+fn world_save_after_list_edit() {
+    let world_path = create_test_world("world_save_after_list_edit", &[Dimension::Overworld], &[], None);
+    let mut mc_world = McWorldDescriptor::new(world_path.clone()).unwrap();
+    let original_world = McWorldDescriptor::new(world_path.clone()).unwrap();
+
+    // the chunk moves to index 0, its source moves with it
+    let removed_chunk = mc_world.tag_compounds_list.remove(0);
+    let (chunk_x, chunk_z) = mc_world.tag_compounds_list[0].chunk_coords.unwrap();
+    mc_world.chunk_handle(0).unwrap().set("Edited", NbtTag::Int(NbtTagInt::new(String::new(), 42)));
+    mc_world.save().unwrap();
+
+    let mut saved_world = McWorldDescriptor::new(world_path).unwrap();
+    assert_eq!(saved_world.tag_compounds_list.len(), original_world.tag_compounds_list.len());
+    let saved_chunk = saved_world.chunk_handle_at(Dimension::Overworld, chunk_x, chunk_z).unwrap();
+    assert_eq!(saved_chunk.get("Edited"), Some(&NbtTag::Int(NbtTagInt::new("Edited".to_string(), 42))));
+
+    // the removed chunk is still in its slot, untouched
+    let (removed_x, removed_z) = removed_chunk.chunk_coords.unwrap();
+    let saved_removed = saved_world.tag_compounds_list.iter()
+        .find(|sourced_compound| sourced_compound.chunk_coords == Some((removed_x, removed_z)))
+        .unwrap();
+    assert_eq!(saved_removed, &removed_chunk);
+    assert_eq!(saved_world.diff_world(&original_world).len(), 1);
+}
+
+#[test]
+fn world_save_duplicate_chunks() {
+    let world_path = create_test_world("world_save_duplicate_chunks", &[Dimension::Overworld], &[], None);
+    let mut mc_world = McWorldDescriptor::new(world_path.clone()).unwrap();
+    let chunks_num = mc_world.tag_compounds_list.len();
+    assert_eq!(mc_world.load_into(world_path.clone()).unwrap(), chunks_num);
+
+    // the same chunk is loaded twice, only the edited copy is modified
+    let chunk_coords = mc_world.tag_compounds_list[0].chunk_coords;
+    let duplicate_index = (1..mc_world.tag_compounds_list.len())
+        .find(|index| mc_world.tag_compounds_list[*index].chunk_coords == chunk_coords)
+        .unwrap();
+    mc_world.chunk_handle(duplicate_index).unwrap().set("Edited", NbtTag::Int(NbtTagInt::new(String::new(), 42)));
+    assert!(mc_world.is_dirty(duplicate_index));
+    assert!(!mc_world.is_dirty(0));
+    assert!(!mc_world.chunk_handle(0).unwrap().is_dirty());
+
+    mc_world.save().unwrap();
+    assert!(!mc_world.is_dirty(duplicate_index));
+    let (chunk_x, chunk_z) = chunk_coords.unwrap();
+    let mut saved_world = McWorldDescriptor::new(world_path).unwrap();
+    let saved_chunk = saved_world.chunk_handle_at(Dimension::Overworld, chunk_x, chunk_z).unwrap();
+    assert_eq!(saved_chunk.get("Edited"), Some(&NbtTag::Int(NbtTagInt::new("Edited".to_string(), 42))));
+}
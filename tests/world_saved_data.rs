@@ -0,0 +1,103 @@
+//! Tests the reading of the saved data (scoreboard, maps, command storage) of a world folder.
+use fastnbt::McWorldDescriptor;
+use fastnbt::saved_data::{self, COMMAND_STORAGE_FILE_PREFIX, DATA_FOLDER_NAME, IDCOUNTS_FILE_NAME, MAP_SIZE, SCOREBOARD_FILE_NAME};
+use fastnbt::nbt_tag::{self, NbtTag, NbtTagByteArray, NbtTagCompound, NbtTagInt, NbtTagList, NbtTagType};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn write_data_file(file_path: &Path, data: NbtTagCompound) {
+    let mut root = NbtTagCompound::new("");
+    root.values.insert("data".to_string(), NbtTag::Compound(data));
+    let mut bytes = Vec::new();
+    nbt_tag::write(&mut bytes, &root);
+    fs::write(file_path, bytes).unwrap();
+}
+
+fn map_data(color: i8) -> NbtTagCompound {
+    let mut data = NbtTagCompound::new("data");
+    data.values.insert("colors".to_string(), NbtTag::ByteArray(NbtTagByteArray::new("colors".to_string(), vec![color; MAP_SIZE * MAP_SIZE])));
+    data
+}
+
+#[test]
+fn world_saved_data() {
+    let resources_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/resources");
+    let world_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("world_saved_data");
+    let _ = fs::remove_dir_all(&world_path);
+    fs::create_dir_all(world_path.join("region")).unwrap();
+    fs::copy(resources_path.join("test_world/r.-1.0.mca"), world_path.join("region/r.-1.0.mca")).unwrap();
+
+    // no data folder: no maps, no scoreboard
+    let mc_world = McWorldDescriptor::new(world_path.clone()).unwrap();
+    assert!(mc_world.maps().unwrap().is_empty());
+    assert_eq!(mc_world.last_map_id().unwrap(), None);
+    assert_eq!(mc_world.next_map_id(), None);
+    assert!(mc_world.scoreboard().is_err());
+
+    let data_path = world_path.join(DATA_FOLDER_NAME);
+    fs::create_dir_all(&data_path).unwrap();
+    write_data_file(&data_path.join("map_10.dat"), map_data(4 * 12 + 2));
+    write_data_file(&data_path.join("map_2.dat"), map_data(0));
+    fs::write(data_path.join("map_3.dat"), [0x1F, 0x8B, 0]).unwrap();
+    let mut idcounts = NbtTagCompound::new("data");
+    idcounts.values.insert("map".to_string(), NbtTag::Int(NbtTagInt::new("map".to_string(), 10)));
+    write_data_file(&data_path.join(IDCOUNTS_FILE_NAME), idcounts);
+    let mut scoreboard = NbtTagCompound::new("data");
+    scoreboard.values.insert("Objectives".to_string(), NbtTag::List(NbtTagList::new("Objectives".to_string(), NbtTagType::Compound, vec![])));
+    write_data_file(&data_path.join(SCOREBOARD_FILE_NAME), scoreboard);
+
+    // the broken map_3.dat is skipped
+    let maps = mc_world.maps().unwrap();
+    assert_eq!(maps.iter().map(|map| map.id).collect::<Vec<_>>(), vec![2, 10]);
+    assert_eq!(maps[0].pixels()[0], [0, 0, 0, 0]);
+    assert_eq!(maps[1].pixels()[MAP_SIZE * MAP_SIZE - 1], [64, 64, 255, 255]);
+    assert_eq!(mc_world.last_map_id().unwrap(), Some(10));
+    assert_eq!(mc_world.next_map_id(), Some(11));
+    let mut full_idcounts = NbtTagCompound::new("data");
+    full_idcounts.values.insert("map".to_string(), NbtTag::Int(NbtTagInt::new("map".to_string(), i32::MAX)));
+    write_data_file(&data_path.join(IDCOUNTS_FILE_NAME), full_idcounts);
+    assert_eq!(saved_data::read_next_map_id(&world_path).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(mc_world.next_map_id(), None);
+    assert!(mc_world.scoreboard().unwrap().objectives().is_empty());
+
+    // a region file is not a world folder
+    let mc_region = McWorldDescriptor::new(world_path.join("region/r.-1.0.mca")).unwrap();
+    assert_eq!(mc_region.maps().unwrap_err().kind(), std::io::ErrorKind::NotFound);
+    assert_eq!(mc_region.next_map_id(), None);
+}
+
+#[test]
+fn world_command_storage() {
+    let resources_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/resources");
+    let world_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("world_command_storage");
+    let _ = fs::remove_dir_all(&world_path);
+    fs::create_dir_all(world_path.join("region")).unwrap();
+    fs::copy(resources_path.join("test_world/r.-1.0.mca"), world_path.join("region/r.-1.0.mca")).unwrap();
+    assert!(McWorldDescriptor::new(world_path.clone()).unwrap().command_storage().is_none());
+
+    // two namespaces, and a broken file skipped
+    let storage = |path: &str, value: i32| {
+        let mut stored = NbtTagCompound::new(path);
+        stored.values.insert("round".to_string(), NbtTag::Int(NbtTagInt::new("round".to_string(), value)));
+        let mut contents = NbtTagCompound::new("contents");
+        contents.values.insert(path.to_string(), NbtTag::Compound(stored));
+        let mut data = NbtTagCompound::new("data");
+        data.values.insert("contents".to_string(), NbtTag::Compound(contents));
+        data
+    };
+    let data_path = world_path.join(DATA_FOLDER_NAME);
+    fs::create_dir_all(&data_path).unwrap();
+    write_data_file(&data_path.join(format!("{}mymap.dat", COMMAND_STORAGE_FILE_PREFIX)), storage("state", 3));
+    write_data_file(&data_path.join(format!("{}minecraft.dat", COMMAND_STORAGE_FILE_PREFIX)), storage("temp", 1));
+    fs::write(data_path.join(format!("{}broken.dat", COMMAND_STORAGE_FILE_PREFIX)), [0x1F, 0x8B, 0]).unwrap();
+
+    let mc_world = McWorldDescriptor::new(world_path.clone()).unwrap();
+    let command_storage = mc_world.command_storage().unwrap();
+    let mut ids: Vec<&String> = command_storage.keys().collect();
+    ids.sort();
+    assert_eq!(ids, vec!["minecraft:temp", "mymap:state"]);
+    assert_eq!(command_storage.get_path("mymap:state/round").and_then(|tag| i32::try_from(tag).ok()), Some(3));
+
+    // a region file is not a world folder
+    assert!(McWorldDescriptor::new(world_path.join("region/r.-1.0.mca")).unwrap().command_storage().is_none());
+}
@@ -0,0 +1,41 @@
+//! Tests the loading of a world with a corrupt region file.
+use fastnbt::{LoadMode, McWorldDescriptor, WorldLoadOptions};
+use std::fs;
+use std::path::PathBuf;
+
+/// Creates a world with the test region file and a region file too short to hold the region header.
+fn create_corrupt_world(world_name: &str) -> PathBuf {
+    let resources_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/resources");
+    let world_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(world_name);
+    let _ = fs::remove_dir_all(&world_path);
+
+    let region_path = world_path.join("region");
+    fs::create_dir_all(&region_path).unwrap();
+    fs::copy(resources_path.join("test_world/r.-1.0.mca"), region_path.join("r.-1.0.mca")).unwrap();
+    fs::write(region_path.join("r.0.0.mca"), [0xFF; 100]).unwrap();
+
+    world_path
+}
+
+#[test]
+fn world_skip_corrupt_region_file() {
+    let world_path = create_corrupt_world("world_skip_corrupt_region_file");
+    let corrupt_path = world_path.join("region").join("r.0.0.mca");
+    let single_region = McWorldDescriptor::new(world_path.join("region").join("r.-1.0.mca")).unwrap();
+
+    // fail-fast by default
+    assert!(McWorldDescriptor::new(world_path.clone()).is_err());
+
+    for load_mode in [LoadMode::Eager, LoadMode::Lazy] {
+        let load_options = WorldLoadOptions { skip_corrupt: true, load_mode, ..Default::default() };
+        let mut mc_world = McWorldDescriptor::new_with_options(world_path.clone(), load_options).unwrap();
+        mc_world.decode_all().unwrap();
+
+        assert_eq!(mc_world.tag_compounds_list.len(), single_region.tag_compounds_list.len());
+        assert_eq!(mc_world.skipped_files().len(), 1);
+        assert_eq!(mc_world.skipped_files()[0].path, corrupt_path);
+        assert!(!mc_world.skipped_files()[0].error.is_empty());
+    }
+
+    assert!(single_region.skipped_files().is_empty());
+}